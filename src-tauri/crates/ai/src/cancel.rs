@@ -0,0 +1,101 @@
+use std::future::Future;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+use tokio::sync::Notify;
+use tracing::info;
+
+/// User-initiated cancellation for in-flight Gemini calls. Clones share one
+/// flag, so the token handed to a request can be cancelled from elsewhere
+/// (e.g. a Tauri `cancel_analysis` command). Once cancelled it stays
+/// cancelled; start later requests with a fresh token.
+#[derive(Debug, Clone, Default)]
+pub struct CancelToken {
+    inner: Arc<CancelState>,
+}
+
+#[derive(Debug, Default)]
+struct CancelState {
+    cancelled: AtomicBool,
+    notify: Notify,
+}
+
+impl CancelToken {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Cancel every [`cancellable`] call waiting on this token.
+    pub fn cancel(&self) {
+        self.inner.cancelled.store(true, Ordering::SeqCst);
+        self.inner.notify.notify_waiters();
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.inner.cancelled.load(Ordering::SeqCst)
+    }
+
+    /// Resolves once [`Self::cancel`] has been called.
+    pub async fn cancelled(&self) {
+        loop {
+            // Register before checking the flag so a cancel in between is not missed.
+            let notified = self.inner.notify.notified();
+            if self.is_cancelled() {
+                return;
+            }
+            notified.await;
+        }
+    }
+}
+
+/// Run `future` (typically a [`crate::GeminiClient`] call) until it finishes
+/// or `cancel` fires. On cancel the future is dropped, which drops the
+/// pending `reqwest` request and closes its connection instead of leaving it
+/// to finish in the background.
+pub async fn cancellable<F, T, E>(cancel: &CancelToken, future: F) -> Result<T, E>
+where
+    F: Future<Output = Result<T, E>>,
+    E: From<&'static str>,
+{
+    tokio::select! {
+        biased;
+        () = cancel.cancelled() => {
+            info!("cancellable: request cancelled by user");
+            Err(E::from("request cancelled"))
+        }
+        result = future => result,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{cancellable, CancelToken};
+    use crate::AiResult;
+
+    #[tokio::test]
+    async fn cancel_drops_pending_request() {
+        let cancel = CancelToken::new();
+        let trigger = cancel.clone();
+        tokio::spawn(async move {
+            tokio::task::yield_now().await;
+            trigger.cancel();
+        });
+
+        let result: AiResult<String> =
+            cancellable(&cancel, std::future::pending::<AiResult<String>>()).await;
+        let err = result.expect_err("cancelled");
+        assert_eq!(err.to_string(), "request cancelled");
+        assert!(cancel.is_cancelled());
+    }
+
+    #[tokio::test]
+    async fn uncancelled_request_returns_its_result() {
+        let cancel = CancelToken::new();
+        let result: Result<&str, String> = cancellable(&cancel, async { Ok("done") }).await;
+        assert_eq!(result, Ok("done"));
+
+        cancel.cancel();
+        let result: Result<&str, String> = cancellable(&cancel, async { Ok("late") }).await;
+        assert_eq!(result, Err("request cancelled".to_string()));
+    }
+}
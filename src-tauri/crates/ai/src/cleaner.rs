@@ -1,8 +1,9 @@
 use duckdb::Connection;
-use spatia_engine::{table_schema, TableColumn};
+use spatia_engine::{open_connection, table_schema, TableColumn};
 use tracing::{debug, error, info, warn};
 
 use crate::client::GeminiClient;
+use crate::fences::is_fence_line;
 use crate::prompts::{build_clean_prompt, build_clean_batch_retry_prompt};
 use crate::AiResult;
 
@@ -13,6 +14,24 @@ const RAW_STAGING_TABLE: &str = "raw_staging";
 const MAX_CLEAN_ROUNDS: usize = 3;
 /// If a round applies fewer than this many statements, skip subsequent rounds.
 const EARLY_EXIT_THRESHOLD: usize = 3;
+/// Default cap on how many statements one AI response may ask to run.
+const DEFAULT_MAX_STATEMENTS: usize = 25;
+
+/// Options for [`clean_table_with_options`].
+#[derive(Debug, Clone)]
+pub struct CleanOptions {
+    /// Most statements a single AI response (or batch retry response) may
+    /// contain. A response over the cap is rejected before any of it runs.
+    pub max_statements: usize,
+}
+
+impl Default for CleanOptions {
+    fn default() -> Self {
+        Self {
+            max_statements: DEFAULT_MAX_STATEMENTS,
+        }
+    }
+}
 
 /// The result of a cleaning run.
 #[derive(Debug, Clone)]
@@ -121,7 +140,7 @@ fn extract_sql_statements(ai_text: &str) -> Vec<String> {
     let joined: String = ai_text
         .lines()
         .map(str::trim)
-        .filter(|l| !l.is_empty() && !l.starts_with("```") && !l.starts_with("--"))
+        .filter(|l| !l.is_empty() && !is_fence_line(l) && !l.starts_with("--"))
         .collect::<Vec<_>>()
         .join(" ");
 
@@ -177,6 +196,20 @@ fn validate_schema_types(before: &[TableColumn], after: &[TableColumn]) -> AiRes
     Ok(())
 }
 
+/// Reject a response with more than `max_statements` statements, so a
+/// runaway model cannot issue an unbounded number of updates.
+fn check_statement_count(statements: &[String], max_statements: usize) -> AiResult<()> {
+    if statements.len() > max_statements {
+        return Err(format!(
+            "AI returned {} cleaning statements, more than the limit of {}; none were applied",
+            statements.len(),
+            max_statements
+        )
+        .into());
+    }
+    Ok(())
+}
+
 /// Clean the default ingestion table (`raw_staging`) using the Gemini AI.
 pub async fn clean_raw_staging(db_path: &str, client: &GeminiClient) -> AiResult<CleanResult> {
     clean_table(db_path, RAW_STAGING_TABLE, client).await
@@ -189,12 +222,16 @@ pub async fn clean_raw_staging(db_path: &str, client: &GeminiClient) -> AiResult
 /// — but retry is handled by the caller to keep this fn synchronous and thus
 /// avoid holding a `Connection` across `.await` points.
 ///
-/// Returns the list of successfully applied statements.
+/// Returns the list of successfully applied statements, or an error without
+/// executing anything when `statements` exceeds `max_statements`.
 fn try_execute_statements(
     conn: &Connection,
     table_name: &str,
     statements: &[String],
-) -> (Vec<String>, Vec<(String, String)>) {
+    max_statements: usize,
+) -> AiResult<(Vec<String>, Vec<(String, String)>)> {
+    check_statement_count(statements, max_statements)?;
+
     let mut applied: Vec<String> = Vec::new();
     let mut needs_retry: Vec<(String, String)> = Vec::new();
 
@@ -223,7 +260,17 @@ fn try_execute_statements(
         }
     }
 
-    (applied, needs_retry)
+    Ok((applied, needs_retry))
+}
+
+/// Clean the data in `table_name` using the Gemini AI with default
+/// [`CleanOptions`].
+pub async fn clean_table(
+    db_path: &str,
+    table_name: &str,
+    client: &GeminiClient,
+) -> AiResult<CleanResult> {
+    clean_table_with_options(db_path, table_name, client, &CleanOptions::default()).await
 }
 
 /// Clean the data in `table_name` using the Gemini AI.
@@ -234,15 +281,23 @@ fn try_execute_statements(
 /// 3. Executes them; for any that fail, asks AI to fix and retries once.
 ///
 /// Stops early if a round applies 0 statements (AI found nothing left to fix).
+/// A response with more than `options.max_statements` statements fails the
+/// run before any of that response is executed.
 ///
 /// Note: `Connection` is not `Send`, so it is opened, used synchronously, and
 /// dropped before every `.await` point to keep the future `Send`.
-pub async fn clean_table(
+pub async fn clean_table_with_options(
     db_path: &str,
     table_name: &str,
     client: &GeminiClient,
+    options: &CleanOptions,
 ) -> AiResult<CleanResult> {
-    info!(table = %table_name, max_rounds = MAX_CLEAN_ROUNDS, "clean_table: starting AI clean");
+    info!(
+        table = %table_name,
+        max_rounds = MAX_CLEAN_ROUNDS,
+        max_statements = options.max_statements,
+        "clean_table: starting AI clean"
+    );
 
     let schema = table_schema(db_path, table_name)?;
 
@@ -251,7 +306,7 @@ pub async fn clean_table(
     for round in 1..=MAX_CLEAN_ROUNDS {
         // Open a connection, do all synchronous work, then drop it before awaiting.
         let prompt = {
-            let conn = Connection::open(db_path)?;
+            let conn = open_connection(db_path)?;
             let rows = fetch_sample_rows(&conn, table_name)?;
             let masked = mask_sample_rows(&rows);
             build_clean_prompt(table_name, &schema, &masked)
@@ -291,8 +346,12 @@ pub async fn clean_table(
 
         // Execute synchronously; collect any that need AI-assisted retry.
         let (round_applied, needs_retry) = {
-            let conn = Connection::open(db_path)?;
-            try_execute_statements(&conn, table_name, &statements)
+            let conn = open_connection(db_path)?;
+            try_execute_statements(&conn, table_name, &statements, options.max_statements)
+                .map_err(|e| {
+                    error!(table = %table_name, round = round, error = %e, "clean_table: rejected AI response");
+                    e
+                })?
         };
 
         // Batch all failures into a single AI retry call instead of N serial calls.
@@ -313,9 +372,17 @@ pub async fn clean_table(
                     );
                 }
                 Ok(retry_response) => {
-                    let retry_stmts = extract_sql_statements(&retry_response);
+                    let mut retry_stmts = extract_sql_statements(&retry_response);
+                    if let Err(e) = check_statement_count(&retry_stmts, options.max_statements) {
+                        warn!(
+                            table = %table_name,
+                            error = %e,
+                            "clean_table: batch retry response over the statement limit, skipping all retries"
+                        );
+                        retry_stmts.clear();
+                    }
                     // Validate and execute corrected statements on a single connection.
-                    let conn = Connection::open(db_path)?;
+                    let conn = open_connection(db_path)?;
                     for corrected in &retry_stmts {
                         if let Err(e) = validate_statement(corrected) {
                             warn!(
@@ -410,7 +477,11 @@ pub async fn clean_table(
 
 #[cfg(test)]
 mod tests {
-    use super::{extract_sql_statements, mask_cell, mask_sample_rows, validate_schema_types, validate_statement};
+    use super::{
+        extract_sql_statements, mask_cell, mask_sample_rows, try_execute_statements,
+        validate_schema_types, validate_statement, CleanOptions,
+    };
+    use duckdb::Connection;
     use spatia_engine::TableColumn;
 
     fn col(name: &str, data_type: &str, cid: i64) -> TableColumn {
@@ -482,6 +553,32 @@ WHERE city IS NOT NULL;"#;
         assert!(validate_statement("update foo SET a = 1").is_ok());
     }
 
+    #[test]
+    fn over_limit_response_is_rejected_before_any_update_runs() {
+        let conn = Connection::open_in_memory().expect("open");
+        conn.execute_batch("CREATE TABLE t (a VARCHAR); INSERT INTO t VALUES (' x ')")
+            .expect("create");
+        let ai_text = (0..30)
+            .map(|_| "UPDATE t SET a = TRIM(a);")
+            .collect::<Vec<_>>()
+            .join("\n");
+        let statements = extract_sql_statements(&ai_text);
+        let max_statements = CleanOptions::default().max_statements;
+        assert_eq!(max_statements, 25);
+
+        let err = try_execute_statements(&conn, "t", &statements, max_statements)
+            .expect_err("over limit");
+        assert!(err.to_string().contains("more than the limit of 25"));
+        let value: String = conn
+            .query_row("SELECT a FROM t", [], |row| row.get(0))
+            .expect("select");
+        assert_eq!(value, " x ");
+
+        let (applied, failed) =
+            try_execute_statements(&conn, "t", &statements[..25], max_statements).expect("run");
+        assert_eq!((applied.len(), failed.len()), (25, 0));
+    }
+
     #[test]
     fn schema_type_validation_accepts_unchanged_schema() {
         let before = vec![col("city", "VARCHAR", 0), col("count", "INTEGER", 1)];
@@ -1,7 +1,8 @@
 use std::time::Duration;
 
 use serde::{Deserialize, Serialize};
-use tracing::{debug, error};
+use spatia_engine::http_client_builder;
+use tracing::{debug, error, warn};
 
 use crate::AiResult;
 
@@ -11,11 +12,67 @@ const GEMINI_API_BASE: &str =
 /// Default Gemini model used when none is specified.
 pub const DEFAULT_MODEL: &str = "gemini-2.5-flash";
 
+/// Models a caller may switch to per request with
+/// [`GeminiClient::with_requested_model`].
+pub const KNOWN_MODELS: &[&str] = &[
+    "gemini-2.5-flash",
+    "gemini-2.5-pro",
+    "gemini-2.0-flash",
+    "gemini-1.5-flash",
+    "gemini-1.5-pro",
+];
+
+/// Default cap on a `generateContent` response body. Generated SQL and chat
+/// replies are a few KB; anything near this size is a broken upstream.
+pub const DEFAULT_MAX_RESPONSE_BYTES: usize = 4 * 1024 * 1024;
+
+/// Overrides [`DEFAULT_MAX_RESPONSE_BYTES`] for clients built by
+/// [`GeminiClient::from_env`].
+pub const MAX_RESPONSE_BYTES_ENV: &str = "SPATIA_GEMINI_MAX_RESPONSE_BYTES";
+
+/// Harm categories relaxed to `BLOCK_NONE` by default. Place, street and
+/// person names in data-cleaning prompts trip these filters far more often
+/// than real harmful content does; sexually explicit content keeps Gemini's
+/// default threshold.
+const DEFAULT_RELAXED_CATEGORIES: &[&str] = &[
+    "HARM_CATEGORY_HARASSMENT",
+    "HARM_CATEGORY_HATE_SPEECH",
+    "HARM_CATEGORY_DANGEROUS_CONTENT",
+];
+
+/// One `safetySettings` entry: a Gemini harm category and the threshold at
+/// which responses in it are blocked (e.g. `BLOCK_NONE`,
+/// `BLOCK_ONLY_HIGH`, `BLOCK_MEDIUM_AND_ABOVE`).
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct SafetySetting {
+    pub category: String,
+    pub threshold: String,
+}
+
+impl SafetySetting {
+    pub fn new(category: impl Into<String>, threshold: impl Into<String>) -> Self {
+        Self {
+            category: category.into(),
+            threshold: threshold.into(),
+        }
+    }
+}
+
+/// The settings every client starts with; see [`DEFAULT_RELAXED_CATEGORIES`].
+pub fn default_safety_settings() -> Vec<SafetySetting> {
+    DEFAULT_RELAXED_CATEGORIES
+        .iter()
+        .map(|category| SafetySetting::new(*category, "BLOCK_NONE"))
+        .collect()
+}
+
 // ── Request / response shapes ────────────────────────────────────────────────
 
 #[derive(Serialize)]
 struct GenerateRequest<'a> {
     contents: Vec<Content<'a>>,
+    #[serde(skip_serializing_if = "<[_]>::is_empty")]
+    safety_settings: &'a [SafetySetting],
 }
 
 #[derive(Serialize)]
@@ -39,6 +96,8 @@ struct GenerationConfig {
 struct GenerateRequestWithConfig<'a> {
     contents: Vec<Content<'a>>,
     generation_config: GenerationConfig,
+    #[serde(skip_serializing_if = "<[_]>::is_empty")]
+    safety_settings: &'a [SafetySetting],
 }
 
 #[derive(Serialize)]
@@ -50,20 +109,48 @@ struct TemperatureConfig {
 struct GenerateRequestWithTemperature<'a> {
     contents: Vec<Content<'a>>,
     generation_config: TemperatureConfig,
+    #[serde(skip_serializing_if = "<[_]>::is_empty")]
+    safety_settings: &'a [SafetySetting],
 }
 
 #[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
 struct GenerateResponse {
+    #[serde(default)]
     candidates: Vec<Candidate>,
+    /// Present when the prompt itself was blocked; `candidates` is then empty.
+    prompt_feedback: Option<PromptFeedback>,
 }
 
 #[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
 struct Candidate {
-    content: ResponseContent,
+    /// Missing, or without parts, when the candidate was blocked.
+    content: Option<ResponseContent>,
+    finish_reason: Option<String>,
+    #[serde(default)]
+    safety_ratings: Vec<SafetyRating>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct PromptFeedback {
+    block_reason: Option<String>,
+    #[serde(default)]
+    safety_ratings: Vec<SafetyRating>,
+}
+
+#[derive(Deserialize)]
+struct SafetyRating {
+    category: String,
+    probability: String,
+    #[serde(default)]
+    blocked: bool,
 }
 
 #[derive(Deserialize)]
 struct ResponseContent {
+    #[serde(default)]
     parts: Vec<ResponsePart>,
 }
 
@@ -85,6 +172,9 @@ pub struct GeminiClient {
     model: String,
     http: reqwest::Client,
     temperature: Option<f32>,
+    base_url: String,
+    max_response_bytes: usize,
+    safety_settings: Vec<SafetySetting>,
 }
 
 impl GeminiClient {
@@ -93,12 +183,15 @@ impl GeminiClient {
         Self {
             api_key: api_key.into(),
             model: DEFAULT_MODEL.to_string(),
-            http: reqwest::Client::builder()
+            http: http_client_builder()
                 .connect_timeout(Duration::from_secs(10))
                 .timeout(Duration::from_secs(90))
                 .build()
                 .unwrap_or_default(),
             temperature: None,
+            base_url: GEMINI_API_BASE.to_string(),
+            max_response_bytes: DEFAULT_MAX_RESPONSE_BYTES,
+            safety_settings: default_safety_settings(),
         }
     }
 
@@ -107,12 +200,15 @@ impl GeminiClient {
         Self {
             api_key: api_key.into(),
             model: model.into(),
-            http: reqwest::Client::builder()
+            http: http_client_builder()
                 .connect_timeout(Duration::from_secs(10))
                 .timeout(Duration::from_secs(90))
                 .build()
                 .unwrap_or_default(),
             temperature: None,
+            base_url: GEMINI_API_BASE.to_string(),
+            max_response_bytes: DEFAULT_MAX_RESPONSE_BYTES,
+            safety_settings: default_safety_settings(),
         }
     }
 
@@ -122,15 +218,60 @@ impl GeminiClient {
         self
     }
 
+    /// Switch to `requested` if it is one of [`KNOWN_MODELS`]. `None`, a blank
+    /// name or an unknown model keeps the configured model; unknown names are
+    /// logged rather than rejected so a stale UI setting cannot break a call.
+    pub fn with_requested_model(mut self, requested: Option<&str>) -> Self {
+        let Some(requested) = requested.map(str::trim).filter(|m| !m.is_empty()) else {
+            return self;
+        };
+        if KNOWN_MODELS.contains(&requested) {
+            self.model = requested.to_string();
+        } else {
+            warn!(
+                requested,
+                model = %self.model,
+                "gemini: unknown model requested, keeping configured model"
+            );
+        }
+        self
+    }
+
+    /// Override the models endpoint (e.g. a proxy or a test server).
+    pub fn with_base_url(mut self, base_url: impl Into<String>) -> Self {
+        self.base_url = base_url.into().trim_end_matches('/').to_string();
+        self
+    }
+
+    /// Cap the size of a response body; larger responses fail with a
+    /// "response too large" error before any JSON parsing.
+    pub fn with_max_response_bytes(mut self, max_response_bytes: usize) -> Self {
+        self.max_response_bytes = max_response_bytes;
+        self
+    }
+
+    /// Replace the `safetySettings` sent with every request (defaults to
+    /// [`default_safety_settings`]). An empty list sends none, so Gemini's own
+    /// defaults apply.
+    pub fn with_safety_settings(mut self, safety_settings: Vec<SafetySetting>) -> Self {
+        self.safety_settings = safety_settings;
+        self
+    }
+
     /// Try to build a client from the `SPATIA_GEMINI_API_KEY` environment
-    /// variable.  Returns `Err` if the variable is absent or empty.
+    /// variable.  Returns `Err` if the variable is absent or empty, or if
+    /// `SPATIA_GEMINI_MAX_RESPONSE_BYTES` is set but not a positive integer.
     pub fn from_env() -> AiResult<Self> {
         let key = std::env::var("SPATIA_GEMINI_API_KEY")
             .map_err(|_| "SPATIA_GEMINI_API_KEY environment variable is not set")?;
         if key.trim().is_empty() {
             return Err("SPATIA_GEMINI_API_KEY is set but empty".into());
         }
-        Ok(Self::new(key))
+        let max_response_bytes = match std::env::var(MAX_RESPONSE_BYTES_ENV) {
+            Ok(raw) => parse_max_response_bytes(&raw)?,
+            Err(_) => DEFAULT_MAX_RESPONSE_BYTES,
+        };
+        Ok(Self::new(key).with_max_response_bytes(max_response_bytes))
     }
 
     /// Return the model name this client is configured to use.
@@ -138,18 +279,41 @@ impl GeminiClient {
         &self.model
     }
 
+    /// Buffer the response body, failing once it exceeds `max_response_bytes`.
+    async fn read_body_limited(&self, mut response: reqwest::Response) -> AiResult<Vec<u8>> {
+        let limit = self.max_response_bytes;
+        if let Some(len) = response.content_length() {
+            if len > limit as u64 {
+                return Err(format!(
+                    "Gemini response too large: {len} bytes exceeds limit of {limit} bytes"
+                )
+                .into());
+            }
+        }
+        let mut body = Vec::new();
+        while let Some(chunk) = response.chunk().await? {
+            if body.len() + chunk.len() > limit {
+                return Err(
+                    format!("Gemini response too large: exceeds limit of {limit} bytes").into(),
+                );
+            }
+            body.extend_from_slice(&chunk);
+        }
+        Ok(body)
+    }
+
     /// Send `prompt` to the Gemini `generateContent` endpoint with
     /// `response_mime_type: "application/json"` and return the first text
     /// response candidate.
     pub async fn generate_json(&self, prompt: &str) -> AiResult<String> {
         let url = format!(
             "{}/{model}:generateContent?key={key}",
-            GEMINI_API_BASE,
+            self.base_url,
             model = self.model,
             key = self.api_key,
         );
         // Safe URL for logging — never expose the API key.
-        let log_url = format!("{}/{model}:generateContent?key=[REDACTED]", GEMINI_API_BASE, model = self.model);
+        let log_url = format!("{}/{model}:generateContent?key=[REDACTED]", self.base_url, model = self.model);
 
         debug!(model = %self.model, prompt_len = prompt.len(), "generate_json: sending JSON-mode request to Gemini");
 
@@ -161,6 +325,7 @@ impl GeminiClient {
                 response_mime_type: "application/json",
                 temperature: self.temperature,
             },
+            safety_settings: &self.safety_settings,
         };
 
         let response = self
@@ -179,59 +344,66 @@ impl GeminiClient {
                 error!(model = %self.model, url = %log_url, error = %redacted, "generate_json: Gemini API returned error status");
             })?;
 
-        let parsed: GenerateResponse = response.json().await?;
+        let body = self.read_body_limited(response).await.inspect_err(|e| {
+            error!(model = %self.model, error = %e, "Gemini response rejected");
+        })?;
+        let parsed: GenerateResponse = serde_json::from_slice(&body)?;
 
-        let result = parsed
-            .candidates
-            .into_iter()
-            .next()
-            .and_then(|c| c.content.parts.into_iter().next())
-            .map(|p| p.text)
-            .ok_or_else(|| -> Box<dyn std::error::Error + Send + Sync> {
-                "Gemini returned no text candidates".into()
-            });
+        let result = first_text(parsed);
 
-        if let Ok(ref text) = result {
-            debug!(model = %self.model, response_len = text.len(), "generate_json: received response");
-        } else {
-            error!(model = %self.model, "generate_json: no candidates in response");
+        match &result {
+            Ok(text) => {
+                debug!(model = %self.model, response_len = text.len(), "generate_json: received response");
+            }
+            Err(err) => {
+                error!(model = %self.model, error = %err, "generate_json: no text in response");
+            }
         }
 
         result
     }
 
+    /// POST `prompt` as a plain-text `generateContent` body (temperature
+    /// included when set) to `url`.
+    fn text_request(&self, url: &str, prompt: &str) -> reqwest::RequestBuilder {
+        let contents = vec![Content {
+            parts: vec![Part { text: prompt }],
+        }];
+
+        if let Some(temp) = self.temperature {
+            let body = GenerateRequestWithTemperature {
+                contents,
+                generation_config: TemperatureConfig { temperature: temp },
+                safety_settings: &self.safety_settings,
+            };
+            self.http.post(url).json(&body)
+        } else {
+            let body = GenerateRequest {
+                contents,
+                safety_settings: &self.safety_settings,
+            };
+            self.http.post(url).json(&body)
+        }
+    }
+
     /// Send `prompt` to the Gemini `generateContent` endpoint and return the
     /// first text response candidate.
     pub async fn generate(&self, prompt: &str) -> AiResult<String> {
         let url = format!(
             "{}/{model}:generateContent?key={key}",
-            GEMINI_API_BASE,
+            self.base_url,
             model = self.model,
             key = self.api_key,
         );
         // Note: the Gemini REST API requires the key as a query parameter (?key=…).
         // This is the only supported authentication method for the v1beta endpoint.
         // Safe URL for logging — never expose the API key.
-        let log_url = format!("{}/{model}:generateContent?key=[REDACTED]", GEMINI_API_BASE, model = self.model);
+        let log_url = format!("{}/{model}:generateContent?key=[REDACTED]", self.base_url, model = self.model);
 
         debug!(model = %self.model, prompt_len = prompt.len(), "generate: sending request to Gemini");
 
-        let contents = vec![Content {
-            parts: vec![Part { text: prompt }],
-        }];
-
-        let request_builder = if let Some(temp) = self.temperature {
-            let body = GenerateRequestWithTemperature {
-                contents,
-                generation_config: TemperatureConfig { temperature: temp },
-            };
-            self.http.post(&url).json(&body)
-        } else {
-            let body = GenerateRequest { contents };
-            self.http.post(&url).json(&body)
-        };
-
-        let response = request_builder
+        let response = self
+            .text_request(&url, prompt)
             .send()
             .await
             .inspect_err(|e| {
@@ -244,31 +416,189 @@ impl GeminiClient {
                 error!(model = %self.model, url = %log_url, error = %redacted, "generate: Gemini API returned error status");
             })?;
 
-        let parsed: GenerateResponse = response.json().await?;
+        let body = self.read_body_limited(response).await.inspect_err(|e| {
+            error!(model = %self.model, error = %e, "Gemini response rejected");
+        })?;
+        let parsed: GenerateResponse = serde_json::from_slice(&body)?;
 
-        let result = parsed
-            .candidates
-            .into_iter()
-            .next()
-            .and_then(|c| c.content.parts.into_iter().next())
-            .map(|p| p.text)
-            .ok_or_else(|| -> Box<dyn std::error::Error + Send + Sync> {
-                "Gemini returned no text candidates".into()
-            });
+        let result = first_text(parsed);
 
-        if let Ok(ref text) = result {
-            debug!(model = %self.model, response_len = text.len(), "generate: received response");
-        } else {
-            error!(model = %self.model, "generate: no candidates in response");
+        match &result {
+            Ok(text) => {
+                debug!(model = %self.model, response_len = text.len(), "generate: received response");
+            }
+            Err(err) => {
+                error!(model = %self.model, error = %err, "generate: no text in response");
+            }
         }
 
         result
     }
+
+    /// Like [`Self::generate`], but through the `streamGenerateContent` SSE
+    /// endpoint: `on_chunk` receives each text fragment as it arrives and the
+    /// concatenated text is returned at the end. Prefer `generate` when the
+    /// whole answer is needed before it can be used, as with SQL.
+    pub async fn generate_stream<F>(&self, prompt: &str, mut on_chunk: F) -> AiResult<String>
+    where
+        F: FnMut(&str),
+    {
+        let url = format!(
+            "{}/{model}:streamGenerateContent?alt=sse&key={key}",
+            self.base_url,
+            model = self.model,
+            key = self.api_key,
+        );
+        // Safe URL for logging — never expose the API key.
+        let log_url = format!(
+            "{}/{model}:streamGenerateContent?alt=sse&key=[REDACTED]",
+            self.base_url,
+            model = self.model
+        );
+
+        debug!(model = %self.model, prompt_len = prompt.len(), "generate_stream: sending request to Gemini");
+
+        let mut response = self
+            .text_request(&url, prompt)
+            .send()
+            .await
+            .inspect_err(|e| {
+                let redacted = e.to_string().replace(self.api_key.as_str(), "[REDACTED]");
+                error!(model = %self.model, url = %log_url, error = %redacted, "generate_stream: HTTP request failed");
+            })?
+            .error_for_status()
+            .inspect_err(|e| {
+                let redacted = e.to_string().replace(self.api_key.as_str(), "[REDACTED]");
+                error!(model = %self.model, url = %log_url, error = %redacted, "generate_stream: Gemini API returned error status");
+            })?;
+
+        // SSE events arrive as `data: {json}` lines; network chunks can split
+        // a line, so bytes are held until the newline shows up.
+        let limit = self.max_response_bytes;
+        let mut received = 0usize;
+        let mut pending = Vec::new();
+        let mut text = String::new();
+        let mut emit = |fragment: String| {
+            on_chunk(&fragment);
+            text.push_str(&fragment);
+        };
+        while let Some(chunk) = response.chunk().await? {
+            received += chunk.len();
+            if received > limit {
+                return Err(
+                    format!("Gemini response too large: exceeds limit of {limit} bytes").into(),
+                );
+            }
+            pending.extend_from_slice(&chunk);
+            while let Some(end) = pending.iter().position(|byte| *byte == b'\n') {
+                let line: Vec<u8> = pending.drain(..=end).collect();
+                if let Some(fragment) = sse_event_text(&line)? {
+                    emit(fragment);
+                }
+            }
+        }
+        if let Some(fragment) = sse_event_text(&pending)? {
+            emit(fragment);
+        }
+
+        if text.is_empty() {
+            error!(model = %self.model, "generate_stream: no text in response");
+            return Err("Gemini returned no text candidates".into());
+        }
+        debug!(model = %self.model, response_len = text.len(), "generate_stream: received response");
+        Ok(text)
+    }
+}
+
+/// Text carried by one SSE line, `None` for non-data lines and for events
+/// without text (such as the final `STOP` marker). Safety blocks are errors.
+fn sse_event_text(line: &[u8]) -> AiResult<Option<String>> {
+    let line = std::str::from_utf8(line)?.trim();
+    let Some(data) = line.strip_prefix("data:") else {
+        return Ok(None);
+    };
+    let response: GenerateResponse = serde_json::from_str(data.trim())?;
+
+    let candidate = response.candidates.first();
+    let has_text = candidate
+        .and_then(|c| c.content.as_ref())
+        .is_some_and(|content| !content.parts.is_empty());
+    let prompt_blocked = response
+        .prompt_feedback
+        .as_ref()
+        .is_some_and(|feedback| feedback.block_reason.is_some());
+    let stopped_early = candidate
+        .and_then(|c| c.finish_reason.as_deref())
+        .is_some_and(|reason| reason != "STOP");
+    if has_text || prompt_blocked || stopped_early {
+        first_text(response).map(Some)
+    } else {
+        Ok(None)
+    }
+}
+
+/// Text of the first candidate. When there is none, the error names the
+/// prompt's `blockReason` or the candidate's `finishReason` and safety
+/// ratings, so a safety block is distinguishable from an empty reply.
+fn first_text(response: GenerateResponse) -> AiResult<String> {
+    if let Some(feedback) = response.prompt_feedback {
+        if let Some(reason) = feedback.block_reason {
+            return Err(format!(
+                "Gemini blocked the prompt (blockReason: {reason}; safetyRatings: {})",
+                describe_ratings(&feedback.safety_ratings)
+            )
+            .into());
+        }
+    }
+
+    let Some(candidate) = response.candidates.into_iter().next() else {
+        return Err("Gemini returned no text candidates".into());
+    };
+    if let Some(part) = candidate.content.and_then(|c| c.parts.into_iter().next()) {
+        return Ok(part.text);
+    }
+    match candidate.finish_reason {
+        Some(reason) => Err(format!(
+            "Gemini returned no text (finishReason: {reason}; safetyRatings: {})",
+            describe_ratings(&candidate.safety_ratings)
+        )
+        .into()),
+        None => Err("Gemini returned no text candidates".into()),
+    }
+}
+
+/// `CATEGORY=PROBABILITY` per rating, marking the ones that caused a block.
+fn describe_ratings(ratings: &[SafetyRating]) -> String {
+    if ratings.is_empty() {
+        return "none".to_string();
+    }
+    ratings
+        .iter()
+        .map(|rating| {
+            let blocked = if rating.blocked { " (blocked)" } else { "" };
+            format!("{}={}{blocked}", rating.category, rating.probability)
+        })
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// Parse a `SPATIA_GEMINI_MAX_RESPONSE_BYTES` value: a positive byte count.
+fn parse_max_response_bytes(raw: &str) -> AiResult<usize> {
+    match raw.trim().parse::<usize>() {
+        Ok(bytes) if bytes > 0 => Ok(bytes),
+        _ => Err(format!(
+            "{MAX_RESPONSE_BYTES_ENV} must be a positive number of bytes, got '{raw}'"
+        )
+        .into()),
+    }
 }
 
 #[cfg(test)]
 mod tests {
-    use super::{GeminiClient, DEFAULT_MODEL};
+    use super::{
+        default_safety_settings, parse_max_response_bytes, GeminiClient, SafetySetting,
+        DEFAULT_MODEL, MAX_RESPONSE_BYTES_ENV,
+    };
 
     #[test]
     fn default_model_is_set() {
@@ -282,6 +612,190 @@ mod tests {
         assert_eq!(client.model(), "gemini-1.5-pro");
     }
 
+    #[test]
+    fn requested_model_is_checked_against_allowlist() {
+        let client = GeminiClient::new("test_key").with_requested_model(Some("gemini-1.5-pro"));
+        assert_eq!(client.model(), "gemini-1.5-pro");
+
+        let client = GeminiClient::new("test_key").with_requested_model(Some("gpt-4o"));
+        assert_eq!(client.model(), DEFAULT_MODEL);
+
+        let client = GeminiClient::new("test_key").with_requested_model(Some("  "));
+        assert_eq!(client.model(), DEFAULT_MODEL);
+        let client = GeminiClient::new("test_key").with_requested_model(None);
+        assert_eq!(client.model(), DEFAULT_MODEL);
+    }
+
+    #[tokio::test]
+    async fn oversized_response_is_rejected() {
+        let mut server = mockito::Server::new_async().await;
+        let _mock = server
+            .mock("POST", mockito::Matcher::Any)
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(format!(
+                r#"{{"candidates":[{{"content":{{"parts":[{{"text":"{}"}}]}}}}]}}"#,
+                "x".repeat(8192)
+            ))
+            .create_async()
+            .await;
+
+        let client = GeminiClient::new("test_key")
+            .with_base_url(server.url())
+            .with_max_response_bytes(1024);
+        let err = client.generate("hello").await.expect_err("should reject");
+        assert!(err.to_string().contains("response too large"), "got: {err}");
+
+        let client = GeminiClient::new("test_key").with_base_url(server.url());
+        let text = client.generate("hello").await.expect("within default limit");
+        assert_eq!(text.len(), 8192);
+    }
+
+    #[tokio::test]
+    async fn safety_settings_are_sent_with_requests() {
+        let mut server = mockito::Server::new_async().await;
+        let mock = server
+            .mock("POST", mockito::Matcher::Any)
+            .match_body(mockito::Matcher::PartialJson(serde_json::json!({
+                "safety_settings": [
+                    {"category": "HARM_CATEGORY_HARASSMENT", "threshold": "BLOCK_ONLY_HIGH"}
+                ]
+            })))
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(r#"{"candidates":[{"content":{"parts":[{"text":"ok"}]}}]}"#)
+            .create_async()
+            .await;
+
+        let client = GeminiClient::new("test_key")
+            .with_base_url(server.url())
+            .with_safety_settings(vec![SafetySetting::new(
+                "HARM_CATEGORY_HARASSMENT",
+                "BLOCK_ONLY_HIGH",
+            )]);
+        assert_eq!(client.generate("hello").await.expect("generate"), "ok");
+        mock.assert_async().await;
+
+        assert!(default_safety_settings()
+            .iter()
+            .all(|setting| setting.threshold == "BLOCK_NONE"));
+    }
+
+    #[tokio::test]
+    async fn blocked_candidate_reports_finish_reason_and_ratings() {
+        let mut server = mockito::Server::new_async().await;
+        let _mock = server
+            .mock("POST", mockito::Matcher::Any)
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(
+                r#"{"candidates":[{"finishReason":"SAFETY","safetyRatings":[
+                    {"category":"HARM_CATEGORY_HARASSMENT","probability":"MEDIUM","blocked":true},
+                    {"category":"HARM_CATEGORY_HATE_SPEECH","probability":"NEGLIGIBLE"}]}]}"#,
+            )
+            .create_async()
+            .await;
+
+        let client = GeminiClient::new("test_key").with_base_url(server.url());
+        let err = client
+            .generate_json("clean these names")
+            .await
+            .expect_err("blocked");
+        let message = err.to_string();
+        assert!(message.contains("finishReason: SAFETY"), "got: {message}");
+        assert!(
+            message.contains("HARM_CATEGORY_HARASSMENT=MEDIUM (blocked)"),
+            "got: {message}"
+        );
+    }
+
+    #[tokio::test]
+    async fn blocked_prompt_reports_block_reason() {
+        let mut server = mockito::Server::new_async().await;
+        let _mock = server
+            .mock("POST", mockito::Matcher::Any)
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(r#"{"promptFeedback":{"blockReason":"SAFETY","safetyRatings":[]}}"#)
+            .create_async()
+            .await;
+
+        let client = GeminiClient::new("test_key").with_base_url(server.url());
+        let err = client.generate("hello").await.expect_err("blocked");
+        assert!(
+            err.to_string().contains("blockReason: SAFETY"),
+            "got: {err}"
+        );
+    }
+
+    #[tokio::test]
+    async fn generate_stream_yields_each_sse_chunk() {
+        let mut server = mockito::Server::new_async().await;
+        let mock = server
+            .mock("POST", mockito::Matcher::Regex("streamGenerateContent".to_string()))
+            .match_query(mockito::Matcher::UrlEncoded("alt".into(), "sse".into()))
+            .with_status(200)
+            .with_header("content-type", "text/event-stream")
+            .with_body(
+                "data: {\"candidates\":[{\"content\":{\"parts\":[{\"text\":\"Most claims \"}]}}]}\r\n\r\n\
+                 data: {\"candidates\":[{\"content\":{\"parts\":[{\"text\":\"are in Seattle.\"}]}}]}\r\n\r\n\
+                 data: {\"candidates\":[{\"finishReason\":\"STOP\"}]}\r\n\r\n",
+            )
+            .create_async()
+            .await;
+
+        let client = GeminiClient::new("test_key").with_base_url(server.url());
+        let mut chunks = Vec::new();
+        let text = client
+            .generate_stream("where are the claims?", |chunk| {
+                chunks.push(chunk.to_string())
+            })
+            .await
+            .expect("stream");
+        assert_eq!(chunks, vec!["Most claims ", "are in Seattle."]);
+        assert_eq!(text, "Most claims are in Seattle.");
+        mock.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn generate_stream_reports_safety_block() {
+        let mut server = mockito::Server::new_async().await;
+        let _mock = server
+            .mock("POST", mockito::Matcher::Any)
+            .with_status(200)
+            .with_body(
+                "data: {\"candidates\":[{\"finishReason\":\"SAFETY\",\"safetyRatings\":[\
+                 {\"category\":\"HARM_CATEGORY_HARASSMENT\",\"probability\":\"HIGH\",\"blocked\":true}]}]}\n\n",
+            )
+            .create_async()
+            .await;
+
+        let client = GeminiClient::new("test_key").with_base_url(server.url());
+        let err = client
+            .generate_stream("hello", |_| {})
+            .await
+            .expect_err("blocked");
+        assert!(
+            err.to_string().contains("finishReason: SAFETY"),
+            "got: {err}"
+        );
+    }
+
+    #[test]
+    fn max_response_bytes_must_be_positive() {
+        assert_eq!(
+            parse_max_response_bytes(" 1048576 ").expect("valid"),
+            1_048_576
+        );
+        for raw in ["0", "-1", "4MB", ""] {
+            let err = parse_max_response_bytes(raw).expect_err("invalid");
+            assert!(
+                err.to_string().contains(MAX_RESPONSE_BYTES_ENV),
+                "got: {err}"
+            );
+        }
+    }
+
     #[test]
     fn from_env_errors_when_var_missing() {
         // Remove the key if it happens to be set in the test environment.
@@ -0,0 +1,400 @@
+//! Rule-based analysis SQL generator used when Gemini is not configured.
+//!
+//! The generator pattern-matches the user's goal against the table's column
+//! names and understands a handful of phrasings:
+//!
+//! - **Count by a column** — "count by city", "number of claims per state",
+//!   "how many rows by category" → `GROUP BY` with a `count` column.
+//! - **Top N by a column** — "top 5 by premium", "top 10 tiv" → `ORDER BY ...
+//!   DESC LIMIT N` (N defaults to 10).
+//! - **Filter** — "where state = CA", "filter where premium > 1000",
+//!   "where city is Seattle" → a `WHERE` clause. Supported operators are
+//!   `=`, `!=`, `<>`, `>`, `<`, `>=`, `<=`, `is`, `is not`, `equals`,
+//!   `greater than`, `less than`, `above` and `below`.
+//!
+//! A filter can be combined with either of the other two shapes, e.g.
+//! "count by city where state = CA". Goals that match nothing fall back to
+//! `SELECT * ... LIMIT 100`.
+
+use spatia_engine::TableColumn;
+
+/// Row cap for goals that do not match any supported pattern.
+const DEFAULT_ROW_LIMIT: usize = 100;
+
+/// Default N for "top N" goals that do not specify a number.
+const DEFAULT_TOP_N: usize = 10;
+
+/// Operators recognised in filter goals, longest first so that `>=` wins over `>`.
+const FILTER_OPERATORS: &[(&str, &str)] = &[
+    (">=", ">="),
+    ("<=", "<="),
+    ("!=", "!="),
+    ("<>", "!="),
+    ("=", "="),
+    (">", ">"),
+    ("<", "<"),
+    ("is not ", "!="),
+    ("is ", "="),
+    ("equals ", "="),
+    ("greater than ", ">"),
+    ("less than ", "<"),
+    ("above ", ">"),
+    ("below ", "<"),
+];
+
+/// Build a `CREATE OR REPLACE VIEW analysis_result AS ...` statement for
+/// `user_goal` without calling an LLM. See the module docs for the supported
+/// phrasings.
+pub fn build_fallback_analysis_sql(
+    table_name: &str,
+    schema: &[TableColumn],
+    user_goal: &str,
+) -> String {
+    // ASCII lowercasing keeps byte offsets aligned with `user_goal`, so filter
+    // values can be sliced out of the original text with their case intact.
+    let goal = user_goal.to_ascii_lowercase().replace('_', " ");
+    let table = quote_identifier(table_name);
+
+    let (shape_text, filter) = match find_word(&goal, "where") {
+        Some(idx) => (
+            &goal[..idx],
+            parse_filter(schema, &goal, user_goal, idx + 5),
+        ),
+        None => (goal.as_str(), None),
+    };
+    let where_clause = filter
+        .map(|condition| format!(" WHERE {condition}"))
+        .unwrap_or_default();
+
+    let body = if let Some((n, column)) = parse_top_n(schema, shape_text) {
+        let column = quote_identifier(&column.name);
+        format!("SELECT * FROM {table}{where_clause} ORDER BY {column} DESC NULLS LAST LIMIT {n}")
+    } else if let Some(column) = parse_count_by(schema, shape_text) {
+        let column = quote_identifier(&column.name);
+        format!(
+            "SELECT {column}, COUNT(*) AS count FROM {table}{where_clause} \
+             GROUP BY {column} ORDER BY count DESC"
+        )
+    } else {
+        format!("SELECT * FROM {table}{where_clause} LIMIT {DEFAULT_ROW_LIMIT}")
+    };
+
+    format!("CREATE OR REPLACE VIEW analysis_result AS {body};")
+}
+
+/// "top N by Y" / "top N Y" → (N, Y).
+fn parse_top_n<'a>(schema: &'a [TableColumn], goal: &str) -> Option<(usize, &'a TableColumn)> {
+    let idx = find_word(goal, "top")?;
+    let rest = goal[idx + 3..].trim_start();
+    let digits: String = rest.chars().take_while(char::is_ascii_digit).collect();
+    let n = digits.parse::<usize>().unwrap_or(DEFAULT_TOP_N).max(1);
+    let rest = &rest[digits.len()..];
+
+    let column = match find_word(rest, "by") {
+        Some(by_idx) => first_column_in(schema, &rest[by_idx + 2..]),
+        None => first_column_in(schema, rest),
+    }?;
+    Some((n, column))
+}
+
+/// "count by X" / "number of ... per X" / "how many ... by X" → X.
+fn parse_count_by<'a>(schema: &'a [TableColumn], goal: &str) -> Option<&'a TableColumn> {
+    let counts = find_word(goal, "count").is_some()
+        || goal.contains("number of")
+        || goal.contains("how many")
+        || find_word(goal, "group").is_some();
+    if !counts {
+        return None;
+    }
+    let idx = find_word(goal, "by")
+        .map(|i| i + 2)
+        .or_else(|| find_word(goal, "per").map(|i| i + 3))?;
+    first_column_in(schema, &goal[idx..])
+}
+
+/// Parse `<column> <operator> <value>` starting at byte `start` of `goal`.
+/// `original` is the untouched user text used to preserve the value's case.
+fn parse_filter(
+    schema: &[TableColumn],
+    goal: &str,
+    original: &str,
+    start: usize,
+) -> Option<String> {
+    let rest_start = start + (goal[start..].len() - goal[start..].trim_start().len());
+    let (column, column_len) = column_at_start(schema, &goal[rest_start..])?;
+
+    let after_column = rest_start + column_len;
+    let op_start =
+        after_column + (goal[after_column..].len() - goal[after_column..].trim_start().len());
+    let (keyword, op) = FILTER_OPERATORS
+        .iter()
+        .find(|(keyword, _)| goal[op_start..].starts_with(keyword))?;
+
+    let value_start = op_start + keyword.len();
+    let raw_value = original[value_start..]
+        .split([',', ';'])
+        .next()
+        .unwrap_or_default();
+    let raw_value = cut_at_word(raw_value, &["and", "order", "sorted", "limit"]);
+    let value = raw_value
+        .trim()
+        .trim_end_matches(['.', '?', '!'])
+        .trim_matches(['\'', '"']);
+    if value.is_empty() {
+        return None;
+    }
+
+    let column_sql = quote_identifier(&column.name);
+    // `nan` and `inf` parse as f64 but are not numeric literals in SQL.
+    if is_numeric_type(&column.data_type) && value.parse::<f64>().is_ok_and(f64::is_finite) {
+        return Some(format!("{column_sql} {op} {value}"));
+    }
+    let literal = format!("'{}'", value.replace('\'', "''"));
+    match *op {
+        "=" => Some(format!("{column_sql} ILIKE {literal}")),
+        "!=" => Some(format!("{column_sql} NOT ILIKE {literal}")),
+        _ => Some(format!("{column_sql} {op} {literal}")),
+    }
+}
+
+/// Return the schema column mentioned earliest in `text`, preferring the
+/// longest name when two columns start at the same position.
+fn first_column_in<'a>(schema: &'a [TableColumn], text: &str) -> Option<&'a TableColumn> {
+    schema
+        .iter()
+        .filter_map(|col| find_word(text, &normalize_column_name(&col.name)).map(|idx| (idx, col)))
+        .min_by(|(a_idx, a), (b_idx, b)| a_idx.cmp(b_idx).then(b.name.len().cmp(&a.name.len())))
+        .map(|(_, col)| col)
+}
+
+/// Match the longest column name at the start of `text`, returning the
+/// column and the number of bytes it occupies.
+fn column_at_start<'a>(schema: &'a [TableColumn], text: &str) -> Option<(&'a TableColumn, usize)> {
+    schema
+        .iter()
+        .filter_map(|col| {
+            let name = normalize_column_name(&col.name);
+            (find_word(text, &name) == Some(0)).then_some((col, name.len()))
+        })
+        .max_by_key(|(_, len)| *len)
+}
+
+/// Byte offset of `word` in `text` where it is delimited by non-alphanumeric
+/// characters (or the string boundary) on both sides.
+fn find_word(text: &str, word: &str) -> Option<usize> {
+    if word.is_empty() {
+        return None;
+    }
+    let bytes = text.as_bytes();
+    let mut from = 0;
+    while let Some(pos) = text[from..].find(word) {
+        let idx = from + pos;
+        let end = idx + word.len();
+        let before_ok = idx == 0 || !bytes[idx - 1].is_ascii_alphanumeric();
+        let after_ok = end == bytes.len() || !bytes[end].is_ascii_alphanumeric();
+        if before_ok && after_ok {
+            return Some(idx);
+        }
+        from = end;
+    }
+    None
+}
+
+/// Truncate `text` at the first occurrence of any stop word.
+fn cut_at_word<'a>(text: &'a str, stop_words: &[&str]) -> &'a str {
+    let lowered = text.to_ascii_lowercase();
+    let end = stop_words
+        .iter()
+        .filter_map(|word| find_word(&lowered, word))
+        .min()
+        .unwrap_or(text.len());
+    &text[..end]
+}
+
+fn normalize_column_name(name: &str) -> String {
+    name.to_ascii_lowercase().replace('_', " ")
+}
+
+/// DuckDB numeric type names and aliases, matched against the type name
+/// without its `(precision, scale)` suffix.
+const NUMERIC_TYPES: &[&str] = &[
+    "TINYINT",
+    "SMALLINT",
+    "INTEGER",
+    "BIGINT",
+    "HUGEINT",
+    "UTINYINT",
+    "USMALLINT",
+    "UINTEGER",
+    "UBIGINT",
+    "UHUGEINT",
+    "INT",
+    "INT1",
+    "INT2",
+    "INT4",
+    "INT8",
+    "SHORT",
+    "LONG",
+    "SIGNED",
+    "FLOAT",
+    "FLOAT4",
+    "FLOAT8",
+    "REAL",
+    "DOUBLE",
+    "DECIMAL",
+    "NUMERIC",
+    "VARINT",
+];
+
+fn is_numeric_type(data_type: &str) -> bool {
+    let base = data_type.split('(').next().unwrap_or_default().trim();
+    NUMERIC_TYPES
+        .iter()
+        .any(|kind| base.eq_ignore_ascii_case(kind))
+}
+
+fn quote_identifier(name: &str) -> String {
+    format!("\"{}\"", name.replace('"', "\"\""))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{build_fallback_analysis_sql, is_numeric_type};
+    use spatia_engine::TableColumn;
+
+    fn column(cid: i64, name: &str, data_type: &str) -> TableColumn {
+        TableColumn {
+            cid,
+            name: name.to_string(),
+            data_type: data_type.to_string(),
+            notnull: false,
+            default_value: None,
+            primary_key: false,
+        }
+    }
+
+    fn sample_schema() -> Vec<TableColumn> {
+        vec![
+            column(0, "id", "INTEGER"),
+            column(1, "city", "VARCHAR"),
+            column(2, "state", "VARCHAR"),
+            column(3, "total_insured_value", "DOUBLE"),
+        ]
+    }
+
+    #[test]
+    fn count_by_column() {
+        let sql = build_fallback_analysis_sql("claims", &sample_schema(), "Count by city");
+        assert_eq!(
+            sql,
+            "CREATE OR REPLACE VIEW analysis_result AS SELECT \"city\", COUNT(*) AS count \
+             FROM \"claims\" GROUP BY \"city\" ORDER BY count DESC;"
+        );
+    }
+
+    #[test]
+    fn number_of_rows_per_column() {
+        let sql =
+            build_fallback_analysis_sql("claims", &sample_schema(), "number of claims per state");
+        assert!(sql.contains("GROUP BY \"state\""));
+    }
+
+    #[test]
+    fn top_n_by_column_with_underscored_name() {
+        let sql = build_fallback_analysis_sql(
+            "claims",
+            &sample_schema(),
+            "show the top 5 by total insured value",
+        );
+        assert!(sql.contains("ORDER BY \"total_insured_value\" DESC NULLS LAST LIMIT 5"));
+    }
+
+    #[test]
+    fn top_defaults_to_ten() {
+        let sql =
+            build_fallback_analysis_sql("claims", &sample_schema(), "top total_insured_value");
+        assert!(sql.ends_with("LIMIT 10;"));
+    }
+
+    #[test]
+    fn filter_on_text_column_preserves_value_case() {
+        let sql =
+            build_fallback_analysis_sql("claims", &sample_schema(), "filter where city is Seattle");
+        assert!(sql.contains("WHERE \"city\" ILIKE 'Seattle'"));
+    }
+
+    #[test]
+    fn filter_on_numeric_column_is_unquoted() {
+        let sql = build_fallback_analysis_sql(
+            "claims",
+            &sample_schema(),
+            "rows where total insured value >= 250000",
+        );
+        assert!(sql.contains("WHERE \"total_insured_value\" >= 250000"));
+    }
+
+    #[test]
+    fn non_finite_values_are_quoted_on_numeric_columns() {
+        for value in ["nan", "inf", "-Infinity"] {
+            let sql = build_fallback_analysis_sql(
+                "claims",
+                &sample_schema(),
+                &format!("rows where total insured value > {value}"),
+            );
+            assert!(
+                sql.contains(&format!("\"total_insured_value\" > '{value}'")),
+                "{sql}"
+            );
+        }
+    }
+
+    #[test]
+    fn numeric_types_match_whole_names() {
+        for data_type in [
+            "INTEGER",
+            "BIGINT",
+            "DOUBLE",
+            "DECIMAL(18,3)",
+            "uhugeint",
+            "FLOAT",
+        ] {
+            assert!(is_numeric_type(data_type), "{data_type}");
+        }
+        for data_type in [
+            "POINT_2D",
+            "INTERVAL",
+            "GEOMETRY",
+            "VARCHAR",
+            "INTEGER[]",
+            "TIMESTAMP",
+        ] {
+            assert!(!is_numeric_type(data_type), "{data_type}");
+        }
+    }
+
+    #[test]
+    fn count_combined_with_filter() {
+        let sql = build_fallback_analysis_sql(
+            "claims",
+            &sample_schema(),
+            "count by city where state = CA",
+        );
+        assert!(sql.contains("WHERE \"state\" ILIKE 'CA'"));
+        assert!(sql.contains("GROUP BY \"city\""));
+    }
+
+    #[test]
+    fn filter_value_escapes_quotes() {
+        let sql = build_fallback_analysis_sql("claims", &sample_schema(), "where city = O'Fallon");
+        assert!(sql.contains("'O''Fallon'"));
+    }
+
+    #[test]
+    fn unmatched_goal_falls_back_to_limit() {
+        let sql = build_fallback_analysis_sql("claims", &sample_schema(), "show me something");
+        assert_eq!(
+            sql,
+            "CREATE OR REPLACE VIEW analysis_result AS SELECT * FROM \"claims\" LIMIT 100;"
+        );
+    }
+}
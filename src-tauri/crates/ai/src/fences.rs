@@ -0,0 +1,69 @@
+//! Markdown code fences around model replies.
+//!
+//! Prompts ask for bare SQL or JSON, but Gemini still wraps answers in
+//! ```` ```sql ```` (or bare ```` ``` ````) fences often enough that every
+//! caller has to undo it before validation.
+
+/// Whether `line` opens or closes a fenced block.
+#[cfg(feature = "gemini")]
+pub(crate) fn is_fence_line(line: &str) -> bool {
+    line.trim_start().starts_with("```")
+}
+
+/// Return the contents of the first fenced block in `text`, trimmed, or the
+/// whole trimmed text when there is no fence. The opening fence's info string
+/// (`sql`, `SQL`, `json`, ...) is dropped and a missing closing fence is
+/// tolerated.
+pub fn strip_markdown_fences(text: &str) -> String {
+    let trimmed = text.trim();
+    let Some(start) = trimmed.find("```") else {
+        return trimmed.to_string();
+    };
+    let rest = &trimmed[start + 3..];
+    let body = match rest.split_once('\n') {
+        Some((info, body)) if info.trim().chars().all(|c| c.is_ascii_alphanumeric()) => body,
+        _ => rest,
+    };
+    let body = body.find("```").map_or(body, |end| &body[..end]);
+    body.trim().to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::strip_markdown_fences;
+
+    #[test]
+    fn strips_sql_fence_around_single_statement() {
+        let reply =
+            "\n  ```sql\nCREATE OR REPLACE VIEW analysis_result AS SELECT * FROM t;\n```  \n";
+        assert_eq!(
+            strip_markdown_fences(reply),
+            "CREATE OR REPLACE VIEW analysis_result AS SELECT * FROM t;"
+        );
+    }
+
+    #[test]
+    fn strips_bare_and_uppercase_fences() {
+        assert_eq!(strip_markdown_fences("```\nSELECT 1\n```"), "SELECT 1");
+        assert_eq!(strip_markdown_fences("```SQL\nSELECT 1\n```"), "SELECT 1");
+        assert_eq!(strip_markdown_fences("```json\n{\"a\": 1}"), "{\"a\": 1}");
+    }
+
+    #[test]
+    fn keeps_only_the_fenced_block_when_prose_surrounds_it() {
+        let reply = "Here is the query:\n```sql\nSELECT 1\n```\nIt counts rows.";
+        assert_eq!(strip_markdown_fences(reply), "SELECT 1");
+    }
+
+    #[test]
+    fn unfenced_text_is_only_trimmed() {
+        assert_eq!(strip_markdown_fences("  SELECT 1\n"), "SELECT 1");
+    }
+
+    #[cfg(feature = "gemini")]
+    #[test]
+    fn fence_lines_start_with_backticks() {
+        assert!(super::is_fence_line("  ```sql"));
+        assert!(!super::is_fence_line("SELECT '```'"));
+    }
+}
@@ -1,22 +1,38 @@
 #[cfg(feature = "gemini")]
+mod cancel;
+#[cfg(feature = "gemini")]
 mod cleaner;
 #[cfg(feature = "gemini")]
 mod client;
+mod fallback;
+mod fences;
 #[cfg(feature = "gemini")]
 mod prompts;
 
 #[cfg(feature = "gemini")]
-pub use cleaner::{clean_raw_staging, clean_table, CleanResult};
+pub use cancel::{cancellable, CancelToken};
+#[cfg(feature = "gemini")]
+pub use cleaner::{
+    clean_raw_staging, clean_table, clean_table_with_options, CleanOptions, CleanResult,
+};
 #[cfg(feature = "gemini")]
-pub use client::{GeminiClient, DEFAULT_MODEL};
+pub use client::{
+    default_safety_settings, GeminiClient, SafetySetting, DEFAULT_MAX_RESPONSE_BYTES,
+    DEFAULT_MODEL, KNOWN_MODELS, MAX_RESPONSE_BYTES_ENV,
+};
+pub use fallback::build_fallback_analysis_sql;
+pub use fences::strip_markdown_fences;
 #[cfg(feature = "gemini")]
 pub use prompts::{
     build_analysis_chat_system_prompt, build_analysis_chat_system_prompt_with_domain,
+    build_analysis_chat_system_prompt_with_samples,
     build_analysis_retry_prompt, build_analysis_retry_prompt_with_domain,
     build_analysis_retry_prompt_with_samples, build_analysis_sql_prompt,
+    build_analysis_sql_prompt_focused, build_analysis_sql_prompt_multi,
     build_analysis_sql_prompt_with_domain, build_clean_prompt, build_unified_chat_prompt,
     build_unified_chat_prompt_with_domain, build_unified_chat_prompt_with_samples,
-    build_visualization_command_prompt, ColumnSamples,
+    build_visualization_command_prompt, select_analysis_columns, ColumnSamples,
+    ANALYSIS_PROMPT_MAX_COLUMNS,
 };
 
 /// Shared result type for the AI crate.
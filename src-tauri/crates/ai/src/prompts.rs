@@ -5,6 +5,20 @@ use std::collections::HashMap;
 /// Type alias for column sample values: column_name → list of distinct values.
 pub type ColumnSamples = HashMap<String, Vec<String>>;
 
+/// Most sample values listed per column in the analysis chat prompt.
+const CHAT_SAMPLE_VALUES_PER_COLUMN: usize = 10;
+/// Longest sample value quoted in the analysis chat prompt before truncation.
+const CHAT_SAMPLE_VALUE_MAX_CHARS: usize = 60;
+
+/// Widest table whose full schema goes into the focused analysis SQL prompt.
+pub const ANALYSIS_PROMPT_MAX_COLUMNS: usize = 30;
+
+/// Columns always kept in a focused analysis SQL prompt: they identify and
+/// place rows on the map.
+const KEY_COLUMN_NAMES: &[&str] = &[
+    "id", "geometry", "geom", "lat", "lon", "lng", "latitude", "longitude", "_lat", "_lon",
+];
+
 /// Format the schema section with optional sample values for each column.
 fn format_schema_with_samples(
     table_schemas: &[(String, Vec<TableColumn>)],
@@ -305,14 +319,39 @@ pub fn build_analysis_chat_system_prompt_with_domain(
     table_name: &str,
     schema: &[TableColumn],
     domain_context: Option<&str>,
+) -> String {
+    build_analysis_chat_system_prompt_with_samples(table_name, schema, None, domain_context)
+}
+
+/// Analysis chat system prompt with optional distinct values per column, so
+/// the model can answer questions about specific values ("which cities
+/// exist?"). At most `CHAT_SAMPLE_VALUES_PER_COLUMN` values are listed per
+/// column, each cut to `CHAT_SAMPLE_VALUE_MAX_CHARS` characters, to keep the
+/// prompt within token limits.
+pub fn build_analysis_chat_system_prompt_with_samples(
+    table_name: &str,
+    schema: &[TableColumn],
+    column_samples: Option<&ColumnSamples>,
+    domain_context: Option<&str>,
 ) -> String {
     let schema_lines: Vec<String> = schema
         .iter()
         .map(|col| {
-            format!(
+            let mut line = format!(
                 "  - \"{}\" {} (not_null: {}, primary_key: {})",
                 col.name, col.data_type, col.notnull, col.primary_key
-            )
+            );
+            if let Some(samples) = column_samples.and_then(|s| s.get(&col.name)) {
+                if !samples.is_empty() {
+                    let quoted: Vec<String> = samples
+                        .iter()
+                        .take(CHAT_SAMPLE_VALUES_PER_COLUMN)
+                        .map(|v| format!("\"{}\"", truncate_chat_sample(v)))
+                        .collect();
+                    line.push_str(&format!(" — sample values: {}", quoted.join(", ")));
+                }
+            }
+            line
         })
         .collect();
 
@@ -344,6 +383,13 @@ You are helping the user analyze geospatial data in DuckDB.
     )
 }
 
+fn truncate_chat_sample(value: &str) -> String {
+    match value.char_indices().nth(CHAT_SAMPLE_VALUE_MAX_CHARS) {
+        Some((idx, _)) => format!("{}…", &value[..idx]),
+        None => value.to_string(),
+    }
+}
+
 /// Build a prompt that asks the model for a single DuckDB SQL statement that
 /// creates or replaces an `analysis_result` view from an input table schema.
 pub fn build_analysis_sql_prompt(
@@ -361,7 +407,112 @@ pub fn build_analysis_sql_prompt_with_domain(
     user_goal: &str,
     domain_context: Option<&str>,
 ) -> String {
-    let schema_lines: Vec<String> = schema
+    analysis_sql_prompt(table_name, schema, 0, user_goal, domain_context)
+}
+
+/// Analysis SQL prompt for wide tables: only the columns picked by
+/// [`select_analysis_columns`] are listed, and the model is told to select
+/// the ones it needs instead of `SELECT *`. Tables of up to
+/// [`ANALYSIS_PROMPT_MAX_COLUMNS`] columns with no hint get the same prompt as
+/// [`build_analysis_sql_prompt_with_domain`].
+pub fn build_analysis_sql_prompt_focused(
+    table_name: &str,
+    schema: &[TableColumn],
+    user_goal: &str,
+    relevant_columns: &[String],
+    domain_context: Option<&str>,
+) -> String {
+    let columns = select_analysis_columns(schema, user_goal, relevant_columns);
+    let omitted = schema.len() - columns.len();
+    analysis_sql_prompt(table_name, &columns, omitted, user_goal, domain_context)
+}
+
+/// Columns of `schema` worth showing the model for `user_goal`, in schema
+/// order.
+///
+/// With a `relevant_columns` hint (matched case-insensitively, unknown names
+/// ignored) the result is the hinted columns plus the key columns (primary
+/// key, id, geometry and coordinates); a hint that names no column of the
+/// table gives the full schema. Without one, tables of up to
+/// [`ANALYSIS_PROMPT_MAX_COLUMNS`] columns are kept whole; wider ones keep the
+/// key columns, any column named in the goal, and then the leading columns
+/// until the limit is reached.
+pub fn select_analysis_columns(
+    schema: &[TableColumn],
+    user_goal: &str,
+    relevant_columns: &[String],
+) -> Vec<TableColumn> {
+    if relevant_columns.is_empty() && schema.len() <= ANALYSIS_PROMPT_MAX_COLUMNS {
+        return schema.to_vec();
+    }
+    let is_hinted = |col: &TableColumn| {
+        relevant_columns
+            .iter()
+            .any(|hint| hint.trim().eq_ignore_ascii_case(&col.name))
+    };
+    if !relevant_columns.is_empty() && !schema.iter().any(is_hinted) {
+        return schema.to_vec();
+    }
+
+    let is_key = |col: &TableColumn| {
+        col.primary_key || KEY_COLUMN_NAMES.contains(&col.name.to_ascii_lowercase().as_str())
+    };
+    let mut keep: Vec<bool> = if relevant_columns.is_empty() {
+        let goal = user_goal.to_lowercase();
+        let goal_words: Vec<&str> = goal
+            .split(|c: char| !c.is_alphanumeric())
+            .filter(|word| word.len() >= 3)
+            .collect();
+        schema
+            .iter()
+            .map(|col| {
+                let name = col.name.to_lowercase();
+                is_key(col)
+                    || goal.contains(&name)
+                    || name
+                        .split(|c: char| !c.is_alphanumeric())
+                        .any(|part| part.len() >= 3 && goal_words.contains(&part))
+            })
+            .collect()
+    } else {
+        schema
+            .iter()
+            .map(|col| is_key(col) || is_hinted(col))
+            .collect()
+    };
+
+    if relevant_columns.is_empty() {
+        let kept = keep.iter().filter(|kept| **kept).count();
+        let mut room = ANALYSIS_PROMPT_MAX_COLUMNS.saturating_sub(kept);
+        for kept in keep.iter_mut() {
+            if room == 0 {
+                break;
+            }
+            if !*kept {
+                *kept = true;
+                room -= 1;
+            }
+        }
+    }
+
+    schema
+        .iter()
+        .zip(keep)
+        .filter(|(_, kept)| *kept)
+        .map(|(col, _)| col.clone())
+        .collect()
+}
+
+/// Single-table analysis SQL prompt listing `columns`; `omitted` is how many
+/// other columns the table has, which adds a note and a no-`SELECT *` rule.
+fn analysis_sql_prompt(
+    table_name: &str,
+    columns: &[TableColumn],
+    omitted: usize,
+    user_goal: &str,
+    domain_context: Option<&str>,
+) -> String {
+    let mut schema_lines: Vec<String> = columns
         .iter()
         .map(|col| {
             format!(
@@ -370,6 +521,15 @@ pub fn build_analysis_sql_prompt_with_domain(
             )
         })
         .collect();
+    let mut extra_requirements = String::new();
+    if omitted > 0 {
+        schema_lines.push(format!(
+            "  ({omitted} more columns not relevant to this goal are not listed)"
+        ));
+        extra_requirements.push_str(
+            "8. Select only the listed columns the goal needs; never use SELECT *.\n",
+        );
+    }
 
     let domain_section = match domain_context {
         Some(ctx) if !ctx.is_empty() => format!("\n{}\n", ctx),
@@ -396,11 +556,58 @@ pub fn build_analysis_sql_prompt_with_domain(
 5. Do not include markdown, comments, or explanation text.
 6. DO NOT use H3 functions (h3_latlng_to_cell, h3_cell_to_latlng, etc.) or ST_HexagonGrid — they do not exist in DuckDB.
 7. For heatmap/hexbin visualizations, just SELECT rows with lat/lon — the frontend handles spatial aggregation.
-"#,
+{extra}"#,
         domain = domain_section,
         table = table_name,
         schema = schema_lines.join("\n"),
         goal = user_goal.trim(),
+        extra = extra_requirements,
+    )
+}
+
+/// Analysis SQL prompt covering several input tables, so the view can JOIN
+/// across them. Each schema is listed under its own `### Table:` heading.
+pub fn build_analysis_sql_prompt_multi(
+    table_schemas: &[(String, Vec<TableColumn>)],
+    user_goal: &str,
+    domain_context: Option<&str>,
+) -> String {
+    let schema_section = format_schema_with_samples(table_schemas, None);
+    let table_list: Vec<String> = table_schemas
+        .iter()
+        .map(|(name, _)| format!("\"{}\"", name))
+        .collect();
+
+    let domain_section = match domain_context {
+        Some(ctx) if !ctx.is_empty() => format!("\n{}\n", ctx),
+        _ => String::new(),
+    };
+
+    format!(
+        r#"You are Spatia's DuckDB analysis SQL assistant.
+{domain}
+## Input tables
+{tables}
+
+## Current schemas
+{schemas}
+
+## User goal
+{goal}
+
+## Requirements
+1. Return exactly one DuckDB SQL statement.
+2. The SQL must be `CREATE OR REPLACE VIEW analysis_result AS ...`.
+3. Use only the tables and columns listed above. JOIN across tables when the goal needs data from more than one.
+4. ALWAYS double-quote every table and column name, and qualify columns with their table name or alias (e.g. SELECT "p"."city" FROM "policies" AS "p"). Unqualified names are ambiguous when tables share a column.
+5. Do not include markdown, comments, or explanation text.
+6. DO NOT use H3 functions (h3_latlng_to_cell, h3_cell_to_latlng, etc.) or ST_HexagonGrid — they do not exist in DuckDB.
+7. For heatmap/hexbin visualizations, just SELECT rows with lat/lon — the frontend handles spatial aggregation.
+"#,
+        domain = domain_section,
+        tables = table_list.join(", "),
+        schemas = schema_section,
+        goal = user_goal.trim(),
     )
 }
 
@@ -588,8 +795,11 @@ Only include map_actions when relevant. sql can be empty string if no query is n
 #[cfg(test)]
 mod tests {
     use super::{
-        build_analysis_chat_system_prompt, build_analysis_retry_prompt, build_analysis_sql_prompt,
-        build_clean_prompt, build_clean_retry_prompt, build_visualization_command_prompt,
+        build_analysis_chat_system_prompt, build_analysis_chat_system_prompt_with_samples,
+        build_analysis_retry_prompt, build_analysis_sql_prompt,
+        build_analysis_sql_prompt_focused, build_analysis_sql_prompt_multi, build_clean_prompt,
+        build_clean_retry_prompt, build_visualization_command_prompt, select_analysis_columns,
+        ColumnSamples,
     };
     use spatia_engine::TableColumn;
 
@@ -650,6 +860,21 @@ mod tests {
         assert!(prompt.contains("city"));
     }
 
+    #[test]
+    fn analysis_chat_prompt_lists_capped_sample_values() {
+        let mut samples = ColumnSamples::new();
+        let cities: Vec<String> = (0..15).map(|i| format!("City {i:02}")).collect();
+        samples.insert("city".to_string(), cities);
+        let prompt =
+            build_analysis_chat_system_prompt_with_samples("places", &sample_schema(), Some(&samples), None);
+        assert!(prompt.contains("\"city\" VARCHAR (not_null: false, primary_key: false) — sample values: \"City 00\""));
+        assert!(prompt.contains("\"City 09\""));
+        assert!(!prompt.contains("City 10"));
+
+        let schema_only = build_analysis_chat_system_prompt("places", &sample_schema());
+        assert!(!schema_only.contains("sample values"));
+    }
+
     #[test]
     fn analysis_sql_prompt_requires_analysis_result_view() {
         let prompt = build_analysis_sql_prompt(
@@ -662,6 +887,79 @@ mod tests {
         assert!(prompt.contains("find top cities by record count"));
     }
 
+    fn wide_schema(columns: usize) -> Vec<TableColumn> {
+        (0..columns)
+            .map(|i| TableColumn {
+                cid: i as i64,
+                name: match i {
+                    0 => "id".to_string(),
+                    1 => "geometry".to_string(),
+                    70 => "confidence".to_string(),
+                    _ => format!("extra_{i}"),
+                },
+                data_type: "VARCHAR".to_string(),
+                notnull: false,
+                default_value: None,
+                primary_key: false,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn focused_analysis_sql_prompt_keeps_small_tables_whole() {
+        let full = build_analysis_sql_prompt("places", &sample_schema(), "count by city");
+        let focused = build_analysis_sql_prompt_focused(
+            "places",
+            &sample_schema(),
+            "count by city",
+            &[],
+            None,
+        );
+        assert_eq!(focused, full);
+    }
+
+    #[test]
+    fn focused_analysis_sql_prompt_trims_wide_tables() {
+        let schema = wide_schema(120);
+        let prompt = build_analysis_sql_prompt_focused(
+            "overture_places_place",
+            &schema,
+            "places with high confidence",
+            &[],
+            None,
+        );
+        assert!(prompt.contains("\"confidence\""));
+        assert!(prompt.contains("\"geometry\""));
+        assert!(!prompt.contains("\"extra_100\""));
+        assert!(prompt.contains("90 more columns"));
+        assert!(prompt.contains("never use SELECT *"));
+
+        let columns = select_analysis_columns(&schema, "anything", &["EXTRA_5".to_string()]);
+        let names: Vec<&str> = columns.iter().map(|col| col.name.as_str()).collect();
+        assert_eq!(names, vec!["id", "geometry", "extra_5"]);
+    }
+
+    #[test]
+    fn unmatched_column_hint_falls_back_to_the_full_schema() {
+        let schema = wide_schema(120);
+        let columns = select_analysis_columns(&schema, "anything", &["no_such".to_string()]);
+        assert_eq!(columns.len(), 120);
+    }
+
+    #[test]
+    fn analysis_sql_prompt_multi_includes_each_table_schema() {
+        let tables = vec![
+            ("policies".to_string(), sample_schema()),
+            ("overture_places_place".to_string(), sample_schema()),
+        ];
+        let prompt = build_analysis_sql_prompt_multi(&tables, "join policies to places", None);
+        assert!(prompt.contains("\"policies\", \"overture_places_place\""));
+        assert!(prompt.contains("### Table: policies"));
+        assert!(prompt.contains("### Table: overture_places_place"));
+        assert!(prompt.contains("CREATE OR REPLACE VIEW analysis_result AS"));
+        assert!(prompt.contains("JOIN"));
+    }
+
     #[test]
     fn visualization_prompt_requires_json_shape() {
         let prompt = build_visualization_command_prompt("analysis_result", "show hotspots");
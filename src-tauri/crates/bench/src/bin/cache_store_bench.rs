@@ -0,0 +1,104 @@
+//! Benchmark for geocode cache writes: one `INSERT OR REPLACE` per row (the
+//! old loop) against `cache_store`'s chunked multi-row upsert in a single
+//! transaction.
+//!
+//! Usage:
+//!   cargo run --release -p spatia_bench --bin cache_store_bench
+//!   cargo run --release -p spatia_bench --bin cache_store_bench -- --rows 50000 --runs 5
+
+use std::time::Instant;
+
+use clap::Parser;
+use duckdb::Connection;
+use spatia_geocode::{cache_store, ensure_cache_table, GeocodeResult};
+
+#[derive(Parser, Debug)]
+#[command(
+    name = "cache_store_bench",
+    about = "Compare per-row cache upserts with cache_store's batched upsert"
+)]
+struct Cli {
+    /// Number of cache rows written per run
+    #[arg(long, default_value_t = 10_000)]
+    rows: usize,
+
+    /// Runs per strategy; the median is reported
+    #[arg(long, default_value_t = 3)]
+    runs: usize,
+}
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let cli = Cli::parse();
+    let runs = cli.runs.max(1);
+    let records: Vec<GeocodeResult> = (0..cli.rows)
+        .map(|i| GeocodeResult {
+            address: format!("{i} Bench St, Springfield, IL"),
+            lat: 39.0 + (i % 1000) as f64 / 1000.0,
+            lon: -89.0 - (i % 1000) as f64 / 1000.0,
+            source: "geocodio".to_string(),
+        })
+        .collect();
+
+    println!("cache_store_bench: {} rows, {} runs each", cli.rows, runs);
+    println!();
+
+    let mut per_row = Vec::with_capacity(runs);
+    let mut batched = Vec::with_capacity(runs);
+    for run in 0..runs {
+        let db_path = temp_db_path(&format!("per_row_{run}"));
+        let conn = Connection::open(&db_path)?;
+        ensure_cache_table(&conn)?;
+        let start = Instant::now();
+        let mut stmt = conn.prepare(
+            "INSERT OR REPLACE INTO geocode_cache (address, lat, lon, source, cached_at) \
+             VALUES (?, ?, ?, ?, current_timestamp)",
+        )?;
+        for record in &records {
+            stmt.execute(duckdb::params![record.address, record.lat, record.lon, "geocodio"])?;
+        }
+        per_row.push(start.elapsed().as_millis() as u64);
+        drop(stmt);
+        drop(conn);
+        cleanup_db(&db_path);
+
+        let db_path = temp_db_path(&format!("batched_{run}"));
+        let mut conn = Connection::open(&db_path)?;
+        ensure_cache_table(&conn)?;
+        let start = Instant::now();
+        cache_store(&mut conn, &records, "geocodio")?;
+        batched.push(start.elapsed().as_millis() as u64);
+        drop(conn);
+        cleanup_db(&db_path);
+    }
+
+    let per_row_ms = median(&mut per_row);
+    let batched_ms = median(&mut batched);
+    println!("  {:12} {:>10}", "Strategy", "Median");
+    println!("  {}", "-".repeat(23));
+    println!("  {:12} {:>7} ms", "per-row", per_row_ms);
+    println!("  {:12} {:>7} ms", "cache_store", batched_ms);
+    if batched_ms > 0 {
+        println!();
+        println!("  Speedup: {:.1}x", per_row_ms as f64 / batched_ms as f64);
+    }
+    Ok(())
+}
+
+fn median(samples: &mut [u64]) -> u64 {
+    samples.sort_unstable();
+    samples[samples.len() / 2]
+}
+
+fn temp_db_path(suffix: &str) -> String {
+    let ns = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_nanos();
+    format!("/tmp/spatia_cache_store_bench_{}_{}.duckdb", suffix, ns)
+}
+
+fn cleanup_db(path: &str) {
+    let _ = std::fs::remove_file(path);
+    let _ = std::fs::remove_file(format!("{path}.wal"));
+    let _ = std::fs::remove_file(format!("{path}.wal.lck"));
+}
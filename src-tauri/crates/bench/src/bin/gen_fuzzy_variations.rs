@@ -164,7 +164,7 @@ async fn call_gemini(
         api_key
     );
 
-    let client = reqwest::Client::new();
+    let client = spatia_geocode::http_client_builder().build()?;
     let body = serde_json::json!({
         "contents": [{
             "parts": [{"text": prompt}]
@@ -0,0 +1,125 @@
+//! Benchmark for the CSV ingest cut-over: the single `CREATE TABLE AS
+//! SELECT * FROM read_csv_auto(...)` load against the streamed appender load
+//! that `ingest_csv_with_row_progress` switches to at 256 MiB.
+//!
+//! Usage:
+//!   cargo run --release -p spatia_bench --bin ingest_cutover_bench
+//!   cargo run --release -p spatia_bench --bin ingest_cutover_bench -- --sizes-mib 128,256,512 --runs 5
+
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::time::Instant;
+
+use clap::Parser;
+use spatia_engine::{ingest_csv_streamed, ingest_csv_to_table_with_types};
+
+#[derive(Parser, Debug)]
+#[command(
+    name = "ingest_cutover_bench",
+    about = "Compare single-statement and streamed CSV loads across file sizes"
+)]
+struct Cli {
+    /// CSV sizes to generate, in MiB (comma-separated)
+    #[arg(long, default_value = "32,64,128,256,512")]
+    sizes_mib: String,
+
+    /// Runs per strategy and size; the median is reported
+    #[arg(long, default_value_t = 3)]
+    runs: usize,
+}
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let cli = Cli::parse();
+    let runs = cli.runs.max(1);
+    let sizes = cli
+        .sizes_mib
+        .split(',')
+        .map(|size| size.trim().parse::<u64>())
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|err| format!("invalid --sizes-mib '{}': {err}", cli.sizes_mib))?;
+
+    println!("ingest_cutover_bench: {} runs each", runs);
+    println!();
+    println!(
+        "  {:>8} {:>10} {:>12} {:>12} {:>8}",
+        "MiB", "Rows", "CTAS", "Streamed", "Ratio"
+    );
+    println!("  {}", "-".repeat(54));
+
+    for size_mib in sizes {
+        let csv_path = temp_path(&format!("{size_mib}mib"), "csv");
+        let rows = write_csv(&csv_path, size_mib * 1024 * 1024)?;
+
+        let mut single = Vec::with_capacity(runs);
+        let mut streamed = Vec::with_capacity(runs);
+        for _ in 0..runs {
+            let db_path = temp_path("ctas", "duckdb");
+            let start = Instant::now();
+            ingest_csv_to_table_with_types(&db_path, &csv_path, "places", &[])?;
+            single.push(start.elapsed().as_millis() as u64);
+            cleanup_db(&db_path);
+
+            let db_path = temp_path("streamed", "duckdb");
+            let start = Instant::now();
+            ingest_csv_streamed(&db_path, &csv_path, Some("places"), &[], |_| {})?;
+            streamed.push(start.elapsed().as_millis() as u64);
+            cleanup_db(&db_path);
+        }
+        let _ = std::fs::remove_file(&csv_path);
+
+        let single_ms = median(&mut single);
+        let streamed_ms = median(&mut streamed);
+        println!(
+            "  {:>8} {:>10} {:>9} ms {:>9} ms {:>7.2}x",
+            size_mib,
+            rows,
+            single_ms,
+            streamed_ms,
+            streamed_ms as f64 / single_ms.max(1) as f64
+        );
+    }
+    Ok(())
+}
+
+/// Write synthetic place rows until the file reaches `target_bytes`.
+/// Returns the number of data rows written.
+fn write_csv(path: &str, target_bytes: u64) -> std::io::Result<u64> {
+    let mut out = BufWriter::new(File::create(path)?);
+    let header = "id,name,address,lat,lon,zip\n";
+    out.write_all(header.as_bytes())?;
+    let mut written = header.len() as u64;
+    let mut rows = 0u64;
+    while written < target_bytes {
+        let line = format!(
+            "{rows},Place {rows},{} Bench St Springfield IL,{:.6},{:.6},{:05}\n",
+            rows % 10_000,
+            39.0 + (rows % 1000) as f64 / 1000.0,
+            -89.0 - (rows % 1000) as f64 / 1000.0,
+            rows % 100_000
+        );
+        out.write_all(line.as_bytes())?;
+        written += line.len() as u64;
+        rows += 1;
+    }
+    out.flush()?;
+    Ok(rows)
+}
+
+fn median(samples: &mut [u64]) -> u64 {
+    samples.sort_unstable();
+    samples[samples.len() / 2]
+}
+
+fn temp_path(suffix: &str, ext: &str) -> String {
+    let ns = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_nanos();
+    format!("/tmp/spatia_ingest_cutover_bench_{}_{}.{}", suffix, ns, ext)
+}
+
+fn cleanup_db(path: &str) {
+    let _ = std::fs::remove_file(path);
+    let _ = std::fs::remove_file(format!("{path}.wal"));
+    let _ = std::fs::remove_file(format!("{path}.wal.lck"));
+}
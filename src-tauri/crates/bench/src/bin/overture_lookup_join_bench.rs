@@ -0,0 +1,133 @@
+//! Benchmark for the Overture lookup join: `overture_geocode_page` and
+//! `overture_search_page --coords` on a synthetic extract, before and after
+//! `overture_index_extract` adds the ART index on `id`.
+//!
+//! Usage:
+//!   cargo run --release -p spatia_bench --bin overture_lookup_join_bench
+//!   cargo run --release -p spatia_bench --bin overture_lookup_join_bench -- --rows 5000000 --runs 5
+
+use std::time::Instant;
+
+use clap::Parser;
+use duckdb::Connection;
+use spatia_engine::{
+    load_extension, overture_geocode_page, overture_index_extract, overture_search_page,
+    SearchWeights,
+};
+
+#[derive(Parser, Debug)]
+#[command(
+    name = "overture_lookup_join_bench",
+    about = "Time Overture geocode and coordinate search with and without the id index"
+)]
+struct Cli {
+    /// Rows in the synthetic places extract
+    #[arg(long, default_value_t = 1_000_000)]
+    rows: usize,
+
+    /// Runs per query; the median is reported
+    #[arg(long, default_value_t = 3)]
+    runs: usize,
+
+    /// Search text; the default matches a few hundred labels
+    #[arg(long, default_value = "market 4242")]
+    query: String,
+
+    /// Result limit passed to both queries
+    #[arg(long, default_value_t = 20)]
+    limit: usize,
+}
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let cli = Cli::parse();
+    let runs = cli.runs.max(1);
+    let db_path = temp_db_path();
+    seed_extract(&db_path, cli.rows)?;
+
+    println!(
+        "overture_lookup_join_bench: {} rows, query '{}', limit {}, {} runs each",
+        cli.rows, cli.query, cli.limit, runs
+    );
+    println!();
+    println!("  {:10} {:>12} {:>12}", "Index", "Geocode", "Search+xy");
+    println!("  {}", "-".repeat(36));
+
+    let before = time_queries(&db_path, &cli.query, cli.limit, runs)?;
+    println!("  {:10} {:>9} ms {:>9} ms", "none", before.0, before.1);
+
+    let indexes = overture_index_extract(&db_path, "places")?;
+    let after = time_queries(&db_path, &cli.query, cli.limit, runs)?;
+    println!("  {:10} {:>9} ms {:>9} ms", "id", after.0, after.1);
+    println!();
+    println!("  Indexes: {}", indexes.join(", "));
+
+    cleanup_db(&db_path);
+    Ok(())
+}
+
+/// Median milliseconds of the geocode page and the coordinate search.
+fn time_queries(
+    db_path: &str,
+    query: &str,
+    limit: usize,
+    runs: usize,
+) -> Result<(u64, u64), Box<dyn std::error::Error>> {
+    let mut geocode = Vec::with_capacity(runs);
+    let mut search = Vec::with_capacity(runs);
+    for _ in 0..runs {
+        let start = Instant::now();
+        overture_geocode_page(db_path, "places", query, limit)?;
+        geocode.push(start.elapsed().as_millis() as u64);
+
+        let start = Instant::now();
+        overture_search_page(
+            db_path,
+            "places",
+            query,
+            limit,
+            false,
+            true,
+            SearchWeights::default(),
+        )?;
+        search.push(start.elapsed().as_millis() as u64);
+    }
+    Ok((median(&mut geocode), median(&mut search)))
+}
+
+/// An extract shaped like the places theme (VARCHAR GERS-style ids, point
+/// geometry, `names`) and its `_lookup` table.
+fn seed_extract(db_path: &str, rows: usize) -> Result<(), Box<dyn std::error::Error>> {
+    let conn = Connection::open(db_path)?;
+    load_extension(&conn, "spatial")?;
+    conn.execute_batch(&format!(
+        "CREATE TABLE places AS \
+         SELECT md5(CAST(i AS VARCHAR)) AS id, \
+                ST_Point(-122.5 + (i % 1000) / 2000.0, 47.4 + (i // 1000 % 1000) / 2000.0) \
+                    AS geometry, \
+                {{'primary': 'Market ' || CAST(i AS VARCHAR)}} AS names \
+         FROM range({rows}) r(i); \
+         CREATE TABLE places_lookup AS \
+         SELECT id AS source_id, names.primary AS label, lower(names.primary) AS label_norm \
+         FROM places;"
+    ))?;
+    Ok(())
+}
+
+fn median(samples: &mut [u64]) -> u64 {
+    samples.sort_unstable();
+    samples[samples.len() / 2]
+}
+
+fn temp_db_path() -> String {
+    let ns = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_nanos();
+    format!("/tmp/spatia_overture_lookup_join_bench_{}.duckdb", ns)
+}
+
+fn cleanup_db(path: &str) {
+    let _ = std::fs::remove_file(path);
+    let _ = std::fs::remove_file(format!("{path}.wal"));
+    let _ = std::fs::remove_file(format!("{path}.wal.lck"));
+}
@@ -70,7 +70,7 @@ pub fn run_test(
     // Setup phase
     let setup_start = Instant::now();
 
-    let conn = match Connection::open(&db_path) {
+    let mut conn = match Connection::open(&db_path) {
         Ok(c) => c,
         Err(e) => {
             timing.setup_ms = setup_start.elapsed().as_millis() as u64;
@@ -137,7 +137,7 @@ pub fn run_test(
                 source: s.source.clone(),
             })
             .collect();
-        if let Err(e) = cache_store(&conn, &records, "geocodio") {
+        if let Err(e) = cache_store(&mut conn, &records, "geocodio") {
             timing.setup_ms = setup_start.elapsed().as_millis() as u64;
             timing.total_ms = test_start.elapsed().as_millis() as u64;
             cleanup_db(&db_path);
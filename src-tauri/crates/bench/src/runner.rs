@@ -239,7 +239,7 @@ async fn ai_sql_loop(
         let sql = if attempt == 0 {
             extract_sql_from_json(&raw_response)
         } else {
-            Some(strip_markdown_fences(&raw_response))
+            Some(spatia_ai::strip_markdown_fences(&raw_response))
         };
 
         let sql = match sql {
@@ -258,7 +258,7 @@ async fn ai_sql_loop(
 
         // Execute SQL
         let sql_start = Instant::now();
-        let exec_result = execute_analysis_sql_to_geojson(db_path, &sql);
+        let exec_result = execute_analysis_sql_to_geojson(db_path, &sql, None);
         detail.timing.sql_ms += sql_start.elapsed().as_millis() as u64;
 
         match exec_result {
@@ -296,18 +296,6 @@ fn extract_sql_from_json(raw: &str) -> Option<String> {
     }
 }
 
-fn strip_markdown_fences(text: &str) -> String {
-    let trimmed = text.trim();
-    let without_start = trimmed
-        .strip_prefix("```sql")
-        .or_else(|| trimmed.strip_prefix("```"))
-        .unwrap_or(trimmed);
-    let without_end = without_start
-        .strip_suffix("```")
-        .unwrap_or(without_start);
-    without_end.trim().to_string()
-}
-
 fn run_assertions(
     tc: &TestCase,
     sql: &str,
@@ -9,25 +9,120 @@ pub fn print_help() {
     println!("spatia_cli - string-command interface");
     println!();
     println!("usage:");
-    println!("  spatia_cli ingest <db_path> <csv_path> [table_name]");
-    println!("  spatia_cli schema <db_path> <table_name>");
-    println!("  spatia_cli overture_extract <db_path> <theme> <type> <xmin,ymin,xmax,ymax> [table_name]");
-    println!("  spatia_cli overture_search <db_path> <table_name> <query> [limit]");
-    println!("  spatia_cli overture_geocode <db_path> <addresses_table> <query> [limit]");
-    println!("  spatia_cli geocode <db_path> <address> [address2...]");
+    println!("  spatia_cli ingest <db_path> <csv_path> [table_name] [--type column:TYPE ...] [--wkt column] [--text-fallback] [--skip-unchanged]");
+    println!("  spatia_cli schema <db_path> <table_name> [--columns col1,col2,...] [--samples]");
+    println!("  spatia_cli list_tables <db_path> [--schema <name>]");
+    println!("  spatia_cli list_views <db_path>");
+    println!("  spatia_cli drop_view <db_path> <view_name>");
+    println!("  spatia_cli table_preview <db_path> <table_name> [n]");
+    println!("  spatia_cli profile_column <db_path> <table_name> <column>");
+    println!("  spatia_cli clean_columns <db_path> <table_name> <column=op[,op...][;column=...]>");
+    println!("  spatia_cli spatial_join <db_path> <points_table> <polygons_table> <output_table>");
+    println!("  spatia_cli overture_extract <db_path> <theme> <type> <xmin,ymin,xmax,ymax> [table_name] [--require names|geometry] [--level country|region|county|locality] [--skip-if-current] [--index] [--force] [--schema <name>] [--since YYYY-MM-DD] [--projected] [--explain]");
+    println!("  spatia_cli overture_themes");
+    println!("  spatia_cli overture_search <db_path> <table_name> <query> [limit] [--geojson] [--highlight] [--coords] [--ranking <preset>] [--into <table>]");
+    println!("  spatia_cli overture_geocode <db_path> <addresses_table> <query> [limit] [--geojson] [--into <table>] [--precision <digits>]");
+    println!("  spatia_cli overture_query <theme> <type> <xmin,ymin,xmax,ymax> <query> [limit] [--projected]");
+    println!("  spatia_cli overture_rebuild_lookup <db_path> <table_name> <theme>");
+    println!("  spatia_cli geocode <db_path> <address> [address2...] [--summary] [--dedupe] [--normalize] [--unresolved-only] [--precision <digits>] [--providers <mode>]");
+    println!("  spatia_cli geocode <db_path> --input-file <addresses.txt> [--output-file <out.json>]");
+    println!("  spatia_cli reverse_geocode <db_path> <lat,lon> [lat,lon...]");
+    println!("  spatia_cli geocode_cache_export <db_path> <out.csv|out.json>");
+    println!("  spatia_cli geocode_cache_import <db_path> <in.csv|in.json>");
+    println!("  spatia_cli geocode_cache_refresh <db_path> [--max-age-days <days>] [--providers <mode>]");
+    println!("  spatia_cli db_info <db_path>");
+    println!("  spatia_cli json_schema <db_path> <table_name>");
+    println!("  spatia_cli --script <path> [--keep-going]");
+    println!("  spatia_cli repl <db_path>");
     println!("  spatia_cli help");
     println!();
+    println!("options:");
+    println!("  --quiet    Suppress the result body; only the exit code reports success");
+    println!("  --pretty   Indent JSON output (plain-text output is printed as is)");
+    println!("  --ndjson   One JSON value per line; arrays print one element per line");
+    println!("  --output json|table  json (default) or an aligned table for arrays of objects");
+    println!("  --no-truncate        With --output table, print long cells in full");
+    println!();
+    println!("exit codes:");
+    println!("  0  success");
+    println!("  1  other error");
+    println!("  2  command parse error (unknown command, bad usage, bbox or limit)");
+    println!("  3  not found (missing file, table or column)");
+    println!("  4  DuckDB error");
+    println!();
     println!("examples:");
     println!("  spatia_cli ingest ./spatia.duckdb ./data/sample.csv");
     println!("  spatia_cli ingest ./spatia.duckdb ./data/sample.csv places");
+    println!("  spatia_cli ingest ./spatia.duckdb ./data/policies.csv policies --type zip:VARCHAR");
+    println!("  spatia_cli ingest ./spatia.duckdb ./data/parcels.csv parcels --wkt geom_wkt");
+    println!("  spatia_cli ingest ./spatia.duckdb ./data/claims.csv claims --text-fallback");
+    println!("  spatia_cli ingest ./spatia.duckdb ./data/sample.csv --skip-unchanged");
     println!("  spatia_cli schema ./spatia.duckdb raw_staging");
+    println!("  spatia_cli schema ./spatia.duckdb raw_staging --samples");
+    println!("  spatia_cli list_tables ./spatia.duckdb");
+    println!("  spatia_cli list_tables ./spatia.duckdb --schema overture");
+    println!("  spatia_cli drop_view ./spatia.duckdb analysis_result");
+    println!("  spatia_cli table_preview ./spatia.duckdb raw_staging 5");
+    println!("  spatia_cli profile_column ./spatia.duckdb raw_staging premium");
+    println!("  spatia_cli clean_columns ./spatia.duckdb raw_staging 'city=trim,null_if:N/A;state=upper'");
+    println!("  spatia_cli spatial_join ./spatia.duckdb stores counties stores_by_county");
+    println!("  spatia_cli schema ./spatia.duckdb places_wa --columns id,names,categories");
     println!("  spatia_cli overture_extract ./spatia.duckdb places place -122.4,47.5,-122.2,47.7 places_wa");
+    println!("  spatia_cli overture_extract ./spatia.duckdb places place -122.4,47.5,-122.2,47.7 --require names");
+    println!("  spatia_cli overture_extract ./spatia.duckdb divisions division_area -125,45,-116,49 --level region");
+    println!("  spatia_cli overture_extract ./spatia.duckdb places place -122.4,47.5,-122.2,47.7 --schema overture");
+    println!("  spatia_cli overture_extract ./spatia.duckdb places place -122.4,47.5,-122.2,47.7 places_wa --since 2026-01-01");
+    println!("  spatia_cli overture_themes");
     println!("  spatia_cli overture_search ./spatia.duckdb places_wa \"lincoln\" 10");
+    println!("  spatia_cli overture_search ./spatia.duckdb places_wa \"lincoln\" 10 --output table");
+    println!("  spatia_cli overture_search ./spatia.duckdb places_wa \"coffee\" 50 --into saved_coffee");
     println!("  spatia_cli overture_geocode ./spatia.duckdb addresses_ca \"321 n lincoln st redlands ca 92374\" 5");
+    println!("  spatia_cli overture_geocode ./spatia.duckdb addresses_ca \"lincoln st\" 50 --geojson > out.geojson");
+    println!("  spatia_cli overture_query places place -122.36,47.60,-122.33,47.62 \"pike place\" 5");
+    println!("  spatia_cli overture_rebuild_lookup ./spatia.duckdb addresses_ca addresses");
     println!("  spatia_cli geocode ./spatia.duckdb \"123 Main St, Springfield, IL\"");
+    println!("  spatia_cli geocode ./spatia.duckdb \"1 Main St\" \"2 Oak Ave\" --summary");
+    println!("  spatia_cli geocode ./spatia.duckdb --input-file addresses.txt --unresolved-only");
+    println!("  spatia_cli geocode ./spatia.duckdb --input-file addresses.txt --precision 6");
+    println!("  spatia_cli geocode ./spatia.duckdb --input-file addresses.txt --dedupe");
+    println!("  spatia_cli geocode ./spatia.duckdb \"123 N Main St, Seattle, WA\" --normalize");
+    println!("  spatia_cli geocode ./spatia.duckdb --input-file addresses.txt --output-file geocoded.json");
+    println!("  spatia_cli reverse_geocode ./spatia.duckdb 47.6205,-122.3493");
+    println!("  spatia_cli geocode_cache_export ./spatia.duckdb geocode_cache.csv");
+    println!("  spatia_cli geocode_cache_refresh ./spatia.duckdb --max-age-days 30");
+    println!("  spatia_cli db_info ./spatia.duckdb");
+    println!("  spatia_cli json_schema ./spatia.duckdb raw_staging > raw_staging.schema.json");
+    println!("  spatia_cli --script ./pipeline.txt --keep-going");
+    println!("  spatia_cli repl ./spatia.duckdb");
+    println!();
+    println!("script files:");
+    println!("  One command per line (without the spatia_cli prefix); blank lines and # comments are skipped.");
+    println!("  Output lines are prefixed with the script line number.");
+    println!();
+    println!("repl:");
+    println!("  Reads commands from stdin until EOF or `quit`, without spatia_cli or <db_path>");
+    println!("  (e.g. `schema raw_staging`). Errors are printed and the session continues.");
+    println!("  .tables lists tables and views; .schema <table> lists its columns and types.");
     println!();
     println!("geocode env vars:");
     println!("  SPATIA_GEOCODIO_API_KEY      Geocodio API key (required for cache misses)");
     println!("  SPATIA_GEOCODIO_BATCH_SIZE   Max addresses per Geocodio request (default 100, max 10000)");
     println!("  SPATIA_GEOCODIO_BASE_URL     Override Geocodio API base URL (default https://api.geocod.io)");
+    println!("  SPATIA_LOG                   Stderr log filter (default warn, e.g. debug)");
+    println!();
+    println!("ai env vars:");
+    println!("  SPATIA_GEMINI_API_KEY        Gemini API key for AI analysis and cleaning");
+    println!("  SPATIA_GEMINI_MAX_RESPONSE_BYTES Cap on a Gemini response body (default 4194304)");
+    println!();
+    println!("overture env vars:");
+    println!("  SPATIA_OVERTURE_RELEASE      Overture release to extract from");
+    println!("  SPATIA_OVERTURE_MAX_RESULTS  Cap on search/geocode limits (default 1000)");
+    println!();
+    println!("duckdb env vars:");
+    println!("  SPATIA_DUCKDB_MEMORY_LIMIT   DuckDB memory_limit, e.g. 4GB (default 80% of RAM)");
+    println!("  SPATIA_DUCKDB_THREADS        DuckDB worker threads (default: all cores)");
+    println!("  SPATIA_DUCKDB_EXTENSION_DIR  directory of preinstalled extensions (offline use)");
+    println!("  SPATIA_DUCKDB_TEMP_DIR       spill directory for queries over the memory limit");
+    println!("  SPATIA_DUCKDB_INIT_SQL       ;-separated SET/PRAGMA statements run on every connection");
+    println!("  These apply to every connection Spatia opens, including overture, ingest and geocode.");
 }
@@ -1 +1,5 @@
 pub mod help;
+pub mod output;
+pub mod repl;
+pub mod script;
+pub mod suggest;
@@ -0,0 +1,301 @@
+use serde_json::{Map, Value};
+use spatia_engine::CommandParseError;
+
+/// Cells wider than this are cut with an ellipsis unless `--no-truncate`
+/// is given.
+const MAX_CELL_WIDTH: usize = 40;
+
+/// How engine output is written to stdout.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OutputFormat {
+    /// The engine's output unchanged (compact single-line JSON).
+    Compact,
+    /// Indented JSON via `serde_json::to_string_pretty`.
+    Pretty,
+    /// One compact JSON value per line; top-level arrays are split into
+    /// their elements.
+    Ndjson,
+    /// Arrays of objects as an aligned text table with a header row.
+    Table { truncate: bool },
+}
+
+/// Remove `--pretty` / `--ndjson` / `--output json|table` / `--no-truncate`
+/// from `args` and return the requested format. Only one format may be
+/// chosen, and `--no-truncate` needs `--output table`.
+pub fn take_output_format(args: &mut Vec<String>) -> Result<OutputFormat, CommandParseError> {
+    let output = take_output_option(args)?;
+    let pretty = args.iter().any(|arg| arg == "--pretty");
+    let ndjson = args.iter().any(|arg| arg == "--ndjson");
+    let no_truncate = args.iter().any(|arg| arg == "--no-truncate");
+    args.retain(|arg| arg != "--pretty" && arg != "--ndjson" && arg != "--no-truncate");
+    let table = match output.as_deref() {
+        None | Some("json") => false,
+        Some("table") => true,
+        Some(other) => {
+            return Err(CommandParseError(format!(
+                "--output expects json or table, got '{other}'"
+            )))
+        }
+    };
+    if no_truncate && !table {
+        return Err(CommandParseError(
+            "--no-truncate only applies to --output table".to_string(),
+        ));
+    }
+    match (pretty, ndjson, table) {
+        (false, false, false) => Ok(OutputFormat::Compact),
+        (true, false, false) => Ok(OutputFormat::Pretty),
+        (false, true, false) => Ok(OutputFormat::Ndjson),
+        (false, false, true) => Ok(OutputFormat::Table {
+            truncate: !no_truncate,
+        }),
+        _ => Err(CommandParseError(
+            "--pretty, --ndjson and --output table cannot be combined".to_string(),
+        )),
+    }
+}
+
+/// Remove `--output <value>` from `args`, returning the value.
+fn take_output_option(args: &mut Vec<String>) -> Result<Option<String>, CommandParseError> {
+    let Some(index) = args.iter().position(|arg| arg == "--output") else {
+        return Ok(None);
+    };
+    if index + 1 >= args.len() {
+        return Err(CommandParseError("--output requires a value".to_string()));
+    }
+    let value = args.remove(index + 1);
+    args.remove(index);
+    Ok(Some(value.to_ascii_lowercase()))
+}
+
+/// Reformat `output` for `format`. Output that is not valid JSON (some
+/// commands return plain strings) is passed through unchanged.
+pub fn format_output(output: &str, format: OutputFormat) -> String {
+    if format == OutputFormat::Compact {
+        return output.to_string();
+    }
+    let Ok(value) = serde_json::from_str::<Value>(output) else {
+        return output.to_string();
+    };
+    match (format, value) {
+        (OutputFormat::Pretty, value) => {
+            serde_json::to_string_pretty(&value).unwrap_or_else(|_| output.to_string())
+        }
+        (OutputFormat::Ndjson, Value::Array(items)) => items
+            .iter()
+            .map(Value::to_string)
+            .collect::<Vec<_>>()
+            .join("\n"),
+        (OutputFormat::Table { truncate }, value) => match table_rows(&value) {
+            Some(rows) => render_table(&rows, truncate),
+            None => output.to_string(),
+        },
+        (_, value) => value.to_string(),
+    }
+}
+
+/// The rows of a tabular result: a top-level array of objects, or the
+/// `results` array of a paged search/geocode reply. `None` for anything
+/// else, which is printed raw.
+fn table_rows(value: &Value) -> Option<Vec<&Map<String, Value>>> {
+    let items = match value {
+        Value::Array(items) => items,
+        Value::Object(map) => match map.get("results") {
+            Some(Value::Array(items)) => items,
+            _ => return None,
+        },
+        _ => return None,
+    };
+    if items.is_empty() {
+        return None;
+    }
+    items.iter().map(Value::as_object).collect()
+}
+
+/// Render `rows` with one column per key, in first-seen order, padded to the
+/// widest cell.
+fn render_table(rows: &[&Map<String, Value>], truncate: bool) -> String {
+    let mut headers: Vec<&str> = Vec::new();
+    for row in rows {
+        for key in row.keys() {
+            if !headers.contains(&key.as_str()) {
+                headers.push(key);
+            }
+        }
+    }
+    let cells: Vec<Vec<String>> = rows
+        .iter()
+        .map(|row| {
+            headers
+                .iter()
+                .map(|header| cell_text(row.get(*header), truncate))
+                .collect()
+        })
+        .collect();
+    let widths: Vec<usize> = headers
+        .iter()
+        .enumerate()
+        .map(|(index, header)| {
+            cells
+                .iter()
+                .map(|row| row[index].chars().count())
+                .fold(header.chars().count(), usize::max)
+        })
+        .collect();
+
+    let mut lines = Vec::with_capacity(rows.len() + 2);
+    lines.push(format_row(headers.iter().copied(), &widths));
+    lines.push(
+        widths
+            .iter()
+            .map(|width| "-".repeat(*width))
+            .collect::<Vec<_>>()
+            .join("-+-"),
+    );
+    for row in &cells {
+        lines.push(format_row(row.iter().map(String::as_str), &widths));
+    }
+    lines.join("\n")
+}
+
+fn format_row<'a>(cells: impl Iterator<Item = &'a str>, widths: &[usize]) -> String {
+    let padded: Vec<String> = cells
+        .zip(widths)
+        .map(|(cell, &width)| format!("{cell:<width$}"))
+        .collect();
+    padded.join(" | ").trim_end().to_string()
+}
+
+/// Strings print without quotes, nulls and missing keys as blanks, and
+/// nested values as compact JSON. Line breaks become spaces so every row
+/// stays on one line.
+fn cell_text(value: Option<&Value>, truncate: bool) -> String {
+    let text = match value {
+        None | Some(Value::Null) => String::new(),
+        Some(Value::String(text)) => text.clone(),
+        Some(other) => other.to_string(),
+    };
+    let text = text.replace(['\n', '\r', '\t'], " ");
+    if !truncate || text.chars().count() <= MAX_CELL_WIDTH {
+        return text;
+    }
+    let mut cut: String = text.chars().take(MAX_CELL_WIDTH - 1).collect();
+    cut.push('…');
+    cut
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{cell_text, format_output, render_table, table_rows, take_output_format};
+    use super::{OutputFormat, MAX_CELL_WIDTH};
+    use serde_json::json;
+
+    fn args(list: &[&str]) -> Vec<String> {
+        list.iter().map(|arg| arg.to_string()).collect()
+    }
+
+    #[test]
+    fn output_flags_are_removed_and_parsed() {
+        let mut list = args(&["list_tables", "./db", "--output", "TABLE", "--no-truncate"]);
+        assert_eq!(
+            take_output_format(&mut list).expect("table"),
+            OutputFormat::Table { truncate: false }
+        );
+        assert_eq!(list, args(&["list_tables", "./db"]));
+
+        let mut list = args(&["schema", "--pretty", "./db", "t"]);
+        assert_eq!(
+            take_output_format(&mut list).expect("pretty"),
+            OutputFormat::Pretty
+        );
+        assert_eq!(list, args(&["schema", "./db", "t"]));
+
+        let mut list = args(&["schema", "./db", "t"]);
+        assert_eq!(
+            take_output_format(&mut list).expect("default"),
+            OutputFormat::Compact
+        );
+    }
+
+    #[test]
+    fn conflicting_or_incomplete_output_flags_are_rejected() {
+        for (list, message) in [
+            (args(&["x", "--pretty", "--ndjson"]), "cannot be combined"),
+            (
+                args(&["x", "--ndjson", "--output", "table"]),
+                "cannot be combined",
+            ),
+            (args(&["x", "--output"]), "--output requires a value"),
+            (
+                args(&["x", "--output", "csv"]),
+                "--output expects json or table, got 'csv'",
+            ),
+            (args(&["x", "--no-truncate"]), "--no-truncate only applies"),
+        ] {
+            let mut list = list;
+            let err = take_output_format(&mut list).expect_err(message);
+            assert!(err.0.contains(message), "{}", err.0);
+        }
+    }
+
+    #[test]
+    fn table_rows_come_from_arrays_or_paged_results() {
+        let array = json!([{"name": "a"}, {"name": "b"}]);
+        assert_eq!(table_rows(&array).expect("array").len(), 2);
+        let page = json!({"results": [{"id": "p1"}], "truncated": false});
+        assert_eq!(table_rows(&page).expect("page").len(), 1);
+
+        assert!(table_rows(&json!([])).is_none());
+        assert!(table_rows(&json!([1, 2])).is_none());
+        assert!(table_rows(&json!({"status": "ok"})).is_none());
+        assert!(table_rows(&json!("text")).is_none());
+    }
+
+    #[test]
+    fn tables_align_columns_in_first_seen_order() {
+        let value = json!([
+            {"name": "places", "type": "table"},
+            {"name": "v", "rows": 3},
+        ]);
+        let rows = table_rows(&value).expect("rows");
+        assert_eq!(
+            render_table(&rows, true),
+            "name   | type  | rows\n\
+             -------+-------+-----\n\
+             places | table |\n\
+             v      |       | 3"
+        );
+    }
+
+    #[test]
+    fn cells_flatten_nulls_nesting_and_line_breaks() {
+        assert_eq!(cell_text(None, true), "");
+        assert_eq!(cell_text(Some(&json!(null)), true), "");
+        assert_eq!(cell_text(Some(&json!("two\nlines")), true), "two lines");
+        assert_eq!(
+            cell_text(Some(&json!({"a": [1, 2]})), true),
+            r#"{"a":[1,2]}"#
+        );
+        assert_eq!(cell_text(Some(&json!(1.5)), true), "1.5");
+
+        let long = "x".repeat(MAX_CELL_WIDTH + 5);
+        let cut = cell_text(Some(&json!(long)), true);
+        assert_eq!(cut.chars().count(), MAX_CELL_WIDTH);
+        assert!(cut.ends_with('…'));
+        assert_eq!(cell_text(Some(&json!(long)), false), long);
+    }
+
+    #[test]
+    fn non_tabular_output_passes_through() {
+        let table = OutputFormat::Table { truncate: true };
+        assert_eq!(
+            format_output(r#"{"status":"ok"}"#, table),
+            r#"{"status":"ok"}"#
+        );
+        assert_eq!(format_output("not json", table), "not json");
+        assert_eq!(
+            format_output(r#"[{"a":1},{"a":2}]"#, OutputFormat::Ndjson),
+            "{\"a\":1}\n{\"a\":2}"
+        );
+    }
+}
@@ -0,0 +1,190 @@
+use std::io::{self, BufRead, IsTerminal};
+
+use spatia_engine::{execute_command_with, shell_join, shell_tokenize, DbManager};
+
+use super::output::{format_output, OutputFormat};
+use super::suggest::{is_known_command, takes_db_path, unknown_command_message};
+
+type ReplResult<T> = Result<T, Box<dyn std::error::Error + Send + Sync>>;
+
+pub fn is_repl_request(args: &[String]) -> bool {
+    args.first().map(String::as_str) == Some("repl")
+}
+
+/// Read commands from stdin, one per line, against a single database until
+/// EOF or `quit`. Commands are written without the `spatia_cli` prefix and
+/// without `<db_path>`, which the session fills in. Every line runs on one
+/// connection kept open for the whole session; `.tables` and
+/// `.schema <table>` are answered from it directly. A failing line is
+/// reported on stderr and the session goes on.
+pub fn run_repl(args: &[String], format: OutputFormat) -> ReplResult<()> {
+    let [_, db_path] = args else {
+        return Err("Usage: spatia_cli repl <db_path>".into());
+    };
+    let session = Session::open(db_path)?;
+
+    let stdin = io::stdin();
+    let interactive = stdin.is_terminal();
+    prompt(interactive);
+    for line in stdin.lock().lines() {
+        let line = line?;
+        let line = line.trim();
+        if matches!(line, "quit" | "exit" | ".quit" | ".exit") {
+            break;
+        }
+        if !line.is_empty() && !line.starts_with('#') {
+            match session.run_line(line) {
+                Ok(output) => println!("{}", format_output(&output, format)),
+                Err(err) => eprintln!("error: {err}"),
+            }
+        }
+        prompt(interactive);
+    }
+    Ok(())
+}
+
+/// The prompt goes to stderr so piped stdout only carries results.
+fn prompt(interactive: bool) {
+    if interactive {
+        eprint!("spatia> ");
+    }
+}
+
+struct Session {
+    db_path: String,
+    /// Shared with engine commands through `execute_command_with`, so they
+    /// do not open a second DuckDB instance on the same file.
+    manager: DbManager,
+}
+
+impl Session {
+    fn open(db_path: &str) -> ReplResult<Self> {
+        Ok(Self {
+            db_path: db_path.to_string(),
+            manager: DbManager::open_file(db_path)?,
+        })
+    }
+
+    fn run_line(&self, line: &str) -> ReplResult<String> {
+        if let Some(shortcut) = line.strip_prefix('.') {
+            return self.run_shortcut(shortcut);
+        }
+
+        let mut tokens = shell_tokenize(line)?;
+        if !is_known_command(&tokens[0]) {
+            return Err(unknown_command_message(&tokens[0]).into());
+        }
+        if takes_db_path(&tokens[0]) {
+            tokens.insert(1, self.db_path.clone());
+        }
+        Ok(execute_command_with(&self.manager, &shell_join(&tokens))?)
+    }
+
+    fn run_shortcut(&self, shortcut: &str) -> ReplResult<String> {
+        let tokens = shell_tokenize(shortcut)?;
+        match tokens.as_slice() {
+            [name] if name == "tables" => list_tables(&self.manager),
+            [name, table] if name == "schema" => describe_table(&self.manager, table),
+            _ => Err(
+                format!("unknown shortcut .{shortcut}; use .tables, .schema <table> or quit")
+                    .into(),
+            ),
+        }
+    }
+}
+
+/// Tables and views in `main`, one per line.
+fn list_tables(manager: &DbManager) -> ReplResult<String> {
+    let mut stmt = manager.connection().prepare(
+        "SELECT table_name FROM information_schema.tables \
+         WHERE table_schema = 'main' ORDER BY table_name",
+    )?;
+    let mut rows = stmt.query([])?;
+    let mut names = Vec::new();
+    while let Some(row) = rows.next()? {
+        names.push(row.get::<_, String>(0)?);
+    }
+    Ok(names.join("\n"))
+}
+
+/// `<column> <type>` per line for `table`.
+fn describe_table(manager: &DbManager, table: &str) -> ReplResult<String> {
+    let mut stmt = manager.connection().prepare(
+        "SELECT column_name, data_type FROM information_schema.columns \
+         WHERE table_schema = 'main' AND table_name = ? ORDER BY ordinal_position",
+    )?;
+    let mut rows = stmt.query([table])?;
+    let mut columns = Vec::new();
+    while let Some(row) = rows.next()? {
+        let name: String = row.get(0)?;
+        let data_type: String = row.get(1)?;
+        columns.push(format!("{name} {data_type}"));
+    }
+    if columns.is_empty() {
+        return Err(format!("table not found: {table}").into());
+    }
+    Ok(columns.join("\n"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Session;
+    use std::fs;
+    use std::time::{SystemTime, UNIX_EPOCH};
+
+    fn temp_session() -> (String, Session) {
+        let suffix = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .expect("time")
+            .as_nanos();
+        let path = format!("/tmp/spatia_repl_test_{suffix}.duckdb");
+        let session = Session::open(&path).expect("open session");
+        session
+            .manager
+            .connection()
+            .execute_batch("CREATE TABLE places (id INTEGER, name VARCHAR)")
+            .expect("create table");
+        (path, session)
+    }
+
+    fn cleanup(path: &str, session: Session) {
+        drop(session);
+        let _ = fs::remove_file(path);
+        let _ = fs::remove_file(format!("{path}.wal"));
+    }
+
+    #[test]
+    fn commands_get_the_session_db_path() {
+        let (path, session) = temp_session();
+        let output = session.run_line("list_tables").expect("list_tables");
+        assert!(output.contains("places"), "{output}");
+        // The session connection stays usable after an engine command.
+        let schema = session.run_line(".schema places").expect("schema");
+        assert_eq!(schema, "id INTEGER\nname VARCHAR");
+        cleanup(&path, session);
+    }
+
+    #[test]
+    fn unknown_commands_are_rejected_with_a_suggestion() {
+        let (path, session) = temp_session();
+        let err = session.run_line("lst_tables").expect_err("unknown command");
+        assert_eq!(
+            err.to_string(),
+            "unknown command: lst_tables (did you mean `list_tables`?)"
+        );
+        cleanup(&path, session);
+    }
+
+    #[test]
+    fn dot_shortcuts_answer_from_the_session_connection() {
+        let (path, session) = temp_session();
+        assert_eq!(session.run_line(".tables").expect("tables"), "places");
+        assert!(session.run_line(".schema missing").is_err());
+        let err = session.run_line(".bogus").expect_err("unknown shortcut");
+        assert!(
+            err.to_string().starts_with("unknown shortcut .bogus"),
+            "{err}"
+        );
+        cleanup(&path, session);
+    }
+}
@@ -0,0 +1,170 @@
+use std::fs;
+
+use spatia_engine::{execute_command, execute_command_with, shell_tokenize, DbManager};
+
+use super::output::{format_output, OutputFormat};
+use super::suggest::takes_db_path;
+
+type ScriptResult<T> = Result<T, Box<dyn std::error::Error + Send + Sync>>;
+
+pub fn is_script_request(args: &[String]) -> bool {
+    args.first().map(String::as_str) == Some("--script")
+}
+
+/// Run every command in a script file, one per line. Blank lines and lines
+/// starting with `#` are skipped. Output is prefixed with the 1-based line
+/// number. Without `--keep-going` the first failing line aborts the run.
+/// `quiet` suppresses the per-line results; errors are still reported.
+/// `format` applies to each result as it does for a single command.
+/// Consecutive lines on the same database share one connection.
+pub fn run_script(args: &[String], quiet: bool, format: OutputFormat) -> ScriptResult<()> {
+    let keep_going = args.iter().any(|arg| arg == "--keep-going");
+    let positional: Vec<&String> = args[1..]
+        .iter()
+        .filter(|arg| *arg != "--keep-going")
+        .collect();
+    let [path] = positional.as_slice() else {
+        return Err("Usage: spatia_cli --script <path> [--keep-going]".into());
+    };
+
+    let contents =
+        fs::read_to_string(path).map_err(|err| format!("failed to read script {path}: {err}"))?;
+
+    let mut executed = 0usize;
+    let mut failed = 0usize;
+    let mut current: Option<DbManager> = None;
+    for (index, line) in contents.lines().enumerate() {
+        let line_no = index + 1;
+        let command = line.trim();
+        if command.is_empty() || command.starts_with('#') {
+            continue;
+        }
+
+        executed += 1;
+        match run_command(&mut current, command) {
+            Ok(output) if !quiet => println!("{line_no}: {}", format_output(&output, format)),
+            Ok(_) => {}
+            Err(err) if keep_going => {
+                failed += 1;
+                eprintln!("{line_no}: error: {err}");
+            }
+            Err(err) => return Err(format!("line {line_no}: {err}").into()),
+        }
+    }
+
+    if failed > 0 {
+        return Err(format!("{failed} of {executed} script commands failed").into());
+    }
+    Ok(())
+}
+
+/// Run one script line through [`execute_command_with`], keeping `current`
+/// open for the next line when it names the same database.
+fn run_command(current: &mut Option<DbManager>, command: &str) -> ScriptResult<String> {
+    let tokens = shell_tokenize(command)?;
+    let db_path = match tokens.as_slice() {
+        [name, db_path, ..] if takes_db_path(name) && !db_path.starts_with('-') => db_path,
+        _ => return Ok(execute_command(command)?),
+    };
+    let manager = match current.take() {
+        Some(manager) if manager.path() == Some(db_path.as_str()) => manager,
+        previous => {
+            // Release the previous database before opening the next one.
+            drop(previous);
+            DbManager::open_file(db_path)?
+        }
+    };
+    let output = execute_command_with(&manager, command);
+    *current = Some(manager);
+    Ok(output?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{run_script, OutputFormat};
+    use spatia_engine::DbManager;
+    use std::fs;
+    use std::time::{SystemTime, UNIX_EPOCH};
+
+    /// A database with a view `v`, and the path for a script next to it.
+    fn temp_paths() -> (String, String) {
+        let suffix = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .expect("time")
+            .as_nanos();
+        let db_path = format!("/tmp/spatia_script_test_{suffix}.duckdb");
+        DbManager::open_file(&db_path)
+            .expect("open")
+            .connection()
+            .execute_batch("CREATE TABLE t (id INTEGER); CREATE VIEW v AS SELECT * FROM t")
+            .expect("seed");
+        (db_path, format!("/tmp/spatia_script_test_{suffix}.txt"))
+    }
+
+    fn run(script_path: &str, keep_going: bool) -> Result<(), String> {
+        let mut args = vec!["--script".to_string(), script_path.to_string()];
+        if keep_going {
+            args.push("--keep-going".to_string());
+        }
+        run_script(&args, true, OutputFormat::Compact).map_err(|err| err.to_string())
+    }
+
+    fn has_view(db_path: &str) -> bool {
+        let manager = DbManager::open_file(db_path).expect("reopen");
+        let count: i64 = manager
+            .connection()
+            .query_row(
+                "SELECT COUNT(*) FROM information_schema.tables WHERE table_name = 'v'",
+                [],
+                |row| row.get(0),
+            )
+            .expect("count");
+        count == 1
+    }
+
+    fn cleanup(db_path: &str, script_path: &str) {
+        let _ = fs::remove_file(db_path);
+        let _ = fs::remove_file(format!("{db_path}.wal"));
+        let _ = fs::remove_file(script_path);
+    }
+
+    #[test]
+    fn comments_and_blank_lines_are_skipped() {
+        let (db_path, script_path) = temp_paths();
+        fs::write(
+            &script_path,
+            format!("# setup\n\n   \nlist_tables {db_path}\n  # indented comment\n"),
+        )
+        .expect("write script");
+        assert_eq!(run(&script_path, false), Ok(()));
+        cleanup(&db_path, &script_path);
+    }
+
+    #[test]
+    fn the_first_failing_line_stops_the_script() {
+        let (db_path, script_path) = temp_paths();
+        fs::write(
+            &script_path,
+            format!("list_tables {db_path}\ndrop_view {db_path} missing\ndrop_view {db_path} v\n"),
+        )
+        .expect("write script");
+        let err = run(&script_path, false).expect_err("line 2 fails");
+        assert_eq!(err, "line 2: view not found: missing");
+        assert!(has_view(&db_path), "line 3 must not run");
+        cleanup(&db_path, &script_path);
+    }
+
+    #[test]
+    fn keep_going_runs_every_line_and_counts_failures() {
+        let (db_path, script_path) = temp_paths();
+        fs::write(
+            &script_path,
+            format!("drop_view {db_path} missing\ndrop_view {db_path} v\n"),
+        )
+        .expect("write script");
+        let err = run(&script_path, true).expect_err("one line fails");
+        assert_eq!(err, "1 of 2 script commands failed");
+        assert!(!has_view(&db_path), "line 2 must still run");
+        cleanup(&db_path, &script_path);
+    }
+}
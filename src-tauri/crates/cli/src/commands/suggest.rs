@@ -0,0 +1,83 @@
+use spatia_engine::edit_distance;
+
+/// Commands the CLI forwards to the engine.
+pub const COMMANDS: &[&str] = &[
+    "ingest",
+    "schema",
+    "list_tables",
+    "list_views",
+    "drop_view",
+    "table_preview",
+    "profile_column",
+    "clean_columns",
+    "spatial_join",
+    "overture_extract",
+    "overture_themes",
+    "overture_search",
+    "overture_geocode",
+    "overture_query",
+    "overture_rebuild_lookup",
+    "geocode",
+    "reverse_geocode",
+    "geocode_cache_export",
+    "geocode_cache_import",
+    "geocode_cache_refresh",
+    "db_info",
+    "json_schema",
+];
+
+/// Commands that take no `<db_path>` as their first argument.
+const COMMANDS_WITHOUT_DB: &[&str] = &["overture_themes"];
+
+pub fn is_known_command(name: &str) -> bool {
+    COMMANDS.contains(&name)
+}
+
+pub fn takes_db_path(name: &str) -> bool {
+    !COMMANDS_WITHOUT_DB.contains(&name)
+}
+
+/// `unknown command: <name>`, with the closest valid name when there is one.
+pub fn unknown_command_message(name: &str) -> String {
+    let mut message = format!("unknown command: {name}");
+    if let Some(suggestion) = closest_command(name) {
+        message.push_str(&format!(" (did you mean `{suggestion}`?)"));
+    }
+    message
+}
+
+/// Closest allowlisted command by edit distance, if it is near enough to be a
+/// plausible typo (at most a third of the input's length, minimum 2 edits).
+pub fn closest_command(name: &str) -> Option<&'static str> {
+    let max_distance = (name.chars().count() / 3).max(2);
+    COMMANDS
+        .iter()
+        .map(|candidate| (edit_distance(name, candidate), *candidate))
+        .filter(|(distance, _)| *distance <= max_distance)
+        .min_by_key(|(distance, _)| *distance)
+        .map(|(_, candidate)| candidate)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{closest_command, unknown_command_message};
+
+    #[test]
+    fn near_miss_suggests_the_closest_command() {
+        assert_eq!(closest_command("overture_serch"), Some("overture_search"));
+        assert_eq!(closest_command("shcema"), Some("schema"));
+        assert_eq!(
+            unknown_command_message("injest"),
+            "unknown command: injest (did you mean `ingest`?)"
+        );
+    }
+
+    #[test]
+    fn unrelated_name_gets_no_suggestion() {
+        assert_eq!(closest_command("frobnicate"), None);
+        assert_eq!(
+            unknown_command_message("frobnicate"),
+            "unknown command: frobnicate"
+        );
+    }
+}
@@ -1,58 +1,79 @@
 use std::env;
-use std::io::{self, Read};
+use std::io::{self, Read, Write};
 
 mod commands;
-use spatia_engine::execute_command;
+use spatia_engine::{
+    classify_error, execute_command, execute_command_to, shell_join, shell_tokenize,
+    CommandParseError,
+};
 
 fn main() {
+    init_tracing();
     if let Err(err) = run() {
         eprintln!("error: {err}");
-        std::process::exit(1);
+        std::process::exit(classify_error(err.as_ref()).exit_code());
     }
 }
 
+/// Log to stderr so command output on stdout stays machine-readable.
+/// `SPATIA_LOG` takes an `EnvFilter` directive (e.g. `debug`,
+/// `spatia_geocode=debug`); the default only surfaces warnings.
+fn init_tracing() {
+    let filter = env::var("SPATIA_LOG")
+        .ok()
+        .and_then(|value| tracing_subscriber::EnvFilter::try_new(value).ok())
+        .unwrap_or_else(|| tracing_subscriber::EnvFilter::new("warn"));
+    tracing_subscriber::fmt()
+        .with_env_filter(filter)
+        .with_writer(io::stderr)
+        .init();
+}
+
 fn run() -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
     let mut args = env::args().skip(1).collect::<Vec<String>>();
     if args.is_empty() {
         let mut input = String::new();
         io::stdin().read_to_string(&mut input)?;
-        args = input.split_whitespace().map(str::to_string).collect();
+        args = shell_tokenize(&input)?;
     }
 
+    let quiet = args.iter().any(|arg| arg == "--quiet");
+    args.retain(|arg| arg != "--quiet");
+    let format = commands::output::take_output_format(&mut args)?;
+
     if args.is_empty() || commands::help::is_help_request(&args) {
         commands::help::print_help();
         return Ok(());
     }
 
-    if !matches!(
-        args[0].as_str(),
-        "ingest"
-            | "schema"
-            | "overture_extract"
-            | "overture_search"
-            | "overture_geocode"
-            | "geocode"
-    ) {
-        commands::help::print_help();
-        return Ok(());
+    if commands::repl::is_repl_request(&args) {
+        return commands::repl::run_repl(&args, format);
+    }
+
+    if commands::script::is_script_request(&args) {
+        return commands::script::run_script(&args, quiet, format);
     }
 
-    let command = serialize_command(&args);
-    let output = execute_command(&command)?;
-    println!("{output}");
+    if !commands::suggest::is_known_command(&args[0]) {
+        let mut message = commands::suggest::unknown_command_message(&args[0]);
+        message.push_str("; run `spatia_cli help` for usage");
+        return Err(CommandParseError(message).into());
+    }
 
-    Ok(())
-}
+    let command = shell_join(&args);
+    if quiet {
+        execute_command(&command)?;
+    } else if format == commands::output::OutputFormat::Compact {
+        // Compact output needs no reformatting, so large results are
+        // written to stdout as they are serialized.
+        let mut stdout = io::BufWriter::new(io::stdout().lock());
+        execute_command_to(&command, &mut stdout)?;
+        writeln!(stdout)?;
+        stdout.flush()?;
+    } else {
+        let output = execute_command(&command)?;
+        println!("{}", commands::output::format_output(&output, format));
+    }
 
-fn serialize_command(args: &[String]) -> String {
-    args.iter()
-        .map(|arg| {
-            if arg.chars().any(char::is_whitespace) {
-                format!("\"{}\"", arg.replace('"', "\\\""))
-            } else {
-                arg.clone()
-            }
-        })
-        .collect::<Vec<String>>()
-        .join(" ")
+    Ok(())
 }
@@ -1,11 +1,14 @@
 use duckdb::Connection;
 use regex::Regex;
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 use serde_json::{json, Map, Value};
+use std::collections::{HashMap, HashSet};
 use std::sync::OnceLock;
 use tracing::{debug, error, info};
 
-use crate::EngineResult;
+use crate::db_manager::open_connection;
+use crate::identifiers::{validate_column_name, validate_identifier, validate_table_name};
+use crate::{load_extension, EngineResult};
 
 /// Raw tabular result limited to the first `TABULAR_ROW_LIMIT` rows.
 /// Each inner `Vec<Value>` corresponds to one row; values are in column order.
@@ -16,18 +19,180 @@ pub struct TabularResult {
     pub truncated: bool,
 }
 
+/// Result rows with typed cells: integers, floats and booleans are JSON
+/// numbers and booleans, NULL is `null` and every other type is its text form.
+#[derive(Debug, Clone, Serialize)]
+pub struct AnalysisRows {
+    pub columns: Vec<String>,
+    pub rows: Vec<Vec<Value>>,
+}
+
 #[derive(Debug, Clone, Serialize)]
 pub struct AnalysisExecutionResult {
     pub status: &'static str,
+    /// View the result was read from, as named by the final statement.
+    pub view_name: String,
     pub row_count: usize,
     pub total_count: usize,
+    /// `null` and omitted with [`AnalysisFormat::Rows`].
+    #[serde(skip_serializing_if = "Value::is_null")]
     pub geojson: Value,
+    /// Only set with [`AnalysisFormat::Rows`].
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub rows: Option<AnalysisRows>,
     pub tabular: TabularResult,
 }
 
 /// Maximum rows included in the tabular preview.
 const TABULAR_ROW_LIMIT: usize = 20;
 
+/// Default cap on rows converted to GeoJSON features.
+const DEFAULT_FEATURE_LIMIT: usize = 1000;
+
+/// How features without a point geometry are handled in the GeoJSON output.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum NullGeometryMode {
+    /// Emit them with `"geometry": null` (the historical behaviour).
+    #[default]
+    Keep,
+    /// Omit them from the FeatureCollection; they remain in `tabular`.
+    Drop,
+    /// When *no* row has a geometry, return `{columns, rows}` in place of the
+    /// FeatureCollection. Mixed results fall back to `Drop`.
+    Tabular,
+}
+
+impl std::str::FromStr for NullGeometryMode {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "keep" => Ok(Self::Keep),
+            "drop" => Ok(Self::Drop),
+            "tabular" => Ok(Self::Tabular),
+            other => Err(format!(
+                "unknown null geometry mode '{other}' (expected keep, drop or tabular)"
+            )),
+        }
+    }
+}
+
+/// Shape of the main result of [`execute_analysis_sql_with_options`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum AnalysisFormat {
+    /// A GeoJSON FeatureCollection for map views.
+    #[default]
+    GeoJson,
+    /// [`AnalysisRows`] for charts and tables; no GeoJSON is built.
+    Rows,
+}
+
+impl std::str::FromStr for AnalysisFormat {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "geojson" => Ok(Self::GeoJson),
+            "rows" => Ok(Self::Rows),
+            other => Err(format!(
+                "unknown analysis format '{other}' (expected geojson or rows)"
+            )),
+        }
+    }
+}
+
+/// Look up a point for features without coordinates by matching one of their
+/// properties against a name column of another table, e.g. a count per city
+/// joined to a divisions extract. The point is the centroid of that table's
+/// `geometry`.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct GeometryJoin {
+    /// Result column holding the name, e.g. `city`.
+    pub property: String,
+    /// Table with a `geometry` column, one row per name.
+    pub table: String,
+    /// Column (or struct field path such as `names.primary`) of `table`
+    /// compared with the property, trimmed and case-insensitively.
+    pub key_column: String,
+}
+
+/// Output options for [`execute_analysis_sql_with_options`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AnalysisOptions {
+    /// Maximum rows read from the result view into the GeoJSON features, or
+    /// into `rows` with [`AnalysisFormat::Rows`].
+    pub feature_limit: usize,
+    pub format: AnalysisFormat,
+    /// Only used for GeoJSON output, like `geometry_join`.
+    pub null_geometry: NullGeometryMode,
+    /// Off by default; see [`GeometryJoin`].
+    pub geometry_join: Option<GeometryJoin>,
+    /// `(alias, path)` pairs of other DuckDB files attached read-only for the
+    /// duration of the run, so the SQL can reference `alias.table`. They are
+    /// detached again afterwards, which leaves a result view over them
+    /// unreadable until the next run.
+    pub attach: Vec<(String, String)>,
+}
+
+impl Default for AnalysisOptions {
+    fn default() -> Self {
+        Self {
+            feature_limit: DEFAULT_FEATURE_LIMIT,
+            format: AnalysisFormat::GeoJson,
+            null_geometry: NullGeometryMode::Keep,
+            geometry_join: None,
+            attach: Vec::new(),
+        }
+    }
+}
+
+/// Catalog names DuckDB reserves for itself.
+const RESERVED_ATTACH_ALIASES: &[&str] = &["main", "memory", "system", "temp"];
+
+/// Check every `(alias, path)` pair before anything is attached.
+fn validate_attachments(attach: &[(String, String)]) -> EngineResult<()> {
+    let mut seen = HashSet::new();
+    for (alias, path) in attach {
+        validate_identifier(alias, "attach alias")?;
+        let lowered = alias.to_ascii_lowercase();
+        if RESERVED_ATTACH_ALIASES.contains(&lowered.as_str()) {
+            return Err(format!("attach alias {alias} is reserved").into());
+        }
+        if !seen.insert(lowered) {
+            return Err(format!("attach alias {alias} is used more than once").into());
+        }
+        if !std::path::Path::new(path).is_file() {
+            return Err(format!("attach path {path} is not an existing file").into());
+        }
+    }
+    Ok(())
+}
+
+/// Attach each database read-only. If one fails, the ones already attached
+/// are detached again before the error is returned.
+fn attach_databases(conn: &Connection, attach: &[(String, String)]) -> EngineResult<()> {
+    for (i, (alias, path)) in attach.iter().enumerate() {
+        let sql = format!("ATTACH '{}' AS {alias} (READ_ONLY)", path.replace('\'', "''"));
+        if let Err(e) = conn.execute_batch(&sql) {
+            error!(alias = %alias, path = %path, error = %e, "execute_analysis_sql: attach failed");
+            detach_databases(conn, &attach[..i]);
+            return Err(format!("failed to attach {path} as {alias}: {e}").into());
+        }
+        debug!(alias = %alias, path = %path, "execute_analysis_sql: attached database");
+    }
+    Ok(())
+}
+
+/// Detach databases attached by [`attach_databases`]. Best-effort, like
+/// [`cleanup_intermediate_views`].
+fn detach_databases(conn: &Connection, attach: &[(String, String)]) {
+    for (alias, _) in attach {
+        if let Err(e) = conn.execute_batch(&format!("DETACH DATABASE IF EXISTS {alias}")) {
+            error!(alias = %alias, error = %e, "execute_analysis_sql: failed to detach database");
+        }
+    }
+}
+
 /// Drop all `_spatia_step_*` intermediate views from the given connection.
 /// Errors are logged but not propagated, since this is a best-effort cleanup.
 fn cleanup_intermediate_views(conn: &Connection) {
@@ -40,33 +205,69 @@ fn cleanup_intermediate_views(conn: &Connection) {
     }
 }
 
+/// Execute analysis SQL and read its result view as GeoJSON.
+///
+/// The result is read from whichever view the final statement creates
+/// (conventionally `analysis_result`). Passing `view_name` asserts which view
+/// that must be, so callers keeping several analyses around read the one
+/// they expect.
 pub fn execute_analysis_sql_to_geojson(
     db_path: &str,
     sql: &str,
+    view_name: Option<&str>,
+) -> EngineResult<AnalysisExecutionResult> {
+    execute_analysis_sql_with_options(db_path, sql, view_name, AnalysisOptions::default())
+}
+
+/// Like [`execute_analysis_sql_to_geojson`], returning typed `rows` instead
+/// of GeoJSON, for non-spatial results shown as a chart or table.
+pub fn execute_analysis_sql_to_rows(
+    db_path: &str,
+    sql: &str,
+    view_name: Option<&str>,
+) -> EngineResult<AnalysisExecutionResult> {
+    let options = AnalysisOptions {
+        format: AnalysisFormat::Rows,
+        ..AnalysisOptions::default()
+    };
+    execute_analysis_sql_with_options(db_path, sql, view_name, options)
+}
+
+/// Execute analysis SQL with explicit output options (feature row cap and
+/// null-geometry handling). `view_name` behaves as in
+/// [`execute_analysis_sql_to_geojson`].
+pub fn execute_analysis_sql_with_options(
+    db_path: &str,
+    sql: &str,
+    view_name: Option<&str>,
+    options: AnalysisOptions,
 ) -> EngineResult<AnalysisExecutionResult> {
     info!("execute_analysis_sql: starting analysis SQL execution");
     debug!(sql = %sql, "execute_analysis_sql: SQL statement");
 
-    validate_analysis_sql(sql)?;
+    let detected = validate_analysis_sql(sql)?;
+    if let Some(expected) = view_name {
+        if !expected.eq_ignore_ascii_case(&detected) {
+            return Err(format!(
+                "analysis SQL creates view {detected}, but view {expected} was requested"
+            )
+            .into());
+        }
+    }
+
+    validate_attachments(&options.attach)?;
 
     // Split into individual statements (same logic as validate_analysis_sql).
-    let statements: Vec<&str> = sql
-        .split(';')
-        .map(|s| s.trim())
-        .filter(|s| !s.is_empty())
-        .collect();
+    let statements = split_statements(sql);
 
-    let conn = Connection::open(db_path)?;
+    let conn = open_connection(db_path)?;
+    attach_databases(&conn, &options.attach)?;
 
     // Execute each statement in order. On failure, clean up intermediate views
     // before returning the error.
     for (i, stmt) in statements.iter().enumerate() {
         if let Err(e) = conn.execute_batch(stmt) {
-            let step_label = if i + 1 < statements.len() {
-                format!("Step {}", i + 1)
-            } else {
-                "Final step".to_string()
-            };
+            let step_label = step_label(i, statements.len());
             error!(
                 step = step_label,
                 sql = %stmt,
@@ -74,28 +275,126 @@ pub fn execute_analysis_sql_to_geojson(
                 "execute_analysis_sql: failed to execute statement"
             );
             cleanup_intermediate_views(&conn);
+            detach_databases(&conn, &options.attach);
             return Err(format!("{step_label} failed: {e}").into());
         }
     }
 
     // Read results into an owned value, then always clean up intermediate views
     // regardless of whether reading succeeds or fails.
-    let read_result = read_analysis_result(&conn);
+    let read_result = read_analysis_result(&conn, &detected, &options);
     cleanup_intermediate_views(&conn);
+    detach_databases(&conn, &options.attach);
     read_result
 }
 
-/// Read from the `analysis_result` view and build the `AnalysisExecutionResult`.
-fn read_analysis_result(conn: &Connection) -> EngineResult<AnalysisExecutionResult> {
+/// Validate `sql` and bind every statement against the database without
+/// keeping the result. The statements run inside a transaction that is always
+/// rolled back, so unknown tables or columns are reported here rather than
+/// when the SQL is executed for real.
+pub fn check_analysis_sql(db_path: &str, sql: &str) -> EngineResult<()> {
+    validate_analysis_sql(sql)?;
+    let statements = split_statements(sql);
+
+    let conn = open_connection(db_path)?;
+    conn.execute_batch("BEGIN TRANSACTION")?;
+    let mut outcome: EngineResult<()> = Ok(());
+    for (i, stmt) in statements.iter().enumerate() {
+        if let Err(e) = conn.execute_batch(stmt) {
+            let step_label = step_label(i, statements.len());
+            debug!(step = step_label, error = %e, "check_analysis_sql: statement failed");
+            outcome = Err(format!("{step_label} failed: {e}").into());
+            break;
+        }
+    }
+    conn.execute_batch("ROLLBACK")?;
+    outcome
+}
+
+/// DuckDB's plan for the view created by analysis SQL.
+#[derive(Debug, Clone, Serialize)]
+pub struct AnalysisPlan {
+    pub view_name: String,
+    /// Whether the plan comes from `EXPLAIN ANALYZE`, which runs the query
+    /// and reports per-operator timings and row counts.
+    pub analyze: bool,
+    pub plan: String,
+}
+
+/// Create the analysis views inside a rolled-back transaction and return
+/// `EXPLAIN` (or, with `analyze`, `EXPLAIN ANALYZE`) output for a
+/// `SELECT *` from the result view. Nothing is left in the database; with
+/// `analyze` the full query does run, so it costs as much as executing it.
+pub fn explain_analysis_sql(
+    db_path: &str,
+    sql: &str,
+    analyze: bool,
+) -> EngineResult<AnalysisPlan> {
+    let view_name = validate_analysis_sql(sql)?;
+    let statements = split_statements(sql);
+
+    let conn = open_connection(db_path)?;
+    conn.execute_batch("BEGIN TRANSACTION")?;
+    let outcome = explain_view(&conn, &statements, &view_name, analyze);
+    conn.execute_batch("ROLLBACK")?;
+    let plan = outcome?;
+    info!(view = %view_name, analyze, "explain_analysis_sql: plan ready");
+    Ok(AnalysisPlan {
+        view_name,
+        analyze,
+        plan,
+    })
+}
+
+fn explain_view(
+    conn: &Connection,
+    statements: &[&str],
+    view_name: &str,
+    analyze: bool,
+) -> EngineResult<String> {
+    for (i, stmt) in statements.iter().enumerate() {
+        if let Err(e) = conn.execute_batch(stmt) {
+            let step_label = step_label(i, statements.len());
+            return Err(format!("{step_label} failed: {e}").into());
+        }
+    }
+    let explain = if analyze { "EXPLAIN ANALYZE" } else { "EXPLAIN" };
+    let mut stmt = conn.prepare(&format!(r#"{explain} SELECT * FROM "{view_name}""#))?;
+    let mut rows = stmt.query([])?;
+    let mut sections: Vec<String> = Vec::new();
+    while let Some(row) = rows.next()? {
+        // Rows are (explain_key, explain_value); the value holds the plan text.
+        sections.push(row.get(1)?);
+    }
+    Ok(sections.join("\n"))
+}
+
+/// "Step N" for intermediate statements, "Final step" for the last one.
+fn step_label(index: usize, total: usize) -> String {
+    if index + 1 < total {
+        format!("Step {}", index + 1)
+    } else {
+        "Final step".to_string()
+    }
+}
+
+/// Read from the result view `view_name` and build the `AnalysisExecutionResult`.
+fn read_analysis_result(
+    conn: &Connection,
+    view_name: &str,
+    options: &AnalysisOptions,
+) -> EngineResult<AnalysisExecutionResult> {
     let mut schema_stmt = conn.prepare(
-        "SELECT column_name FROM information_schema.columns \
-         WHERE table_schema = 'main' AND table_name = 'analysis_result' \
+        "SELECT column_name, data_type FROM information_schema.columns \
+         WHERE table_schema = 'main' AND lower(table_name) = lower(?) \
          ORDER BY ordinal_position"
     )?;
-    let mut schema_rows = schema_stmt.query([])?;
+    let mut schema_rows = schema_stmt.query([view_name])?;
     let mut col_names: Vec<String> = Vec::new();
+    let mut col_types: Vec<String> = Vec::new();
     while let Some(row) = schema_rows.next()? {
         col_names.push(row.get::<_, String>(0)?);
+        col_types.push(row.get::<_, String>(1)?);
     }
 
     // Build a CAST-to-VARCHAR select list so that non-string column types
@@ -111,7 +410,7 @@ fn read_analysis_result(conn: &Connection) -> EngineResult<AnalysisExecutionResu
 
     // --- Total count (before truncation) ---
     let total_count: usize = {
-        let mut count_stmt = conn.prepare("SELECT COUNT(*) FROM analysis_result")?;
+        let mut count_stmt = conn.prepare(&format!(r#"SELECT COUNT(*) FROM "{view_name}""#))?;
         let mut count_rows = count_stmt.query([])?;
         match count_rows.next()? {
             Some(row) => row.get::<_, i64>(0).unwrap_or(0) as usize,
@@ -119,9 +418,74 @@ fn read_analysis_result(conn: &Connection) -> EngineResult<AnalysisExecutionResu
         }
     };
 
-    // --- GeoJSON pass (up to 1000 rows) ---
+    let (geojson, rows, row_count) = match options.format {
+        AnalysisFormat::GeoJson => {
+            let (geojson, row_count) =
+                read_features(conn, view_name, &col_names, &cast_select, options)?;
+            (geojson, None, row_count)
+        }
+        AnalysisFormat::Rows => {
+            let rows = read_typed_rows(conn, view_name, &col_names, &col_types, options)?;
+            let row_count = rows.rows.len();
+            (Value::Null, Some(rows), row_count)
+        }
+    };
+
+    // --- Tabular pass (up to TABULAR_ROW_LIMIT + 1 to detect truncation) ---
+    let fetch_limit = TABULAR_ROW_LIMIT + 1;
+    let mut tab_stmt = conn.prepare(&format!(
+        r#"SELECT {cast_select} FROM "{view_name}" LIMIT {fetch_limit}"#
+    ))?;
+    let mut tab_rows = tab_stmt.query([])?;
+    let mut raw_rows: Vec<Vec<Value>> = Vec::new();
+
+    while let Some(row) = tab_rows.next()? {
+        let mut cells: Vec<Value> = Vec::with_capacity(col_names.len());
+        for index in 0..col_names.len() {
+            let cell: Option<String> = row.get(index).ok();
+            cells.push(match cell {
+                Some(v) => Value::String(v),
+                None => Value::Null,
+            });
+        }
+        raw_rows.push(cells);
+    }
+
+    let truncated = raw_rows.len() > TABULAR_ROW_LIMIT;
+    raw_rows.truncate(TABULAR_ROW_LIMIT);
+
+    let tabular = TabularResult {
+        columns: col_names,
+        rows: raw_rows,
+        truncated,
+    };
+
+    info!(row_count = row_count, total_count = total_count, "execute_analysis_sql: completed successfully");
+    Ok(AnalysisExecutionResult {
+        status: "ok",
+        view_name: view_name.to_string(),
+        row_count,
+        total_count,
+        geojson,
+        rows,
+        tabular,
+    })
+}
+
+/// GeoJSON pass of [`read_analysis_result`]: up to `options.feature_limit`
+/// rows as features, with `null_geometry` and `geometry_join` applied.
+/// Returns the collection (or `{columns, rows}` in tabular mode) and the
+/// number of rows in it.
+fn read_features(
+    conn: &Connection,
+    view_name: &str,
+    col_names: &[String],
+    cast_select: &str,
+    options: &AnalysisOptions,
+) -> EngineResult<(Value, usize)> {
+    let feature_limit = options.feature_limit.max(1);
     let mut stmt = conn.prepare(&format!(
-        "SELECT {cast_select} FROM analysis_result LIMIT 1000"
+        r#"SELECT {cast_select} FROM "{view_name}" LIMIT {feature_limit}"#
     ))?;
 
     let mut rows = stmt.query([])?;
@@ -159,46 +523,194 @@ fn read_analysis_result(conn: &Connection) -> EngineResult<AnalysisExecutionResu
         }));
     }
 
-    // --- Tabular pass (up to TABULAR_ROW_LIMIT + 1 to detect truncation) ---
-    let fetch_limit = TABULAR_ROW_LIMIT + 1;
-    let mut tab_stmt = conn.prepare(&format!(
-        "SELECT {cast_select} FROM analysis_result LIMIT {fetch_limit}"
-    ))?;
-    let mut tab_rows = tab_stmt.query([])?;
-    let mut raw_rows: Vec<Vec<Value>> = Vec::new();
+    if let Some(join) = &options.geometry_join {
+        join_geometry(conn, join, col_names, &mut features)?;
+    }
 
-    while let Some(row) = tab_rows.next()? {
-        let mut cells: Vec<Value> = Vec::with_capacity(col_names.len());
-        for index in 0..col_names.len() {
-            let cell: Option<String> = row.get(index).ok();
-            cells.push(match cell {
-                Some(v) => Value::String(v),
-                None => Value::Null,
-            });
+    let any_geometry = features.iter().any(|f| !f["geometry"].is_null());
+    let geojson = match options.null_geometry {
+        NullGeometryMode::Keep => json!({
+            "type": "FeatureCollection",
+            "features": features,
+        }),
+        NullGeometryMode::Tabular if !any_geometry => {
+            let rows: Vec<Value> = features
+                .iter()
+                .map(|feature| {
+                    let props = &feature["properties"];
+                    Value::Array(col_names.iter().map(|c| props[c].clone()).collect())
+                })
+                .collect();
+            json!({
+                "columns": col_names,
+                "rows": rows,
+            })
+        }
+        NullGeometryMode::Drop | NullGeometryMode::Tabular => {
+            features.retain(|f| !f["geometry"].is_null());
+            json!({
+                "type": "FeatureCollection",
+                "features": features,
+            })
+        }
+    };
+    let row_count = match geojson["rows"].as_array() {
+        Some(rows) => rows.len(),
+        None => features.len(),
+    };
+    Ok((geojson, row_count))
+}
+
+/// How a result column is read into [`AnalysisRows`].
+#[derive(Clone, Copy)]
+enum CellKind {
+    Integer,
+    Float,
+    Boolean,
+    Text,
+}
+
+impl CellKind {
+    fn of(data_type: &str) -> Self {
+        let data_type = data_type.trim().to_ascii_uppercase();
+        match data_type.as_str() {
+            "TINYINT" | "SMALLINT" | "INTEGER" | "BIGINT" | "UTINYINT" | "USMALLINT"
+            | "UINTEGER" => Self::Integer,
+            "FLOAT" | "DOUBLE" => Self::Float,
+            "BOOLEAN" => Self::Boolean,
+            _ if data_type.starts_with("DECIMAL") => Self::Float,
+            // HUGEINT and UBIGINT may not fit an i64, so they stay text.
+            _ => Self::Text,
         }
-        raw_rows.push(cells);
     }
 
-    let truncated = raw_rows.len() > TABULAR_ROW_LIMIT;
-    raw_rows.truncate(TABULAR_ROW_LIMIT);
+    fn select_expr(self, column: &str) -> String {
+        match self {
+            Self::Integer => format!(r#"CAST("{column}" AS BIGINT)"#),
+            Self::Float => format!(r#"CAST("{column}" AS DOUBLE)"#),
+            Self::Boolean => format!(r#""{column}""#),
+            Self::Text => format!(r#"CAST("{column}" AS VARCHAR)"#),
+        }
+    }
+}
 
-    let tabular = TabularResult {
-        columns: col_names,
-        rows: raw_rows,
-        truncated,
+/// Rows pass of [`read_analysis_result`]: up to `options.feature_limit` rows
+/// with typed cells, see [`AnalysisRows`].
+fn read_typed_rows(
+    conn: &Connection,
+    view_name: &str,
+    col_names: &[String],
+    col_types: &[String],
+    options: &AnalysisOptions,
+) -> EngineResult<AnalysisRows> {
+    let kinds: Vec<CellKind> = col_types.iter().map(|t| CellKind::of(t)).collect();
+    let select = col_names
+        .iter()
+        .zip(&kinds)
+        .map(|(c, kind)| format!(r#"{} AS "{c}""#, kind.select_expr(c)))
+        .collect::<Vec<_>>()
+        .join(", ");
+    let limit = options.feature_limit.max(1);
+    let mut stmt = conn.prepare(&format!(r#"SELECT {select} FROM "{view_name}" LIMIT {limit}"#))?;
+    let mut result_rows = stmt.query([])?;
+
+    let mut rows = Vec::new();
+    while let Some(row) = result_rows.next()? {
+        let mut cells = Vec::with_capacity(kinds.len());
+        for (index, kind) in kinds.iter().enumerate() {
+            let cell = match kind {
+                CellKind::Integer => row.get::<_, Option<i64>>(index)?.map(Value::from),
+                CellKind::Float => row
+                    .get::<_, Option<f64>>(index)?
+                    .and_then(serde_json::Number::from_f64)
+                    .map(Value::Number),
+                CellKind::Boolean => row.get::<_, Option<bool>>(index)?.map(Value::Bool),
+                CellKind::Text => row.get::<_, Option<String>>(index)?.map(Value::String),
+            };
+            cells.push(cell.unwrap_or(Value::Null));
+        }
+        rows.push(cells);
+    }
+    Ok(AnalysisRows {
+        columns: col_names.to_vec(),
+        rows,
+    })
+}
+
+/// Fill in `geometry` for features that have none from the centroid of the
+/// matching row of `join.table`. Names without a match stay null; if a name
+/// matches several rows the first one read wins.
+fn join_geometry(
+    conn: &Connection,
+    join: &GeometryJoin,
+    col_names: &[String],
+    features: &mut [Value],
+) -> EngineResult<()> {
+    validate_table_name(&join.table)?;
+    for part in join.key_column.split('.') {
+        validate_column_name(part)?;
+    }
+    let Some(property) = col_names
+        .iter()
+        .find(|name| name.eq_ignore_ascii_case(&join.property))
+    else {
+        return Err(format!(
+            "geometry join property {} is not a column of the analysis result",
+            join.property
+        )
+        .into());
     };
 
-    info!(row_count = features.len(), total_count = total_count, "execute_analysis_sql: completed successfully");
-    Ok(AnalysisExecutionResult {
-        status: "ok",
-        row_count: features.len(),
-        total_count,
-        geojson: json!({
-            "type": "FeatureCollection",
-            "features": features,
-        }),
-        tabular,
-    })
+    let join_key = |feature: &Value| -> Option<String> {
+        if !feature["geometry"].is_null() {
+            return None;
+        }
+        let name = feature["properties"][property.as_str()].as_str()?;
+        Some(name.trim().to_lowercase())
+    };
+    let wanted: HashSet<String> = features.iter().filter_map(join_key).collect();
+    if wanted.is_empty() {
+        return Ok(());
+    }
+
+    load_extension(conn, "spatial")?;
+    let mut stmt = conn.prepare(&format!(
+        "SELECT lower(trim(CAST({key} AS VARCHAR))), \
+                ST_X(ST_Centroid(geometry)), ST_Y(ST_Centroid(geometry)) \
+         FROM {table} WHERE {key} IS NOT NULL AND geometry IS NOT NULL",
+        key = join.key_column,
+        table = join.table,
+    ))?;
+    let mut rows = stmt.query([])?;
+    let mut points: HashMap<String, (f64, f64)> = HashMap::new();
+    while let Some(row) = rows.next()? {
+        let name: String = row.get(0)?;
+        if wanted.contains(&name) && !points.contains_key(&name) {
+            points.insert(name, (row.get(1)?, row.get(2)?));
+        }
+    }
+
+    let mut joined = 0usize;
+    let mut unmatched = 0usize;
+    for feature in features.iter_mut() {
+        let Some(name) = join_key(feature) else {
+            continue;
+        };
+        match points.get(&name) {
+            Some(&(lon, lat)) => {
+                feature["geometry"] = json!({ "type": "Point", "coordinates": [lon, lat] });
+                joined += 1;
+            }
+            None => unmatched += 1,
+        }
+    }
+    info!(
+        table = %join.table,
+        joined,
+        unmatched,
+        "execute_analysis_sql: joined geometry by name"
+    );
+    Ok(())
 }
 
 fn parse_number_property(props: &Map<String, Value>, names: &[&str]) -> Option<f64> {
@@ -270,6 +782,56 @@ fn strip_view_prefix<'a>(normalized: &'a str, view_name: &str) -> Option<&'a str
     }
 }
 
+/// Strip a leading byte-order mark, whitespace, and `--` line comments from a
+/// statement. Models often preface the SQL with a short explanatory comment.
+fn strip_leading_noise(stmt: &str) -> &str {
+    let mut rest = stmt;
+    loop {
+        rest = rest.trim_start_matches(|c: char| c.is_whitespace() || c == '\u{feff}');
+        match rest.strip_prefix("--") {
+            Some(comment) => {
+                rest = comment.find('\n').map_or("", |idx| &comment[idx + 1..]);
+            }
+            None => break,
+        }
+    }
+    rest.trim_end()
+}
+
+/// Name of the view created by a `CREATE [OR REPLACE] VIEW <name> AS ...`
+/// statement, as written. Only bare identifiers are accepted, and names in
+/// the `_spatia_step_` namespace are reserved for intermediate steps.
+fn created_view_name(stmt: &str) -> Option<&str> {
+    let mut words = stmt.split_whitespace();
+    if !words.next()?.eq_ignore_ascii_case("CREATE") {
+        return None;
+    }
+    let mut word = words.next()?;
+    if word.eq_ignore_ascii_case("OR") {
+        if !words.next()?.eq_ignore_ascii_case("REPLACE") {
+            return None;
+        }
+        word = words.next()?;
+    }
+    if !word.eq_ignore_ascii_case("VIEW") {
+        return None;
+    }
+    let name = words.next()?;
+    let is_identifier = name.starts_with(|c: char| c.is_ascii_alphabetic() || c == '_')
+        && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_');
+    let reserved = name.to_ascii_lowercase().starts_with("_spatia_step_");
+    let followed_by_as = words.next()?.to_ascii_uppercase().starts_with("AS");
+    (is_identifier && !reserved && followed_by_as).then_some(name)
+}
+
+/// Split analysis SQL on `;` into cleaned, non-empty statements.
+fn split_statements(sql: &str) -> Vec<&str> {
+    sql.split(';')
+        .map(strip_leading_noise)
+        .filter(|s| !s.is_empty())
+        .collect()
+}
+
 /// Returns the intermediate view name for step N (1-indexed), e.g. "_spatia_step_1".
 fn step_view_name(n: usize) -> String {
     format!("_spatia_step_{n}")
@@ -278,14 +840,12 @@ fn step_view_name(n: usize) -> String {
 /// Maximum number of intermediate `_spatia_step_*` views permitted.
 const MAX_INTERMEDIATE_STEPS: usize = 5;
 
-fn validate_analysis_sql(sql: &str) -> EngineResult<()> {
+/// Check the structure and blocklist of analysis SQL and return the name of
+/// the view created by its final statement.
+fn validate_analysis_sql(sql: &str) -> EngineResult<String> {
     // Split into individual statements, discarding empty ones produced by
-    // trailing semicolons or whitespace-only segments.
-    let statements: Vec<&str> = sql
-        .split(';')
-        .map(|s| s.trim())
-        .filter(|s| !s.is_empty())
-        .collect();
+    // trailing semicolons or whitespace/comment-only segments.
+    let statements = split_statements(sql);
 
     if statements.is_empty() {
         return Err("analysis SQL must not be empty".into());
@@ -296,7 +856,7 @@ fn validate_analysis_sql(sql: &str) -> EngineResult<()> {
     if statements.len() > max_total {
         return Err(format!(
             "analysis SQL may contain at most {MAX_INTERMEDIATE_STEPS} intermediate steps \
-             plus one final analysis_result view ({max_total} statements total); \
+             plus one final result view ({max_total} statements total); \
              got {} statements",
             statements.len()
         )
@@ -322,39 +882,47 @@ fn validate_analysis_sql(sql: &str) -> EngineResult<()> {
 
     // Structural validation: all statements except the last must be
     // `CREATE [OR REPLACE] VIEW _spatia_step_N AS ...` (N = 1..=5),
-    // and the last must be `CREATE [OR REPLACE] VIEW analysis_result AS ...`.
+    // and the last must be `CREATE [OR REPLACE] VIEW <name> AS ...`
+    // (conventionally `analysis_result`).
     let last_idx = statements.len() - 1;
-    for (i, stmt) in statements.iter().enumerate() {
-        let normalized = stmt.to_uppercase();
-        if i < last_idx {
-            // Intermediate step: must be _spatia_step_<i+1>
-            let expected_name = step_view_name(i + 1);
-            if strip_view_prefix(&normalized, &expected_name).is_none() {
-                return Err(format!(
-                    "intermediate statement {} must be \
-                     CREATE [OR REPLACE] VIEW {expected_name} AS ...; \
-                     got: {stmt}",
-                    i + 1,
-                )
-                .into());
-            }
-        } else {
-            // Final statement must create analysis_result
-            if strip_view_prefix(&normalized, "analysis_result").is_none() {
-                return Err(
-                    "analysis SQL must end with CREATE [OR REPLACE] VIEW analysis_result AS ..."
-                        .into(),
-                );
-            }
+    for (i, stmt) in statements.iter().take(last_idx).enumerate() {
+        // Collapse whitespace runs (including CRLF and tabs) so the prefix
+        // check does not depend on how the model laid out the keywords.
+        let normalized = stmt
+            .split_whitespace()
+            .collect::<Vec<_>>()
+            .join(" ")
+            .to_uppercase();
+        // Intermediate step: must be _spatia_step_<i+1>
+        let expected_name = step_view_name(i + 1);
+        if strip_view_prefix(&normalized, &expected_name).is_none() {
+            return Err(format!(
+                "intermediate statement {} must be \
+                 CREATE [OR REPLACE] VIEW {expected_name} AS ...; \
+                 got: {stmt}",
+                i + 1,
+            )
+            .into());
         }
     }
 
-    Ok(())
+    match created_view_name(statements[last_idx]) {
+        Some(name) => Ok(name.to_string()),
+        None => Err(
+            "analysis SQL must end with CREATE [OR REPLACE] VIEW <name> AS ..., \
+             e.g. CREATE [OR REPLACE] VIEW analysis_result AS ..."
+                .into(),
+        ),
+    }
 }
 
 #[cfg(test)]
 mod tests {
-    use super::{execute_analysis_sql_to_geojson, validate_analysis_sql};
+    use super::{
+        check_analysis_sql, execute_analysis_sql_to_geojson, execute_analysis_sql_to_rows,
+        execute_analysis_sql_with_options, explain_analysis_sql, validate_analysis_sql,
+        AnalysisFormat, AnalysisOptions, GeometryJoin, NullGeometryMode,
+    };
     use duckdb::Connection;
     use serde_json::Value;
     use std::fs;
@@ -382,6 +950,81 @@ mod tests {
     // Execution integration tests
     // -----------------------------------------------------------------------
 
+    #[test]
+    fn check_binds_sql_without_leaving_views() {
+        let db_path = temp_db_path();
+        let conn = Connection::open(&db_path).expect("open db");
+        conn.execute_batch("CREATE TABLE places(city VARCHAR, pop INTEGER)")
+            .expect("create table");
+        drop(conn);
+
+        check_analysis_sql(
+            &db_path,
+            "CREATE OR REPLACE VIEW analysis_result AS SELECT \"city\" FROM places",
+        )
+        .expect("valid SQL should pass");
+
+        let err = check_analysis_sql(
+            &db_path,
+            "CREATE OR REPLACE VIEW analysis_result AS SELECT \"county\" FROM places",
+        )
+        .expect_err("unknown column should fail");
+        assert!(err.to_string().starts_with("Final step failed"), "{err}");
+
+        let conn = Connection::open(&db_path).expect("reopen db");
+        let views: i64 = conn
+            .query_row(
+                "SELECT COUNT(*) FROM information_schema.tables WHERE table_type = 'VIEW'",
+                [],
+                |row| row.get(0),
+            )
+            .expect("count views");
+        assert_eq!(views, 0);
+        drop(conn);
+        cleanup_temp_db(&db_path);
+    }
+
+    #[test]
+    fn explain_returns_plan_without_leaving_views() {
+        let db_path = temp_db_path();
+        let conn = Connection::open(&db_path).expect("open db");
+        conn.execute_batch(
+            "CREATE TABLE places AS SELECT range AS id, range % 7 AS bucket FROM range(100)",
+        )
+        .expect("create table");
+        drop(conn);
+
+        let sql = "CREATE OR REPLACE VIEW analysis_result AS \
+                   SELECT bucket, COUNT(*) AS n FROM places GROUP BY bucket";
+        let plan = explain_analysis_sql(&db_path, sql, false).expect("explain");
+        assert_eq!(plan.view_name, "analysis_result");
+        assert!(plan.plan.to_uppercase().contains("SEQ_SCAN"), "{}", plan.plan);
+
+        let analyzed = explain_analysis_sql(&db_path, sql, true).expect("explain analyze");
+        assert!(analyzed.analyze);
+        assert!(analyzed.plan.to_uppercase().contains("SEQ_SCAN"), "{}", analyzed.plan);
+
+        let err = explain_analysis_sql(
+            &db_path,
+            "CREATE OR REPLACE VIEW analysis_result AS SELECT missing FROM places",
+            false,
+        )
+        .expect_err("unknown column");
+        assert!(err.to_string().starts_with("Final step failed"), "{err}");
+
+        let conn = Connection::open(&db_path).expect("reopen db");
+        let views: i64 = conn
+            .query_row(
+                "SELECT COUNT(*) FROM information_schema.tables WHERE table_type = 'VIEW'",
+                [],
+                |row| row.get(0),
+            )
+            .expect("count views");
+        assert_eq!(views, 0);
+        drop(conn);
+        cleanup_temp_db(&db_path);
+    }
+
     #[test]
     fn executes_view_sql_and_returns_geojson_points() {
         let db_path = temp_db_path();
@@ -403,7 +1046,7 @@ mod tests {
         .expect("insert row 2");
 
         let sql = "CREATE OR REPLACE VIEW analysis_result AS SELECT city, lat, lon FROM points";
-        let result = execute_analysis_sql_to_geojson(&db_path, sql).expect("execute analysis sql");
+        let result = execute_analysis_sql_to_geojson(&db_path, sql, None).expect("execute analysis sql");
 
         assert_eq!(result.status, "ok");
         assert_eq!(result.row_count, 2);
@@ -421,7 +1064,7 @@ mod tests {
     #[test]
     fn rejects_non_view_sql() {
         let db_path = temp_db_path();
-        let err = execute_analysis_sql_to_geojson(&db_path, "SELECT 1")
+        let err = execute_analysis_sql_to_geojson(&db_path, "SELECT 1", None)
             .expect_err("expected validation error");
         assert!(err
             .to_string()
@@ -429,6 +1072,64 @@ mod tests {
         cleanup_temp_db(&db_path);
     }
 
+    #[test]
+    fn executes_sql_into_a_named_view() {
+        let db_path = temp_db_path();
+        let conn = Connection::open(&db_path).expect("open duckdb");
+        conn.execute_batch(
+            "CREATE TABLE points (city VARCHAR, lat DOUBLE, lon DOUBLE); \
+             INSERT INTO points VALUES ('Seattle', 47.6062, -122.3321);",
+        )
+        .expect("setup");
+
+        let first = "CREATE OR REPLACE VIEW seattle_points AS SELECT * FROM points";
+        let second = "CREATE OR REPLACE VIEW analysis_result AS SELECT city FROM points";
+        let result = execute_analysis_sql_to_geojson(&db_path, first, Some("seattle_points"))
+            .expect("execute named view");
+        assert_eq!(result.view_name, "seattle_points");
+        assert_eq!(result.row_count, 1);
+        execute_analysis_sql_to_geojson(&db_path, second, None).expect("execute second");
+
+        // Both views survive side by side.
+        let views: i64 = conn
+            .query_row(
+                "SELECT COUNT(*) FROM information_schema.tables \
+                 WHERE table_name IN ('seattle_points', 'analysis_result')",
+                [],
+                |row| row.get(0),
+            )
+            .expect("count views");
+        assert_eq!(views, 2);
+
+        let err = execute_analysis_sql_to_geojson(&db_path, first, Some("analysis_result"))
+            .expect_err("mismatched view name");
+        assert!(err.to_string().contains("creates view seattle_points"));
+
+        cleanup_temp_db(&db_path);
+    }
+
+    #[test]
+    fn validate_returns_the_created_view_name() {
+        let sql = "CREATE OR REPLACE VIEW _spatia_step_1 AS SELECT 1; \
+                   create view Hotspots_2024 as SELECT * FROM _spatia_step_1";
+        assert_eq!(validate_analysis_sql(sql).expect("valid"), "Hotspots_2024");
+        assert_eq!(
+            validate_analysis_sql("CREATE VIEW analysis_result AS SELECT 1").expect("valid"),
+            "analysis_result"
+        );
+    }
+
+    #[test]
+    fn rejects_reserved_or_quoted_final_view_names() {
+        for sql in [
+            "CREATE VIEW _spatia_step_3 AS SELECT 1",
+            "CREATE VIEW \"odd name\" AS SELECT 1",
+            "CREATE VIEW main.result AS SELECT 1",
+        ] {
+            assert!(validate_analysis_sql(sql).is_err(), "{sql} should be rejected");
+        }
+    }
+
     // -----------------------------------------------------------------------
     // validate_analysis_sql — prefix check
     // -----------------------------------------------------------------------
@@ -454,6 +1155,41 @@ mod tests {
         assert!(err.to_string().contains("CREATE [OR REPLACE] VIEW analysis_result AS"));
     }
 
+    #[test]
+    fn accepts_leading_line_comment() {
+        let sql = "-- Count rows per city\n\
+                   CREATE OR REPLACE VIEW analysis_result AS SELECT 1";
+        assert!(validate_analysis_sql(sql).is_ok());
+    }
+
+    #[test]
+    fn accepts_leading_byte_order_mark() {
+        let sql = "\u{feff}CREATE OR REPLACE VIEW analysis_result AS SELECT 1;";
+        assert!(validate_analysis_sql(sql).is_ok());
+    }
+
+    #[test]
+    fn accepts_crlf_line_endings() {
+        let sql = "-- generated\r\nCREATE OR REPLACE VIEW\r\nanalysis_result AS\r\nSELECT 1;\r\n";
+        assert!(validate_analysis_sql(sql).is_ok());
+    }
+
+    #[test]
+    fn rejects_commented_non_view_statement() {
+        let err = validate_analysis_sql("-- just a select\nSELECT 1")
+            .expect_err("should reject");
+        assert!(err.to_string().contains("CREATE [OR REPLACE] VIEW analysis_result AS"));
+    }
+
+    #[test]
+    fn executes_sql_with_leading_comment_and_bom() {
+        let db_path = temp_db_path();
+        let sql = "\u{feff}-- one row\r\nCREATE OR REPLACE VIEW analysis_result AS SELECT 1 AS n;";
+        let result = execute_analysis_sql_to_geojson(&db_path, sql, None).expect("execute");
+        assert_eq!(result.total_count, 1);
+        cleanup_temp_db(&db_path);
+    }
+
     // -----------------------------------------------------------------------
     // validate_analysis_sql — allowed patterns (no false positives)
     // -----------------------------------------------------------------------
@@ -691,7 +1427,7 @@ mod tests {
                    FROM sales \
                    GROUP BY category \
                    ORDER BY category";
-        let result = execute_analysis_sql_to_geojson(&db_path, sql).expect("execute");
+        let result = execute_analysis_sql_to_geojson(&db_path, sql, None).expect("execute");
 
         // Tabular: 2 groups — A(2), B(1)
         assert_eq!(result.tabular.columns, vec!["category", "cnt"]);
@@ -718,6 +1454,275 @@ mod tests {
         cleanup_temp_db(&db_path);
     }
 
+    // -----------------------------------------------------------------------
+    // Null-geometry modes and feature limit
+    // -----------------------------------------------------------------------
+
+    fn setup_mixed_points(db_path: &str) {
+        let conn = Connection::open(db_path).expect("open db");
+        conn.execute_batch(
+            "CREATE TABLE pts(name VARCHAR, lat DOUBLE, lon DOUBLE); \
+             INSERT INTO pts VALUES ('a', 47.6, -122.3), ('b', NULL, NULL), ('c', 45.5, -122.6);",
+        )
+        .expect("setup table");
+    }
+
+    #[test]
+    fn drop_mode_omits_null_geometry_features() {
+        let db_path = temp_db_path();
+        setup_mixed_points(&db_path);
+
+        let options = AnalysisOptions {
+            null_geometry: NullGeometryMode::Drop,
+            ..AnalysisOptions::default()
+        };
+        let sql = "CREATE OR REPLACE VIEW analysis_result AS SELECT * FROM pts ORDER BY name";
+        let result = execute_analysis_sql_with_options(&db_path, sql, None, options).expect("execute");
+
+        let features = result.geojson["features"].as_array().expect("features");
+        assert_eq!(features.len(), 2);
+        assert_eq!(result.row_count, 2);
+        assert_eq!(result.total_count, 3);
+        assert_eq!(result.tabular.rows.len(), 3);
+
+        cleanup_temp_db(&db_path);
+    }
+
+    #[test]
+    fn tabular_mode_returns_columns_and_rows_without_geometry() {
+        let db_path = temp_db_path();
+        setup_mixed_points(&db_path);
+
+        let options = AnalysisOptions {
+            null_geometry: NullGeometryMode::Tabular,
+            ..AnalysisOptions::default()
+        };
+        let sql = "CREATE OR REPLACE VIEW analysis_result AS \
+                   SELECT name, COUNT(*) AS cnt FROM pts GROUP BY name ORDER BY name";
+        let result = execute_analysis_sql_with_options(&db_path, sql, None, options).expect("execute");
+
+        assert!(result.geojson.get("type").is_none());
+        assert_eq!(result.geojson["columns"], serde_json::json!(["name", "cnt"]));
+        assert_eq!(result.geojson["rows"][0], serde_json::json!(["a", "1"]));
+        assert_eq!(result.row_count, 3);
+
+        cleanup_temp_db(&db_path);
+    }
+
+    #[test]
+    fn tabular_mode_keeps_feature_collection_when_some_rows_have_geometry() {
+        let db_path = temp_db_path();
+        setup_mixed_points(&db_path);
+
+        let options = AnalysisOptions {
+            null_geometry: NullGeometryMode::Tabular,
+            ..AnalysisOptions::default()
+        };
+        let sql = "CREATE OR REPLACE VIEW analysis_result AS SELECT * FROM pts";
+        let result = execute_analysis_sql_with_options(&db_path, sql, None, options).expect("execute");
+
+        assert_eq!(result.geojson["type"], "FeatureCollection");
+        assert_eq!(result.geojson["features"].as_array().expect("features").len(), 2);
+
+        cleanup_temp_db(&db_path);
+    }
+
+    #[test]
+    fn feature_limit_caps_geojson_rows() {
+        let db_path = temp_db_path();
+        setup_mixed_points(&db_path);
+
+        let options = AnalysisOptions {
+            feature_limit: 1,
+            ..AnalysisOptions::default()
+        };
+        let sql = "CREATE OR REPLACE VIEW analysis_result AS SELECT * FROM pts ORDER BY name";
+        let result = execute_analysis_sql_with_options(&db_path, sql, None, options).expect("execute");
+
+        assert_eq!(result.row_count, 1);
+        assert_eq!(result.total_count, 3);
+
+        cleanup_temp_db(&db_path);
+    }
+
+    #[test]
+    fn geometry_join_places_aggregates_at_region_centroids() {
+        let db_path = temp_db_path();
+        let conn = Connection::open(&db_path).expect("open db");
+        crate::load_extension(&conn, "spatial").expect("spatial");
+        conn.execute_batch(
+            "CREATE TABLE policies(city VARCHAR); \
+             INSERT INTO policies VALUES ('Seattle'), ('seattle '), ('Tacoma'), ('Atlantis'); \
+             CREATE TABLE cities AS SELECT * FROM (VALUES \
+                 ({'primary': 'Seattle'}, ST_GeomFromText('POLYGON((0 0, 2 0, 2 2, 0 2, 0 0))')), \
+                 ({'primary': 'Tacoma'}, ST_GeomFromText('POLYGON((10 10, 12 10, 12 14, 10 14, 10 10))')) \
+             ) AS t(names, geometry);",
+        )
+        .expect("setup");
+        drop(conn);
+
+        let options = AnalysisOptions {
+            geometry_join: Some(GeometryJoin {
+                property: "city".to_string(),
+                table: "cities".to_string(),
+                key_column: "names.primary".to_string(),
+            }),
+            ..AnalysisOptions::default()
+        };
+        let sql = "CREATE OR REPLACE VIEW analysis_result AS \
+                   SELECT trim(city) AS city, COUNT(*) AS n FROM policies \
+                   GROUP BY trim(city) ORDER BY city";
+        let result =
+            execute_analysis_sql_with_options(&db_path, sql, None, options).expect("execute");
+
+        let features = result.geojson["features"].as_array().expect("features");
+        assert_eq!(features.len(), 4);
+        let geometry_for = |city: &str| {
+            features
+                .iter()
+                .find(|f| f["properties"]["city"] == city)
+                .map(|f| f["geometry"].clone())
+                .expect("feature")
+        };
+        assert_eq!(geometry_for("Seattle")["coordinates"], serde_json::json!([1.0, 1.0]));
+        assert_eq!(geometry_for("seattle")["coordinates"], serde_json::json!([1.0, 1.0]));
+        assert_eq!(geometry_for("Tacoma")["coordinates"], serde_json::json!([11.0, 12.0]));
+        assert!(geometry_for("Atlantis").is_null());
+
+        cleanup_temp_db(&db_path);
+    }
+
+    #[test]
+    fn geometry_join_rejects_unknown_property() {
+        let db_path = temp_db_path();
+        setup_mixed_points(&db_path);
+
+        let options = AnalysisOptions {
+            geometry_join: Some(GeometryJoin {
+                property: "city".to_string(),
+                table: "pts".to_string(),
+                key_column: "name".to_string(),
+            }),
+            ..AnalysisOptions::default()
+        };
+        let sql = "CREATE OR REPLACE VIEW analysis_result AS SELECT name FROM pts";
+        let err = execute_analysis_sql_with_options(&db_path, sql, None, options)
+            .expect_err("unknown property");
+        assert!(err.to_string().contains("geometry join property city"));
+
+        cleanup_temp_db(&db_path);
+    }
+
+    #[test]
+    fn attached_database_is_queryable_and_detached_afterwards() {
+        let db_path = temp_db_path();
+        let ref_path = db_path.replace(".duckdb", "_ref.duckdb");
+        let conn = Connection::open(&ref_path).expect("open ref db");
+        conn.execute_batch(
+            "CREATE TABLE zones(code VARCHAR, label VARCHAR); \
+             INSERT INTO zones VALUES ('A', 'Alpha'), ('B', 'Beta');",
+        )
+        .expect("setup ref");
+        drop(conn);
+        let conn = Connection::open(&db_path).expect("open db");
+        conn.execute_batch(
+            "CREATE TABLE parcels(zone VARCHAR); INSERT INTO parcels VALUES ('A'), ('A'), ('B');",
+        )
+        .expect("setup");
+        drop(conn);
+
+        let options = AnalysisOptions {
+            attach: vec![("refdata".to_string(), ref_path.clone())],
+            ..AnalysisOptions::default()
+        };
+        let sql = "CREATE OR REPLACE VIEW analysis_result AS \
+                   SELECT z.label, COUNT(*) AS n FROM parcels p \
+                   JOIN refdata.zones z ON z.code = p.zone GROUP BY z.label ORDER BY z.label";
+        let result =
+            execute_analysis_sql_with_options(&db_path, sql, None, options).expect("execute");
+        assert_eq!(result.total_count, 2);
+        assert_eq!(result.tabular.rows[0][0], Value::String("Alpha".to_string()));
+
+        let conn = Connection::open(&db_path).expect("reopen db");
+        let attached: i64 = conn
+            .query_row(
+                "SELECT COUNT(*) FROM duckdb_databases() WHERE database_name = 'refdata'",
+                [],
+                |row| row.get(0),
+            )
+            .expect("count databases");
+        assert_eq!(attached, 0);
+        drop(conn);
+
+        cleanup_temp_db(&db_path);
+        cleanup_temp_db(&ref_path);
+    }
+
+    #[test]
+    fn attach_rejects_bad_aliases_and_missing_files() {
+        let db_path = temp_db_path();
+        setup_mixed_points(&db_path);
+        let sql = "CREATE OR REPLACE VIEW analysis_result AS SELECT name FROM pts";
+        let run = |alias: &str, path: &str| {
+            let options = AnalysisOptions {
+                attach: vec![(alias.to_string(), path.to_string())],
+                ..AnalysisOptions::default()
+            };
+            execute_analysis_sql_with_options(&db_path, sql, None, options)
+                .expect_err("invalid attachment")
+                .to_string()
+        };
+
+        assert!(run("ref; DROP", &db_path).contains("attach alias"));
+        assert!(run("main", &db_path).contains("reserved"));
+        assert!(run("refdata", "/nonexistent/ref.duckdb").contains("not an existing file"));
+
+        cleanup_temp_db(&db_path);
+    }
+
+    #[test]
+    fn rows_format_returns_typed_values_for_the_same_view() {
+        let db_path = temp_db_path();
+        setup_mixed_points(&db_path);
+        let sql = "CREATE OR REPLACE VIEW analysis_result AS \
+                   SELECT name, COUNT(*) AS cnt, avg(lat) AS avg_lat, \
+                   bool_or(lat IS NULL) AS missing \
+                   FROM pts GROUP BY name ORDER BY name";
+
+        let geojson = execute_analysis_sql_to_geojson(&db_path, sql, None).expect("geojson");
+        let features = geojson.geojson["features"].as_array().expect("features");
+        assert_eq!(features[0]["properties"]["cnt"], "1");
+        assert!(geojson.rows.is_none());
+
+        let result = execute_analysis_sql_to_rows(&db_path, sql, None).expect("rows");
+        let rows = result.rows.as_ref().expect("rows output");
+        assert_eq!(rows.columns, vec!["name", "cnt", "avg_lat", "missing"]);
+        assert_eq!(rows.rows[0], vec![serde_json::json!("a"), 1.into(), 47.6.into(), false.into()]);
+        assert_eq!(rows.rows[1], vec![serde_json::json!("b"), 1.into(), Value::Null, true.into()]);
+        assert_eq!(result.row_count, 3);
+        assert_eq!(result.total_count, 3);
+
+        let json = serde_json::to_value(&result).expect("serialize");
+        assert!(json.get("geojson").is_none());
+        assert_eq!(json["rows"]["rows"][2][0], "c");
+
+        cleanup_temp_db(&db_path);
+    }
+
+    #[test]
+    fn analysis_format_parses_from_str() {
+        assert_eq!(" Rows ".parse::<AnalysisFormat>(), Ok(AnalysisFormat::Rows));
+        assert_eq!("geojson".parse::<AnalysisFormat>(), Ok(AnalysisFormat::GeoJson));
+        assert!("csv".parse::<AnalysisFormat>().is_err());
+    }
+
+    #[test]
+    fn null_geometry_mode_parses_from_str() {
+        assert_eq!("drop".parse::<NullGeometryMode>(), Ok(NullGeometryMode::Drop));
+        assert_eq!(" Tabular ".parse::<NullGeometryMode>(), Ok(NullGeometryMode::Tabular));
+        assert!("points".parse::<NullGeometryMode>().is_err());
+    }
+
     /// TC-011-02: Spatial query with _lat/_lon columns produces Point geometry.
     /// Properties must be included on every GeoJSON feature.
     #[test]
@@ -734,7 +1739,7 @@ mod tests {
 
         let sql = "CREATE OR REPLACE VIEW analysis_result AS \
                    SELECT name, score, _lat, _lon FROM locations WHERE score >= 1";
-        let result = execute_analysis_sql_to_geojson(&db_path, sql).expect("execute");
+        let result = execute_analysis_sql_to_geojson(&db_path, sql, None).expect("execute");
 
         assert_eq!(result.status, "ok");
         assert_eq!(result.row_count, 2);
@@ -791,7 +1796,7 @@ mod tests {
 
         let sql = "CREATE OR REPLACE VIEW analysis_result AS \
                    SELECT region, value FROM metrics";
-        let result = execute_analysis_sql_to_geojson(&db_path, sql).expect("execute");
+        let result = execute_analysis_sql_to_geojson(&db_path, sql, None).expect("execute");
 
         // All features have null geometry
         let features = result
@@ -832,7 +1837,7 @@ mod tests {
         // The WHERE clause matches no rows
         let sql = "CREATE OR REPLACE VIEW analysis_result AS \
                    SELECT id, active FROM things WHERE active = true";
-        let result = execute_analysis_sql_to_geojson(&db_path, sql).expect("execute — should not error on empty set");
+        let result = execute_analysis_sql_to_geojson(&db_path, sql, None).expect("execute — should not error on empty set");
 
         assert_eq!(result.status, "ok");
         assert_eq!(result.row_count, 0);
@@ -870,7 +1875,7 @@ mod tests {
         drop(conn);
 
         let sql = "CREATE OR REPLACE VIEW analysis_result AS SELECT n FROM nums";
-        let result = execute_analysis_sql_to_geojson(&db_path, sql).expect("execute");
+        let result = execute_analysis_sql_to_geojson(&db_path, sql, None).expect("execute");
 
         // Tabular is capped at 20
         assert_eq!(
@@ -907,7 +1912,7 @@ mod tests {
 
         let sql = "CREATE OR REPLACE VIEW analysis_result AS \
                    SELECT label, count, score, created, active FROM typed";
-        let result = execute_analysis_sql_to_geojson(&db_path, sql).expect("execute");
+        let result = execute_analysis_sql_to_geojson(&db_path, sql, None).expect("execute");
 
         assert_eq!(result.tabular.rows.len(), 1);
         let row = &result.tabular.rows[0];
@@ -955,7 +1960,7 @@ mod tests {
 
         // Deliberately select in non-alphabetical order
         let sql = "CREATE OR REPLACE VIEW analysis_result AS SELECT z, a, m FROM t";
-        let result = execute_analysis_sql_to_geojson(&db_path, sql).expect("execute");
+        let result = execute_analysis_sql_to_geojson(&db_path, sql, None).expect("execute");
 
         assert_eq!(result.tabular.columns, vec!["z", "a", "m"]);
         let row = &result.tabular.rows[0];
@@ -990,7 +1995,7 @@ mod tests {
                    ) \
                    SELECT * FROM avg_vals WHERE avg_val > 5.0 \
                    ORDER BY sensor";
-        let result = execute_analysis_sql_to_geojson(&db_path, sql).expect("execute");
+        let result = execute_analysis_sql_to_geojson(&db_path, sql, None).expect("execute");
 
         // Only sensor 'A' (avg=8.0) qualifies; 'B' (avg=4.0) does not
         assert_eq!(result.row_count, 1);
@@ -1115,7 +2120,7 @@ mod tests {
                    CREATE OR REPLACE VIEW analysis_result AS \
                        SELECT region, total FROM _spatia_step_1 WHERE total > 100 ORDER BY region";
 
-        let result = execute_analysis_sql_to_geojson(&db_path, sql).expect("execute multi-step");
+        let result = execute_analysis_sql_to_geojson(&db_path, sql, None).expect("execute multi-step");
         assert_eq!(result.status, "ok");
         // Only East (total=300) qualifies; West (total=50) does not.
         assert_eq!(result.tabular.rows.len(), 1);
@@ -1161,7 +2166,7 @@ mod tests {
                    CREATE OR REPLACE VIEW analysis_result AS \
                        SELECT nonexistent_col FROM _spatia_step_1";
 
-        let err = execute_analysis_sql_to_geojson(&db_path, sql)
+        let err = execute_analysis_sql_to_geojson(&db_path, sql, None)
             .expect_err("should fail on bad column reference");
         assert!(
             err.to_string().contains("Final step failed"),
@@ -0,0 +1,277 @@
+//! Deterministic column cleaning, the offline counterpart of the AI cleaner.
+//!
+//! A spec lists operations per column, e.g. `city=trim,null_if:N/A;state=upper`.
+//! Each column becomes one `UPDATE` whose expression applies the operations
+//! in the order given; all columns are updated in a single transaction.
+
+use serde::Serialize;
+use tracing::{debug, info};
+
+use crate::db_manager::open_connection;
+use crate::identifiers::{validate_column_name, validate_table_name};
+use crate::schema::table_schema;
+use crate::EngineResult;
+
+/// One operation applied to a text column.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ColumnCleanOp {
+    /// Strip leading and trailing whitespace.
+    Trim,
+    /// Replace this exact value (case-sensitive, may be empty) with NULL.
+    NullIf(String),
+    Lower,
+    Upper,
+}
+
+impl ColumnCleanOp {
+    /// `expr` wrapped in this operation.
+    fn apply(&self, expr: &str) -> String {
+        match self {
+            ColumnCleanOp::Trim => format!("trim({expr})"),
+            ColumnCleanOp::NullIf(value) => {
+                format!("NULLIF({expr}, '{}')", value.replace('\'', "''"))
+            }
+            ColumnCleanOp::Lower => format!("lower({expr})"),
+            ColumnCleanOp::Upper => format!("upper({expr})"),
+        }
+    }
+}
+
+impl std::str::FromStr for ColumnCleanOp {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        if let Some(sentinel) = value.trim_start().strip_prefix("null_if:") {
+            return Ok(ColumnCleanOp::NullIf(sentinel.to_string()));
+        }
+        match value.trim().to_ascii_lowercase().as_str() {
+            "trim" => Ok(ColumnCleanOp::Trim),
+            "lower" => Ok(ColumnCleanOp::Lower),
+            "upper" => Ok(ColumnCleanOp::Upper),
+            other => Err(format!(
+                "unknown clean operation '{other}' (expected trim, null_if:<value>, lower or upper)"
+            )),
+        }
+    }
+}
+
+/// Operations for one column, in application order.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ColumnCleanSpec {
+    pub column: String,
+    pub ops: Vec<ColumnCleanOp>,
+}
+
+/// Rows changed in one column by [`clean_columns`].
+#[derive(Debug, Clone, Serialize)]
+pub struct ColumnCleanSummary {
+    pub column: String,
+    pub rows_changed: usize,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct CleanColumnsResult {
+    pub status: &'static str,
+    pub table: String,
+    pub columns: Vec<ColumnCleanSummary>,
+}
+
+/// Parse `column=op,op;column=op`. Column names are validated here; whether
+/// they exist is checked by [`clean_columns`]. A `null_if` value cannot
+/// contain `,` or `;`.
+pub fn parse_clean_spec(spec: &str) -> EngineResult<Vec<ColumnCleanSpec>> {
+    let mut specs: Vec<ColumnCleanSpec> = Vec::new();
+    for entry in spec.split(';').filter(|entry| !entry.trim().is_empty()) {
+        let Some((column, ops)) = entry.split_once('=') else {
+            return Err(format!("clean spec entry '{entry}' must be column=op[,op...]").into());
+        };
+        let column = column.trim();
+        validate_column_name(column)?;
+        if specs.iter().any(|s| s.column.eq_ignore_ascii_case(column)) {
+            return Err(format!("column {column} appears more than once in the clean spec").into());
+        }
+        let ops = ops
+            .split(',')
+            .map(str::parse::<ColumnCleanOp>)
+            .collect::<Result<Vec<_>, _>>()?;
+        specs.push(ColumnCleanSpec {
+            column: column.to_string(),
+            ops,
+        });
+    }
+    if specs.is_empty() {
+        return Err("clean spec is empty".into());
+    }
+    Ok(specs)
+}
+
+/// Apply `specs` to the text columns of `table_name` and report how many
+/// rows each column update changed. Nothing is written unless every column
+/// exists, is `VARCHAR` and updates cleanly.
+pub fn clean_columns(
+    db_path: &str,
+    table_name: &str,
+    specs: &[ColumnCleanSpec],
+) -> EngineResult<CleanColumnsResult> {
+    validate_table_name(table_name)?;
+    let schema = table_schema(db_path, table_name)?;
+    for spec in specs {
+        let Some(column) = schema
+            .iter()
+            .find(|c| c.name.eq_ignore_ascii_case(&spec.column))
+        else {
+            return Err(format!("table {table_name} has no column {}", spec.column).into());
+        };
+        if !column.data_type.eq_ignore_ascii_case("VARCHAR") {
+            return Err(format!(
+                "column {} is {}; clean_columns only edits VARCHAR columns",
+                column.name, column.data_type
+            )
+            .into());
+        }
+    }
+
+    let conn = open_connection(db_path)?;
+    conn.execute_batch("BEGIN TRANSACTION")?;
+    let mut columns = Vec::with_capacity(specs.len());
+    for spec in specs {
+        let quoted = format!(r#""{}""#, spec.column);
+        let expr = spec
+            .ops
+            .iter()
+            .fold(quoted.clone(), |expr, op| op.apply(&expr));
+        let sql = format!(
+            r#"UPDATE "{table_name}" SET {quoted} = {expr} WHERE {quoted} IS DISTINCT FROM {expr}"#
+        );
+        debug!(sql = %sql, "clean_columns: updating column");
+        match conn.execute(&sql, []) {
+            Ok(rows_changed) => columns.push(ColumnCleanSummary {
+                column: spec.column.clone(),
+                rows_changed,
+            }),
+            Err(err) => {
+                conn.execute_batch("ROLLBACK")?;
+                return Err(format!("cleaning column {} failed: {err}", spec.column).into());
+            }
+        }
+    }
+    conn.execute_batch("COMMIT")?;
+
+    info!(table = %table_name, columns = columns.len(), "clean_columns: complete");
+    Ok(CleanColumnsResult {
+        status: "ok",
+        table: table_name.to_string(),
+        columns,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{clean_columns, parse_clean_spec, ColumnCleanOp};
+    use duckdb::Connection;
+    use std::fs;
+    use std::time::{SystemTime, UNIX_EPOCH};
+
+    fn temp_db_with_people() -> String {
+        let suffix = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .expect("time")
+            .as_nanos();
+        let db_path = format!("/tmp/spatia_clean_test_{suffix}.duckdb");
+        let conn = Connection::open(&db_path).expect("open");
+        conn.execute_batch(
+            "CREATE TABLE people(id INTEGER, name VARCHAR, city VARCHAR); \
+             INSERT INTO people VALUES \
+                 (1, '  Ada ', 'Seattle'), (2, 'N/A', 'TACOMA'), (3, '', 'n/a'), (4, 'Bo', NULL);",
+        )
+        .expect("setup");
+        db_path
+    }
+
+    fn cleanup(db_path: &str) {
+        let _ = fs::remove_file(db_path);
+        let _ = fs::remove_file(format!("{db_path}.wal"));
+    }
+
+    fn column_values(db_path: &str, column: &str) -> Vec<Option<String>> {
+        let conn = Connection::open(db_path).expect("open");
+        let mut stmt = conn
+            .prepare(&format!("SELECT {column} FROM people ORDER BY id"))
+            .expect("prepare");
+        let mut rows = stmt.query([]).expect("query");
+        let mut values = Vec::new();
+        while let Some(row) = rows.next().expect("row") {
+            values.push(row.get(0).expect("value"));
+        }
+        values
+    }
+
+    fn run(db_path: &str, spec: &str) -> Vec<usize> {
+        let specs = parse_clean_spec(spec).expect("spec");
+        let result = clean_columns(db_path, "people", &specs).expect("clean");
+        result.columns.iter().map(|c| c.rows_changed).collect()
+    }
+
+    #[test]
+    fn trim_strips_surrounding_whitespace() {
+        let db_path = temp_db_with_people();
+        assert_eq!(run(&db_path, "name=trim"), vec![1]);
+        assert_eq!(column_values(&db_path, "name")[0].as_deref(), Some("Ada"));
+        cleanup(&db_path);
+    }
+
+    #[test]
+    fn null_if_replaces_exact_sentinels_including_empty() {
+        let db_path = temp_db_with_people();
+        assert_eq!(run(&db_path, "name=null_if:N/A,null_if:"), vec![2]);
+        assert_eq!(
+            column_values(&db_path, "name"),
+            vec![
+                Some("  Ada ".to_string()),
+                None,
+                None,
+                Some("Bo".to_string())
+            ]
+        );
+        cleanup(&db_path);
+    }
+
+    #[test]
+    fn lower_and_upper_change_case_in_order() {
+        let db_path = temp_db_with_people();
+        assert_eq!(run(&db_path, "city=lower,null_if:n/a"), vec![3]);
+        assert_eq!(
+            column_values(&db_path, "city"),
+            vec![
+                Some("seattle".to_string()),
+                Some("tacoma".to_string()),
+                None,
+                None
+            ]
+        );
+        assert_eq!(run(&db_path, "name=trim,upper"), vec![2]);
+        assert_eq!(column_values(&db_path, "name")[0].as_deref(), Some("ADA"));
+        cleanup(&db_path);
+    }
+
+    #[test]
+    fn rejects_bad_specs_and_non_text_columns() {
+        assert_eq!(
+            "null_if:NA".parse::<ColumnCleanOp>(),
+            Ok(ColumnCleanOp::NullIf("NA".to_string()))
+        );
+        assert!(parse_clean_spec("").is_err());
+        assert!(parse_clean_spec("name").is_err());
+        assert!(parse_clean_spec("name=titlecase").is_err());
+        assert!(parse_clean_spec("bad-name=trim").is_err());
+        assert!(parse_clean_spec("name=trim;NAME=lower").is_err());
+
+        let db_path = temp_db_with_people();
+        let specs = parse_clean_spec("id=trim").expect("spec");
+        let err = clean_columns(&db_path, "people", &specs).expect_err("integer column");
+        assert!(err.to_string().contains("only edits VARCHAR columns"));
+        let specs = parse_clean_spec("zip=trim").expect("spec");
+        assert!(clean_columns(&db_path, "people", &specs).is_err());
+        cleanup(&db_path);
+    }
+}
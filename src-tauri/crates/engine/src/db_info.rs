@@ -0,0 +1,130 @@
+use std::fs;
+use std::path::Path;
+
+use serde::Serialize;
+use tracing::{debug, info};
+
+use crate::{local_cache_table_name, open_connection, overture_release, EngineResult};
+
+/// File-level summary of a Spatia database.
+#[derive(Debug, Clone, Serialize)]
+pub struct DbInfo {
+    pub path: String,
+    pub size_bytes: u64,
+    pub table_count: usize,
+    pub tables: Vec<String>,
+    pub overture_release: String,
+    /// Whether the configured geocode cache table is one of `tables`; always
+    /// false when `SPATIA_GEOCODE_CACHE_DB` keeps the cache elsewhere.
+    pub has_geocode_cache: bool,
+}
+
+/// Stat the database file and list its tables.
+///
+/// Unlike most engine entry points this never creates the file: a missing
+/// path is an error rather than a fresh empty database.
+pub fn db_info(db_path: &str) -> EngineResult<DbInfo> {
+    debug!(db_path = %db_path, "db_info: collecting database metadata");
+    let path = Path::new(db_path);
+    if !path.is_file() {
+        return Err(format!("database file not found: {db_path}").into());
+    }
+    let size_bytes = fs::metadata(path)?.len();
+
+    let conn = open_connection(db_path)
+        .map_err(|err| format!("{db_path} is not a readable DuckDB database: {err}"))?;
+
+    let mut stmt = conn.prepare(
+        "SELECT table_name FROM information_schema.tables \
+         WHERE table_schema = 'main' AND table_type = 'BASE TABLE' \
+         ORDER BY table_name",
+    )?;
+    let mut rows = stmt.query([])?;
+    let mut tables = Vec::new();
+    while let Some(row) = rows.next()? {
+        tables.push(row.get::<_, String>(0)?);
+    }
+
+    let has_geocode_cache = match local_cache_table_name()? {
+        Some(cache_table) => tables.iter().any(|name| name.eq_ignore_ascii_case(&cache_table)),
+        None => false,
+    };
+    info!(
+        db_path = %db_path,
+        size_bytes,
+        table_count = tables.len(),
+        "db_info: collected"
+    );
+    Ok(DbInfo {
+        path: db_path.to_string(),
+        size_bytes,
+        table_count: tables.len(),
+        tables,
+        overture_release: overture_release(),
+        has_geocode_cache,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::db_info;
+    use duckdb::Connection;
+    use std::fs;
+    use std::time::{SystemTime, UNIX_EPOCH};
+
+    fn temp_path(ext: &str) -> String {
+        let suffix = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .expect("time")
+            .as_nanos();
+        format!("/tmp/spatia_db_info_test_{suffix}.{ext}")
+    }
+
+    fn cleanup_temp_db(db_path: &str) {
+        let _ = fs::remove_file(db_path);
+        let _ = fs::remove_file(format!("{db_path}.wal"));
+        let _ = fs::remove_file(format!("{db_path}.wal.lck"));
+    }
+
+    #[test]
+    fn reports_tables_and_geocode_cache() {
+        let db_path = temp_path("duckdb");
+        {
+            let conn = Connection::open(&db_path).expect("open");
+            conn.execute_batch(
+                "CREATE TABLE places (id INTEGER); \
+                 CREATE TABLE geocode_cache (address VARCHAR PRIMARY KEY); \
+                 CREATE VIEW analysis_result AS SELECT * FROM places;",
+            )
+            .expect("setup");
+        }
+
+        let info = db_info(&db_path).expect("db_info");
+        assert_eq!(info.tables, vec!["geocode_cache", "places"]);
+        assert_eq!(info.table_count, 2);
+        assert!(info.has_geocode_cache);
+        assert!(info.size_bytes > 0);
+        assert!(!info.overture_release.is_empty());
+
+        cleanup_temp_db(&db_path);
+    }
+
+    #[test]
+    fn missing_file_errors_without_creating_it() {
+        let db_path = temp_path("duckdb");
+        let err = db_info(&db_path).expect_err("should fail");
+        assert!(err.to_string().contains("database file not found"));
+        assert!(!std::path::Path::new(&db_path).exists());
+    }
+
+    #[test]
+    fn non_duckdb_file_errors() {
+        let path = temp_path("txt");
+        fs::write(&path, "id,city\n1,Oakland\n").expect("write");
+        let err = db_info(&path).expect_err("should fail");
+        assert!(err
+            .to_string()
+            .contains("is not a readable DuckDB database"));
+        cleanup_temp_db(&path);
+    }
+}
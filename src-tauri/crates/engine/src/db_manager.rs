@@ -1,20 +1,43 @@
 use duckdb::Connection;
 
+pub use spatia_geocode::{open_connection, SessionSettings, SharedConnection};
+
 use crate::EngineResult;
 
+/// Owns one workspace connection, opened through [`open_connection`] so it
+/// gets the session settings and the lock-contention retry.
 pub struct DbManager {
     conn: Connection,
+    path: Option<String>,
 }
 
 impl DbManager {
     pub fn open_file(path: &str) -> EngineResult<Self> {
-        let conn = Connection::open(path)?;
-        Ok(Self { conn })
+        let conn = open_connection(path)?;
+        Ok(Self {
+            conn,
+            path: Some(path.to_string()),
+        })
     }
 
     pub fn open_in_memory() -> EngineResult<Self> {
-        let conn = Connection::open_in_memory()?;
-        Ok(Self { conn })
+        let conn = spatia_geocode::open_in_memory()?;
+        Ok(Self { conn, path: None })
+    }
+
+    /// Path of the database file, or `None` for an in-memory database.
+    pub fn path(&self) -> Option<&str> {
+        self.path.as_deref()
+    }
+
+    /// Route [`open_connection`] calls for this database to clones of this
+    /// connection until the guard is dropped. In-memory databases have no
+    /// path to share and return `None`.
+    pub fn share(&self) -> EngineResult<Option<SharedConnection>> {
+        match &self.path {
+            Some(path) => Ok(Some(spatia_geocode::share_connection(path, &self.conn)?)),
+            None => Ok(None),
+        }
     }
 
     pub fn connection(&self) -> &Connection {
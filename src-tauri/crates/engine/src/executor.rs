@@ -1,18 +1,77 @@
+use std::fs;
+use std::io::Write;
+
+use serde_json::{json, Map, Value};
+use tracing::info;
+
 use crate::{
-    geocode_batch, ingest_csv, ingest_csv_to_table, overture_extract_to_table, overture_geocode,
-    overture_search, table_schema, BBox, EngineResult,
+    add_geometry_from_wkt, cache_ttl_days, clean_columns, components_from_string, coord_precision,
+    db_info, drop_view, export_cache, geocode_batch_dedupe, geocode_batch_normalized,
+    geocode_batch_with_providers, import_cache, ingest_csv_to_table_with_types,
+    ingest_csv_with_text_fallback, ingest_csv_with_types, list_tables, list_views, open_connection,
+    overture_extract_explain, overture_extract_to_table_filtered,
+    overture_extract_to_table_if_stale, overture_geocode_page, overture_index_extract,
+    overture_materialize_results, overture_query, overture_rebuild_lookup, overture_search_page,
+    overture_themes, parse_clean_spec, parse_coord_precision, partition_results, profile_column,
+    record_ingest_source, refresh_stale_cache, reverse_geocode_batch, round_coord, shell_tokenize,
+    spatial_join, table_json_schema, table_preview, table_schema, table_schema_projection,
+    table_schema_with_samples, unchanged_ingest_row_count, with_coord_precision, BBox,
+    ColumnCleanSpec, CommandParseError, DbManager, DivisionLevel, EngineResult, ExtractFilters,
+    ExtractRequirement, GeocodeSummary, IngestOptions, OvertureGeocodeResult, OvertureResultPage,
+    ProviderMode, SearchWeights,
 };
 
+/// Distinct values listed per column by `schema --samples`.
+const SCHEMA_SAMPLES_PER_COLUMN: usize = 5;
+
 #[derive(Debug, Clone, PartialEq)]
 enum Command {
     Ingest {
         db_path: String,
         csv_path: String,
         table_name: Option<String>,
+        column_types: Vec<(String, String)>,
+        wkt_column: Option<String>,
+        text_fallback: bool,
+        skip_unchanged: bool,
     },
     Schema {
         db_path: String,
         table_name: String,
+        columns: Option<Vec<String>>,
+        samples: bool,
+    },
+    ListTables {
+        db_path: String,
+        schema: String,
+    },
+    ListViews {
+        db_path: String,
+    },
+    DropView {
+        db_path: String,
+        view_name: String,
+    },
+    TablePreview {
+        db_path: String,
+        table_name: String,
+        limit: usize,
+    },
+    ProfileColumn {
+        db_path: String,
+        table_name: String,
+        column: String,
+    },
+    CleanColumns {
+        db_path: String,
+        table_name: String,
+        spec: Vec<ColumnCleanSpec>,
+    },
+    SpatialJoin {
+        db_path: String,
+        points_table: String,
+        polygons_table: String,
+        output_table: String,
     },
     OvertureExtract {
         db_path: String,
@@ -20,99 +79,652 @@ enum Command {
         item_type: String,
         bbox: BBox,
         table_name: Option<String>,
+        require: Vec<ExtractRequirement>,
+        level: Option<DivisionLevel>,
+        explain: bool,
+        skip_if_current: bool,
+        index: bool,
+        force: bool,
+        schema: Option<String>,
+        since: Option<String>,
     },
-    OvertureSearch {
+    OvertureThemes,
+    Overture(OvertureCommand),
+    OvertureRebuildLookup {
         db_path: String,
         table_name: String,
-        query: String,
-        limit: usize,
+        theme: String,
+    },
+    Geocode {
+        db_path: String,
+        addresses: Vec<String>,
+        input_file: Option<String>,
+        output_file: Option<String>,
+        summary: bool,
+        dedupe: bool,
+        normalize: bool,
+        providers: Option<ProviderMode>,
+        unresolved_only: bool,
+        precision: Option<u32>,
+    },
+    ReverseGeocode {
+        db_path: String,
+        points: Vec<(f64, f64)>,
+    },
+    GeocodeCacheExport {
+        db_path: String,
+        out_path: String,
     },
-    OvertureGeocode {
+    GeocodeCacheImport {
+        db_path: String,
+        in_path: String,
+    },
+    GeocodeCacheRefresh {
+        db_path: String,
+        max_age_days: Option<u32>,
+        providers: Option<ProviderMode>,
+    },
+    DbInfo {
+        db_path: String,
+    },
+    JsonSchema {
+        db_path: String,
+        table_name: String,
+    },
+}
+
+/// Overture commands, whose results `execute_command_to` serializes into
+/// the sink directly rather than through a `String`.
+#[derive(Debug, Clone, PartialEq)]
+enum OvertureCommand {
+    Search {
         db_path: String,
         table_name: String,
         query: String,
         limit: usize,
+        geojson: bool,
+        highlight: bool,
+        coords: bool,
+        ranking: SearchWeights,
+        into: Option<String>,
     },
     Geocode {
         db_path: String,
-        addresses: Vec<String>,
+        table_name: String,
+        query: String,
+        limit: usize,
+        geojson: bool,
+        into: Option<String>,
+        precision: Option<u32>,
+    },
+    Query {
+        theme: String,
+        item_type: String,
+        bbox: BBox,
+        query: String,
+        limit: usize,
     },
 }
 
 pub fn execute_command(command: &str) -> EngineResult<String> {
-    let parsed = parse_command(command)?;
+    let mut buffer = Vec::new();
+    execute_command_to(command, &mut buffer)?;
+    Ok(String::from_utf8(buffer)?)
+}
+
+/// Run `command` like [`execute_command`] on `manager`'s connection: while it
+/// runs, every open of the manager's database file reuses that connection
+/// instead of starting a second DuckDB instance on the file. Commands naming
+/// another database open it as usual.
+pub fn execute_command_with(manager: &DbManager, command: &str) -> EngineResult<String> {
+    let _shared = manager.share()?;
+    execute_command(command)
+}
+
+/// Run `command` like [`execute_command`], writing its output to `sink`
+/// instead of returning it. Overture search, geocode and query pages are
+/// serialized into the sink without an intermediate `String` (the page
+/// itself is still collected first); other commands are written once they
+/// finish.
+pub fn execute_command_to<W: Write>(command: &str, sink: &mut W) -> EngineResult<()> {
+    let parsed = parse_command(command).map_err(|err| CommandParseError(err.to_string()))?;
+    info!(
+        command = command.split_whitespace().next().unwrap_or_default(),
+        "execute_command: dispatching"
+    );
+    match parsed {
+        Command::Overture(overture) => write_overture(overture, sink),
+        other => {
+            let output = execute_buffered(other)?;
+            sink.write_all(output.as_bytes())?;
+            Ok(())
+        }
+    }
+}
+
+/// Serialize the result of an Overture command into `sink`.
+fn write_overture<W: Write>(command: OvertureCommand, sink: &mut W) -> EngineResult<()> {
+    match command {
+        OvertureCommand::Search {
+            db_path,
+            table_name,
+            query,
+            limit,
+            geojson,
+            highlight,
+            coords,
+            ranking,
+            into,
+        } => {
+            if geojson {
+                // Search results carry no coordinates, so the GeoJSON form
+                // goes through the geocode join against the base geometry.
+                let page = overture_geocode_page(&db_path, &table_name, &query, limit).map_err(
+                    |err| {
+                        format!(
+                            "overture_search --geojson requires {table_name} to have id and geometry columns: {err}"
+                        )
+                    },
+                )?;
+                let value = geocode_page_to_geojson(&page);
+                let value = match into {
+                    Some(into) => {
+                        let rows = overture_materialize_results(
+                            &db_path,
+                            &table_name,
+                            &into,
+                            &page.results,
+                        )?;
+                        with_into_summary(value, &into, rows)
+                    }
+                    None => value,
+                };
+                serde_json::to_writer(&mut *sink, &value)?;
+                return Ok(());
+            }
+            // A saved table is only useful with coordinates, so `--into`
+            // implies `--coords`.
+            let page = overture_search_page(
+                &db_path,
+                &table_name,
+                &query,
+                limit,
+                highlight,
+                coords || into.is_some(),
+                ranking,
+            )?;
+            match into {
+                Some(into) => {
+                    let results: Vec<OvertureGeocodeResult> = page
+                        .results
+                        .iter()
+                        .map(OvertureGeocodeResult::from)
+                        .collect();
+                    let rows =
+                        overture_materialize_results(&db_path, &table_name, &into, &results)?;
+                    serde_json::to_writer(
+                        &mut *sink,
+                        &with_into_summary(serde_json::to_value(&page)?, &into, rows),
+                    )?;
+                }
+                None => serde_json::to_writer(&mut *sink, &page)?,
+            }
+            Ok(())
+        }
+        OvertureCommand::Geocode {
+            db_path,
+            table_name,
+            query,
+            limit,
+            geojson,
+            into,
+            precision,
+        } => {
+            let mut page = overture_geocode_page(&db_path, &table_name, &query, limit)?;
+            // `--precision` wins over the environment. The GeoJSON form and
+            // the --into table are built from the rows, so they are rounded
+            // here as well as in serialization.
+            let digits = precision.or_else(coord_precision);
+            if let Some(digits) = digits {
+                for row in &mut page.results {
+                    row.lat = row.lat.map(|lat| round_coord(lat, digits));
+                    row.lon = row.lon.map(|lon| round_coord(lon, digits));
+                }
+            }
+            let value = if geojson {
+                geocode_page_to_geojson(&page)
+            } else {
+                with_coord_precision(digits, || serde_json::to_value(&page))?
+            };
+            let value = match into {
+                Some(into) => {
+                    let rows =
+                        overture_materialize_results(&db_path, &table_name, &into, &page.results)?;
+                    with_into_summary(value, &into, rows)
+                }
+                None => value,
+            };
+            serde_json::to_writer(&mut *sink, &value)?;
+            Ok(())
+        }
+        OvertureCommand::Query {
+            theme,
+            item_type,
+            bbox,
+            query,
+            limit,
+        } => {
+            let page = overture_query(&theme, &item_type, bbox, &query, limit)?;
+            serde_json::to_writer(&mut *sink, &page)?;
+            Ok(())
+        }
+    }
+}
+
+/// Commands whose output is built as a `String` before it is written. Overture
+/// commands are serialized into a buffer by [`write_overture`].
+fn execute_buffered(parsed: Command) -> EngineResult<String> {
     match parsed {
         Command::Ingest {
             db_path,
             csv_path,
             table_name,
+            column_types,
+            wkt_column,
+            text_fallback,
+            skip_unchanged,
         } => {
-            if let Some(table_name) = table_name {
-                ingest_csv_to_table(&db_path, &csv_path, &table_name)?;
-                Ok(format!(
-                    "{{\"status\":\"ok\",\"table\":\"{}\"}}",
-                    table_name
-                ))
+            let options = IngestOptions {
+                column_types: column_types.clone(),
+                wkt_column: wkt_column.clone(),
+                text_fallback,
+            };
+            if skip_unchanged {
+                if let Some(row_count) = unchanged_ingest_row_count(
+                    &db_path,
+                    &csv_path,
+                    table_name.as_deref(),
+                    &options,
+                )? {
+                    let payload = json!({
+                        "status": "skipped",
+                        "table": table_name.as_deref().unwrap_or("raw_staging"),
+                        "row_count": row_count,
+                    });
+                    return Ok(payload.to_string());
+                }
+            }
+            let source_table = table_name.clone();
+            let mut warnings = Vec::new();
+            let table_name = if text_fallback {
+                let outcome = ingest_csv_with_text_fallback(
+                    &db_path,
+                    &csv_path,
+                    table_name.as_deref(),
+                    &column_types,
+                )?;
+                warnings = outcome.warnings;
+                outcome.table
+            } else if let Some(table_name) = table_name {
+                ingest_csv_to_table_with_types(&db_path, &csv_path, &table_name, &column_types)?;
+                table_name
             } else {
-                ingest_csv(&db_path, &csv_path)?;
-                Ok("{\"status\":\"ok\",\"table\":\"raw_staging\"}".to_string())
+                ingest_csv_with_types(&db_path, &csv_path, &column_types)?;
+                "raw_staging".to_string()
+            };
+            if let Some(wkt_column) = wkt_column {
+                add_geometry_from_wkt(&db_path, &table_name, &wkt_column)?;
+            }
+            if skip_unchanged {
+                record_ingest_source(&db_path, &csv_path, source_table.as_deref(), &options)?;
             }
+            if !warnings.is_empty() {
+                let payload = json!({
+                    "status": "ok",
+                    "table": table_name,
+                    "loaded_as_text": true,
+                    "warnings": warnings,
+                });
+                return Ok(payload.to_string());
+            }
+            Ok(format!(
+                "{{\"status\":\"ok\",\"table\":\"{}\"}}",
+                table_name
+            ))
         }
         Command::Schema {
             db_path,
             table_name,
+            columns,
+            samples,
         } => {
-            let schema = table_schema(&db_path, &table_name)?;
-            let json = serde_json::to_string(&schema)?;
+            let json = match columns {
+                Some(columns) => serde_json::to_string(&table_schema_projection(
+                    &db_path,
+                    &table_name,
+                    &columns,
+                )?)?,
+                None if samples => serde_json::to_string(&table_schema_with_samples(
+                    &db_path,
+                    &table_name,
+                    SCHEMA_SAMPLES_PER_COLUMN,
+                )?)?,
+                None => serde_json::to_string(&table_schema(&db_path, &table_name)?)?,
+            };
             Ok(json)
         }
+        Command::ListTables { db_path, schema } => {
+            Ok(serde_json::to_string(&list_tables(&db_path, &schema)?)?)
+        }
+        Command::ListViews { db_path } => Ok(serde_json::to_string(&list_views(&db_path)?)?),
+        Command::DropView { db_path, view_name } => {
+            drop_view(&db_path, &view_name)?;
+            Ok(json!({ "status": "ok", "view": view_name }).to_string())
+        }
+        Command::TablePreview {
+            db_path,
+            table_name,
+            limit,
+        } => Ok(serde_json::to_string(&table_preview(
+            &db_path,
+            &table_name,
+            limit,
+        )?)?),
+        Command::ProfileColumn {
+            db_path,
+            table_name,
+            column,
+        } => Ok(serde_json::to_string(&profile_column(
+            &db_path,
+            &table_name,
+            &column,
+        )?)?),
+        Command::CleanColumns {
+            db_path,
+            table_name,
+            spec,
+        } => Ok(serde_json::to_string(&clean_columns(
+            &db_path,
+            &table_name,
+            &spec,
+        )?)?),
+        Command::SpatialJoin {
+            db_path,
+            points_table,
+            polygons_table,
+            output_table,
+        } => Ok(serde_json::to_string(&spatial_join(
+            &db_path,
+            &points_table,
+            &polygons_table,
+            &output_table,
+        )?)?),
         Command::OvertureExtract {
             db_path,
             theme,
             item_type,
             bbox,
             table_name,
+            require,
+            level,
+            explain,
+            skip_if_current,
+            index,
+            force,
+            schema,
+            since,
         } => {
-            let result = overture_extract_to_table(
+            let filters = ExtractFilters {
+                require,
+                level,
+                force,
+                schema,
+                since,
+            };
+            if explain {
+                let plan = overture_extract_explain(
+                    &theme,
+                    &item_type,
+                    bbox,
+                    table_name.as_deref(),
+                    &filters,
+                )?;
+                return Ok(serde_json::to_string(&plan)?);
+            }
+            let extract = if skip_if_current {
+                overture_extract_to_table_if_stale
+            } else {
+                overture_extract_to_table_filtered
+            };
+            let result = extract(
                 &db_path,
                 &theme,
                 &item_type,
                 bbox,
                 table_name.as_deref(),
+                &filters,
             )?;
-            let json = serde_json::to_string(&result)?;
+            if !index {
+                let json = serde_json::to_string(&result)?;
+                return Ok(json);
+            }
+            let indexes = overture_index_extract(&db_path, &result.table)?;
+            let mut value = serde_json::to_value(&result)?;
+            if let Value::Object(map) = &mut value {
+                map.insert("indexes".to_string(), json!(indexes));
+            }
+            let json = serde_json::to_string(&value)?;
             Ok(json)
         }
-        Command::OvertureSearch {
+        Command::OvertureThemes => Ok(serde_json::to_string(&overture_themes())?),
+        Command::OvertureRebuildLookup {
             db_path,
             table_name,
-            query,
-            limit,
+            theme,
         } => {
-            let result = overture_search(&db_path, &table_name, &query, limit)?;
+            let result = overture_rebuild_lookup(&db_path, &table_name, &theme)?;
             let json = serde_json::to_string(&result)?;
             Ok(json)
         }
-        Command::OvertureGeocode {
+        Command::Geocode {
             db_path,
-            table_name,
-            query,
-            limit,
+            mut addresses,
+            input_file,
+            output_file,
+            summary,
+            dedupe,
+            normalize,
+            providers,
+            unresolved_only,
+            precision,
+        } => {
+            if let Some(path) = input_file {
+                addresses.extend(read_address_file(&path)?);
+            }
+            if addresses.is_empty() {
+                return Err("geocode: no addresses to geocode".into());
+            }
+            let providers = match providers {
+                Some(providers) => providers,
+                None => ProviderMode::from_env()?,
+            };
+            let result = if normalize {
+                geocode_batch_normalized(&db_path, &addresses, providers)?
+            } else if dedupe {
+                geocode_batch_dedupe(&db_path, &addresses, providers)?
+            } else {
+                geocode_batch_with_providers(&db_path, &addresses, providers)?
+            };
+            // `--precision` wins over the environment default.
+            let digits = precision.or_else(coord_precision);
+            let json = with_coord_precision(digits, || -> EngineResult<String> {
+                if unresolved_only {
+                    let (results, stats) = result;
+                    let partition = partition_results(&addresses, results).unresolved_only();
+                    let mut payload = serde_json::to_value(&partition)?;
+                    if summary {
+                        payload["summary"] = json!(GeocodeSummary::from(&stats));
+                    }
+                    Ok(serde_json::to_string(&payload)?)
+                } else if summary {
+                    let (results, stats) = result;
+                    let payload = json!({
+                        "results": results,
+                        "summary": GeocodeSummary::from(&stats),
+                    });
+                    Ok(serde_json::to_string(&payload)?)
+                } else {
+                    Ok(serde_json::to_string(&result)?)
+                }
+            })?;
+            let Some(path) = output_file else {
+                return Ok(json);
+            };
+            fs::write(&path, &json)
+                .map_err(|err| format!("failed to write geocode output {path}: {err}"))?;
+            let payload = json!({
+                "status": "ok",
+                "output_file": path,
+                "count": addresses.len(),
+            });
+            Ok(serde_json::to_string(&payload)?)
+        }
+        Command::ReverseGeocode { db_path, points } => {
+            let results = reverse_geocode_batch(&db_path, &points)?;
+            let json = serde_json::to_string(&results)?;
+            Ok(json)
+        }
+        Command::GeocodeCacheExport { db_path, out_path } => {
+            let conn = open_connection(&db_path)?;
+            let count = export_cache(&conn, &out_path)?;
+            let payload = json!({
+                "status": "ok",
+                "output_file": out_path,
+                "count": count,
+            });
+            Ok(serde_json::to_string(&payload)?)
+        }
+        Command::GeocodeCacheImport { db_path, in_path } => {
+            let conn = open_connection(&db_path)?;
+            let summary = import_cache(&conn, &in_path)?;
+            let payload = json!({
+                "status": "ok",
+                "imported": summary.imported,
+                "skipped": summary.skipped,
+            });
+            Ok(serde_json::to_string(&payload)?)
+        }
+        Command::GeocodeCacheRefresh {
+            db_path,
+            max_age_days,
+            providers,
         } => {
-            let result = overture_geocode(&db_path, &table_name, &query, limit)?;
+            let max_age_days = match max_age_days {
+                Some(days) => days,
+                None => cache_ttl_days()?,
+            };
+            let providers = match providers {
+                Some(providers) => providers,
+                None => ProviderMode::from_env()?,
+            };
+            let summary = refresh_stale_cache(&db_path, max_age_days, providers)?;
+            let payload = json!({
+                "status": "ok",
+                "stale": summary.stale,
+                "refreshed": summary.refreshed,
+                "kept": summary.kept,
+                "max_age_days": summary.max_age_days,
+            });
+            Ok(serde_json::to_string(&payload)?)
+        }
+        Command::DbInfo { db_path } => {
+            let result = db_info(&db_path)?;
             let json = serde_json::to_string(&result)?;
             Ok(json)
         }
-        Command::Geocode { db_path, addresses } => {
-            let result = geocode_batch(&db_path, &addresses)?;
+        Command::JsonSchema {
+            db_path,
+            table_name,
+        } => {
+            let result = table_json_schema(&db_path, &table_name)?;
             let json = serde_json::to_string(&result)?;
             Ok(json)
         }
+        Command::Overture(overture) => {
+            let mut buffer = Vec::new();
+            write_overture(overture, &mut buffer)?;
+            Ok(String::from_utf8(buffer)?)
+        }
+    }
+}
+
+/// Build a GeoJSON FeatureCollection from Overture geocode matches.
+///
+/// Follows the same shape as the analysis result layer: one Point feature per
+/// row, with `geometry: null` when the match has no coordinates.
+fn geocode_results_to_geojson(results: &[OvertureGeocodeResult]) -> Value {
+    let features: Vec<Value> = results
+        .iter()
+        .map(|result| {
+            let mut props = Map::new();
+            props.insert(
+                "id".to_string(),
+                result.id.clone().map(Value::String).unwrap_or(Value::Null),
+            );
+            props.insert("label".to_string(), Value::String(result.label.clone()));
+
+            let components = components_from_string(&result.label);
+            for (key, value) in [
+                ("number", components.number),
+                ("state", components.state),
+                ("zip", components.zip),
+            ] {
+                props.insert(
+                    key.to_string(),
+                    value.map(Value::String).unwrap_or(Value::Null),
+                );
+            }
+
+            let geometry = match (result.lat, result.lon) {
+                (Some(lat), Some(lon)) => {
+                    json!({ "type": "Point", "coordinates": [lon, lat] })
+                }
+                _ => Value::Null,
+            };
+
+            json!({
+                "type": "Feature",
+                "geometry": geometry,
+                "properties": Value::Object(props),
+            })
+        })
+        .collect();
+
+    json!({
+        "type": "FeatureCollection",
+        "features": features,
+    })
+}
+
+/// Record a `--into` write on a search or geocode reply as
+/// `"into": {"table", "rows"}`.
+fn with_into_summary(mut value: Value, table: &str, rows: usize) -> Value {
+    if let Value::Object(map) = &mut value {
+        map.insert("into".to_string(), json!({ "table": table, "rows": rows }));
     }
+    value
+}
+
+/// [`geocode_results_to_geojson`] with the page's `truncated` flag added as a
+/// top-level member of the FeatureCollection.
+fn geocode_page_to_geojson(page: &OvertureResultPage<OvertureGeocodeResult>) -> Value {
+    let mut collection = geocode_results_to_geojson(&page.results);
+    collection["truncated"] = Value::Bool(page.truncated);
+    collection
 }
 
 fn parse_command(command: &str) -> EngineResult<Command> {
-    let tokens = tokenize(command)?;
+    let tokens = shell_tokenize(command)?;
     let Some(name) = tokens.first().map(String::as_str) else {
         return Err("Command cannot be empty".into());
     };
@@ -120,17 +732,41 @@ fn parse_command(command: &str) -> EngineResult<Command> {
     match name {
         "ingest" => parse_ingest(&tokens),
         "schema" => parse_schema(&tokens),
+        "list_tables" => parse_list_tables(&tokens),
+        "list_views" => parse_list_views(&tokens),
+        "drop_view" => parse_drop_view(&tokens),
+        "table_preview" => parse_table_preview(&tokens),
+        "profile_column" => parse_profile_column(&tokens),
+        "clean_columns" => parse_clean_columns(&tokens),
+        "spatial_join" => parse_spatial_join(&tokens),
         "overture_extract" => parse_overture_extract(&tokens),
+        "overture_themes" => parse_overture_themes(&tokens),
         "overture_search" => parse_overture_search(&tokens),
         "overture_geocode" => parse_overture_geocode(&tokens),
+        "overture_query" => parse_overture_query(&tokens),
+        "overture_rebuild_lookup" => parse_overture_rebuild_lookup(&tokens),
         "geocode" => parse_geocode(&tokens),
+        "reverse_geocode" => parse_reverse_geocode(&tokens),
+        "geocode_cache_export" => parse_geocode_cache_export(&tokens),
+        "geocode_cache_import" => parse_geocode_cache_import(&tokens),
+        "geocode_cache_refresh" => parse_geocode_cache_refresh(&tokens),
+        "db_info" => parse_db_info(&tokens),
+        "json_schema" => parse_json_schema(&tokens),
         _ => Err(format!("Unknown command: {name}").into()),
     }
 }
 
 fn parse_ingest(tokens: &[String]) -> EngineResult<Command> {
+    let (tokens, column_types) = take_column_types(tokens)?;
+    let (tokens, wkt_column) = take_option(&tokens, "--wkt")?;
+    let (tokens, text_fallback) = take_flag(&tokens, "--text-fallback");
+    let (tokens, skip_unchanged) = take_flag(&tokens, "--skip-unchanged");
     if !(tokens.len() == 3 || tokens.len() == 4) {
-        return Err("Usage: ingest <db_path> <csv_path> [table_name]".into());
+        return Err(
+            "Usage: ingest <db_path> <csv_path> [table_name] [--type column:TYPE ...] \
+             [--wkt column] [--text-fallback] [--skip-unchanged]"
+                .into(),
+        );
     }
     let db_path = tokens[1].clone();
     let csv_path = tokens[2].clone();
@@ -140,126 +776,532 @@ fn parse_ingest(tokens: &[String]) -> EngineResult<Command> {
         db_path,
         csv_path,
         table_name,
+        column_types,
+        wkt_column,
+        text_fallback,
+        skip_unchanged,
     })
 }
 
+/// Pull every `--type column:TYPE` pair out of `tokens`. Type names are
+/// validated later by the ingest crate.
+fn take_column_types(tokens: &[String]) -> EngineResult<(Vec<String>, Vec<(String, String)>)> {
+    let mut remaining = Vec::with_capacity(tokens.len());
+    let mut column_types = Vec::new();
+    let mut iter = tokens.iter();
+    while let Some(token) = iter.next() {
+        if token != "--type" {
+            remaining.push(token.clone());
+            continue;
+        }
+        let Some(spec) = iter.next() else {
+            return Err("--type requires a column:TYPE argument".into());
+        };
+        let Some((column, data_type)) = spec.rsplit_once(':') else {
+            return Err(format!("--type expects column:TYPE, got '{spec}'").into());
+        };
+        column_types.push((column.to_string(), data_type.to_string()));
+    }
+    Ok((remaining, column_types))
+}
+
 fn parse_schema(tokens: &[String]) -> EngineResult<Command> {
-    if tokens.len() != 3 {
-        return Err("Usage: schema <db_path> <table_name>".into());
+    let (tokens, samples) = take_flag(tokens, "--samples");
+    let columns = match tokens.as_slice() {
+        [_, _, _] => None,
+        [_, _, _, flag, list] if flag == "--columns" => Some(
+            list.split(',')
+                .map(str::trim)
+                .filter(|name| !name.is_empty())
+                .map(str::to_string)
+                .collect::<Vec<_>>(),
+        ),
+        _ => {
+            return Err(
+                "Usage: schema <db_path> <table_name> [--columns col1,col2,...] [--samples]".into(),
+            )
+        }
+    };
+    if columns.as_ref().is_some_and(Vec::is_empty) {
+        return Err("--columns requires at least one column name".into());
+    }
+    if samples && columns.is_some() {
+        return Err("--samples cannot be combined with --columns".into());
     }
     Ok(Command::Schema {
         db_path: tokens[1].clone(),
         table_name: tokens[2].clone(),
+        columns,
+        samples,
+    })
+}
+
+fn parse_list_tables(tokens: &[String]) -> EngineResult<Command> {
+    let (tokens, schema) = take_option(tokens, "--schema")?;
+    if tokens.len() != 2 {
+        return Err("Usage: list_tables <db_path> [--schema <name>]".into());
+    }
+    Ok(Command::ListTables {
+        db_path: tokens[1].clone(),
+        schema: schema.unwrap_or_else(|| "main".to_string()),
+    })
+}
+
+fn parse_list_views(tokens: &[String]) -> EngineResult<Command> {
+    if tokens.len() != 2 {
+        return Err("Usage: list_views <db_path>".into());
+    }
+    Ok(Command::ListViews {
+        db_path: tokens[1].clone(),
+    })
+}
+
+fn parse_drop_view(tokens: &[String]) -> EngineResult<Command> {
+    if tokens.len() != 3 {
+        return Err("Usage: drop_view <db_path> <view_name>".into());
+    }
+    Ok(Command::DropView {
+        db_path: tokens[1].clone(),
+        view_name: tokens[2].clone(),
+    })
+}
+
+fn parse_table_preview(tokens: &[String]) -> EngineResult<Command> {
+    if !(tokens.len() == 3 || tokens.len() == 4) {
+        return Err("Usage: table_preview <db_path> <table_name> [n]".into());
+    }
+    Ok(Command::TablePreview {
+        db_path: tokens[1].clone(),
+        table_name: tokens[2].clone(),
+        limit: parse_limit(tokens.get(3))?,
+    })
+}
+
+fn parse_profile_column(tokens: &[String]) -> EngineResult<Command> {
+    if tokens.len() != 4 {
+        return Err("Usage: profile_column <db_path> <table_name> <column>".into());
+    }
+    Ok(Command::ProfileColumn {
+        db_path: tokens[1].clone(),
+        table_name: tokens[2].clone(),
+        column: tokens[3].clone(),
+    })
+}
+
+fn parse_clean_columns(tokens: &[String]) -> EngineResult<Command> {
+    if tokens.len() != 4 {
+        return Err("Usage: clean_columns <db_path> <table_name> \
+             <column=trim|null_if:VALUE|lower|upper[,...][;column=...]>"
+            .into());
+    }
+    Ok(Command::CleanColumns {
+        db_path: tokens[1].clone(),
+        table_name: tokens[2].clone(),
+        spec: parse_clean_spec(&tokens[3])?,
+    })
+}
+
+fn parse_spatial_join(tokens: &[String]) -> EngineResult<Command> {
+    if tokens.len() != 5 {
+        return Err(
+            "Usage: spatial_join <db_path> <points_table> <polygons_table> <output_table>".into(),
+        );
+    }
+    Ok(Command::SpatialJoin {
+        db_path: tokens[1].clone(),
+        points_table: tokens[2].clone(),
+        polygons_table: tokens[3].clone(),
+        output_table: tokens[4].clone(),
     })
 }
 
 fn parse_overture_extract(tokens: &[String]) -> EngineResult<Command> {
+    let (tokens, explain) = take_flag(tokens, "--explain");
+    let (tokens, skip_if_current) = take_flag(&tokens, "--skip-if-current");
+    let (tokens, index) = take_flag(&tokens, "--index");
+    let (tokens, force) = take_flag(&tokens, "--force");
+    let (tokens, projected) = take_flag(&tokens, "--projected");
+    let (tokens, schema) = take_option(&tokens, "--schema")?;
+    let (tokens, since) = take_option(&tokens, "--since")?;
+    let (tokens, require) = take_requirements(&tokens)?;
+    let (tokens, level) = take_level(&tokens)?;
     if !(tokens.len() == 5 || tokens.len() == 6) {
         return Err(
-            "Usage: overture_extract <db_path> <theme> <type> <xmin,ymin,xmax,ymax> [table_name]"
+            "Usage: overture_extract <db_path> <theme> <type> <xmin,ymin,xmax,ymax> \
+             [table_name] [--require names|geometry ...] \
+             [--level country|region|county|locality] [--skip-if-current] [--index] [--force] \
+             [--schema <name>] [--since YYYY-MM-DD] [--projected] [--explain]"
                 .into(),
         );
     }
-    let bbox = BBox::parse(&tokens[4])?;
+    if level.is_some() && tokens[2] != "divisions" {
+        return Err(format!(
+            "--level only applies to the divisions theme, not '{}'",
+            tokens[2]
+        )
+        .into());
+    }
+    let bbox = parse_bbox(&tokens[4], projected)?;
     Ok(Command::OvertureExtract {
         db_path: tokens[1].clone(),
         theme: tokens[2].clone(),
         item_type: tokens[3].clone(),
         bbox,
         table_name: tokens.get(5).cloned(),
+        require,
+        level,
+        explain,
+        skip_if_current,
+        index,
+        force,
+        schema,
+        since,
     })
 }
 
+/// A lon/lat bbox, or with `--projected` one in any CRS (no range check).
+fn parse_bbox(input: &str, projected: bool) -> EngineResult<BBox> {
+    if projected {
+        BBox::parse_projected(input)
+    } else {
+        BBox::parse(input)
+    }
+}
+
+fn parse_overture_themes(tokens: &[String]) -> EngineResult<Command> {
+    if tokens.len() != 1 {
+        return Err("Usage: overture_themes".into());
+    }
+    Ok(Command::OvertureThemes)
+}
+
+/// Pull a single `--level <division level>` option out of `tokens`.
+fn take_level(tokens: &[String]) -> EngineResult<(Vec<String>, Option<DivisionLevel>)> {
+    let (remaining, value) = take_option(tokens, "--level")?;
+    let level = value
+        .map(|value| value.parse::<DivisionLevel>())
+        .transpose()?;
+    Ok((remaining, level))
+}
+
+/// Pull a single `<option> <value>` pair out of `tokens`.
+fn take_option(tokens: &[String], option: &str) -> EngineResult<(Vec<String>, Option<String>)> {
+    let mut remaining = Vec::with_capacity(tokens.len());
+    let mut value = None;
+    let mut iter = tokens.iter();
+    while let Some(token) = iter.next() {
+        if token != option {
+            remaining.push(token.clone());
+            continue;
+        }
+        let Some(next) = iter.next() else {
+            return Err(format!("{option} requires a value").into());
+        };
+        if value.is_some() {
+            return Err(format!("{option} may only be given once").into());
+        }
+        value = Some(next.clone());
+    }
+    Ok((remaining, value))
+}
+
+/// Pull repeatable `--require names|geometry` options out of `tokens`.
+fn take_requirements(tokens: &[String]) -> EngineResult<(Vec<String>, Vec<ExtractRequirement>)> {
+    let mut remaining = Vec::with_capacity(tokens.len());
+    let mut require = Vec::new();
+    let mut iter = tokens.iter();
+    while let Some(token) = iter.next() {
+        if token != "--require" {
+            remaining.push(token.clone());
+            continue;
+        }
+        let Some(value) = iter.next() else {
+            return Err("--require expects names or geometry".into());
+        };
+        let requirement: ExtractRequirement = value.parse()?;
+        if !require.contains(&requirement) {
+            require.push(requirement);
+        }
+    }
+    Ok((remaining, require))
+}
+
 fn parse_overture_search(tokens: &[String]) -> EngineResult<Command> {
+    let (tokens, geojson) = take_flag(tokens, "--geojson");
+    let (tokens, highlight) = take_flag(&tokens, "--highlight");
+    let (tokens, coords) = take_flag(&tokens, "--coords");
+    let (tokens, ranking) = take_option(&tokens, "--ranking")?;
+    let (tokens, into) = take_option(&tokens, "--into")?;
     if !(tokens.len() == 4 || tokens.len() == 5) {
-        return Err("Usage: overture_search <db_path> <table_name> <query> [limit]".into());
+        return Err(
+            "Usage: overture_search <db_path> <table_name> <query> [limit] [--geojson] \
+             [--highlight] [--coords] [--ranking balanced|prefix_heavy|exact_only] \
+             [--into <table>]"
+                .into(),
+        );
     }
-
-    let limit = if let Some(value) = tokens.get(4) {
-        value.parse::<usize>()?
-    } else {
-        20
+    // The GeoJSON form is built from the geocode join, which has its own
+    // ranking and no highlight markup, so these options would be ignored.
+    if geojson && (highlight || coords || ranking.is_some()) {
+        return Err(
+            "overture_search --geojson cannot be combined with --highlight, --coords or --ranking"
+                .into(),
+        );
+    }
+    let ranking = match ranking {
+        Some(name) => name.parse::<SearchWeights>()?,
+        None => SearchWeights::default(),
     };
 
-    Ok(Command::OvertureSearch {
+    let limit = parse_limit(tokens.get(4))?;
+
+    Ok(Command::Overture(OvertureCommand::Search {
         db_path: tokens[1].clone(),
         table_name: tokens[2].clone(),
         query: tokens[3].clone(),
         limit,
-    })
+        geojson,
+        highlight,
+        coords,
+        ranking,
+        into,
+    }))
 }
 
 fn parse_overture_geocode(tokens: &[String]) -> EngineResult<Command> {
+    let (tokens, geojson) = take_flag(tokens, "--geojson");
+    let (tokens, into) = take_option(&tokens, "--into")?;
+    let (tokens, precision) = take_option(&tokens, "--precision")?;
     if !(tokens.len() == 4 || tokens.len() == 5) {
-        return Err("Usage: overture_geocode <db_path> <table_name> <query> [limit]".into());
+        return Err(
+            "Usage: overture_geocode <db_path> <table_name> <query> [limit] [--geojson] \
+             [--into <table>] [--precision <digits>]"
+                .into(),
+        );
     }
+    let precision = precision
+        .map(|value| parse_coord_precision(&value))
+        .transpose()?;
 
-    let limit = if let Some(value) = tokens.get(4) {
-        value.parse::<usize>()?
-    } else {
-        20
-    };
+    let limit = parse_limit(tokens.get(4))?;
 
-    Ok(Command::OvertureGeocode {
+    Ok(Command::Overture(OvertureCommand::Geocode {
         db_path: tokens[1].clone(),
         table_name: tokens[2].clone(),
         query: tokens[3].clone(),
         limit,
-    })
+        geojson,
+        into,
+        precision,
+    }))
 }
 
-fn parse_geocode(tokens: &[String]) -> EngineResult<Command> {
-    if tokens.len() < 3 {
-        return Err("Usage: geocode <db_path> <address> [address2...]".into());
+fn parse_overture_query(tokens: &[String]) -> EngineResult<Command> {
+    let (tokens, projected) = take_flag(tokens, "--projected");
+    if !(tokens.len() == 5 || tokens.len() == 6) {
+        return Err(
+            "Usage: overture_query <theme> <type> <xmin,ymin,xmax,ymax> <query> [limit] \
+             [--projected]"
+                .into(),
+        );
     }
-    Ok(Command::Geocode {
-        db_path: tokens[1].clone(),
-        addresses: tokens[2..].to_vec(),
-    })
-}
-
-fn tokenize(command: &str) -> EngineResult<Vec<String>> {
-    let mut tokens = Vec::new();
-    let mut current = String::new();
-    let mut in_quote: Option<char> = None;
+    let bbox = parse_bbox(&tokens[3], projected)?;
+    let limit = parse_limit(tokens.get(5))?;
 
-    for ch in command.chars() {
-        match in_quote {
-            Some(quote) => {
-                if ch == quote {
-                    in_quote = None;
-                } else {
-                    current.push(ch);
-                }
-            }
-            None => {
-                if ch == '"' || ch == '\'' {
-                    in_quote = Some(ch);
-                } else if ch.is_whitespace() {
-                    if !current.is_empty() {
-                        tokens.push(std::mem::take(&mut current));
-                    }
-                } else {
-                    current.push(ch);
-                }
-            }
-        }
+    Ok(Command::Overture(OvertureCommand::Query {
+        theme: tokens[1].clone(),
+        item_type: tokens[2].clone(),
+        bbox,
+        query: tokens[4].clone(),
+        limit,
+    }))
+}
+
+fn parse_overture_rebuild_lookup(tokens: &[String]) -> EngineResult<Command> {
+    if tokens.len() != 4 {
+        return Err("Usage: overture_rebuild_lookup <db_path> <table_name> <theme>".into());
+    }
+
+    Ok(Command::OvertureRebuildLookup {
+        db_path: tokens[1].clone(),
+        table_name: tokens[2].clone(),
+        theme: tokens[3].clone(),
+    })
+}
+
+/// Parse the optional `[limit]` argument, defaulting to 20.
+fn parse_limit(value: Option<&String>) -> EngineResult<usize> {
+    let Some(value) = value else {
+        return Ok(20);
+    };
+    match value.parse::<usize>() {
+        Ok(limit) if limit > 0 => Ok(limit),
+        _ => Err(format!("limit must be a positive integer, got '{value}'").into()),
+    }
+}
+
+fn parse_geocode(tokens: &[String]) -> EngineResult<Command> {
+    let (tokens, summary) = take_flag(tokens, "--summary");
+    let (tokens, dedupe) = take_flag(&tokens, "--dedupe");
+    let (tokens, normalize) = take_flag(&tokens, "--normalize");
+    let (tokens, unresolved_only) = take_flag(&tokens, "--unresolved-only");
+    let (tokens, input_file) = take_option(&tokens, "--input-file")?;
+    let (tokens, output_file) = take_option(&tokens, "--output-file")?;
+    let (tokens, providers) = take_option(&tokens, "--providers")?;
+    let (tokens, precision) = take_option(&tokens, "--precision")?;
+    let min_tokens = if input_file.is_some() { 2 } else { 3 };
+    if tokens.len() < min_tokens {
+        return Err("Usage: geocode <db_path> <address> [address2...] \
+                    [--input-file <path>] [--output-file <path>] [--summary] [--dedupe] \
+                    [--normalize] [--unresolved-only] [--precision <digits>] \
+                    [--providers auto|free_only|geocodio_only|cache_only]"
+            .into());
+    }
+    let providers = providers
+        .map(|value| value.parse::<ProviderMode>())
+        .transpose()?;
+    let precision = precision
+        .map(|value| parse_coord_precision(&value))
+        .transpose()?;
+    Ok(Command::Geocode {
+        db_path: tokens[1].clone(),
+        addresses: tokens[2..].to_vec(),
+        input_file,
+        output_file,
+        summary,
+        dedupe,
+        normalize,
+        providers,
+        unresolved_only,
+        precision,
+    })
+}
+
+/// One address per line; surrounding whitespace is trimmed and blank lines
+/// are skipped.
+fn read_address_file(path: &str) -> EngineResult<Vec<String>> {
+    let contents = fs::read_to_string(path)
+        .map_err(|err| format!("failed to read address file {path}: {err}"))?;
+    Ok(contents
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .map(str::to_string)
+        .collect())
+}
+
+fn parse_reverse_geocode(tokens: &[String]) -> EngineResult<Command> {
+    if tokens.len() < 3 {
+        return Err("Usage: reverse_geocode <db_path> <lat,lon> [lat,lon...]".into());
+    }
+    let points = tokens[2..]
+        .iter()
+        .map(|token| parse_point(token))
+        .collect::<EngineResult<Vec<_>>>()?;
+    Ok(Command::ReverseGeocode {
+        db_path: tokens[1].clone(),
+        points,
+    })
+}
+
+/// Parse a `lat,lon` pair, checking both are on the globe.
+fn parse_point(token: &str) -> EngineResult<(f64, f64)> {
+    let invalid = || format!("point must be lat,lon, got '{token}'");
+    let (lat, lon) = token.split_once(',').ok_or_else(invalid)?;
+    let lat: f64 = lat.trim().parse().map_err(|_| invalid())?;
+    let lon: f64 = lon.trim().parse().map_err(|_| invalid())?;
+    if !(-90.0..=90.0).contains(&lat) || !(-180.0..=180.0).contains(&lon) {
+        return Err(format!(
+            "point {token} is out of range: lat must be in [-90, 90] and lon in [-180, 180]"
+        )
+        .into());
+    }
+    Ok((lat, lon))
+}
+
+fn parse_geocode_cache_export(tokens: &[String]) -> EngineResult<Command> {
+    if tokens.len() != 3 {
+        return Err("Usage: geocode_cache_export <db_path> <out_path.csv|.json>".into());
+    }
+    Ok(Command::GeocodeCacheExport {
+        db_path: tokens[1].clone(),
+        out_path: tokens[2].clone(),
+    })
+}
+
+fn parse_geocode_cache_import(tokens: &[String]) -> EngineResult<Command> {
+    if tokens.len() != 3 {
+        return Err("Usage: geocode_cache_import <db_path> <in_path.csv|.json>".into());
+    }
+    Ok(Command::GeocodeCacheImport {
+        db_path: tokens[1].clone(),
+        in_path: tokens[2].clone(),
+    })
+}
+
+fn parse_geocode_cache_refresh(tokens: &[String]) -> EngineResult<Command> {
+    let (tokens, max_age_days) = take_option(tokens, "--max-age-days")?;
+    let (tokens, providers) = take_option(&tokens, "--providers")?;
+    if tokens.len() != 2 {
+        return Err(
+            "Usage: geocode_cache_refresh <db_path> [--max-age-days <days>] \
+             [--providers auto|free_only|geocodio_only|cache_only]"
+                .into(),
+        );
     }
+    let max_age_days = max_age_days
+        .map(|value| {
+            value.parse::<u32>().map_err(|_| {
+                format!("--max-age-days must be a whole number of days, got '{value}'")
+            })
+        })
+        .transpose()?;
+    let providers = providers
+        .map(|value| value.parse::<ProviderMode>())
+        .transpose()?;
+    Ok(Command::GeocodeCacheRefresh {
+        db_path: tokens[1].clone(),
+        max_age_days,
+        providers,
+    })
+}
 
-    if in_quote.is_some() {
-        return Err("Unterminated quoted string".into());
+fn parse_db_info(tokens: &[String]) -> EngineResult<Command> {
+    if tokens.len() != 2 {
+        return Err("Usage: db_info <db_path>".into());
     }
+    Ok(Command::DbInfo {
+        db_path: tokens[1].clone(),
+    })
+}
 
-    if !current.is_empty() {
-        tokens.push(current);
+fn parse_json_schema(tokens: &[String]) -> EngineResult<Command> {
+    if tokens.len() != 3 {
+        return Err("Usage: json_schema <db_path> <table_name>".into());
     }
+    Ok(Command::JsonSchema {
+        db_path: tokens[1].clone(),
+        table_name: tokens[2].clone(),
+    })
+}
 
-    Ok(tokens)
+/// Remove every occurrence of `flag` from `tokens`, returning the remaining
+/// tokens and whether the flag was present.
+fn take_flag(tokens: &[String], flag: &str) -> (Vec<String>, bool) {
+    let remaining: Vec<String> = tokens.iter().filter(|t| *t != flag).cloned().collect();
+    let present = remaining.len() != tokens.len();
+    (remaining, present)
 }
 
 #[cfg(test)]
 mod tests {
-    use super::{execute_command, parse_command, Command};
+    use super::{
+        execute_command, geocode_results_to_geojson, parse_command, read_address_file, Command,
+    };
+    use crate::{
+        cache_store, classify_error, open_connection, ColumnCleanOp, DivisionLevel, ErrorKind,
+        ExtractRequirement, GeocodeResult, OvertureGeocodeResult, ProviderMode, SearchWeights,
+    };
     use std::fs;
     use std::io::Write;
     use std::time::{SystemTime, UNIX_EPOCH};
@@ -273,6 +1315,10 @@ mod tests {
                 db_path: "./db.duckdb".to_string(),
                 csv_path: "./data.csv".to_string(),
                 table_name: Some("places".to_string()),
+                column_types: vec![],
+                wkt_column: None,
+                text_fallback: false,
+                skip_unchanged: false,
             }
         );
     }
@@ -286,10 +1332,78 @@ mod tests {
                 db_path: "./db.duckdb".to_string(),
                 csv_path: "./data.csv".to_string(),
                 table_name: None,
+                column_types: vec![],
+                wkt_column: None,
+                text_fallback: false,
+                skip_unchanged: false,
+            }
+        );
+    }
+
+    #[test]
+    fn parse_ingest_with_repeated_type_flags() {
+        let command = parse_command(
+            "ingest ./db.duckdb ./data.csv places --type zip:VARCHAR --type \"policy id:BIGINT\"",
+        )
+        .expect("parse");
+        assert_eq!(
+            command,
+            Command::Ingest {
+                db_path: "./db.duckdb".to_string(),
+                csv_path: "./data.csv".to_string(),
+                table_name: Some("places".to_string()),
+                column_types: vec![
+                    ("zip".to_string(), "VARCHAR".to_string()),
+                    ("policy id".to_string(), "BIGINT".to_string()),
+                ],
+                wkt_column: None,
+                text_fallback: false,
+                skip_unchanged: false,
+            }
+        );
+    }
+
+    #[test]
+    fn parse_ingest_with_wkt_column() {
+        let command =
+            parse_command("ingest ./db.duckdb ./sites.csv sites --wkt geom_wkt").expect("parse");
+        assert_eq!(
+            command,
+            Command::Ingest {
+                db_path: "./db.duckdb".to_string(),
+                csv_path: "./sites.csv".to_string(),
+                table_name: Some("sites".to_string()),
+                column_types: vec![],
+                wkt_column: Some("geom_wkt".to_string()),
+                text_fallback: false,
+                skip_unchanged: false,
             }
         );
     }
 
+    #[test]
+    fn parse_ingest_with_text_fallback() {
+        let command =
+            parse_command("ingest ./db.duckdb ./dirty.csv --text-fallback dirty").expect("parse");
+        match command {
+            Command::Ingest {
+                table_name,
+                text_fallback,
+                ..
+            } => {
+                assert_eq!(table_name.as_deref(), Some("dirty"));
+                assert!(text_fallback);
+            }
+            _ => panic!("expected ingest command"),
+        }
+    }
+
+    #[test]
+    fn parse_ingest_type_flag_requires_column_and_type() {
+        let err = parse_command("ingest ./db.duckdb ./data.csv --type zip").expect_err("fail");
+        assert!(err.to_string().contains("--type expects column:TYPE"));
+    }
+
     #[test]
     fn parse_overture_extract_with_bbox() {
         let command = parse_command(
@@ -315,84 +1429,1061 @@ mod tests {
     }
 
     #[test]
-    fn parse_overture_search_with_limit() {
-        let command = parse_command("overture_search ./spatia.duckdb places_wa \"lincoln\" 5")
-            .expect("parse");
+    fn execute_command_reports_parse_errors_as_parse_kind() {
+        let err = execute_command("db_info").expect_err("should fail");
+        assert_eq!(classify_error(err.as_ref()), ErrorKind::Parse);
+        assert!(err.to_string().starts_with("Usage: db_info"));
+    }
+
+    #[test]
+    fn parse_overture_extract_explain_flag() {
+        let command = parse_command(
+            "overture_extract ./spatia.duckdb places place -122.4,47.5,-122.2,47.7 --explain",
+        )
+        .expect("parse");
+        match command {
+            Command::OvertureExtract {
+                table_name,
+                explain,
+                skip_if_current,
+                ..
+            } => {
+                assert!(explain);
+                assert!(!skip_if_current);
+                assert_eq!(table_name, None);
+            }
+            _ => panic!("expected overture extract command"),
+        }
+    }
+
+    #[test]
+    fn parse_overture_extract_require_options() {
+        let command = parse_command(
+            "overture_extract ./spatia.duckdb places place 0,0,1,1 places_wa \
+             --require names --require geometry --require names",
+        )
+        .expect("parse");
+        match command {
+            Command::OvertureExtract {
+                table_name,
+                require,
+                ..
+            } => {
+                assert_eq!(table_name.as_deref(), Some("places_wa"));
+                assert_eq!(
+                    require,
+                    vec![ExtractRequirement::Names, ExtractRequirement::Geometry]
+                );
+            }
+            _ => panic!("expected overture extract command"),
+        }
+
+        let err = parse_command("overture_extract ./db places place 0,0,1,1 --require bbox")
+            .expect_err("should fail");
+        assert!(err.to_string().contains("unknown extract requirement"));
+    }
+
+    #[test]
+    fn parse_overture_extract_skip_if_current_flag() {
+        let command =
+            parse_command("overture_extract ./db places place 0,0,1,1 places_wa --skip-if-current")
+                .expect("parse");
+        match command {
+            Command::OvertureExtract {
+                table_name,
+                skip_if_current,
+                ..
+            } => {
+                assert!(skip_if_current);
+                assert_eq!(table_name.as_deref(), Some("places_wa"));
+            }
+            _ => panic!("expected overture extract command"),
+        }
+    }
+
+    #[test]
+    fn parse_spatial_join_tables() {
+        let command =
+            parse_command("spatial_join ./db stores regions stores_by_region").expect("parse");
         assert_eq!(
             command,
-            Command::OvertureSearch {
-                db_path: "./spatia.duckdb".to_string(),
-                table_name: "places_wa".to_string(),
-                query: "lincoln".to_string(),
-                limit: 5,
+            Command::SpatialJoin {
+                db_path: "./db".to_string(),
+                points_table: "stores".to_string(),
+                polygons_table: "regions".to_string(),
+                output_table: "stores_by_region".to_string(),
             }
         );
+        assert!(parse_command("spatial_join ./db stores regions").is_err());
     }
 
     #[test]
-    fn parse_overture_geocode_with_limit() {
+    fn parse_clean_columns_spec() {
+        let command =
+            parse_command("clean_columns ./db people 'name=trim,null_if:N/A;state=upper'")
+                .expect("parse");
+        match command {
+            Command::CleanColumns {
+                table_name, spec, ..
+            } => {
+                assert_eq!(table_name, "people");
+                assert_eq!(spec.len(), 2);
+                assert_eq!(
+                    spec[0].ops,
+                    vec![
+                        ColumnCleanOp::Trim,
+                        ColumnCleanOp::NullIf("N/A".to_string())
+                    ]
+                );
+                assert_eq!(spec[1].column, "state");
+            }
+            _ => panic!("expected clean_columns command"),
+        }
+
+        let err = parse_command("clean_columns ./db people name=titlecase").expect_err("bad op");
+        assert!(err.to_string().contains("unknown clean operation"));
+        assert!(parse_command("clean_columns ./db people").is_err());
+    }
+
+    #[test]
+    fn parse_overture_extract_since_option() {
         let command = parse_command(
-            "overture_geocode ./spatia.duckdb addresses_ca \"321 n lincoln st redlands\" 3",
+            "overture_extract ./db places place 0,0,1,1 places_wa --since 2026-01-01",
         )
         .expect("parse");
+        match command {
+            Command::OvertureExtract {
+                table_name, since, ..
+            } => {
+                assert_eq!(table_name.as_deref(), Some("places_wa"));
+                assert_eq!(since.as_deref(), Some("2026-01-01"));
+            }
+            _ => panic!("expected overture extract command"),
+        }
+    }
+
+    #[test]
+    fn parse_overture_extract_index_flag() {
+        let command = parse_command("overture_extract ./db places place 0,0,1,1 --index places_wa")
+            .expect("parse");
+        match command {
+            Command::OvertureExtract {
+                table_name,
+                index,
+                explain,
+                ..
+            } => {
+                assert!(index);
+                assert!(!explain);
+                assert_eq!(table_name.as_deref(), Some("places_wa"));
+            }
+            _ => panic!("expected overture extract command"),
+        }
+    }
+
+    #[test]
+    fn parse_overture_extract_force_flag() {
+        let command =
+            parse_command("overture_extract ./db places places 0,0,1,1 --force").expect("parse");
+        match command {
+            Command::OvertureExtract {
+                item_type, force, ..
+            } => {
+                assert!(force);
+                assert_eq!(item_type, "places");
+            }
+            _ => panic!("expected overture extract command"),
+        }
+    }
+
+    #[test]
+    fn overture_extract_schema_option_qualifies_table() {
+        let output = execute_command(
+            "overture_extract ./db places place 0,0,1,1 places_wa --schema overture --explain",
+        )
+        .expect("explain");
+        let plan: serde_json::Value = serde_json::from_str(&output).expect("json");
+        assert_eq!(plan["table"], "overture.places_wa");
+
+        let command = parse_command("list_tables ./db --schema overture").expect("parse");
         assert_eq!(
             command,
-            Command::OvertureGeocode {
-                db_path: "./spatia.duckdb".to_string(),
-                table_name: "addresses_ca".to_string(),
-                query: "321 n lincoln st redlands".to_string(),
-                limit: 3,
+            Command::ListTables {
+                db_path: "./db".to_string(),
+                schema: "overture".to_string(),
             }
         );
     }
 
     #[test]
-    fn execute_ingest_and_schema_round_trip() {
-        let (db_path, csv_path) = setup_files();
+    fn list_tables_marks_views_and_drop_view_removes_them() {
+        let suffix = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .expect("time")
+            .as_nanos();
+        let db_path = format!("/tmp/spatia_executor_views_test_{suffix}.duckdb");
+        {
+            let conn = duckdb::Connection::open(&db_path).expect("open");
+            conn.execute_batch(
+                "CREATE TABLE policies (id INTEGER); \
+                 CREATE VIEW analysis_result AS SELECT * FROM policies;",
+            )
+            .expect("setup");
+        }
 
-        let ingest_cmd = format!("ingest {db_path} {csv_path}");
-        let ingest_result = execute_command(&ingest_cmd).expect("ingest execute");
-        assert!(ingest_result.contains("raw_staging"));
+        let output = execute_command(&format!("list_tables {db_path}")).expect("list_tables");
+        let tables: serde_json::Value = serde_json::from_str(&output).expect("json");
+        assert_eq!(
+            tables,
+            serde_json::json!([
+                {"name": "analysis_result", "type": "view"},
+                {"name": "policies", "type": "table"},
+            ])
+        );
+        let output =
+            execute_command(&format!("drop_view {db_path} analysis_result")).expect("drop_view");
+        assert!(output.contains("\"analysis_result\""));
+        let output = execute_command(&format!("list_views {db_path}")).expect("list_views");
+        assert_eq!(output, "[]");
+        assert!(parse_command("drop_view ./db").is_err());
 
-        let schema_cmd = format!("schema {db_path} raw_staging");
-        let schema_result = execute_command(&schema_cmd).expect("schema execute");
-        assert!(schema_result.contains("\"name\":\"id\""));
-        assert!(schema_result.contains("\"name\":\"city\""));
+        let _ = fs::remove_file(&db_path);
+        let _ = fs::remove_file(format!("{db_path}.wal"));
+    }
 
-        cleanup_files(&db_path, &csv_path);
+    #[test]
+    fn overture_themes_lists_known_types() {
+        let output = execute_command("overture_themes").expect("themes");
+        let value: serde_json::Value = serde_json::from_str(&output).expect("json");
+        let themes = value["themes"].as_array().expect("themes");
+        assert!(themes
+            .iter()
+            .any(|theme| theme["theme"] == "places"
+                && theme["types"] == serde_json::json!(["place"])));
+        let err = parse_command("overture_themes ./spatia.duckdb").expect_err("no args");
+        assert!(err.to_string().starts_with("Usage: overture_themes"));
     }
 
     #[test]
-    fn parse_geocode_single_address() {
-        let command = parse_command("geocode ./spatia.duckdb \"123 Main St, Springfield, IL\"")
+    fn parse_overture_extract_level_for_divisions() {
+        let command =
+            parse_command("overture_extract ./db divisions division_area 0,0,1,1 --level Region")
+                .expect("parse");
+        match command {
+            Command::OvertureExtract { level, .. } => {
+                assert_eq!(level, Some(DivisionLevel::Region));
+            }
+            _ => panic!("expected overture extract command"),
+        }
+
+        let err = parse_command("overture_extract ./db divisions division 0,0,1,1 --level state")
+            .expect_err("should fail");
+        assert!(err.to_string().contains("unknown division level"));
+
+        let err = parse_command("overture_extract ./db places place 0,0,1,1 --level country")
+            .expect_err("should fail");
+        assert!(err
+            .to_string()
+            .contains("only applies to the divisions theme"));
+    }
+
+    #[test]
+    fn overture_extract_explain_does_not_open_database() {
+        let output = execute_command(
+            "overture_extract /nonexistent/dir/spatia.duckdb places place 0,0,1,1 --explain",
+        )
+        .expect("explain");
+        let value: serde_json::Value = serde_json::from_str(&output).expect("json");
+        assert_eq!(value["table"], "overture_places_place");
+        assert!(value["sql"].as_str().expect("sql").contains("read_parquet"));
+        assert!(!std::path::Path::new("/nonexistent/dir/spatia.duckdb").exists());
+    }
+
+    #[test]
+    fn parse_overture_search_with_limit() {
+        let command = parse_command("overture_search ./spatia.duckdb places_wa \"lincoln\" 5")
             .expect("parse");
         assert_eq!(
             command,
-            Command::Geocode {
+            Command::Overture(OvertureCommand::Search {
                 db_path: "./spatia.duckdb".to_string(),
-                addresses: vec!["123 Main St, Springfield, IL".to_string()],
-            }
+                table_name: "places_wa".to_string(),
+                query: "lincoln".to_string(),
+                limit: 5,
+                geojson: false,
+                highlight: false,
+                coords: false,
+                ranking: SearchWeights::default(),
+                into: None,
+            })
         );
     }
 
     #[test]
-    fn parse_geocode_multiple_addresses() {
-        let command = parse_command("geocode ./spatia.duckdb \"addr1\" \"addr2\"").expect("parse");
+    fn parse_overture_query_with_bbox_and_limit() {
+        let command =
+            parse_command("overture_query places place -122.4,47.5,-122.2,47.7 \"pike place\" 3")
+                .expect("parse");
         assert_eq!(
             command,
-            Command::Geocode {
-                db_path: "./spatia.duckdb".to_string(),
-                addresses: vec!["addr1".to_string(), "addr2".to_string()],
-            }
+            Command::Overture(OvertureCommand::Query {
+                theme: "places".to_string(),
+                item_type: "place".to_string(),
+                bbox: BBox::parse("-122.4,47.5,-122.2,47.7").expect("bbox"),
+                query: "pike place".to_string(),
+                limit: 3,
+            })
         );
+        assert!(parse_command("overture_query places place -122.4,47.5,-122.2,47.7").is_err());
     }
 
     #[test]
-    fn parse_geocode_missing_address_errors() {
+    fn projected_flag_skips_the_lon_lat_range_check() {
+        let bbox = "500000,5200000,510000,5210000";
+        let err = parse_command(&format!("overture_query places place {bbox} pike"))
+            .expect_err("out of lon/lat range");
+        assert!(err.to_string().contains("longitude range"), "got: {err}");
+
+        let command = parse_command(&format!(
+            "overture_query places place {bbox} pike --projected"
+        ))
+        .expect("projected query");
+        assert!(matches!(
+            command,
+            Command::Overture(OvertureCommand::Query { bbox, .. }) if bbox.xmax == 510000.0
+        ));
+        let command = parse_command(&format!(
+            "overture_extract ./spatia.duckdb places place {bbox} --projected"
+        ))
+        .expect("projected extract");
+        assert!(matches!(command, Command::OvertureExtract { bbox, .. } if bbox.ymin == 5200000.0));
+    }
+
+    #[test]
+    fn parse_overture_geocode_with_limit() {
+        let command = parse_command(
+            "overture_geocode ./spatia.duckdb addresses_ca \"321 n lincoln st redlands\" 3",
+        )
+        .expect("parse");
+        assert_eq!(
+            command,
+            Command::Overture(OvertureCommand::Geocode {
+                db_path: "./spatia.duckdb".to_string(),
+                table_name: "addresses_ca".to_string(),
+                query: "321 n lincoln st redlands".to_string(),
+                limit: 3,
+                geojson: false,
+                into: None,
+                precision: None,
+            })
+        );
+    }
+
+    #[test]
+    fn parse_overture_into_option() {
+        let command = parse_command("overture_search ./db places_wa lincoln --into saved_places")
+            .expect("parse");
+        match command {
+            Command::Overture(OvertureCommand::Search { into, limit, .. }) => {
+                assert_eq!(into.as_deref(), Some("saved_places"));
+                assert_eq!(limit, 20);
+            }
+            _ => panic!("expected overture_search command"),
+        }
+        let command =
+            parse_command("overture_geocode ./db addresses_ca \"1 main st\" 3 --into found")
+                .expect("parse");
+        match command {
+            Command::Overture(OvertureCommand::Geocode { into, limit, .. }) => {
+                assert_eq!(into.as_deref(), Some("found"));
+                assert_eq!(limit, 3);
+            }
+            _ => panic!("expected overture_geocode command"),
+        }
+        let err = parse_command("overture_geocode ./db addresses_ca query --into")
+            .expect_err("missing table");
+        assert!(err.to_string().contains("--into requires a value"));
+    }
+
+    #[test]
+    fn parse_precision_option() {
+        let command =
+            parse_command("geocode ./db \"1 Main St\" --precision 6").expect("parse geocode");
+        match command {
+            Command::Geocode { precision, .. } => assert_eq!(precision, Some(6)),
+            _ => panic!("expected geocode command"),
+        }
+        let command =
+            parse_command("overture_geocode ./db addresses_ca \"1 main st\" --precision 4")
+                .expect("parse overture_geocode");
+        match command {
+            Command::Overture(OvertureCommand::Geocode { precision, .. }) => {
+                assert_eq!(precision, Some(4))
+            }
+            _ => panic!("expected overture_geocode command"),
+        }
+        let err = parse_command("geocode ./db \"1 Main St\" --precision 20").expect_err("range");
+        assert!(err.to_string().contains("coordinate precision"));
+    }
+
+    #[test]
+    fn parse_overture_rebuild_lookup() {
+        let command =
+            parse_command("overture_rebuild_lookup ./spatia.duckdb addresses_ca addresses")
+                .expect("parse");
+        assert_eq!(
+            command,
+            Command::OvertureRebuildLookup {
+                db_path: "./spatia.duckdb".to_string(),
+                table_name: "addresses_ca".to_string(),
+                theme: "addresses".to_string(),
+            }
+        );
+        let err = parse_command("overture_rebuild_lookup ./spatia.duckdb addresses_ca")
+            .expect_err("missing theme");
+        assert!(err
+            .to_string()
+            .starts_with("Usage: overture_rebuild_lookup"));
+    }
+
+    #[test]
+    fn parse_overture_geocode_with_geojson_flag() {
+        let command = parse_command(
+            "overture_geocode ./spatia.duckdb addresses_ca \"321 n lincoln st\" --geojson",
+        )
+        .expect("parse");
+        assert_eq!(
+            command,
+            Command::Overture(OvertureCommand::Geocode {
+                db_path: "./spatia.duckdb".to_string(),
+                table_name: "addresses_ca".to_string(),
+                query: "321 n lincoln st".to_string(),
+                limit: 20,
+                geojson: true,
+                into: None,
+                precision: None,
+            })
+        );
+    }
+
+    #[test]
+    fn parse_overture_search_geojson_flag_before_limit() {
+        let command =
+            parse_command("overture_search ./spatia.duckdb places_wa --geojson lincoln 5")
+                .expect("parse");
+        match command {
+            Command::Overture(OvertureCommand::Search { limit, geojson, .. }) => {
+                assert_eq!(limit, 5);
+                assert!(geojson);
+            }
+            _ => panic!("expected overture search command"),
+        }
+    }
+
+    #[test]
+    fn parse_overture_search_highlight_flag() {
+        let command =
+            parse_command("overture_search ./spatia.duckdb places_wa lincoln --highlight")
+                .expect("parse");
+        match command {
+            Command::Overture(OvertureCommand::Search {
+                highlight, limit, ..
+            }) => {
+                assert!(highlight);
+                assert_eq!(limit, 20);
+            }
+            _ => panic!("expected overture search command"),
+        }
+    }
+
+    #[test]
+    fn parse_overture_search_coords_flag() {
+        let command = parse_command("overture_search ./spatia.duckdb places_wa lincoln 5 --coords")
+            .expect("parse");
+        match command {
+            Command::Overture(OvertureCommand::Search { coords, limit, .. }) => {
+                assert!(coords);
+                assert_eq!(limit, 5);
+            }
+            _ => panic!("expected overture search command"),
+        }
+    }
+
+    #[test]
+    fn parse_overture_search_ranking_option() {
+        let command = parse_command(
+            "overture_search ./spatia.duckdb places_wa lincoln --ranking prefix_heavy",
+        )
+        .expect("parse");
+        match command {
+            Command::Overture(OvertureCommand::Search { ranking, .. }) => {
+                assert_eq!(ranking, SearchWeights::PREFIX_HEAVY);
+            }
+            _ => panic!("expected overture search command"),
+        }
+        let err =
+            parse_command("overture_search ./spatia.duckdb places_wa lincoln --ranking fuzzy")
+                .expect_err("unknown ranking");
+        assert!(err.to_string().contains("unknown search ranking 'fuzzy'"));
+    }
+
+    #[test]
+    fn parse_overture_search_rejects_geojson_with_search_options() {
+        for option in ["--highlight", "--coords", "--ranking exact_only"] {
+            let err = parse_command(&format!(
+                "overture_search ./spatia.duckdb places_wa lincoln --geojson {option}"
+            ))
+            .expect_err("geojson with search option");
+            assert!(
+                err.to_string().contains("cannot be combined"),
+                "{option}: {err}"
+            );
+        }
+    }
+
+    #[test]
+    fn geocode_results_render_as_point_features() {
+        let results = vec![
+            OvertureGeocodeResult {
+                id: Some("abc".to_string()),
+                label: "321 N Lincoln St Redlands CA 92374".to_string(),
+                lat: Some(34.06),
+                lon: Some(-117.18),
+            },
+            OvertureGeocodeResult {
+                id: None,
+                label: "Unknown".to_string(),
+                lat: None,
+                lon: None,
+            },
+        ];
+
+        let geojson = geocode_results_to_geojson(&results);
+        assert_eq!(geojson["type"], "FeatureCollection");
+        let features = geojson["features"].as_array().expect("features");
+        assert_eq!(features.len(), 2);
+        assert_eq!(features[0]["geometry"]["type"], "Point");
+        assert_eq!(features[0]["geometry"]["coordinates"][0], -117.18);
+        assert_eq!(features[0]["geometry"]["coordinates"][1], 34.06);
+        assert_eq!(features[0]["properties"]["id"], "abc");
+        assert_eq!(features[0]["properties"]["zip"], "92374");
+        assert_eq!(features[0]["properties"]["state"], "CA");
+        assert!(features[1]["geometry"].is_null());
+        assert!(features[1]["properties"]["id"].is_null());
+    }
+
+    #[test]
+    fn parse_overture_search_rejects_non_numeric_limit() {
+        let err = parse_command("overture_search ./spatia.duckdb places_wa lincoln ten")
+            .expect_err("should fail");
+        assert_eq!(
+            err.to_string(),
+            "limit must be a positive integer, got 'ten'"
+        );
+    }
+
+    #[test]
+    fn parse_overture_geocode_rejects_zero_limit() {
+        let err = parse_command("overture_geocode ./spatia.duckdb addresses_ca main 0")
+            .expect_err("should fail");
+        assert_eq!(err.to_string(), "limit must be a positive integer, got '0'");
+    }
+
+    #[test]
+    fn parse_overture_extract_reports_bad_bbox_coordinate() {
+        let err =
+            parse_command("overture_extract ./spatia.duckdb places place -122.4,abc,-122.2,47.7")
+                .expect_err("should fail");
+        assert!(err.to_string().contains("ymin 'abc' is not a valid number"));
+    }
+
+    #[test]
+    fn execute_ingest_and_schema_round_trip() {
+        let (db_path, csv_path) = setup_files();
+
+        let ingest_cmd = format!("ingest {db_path} {csv_path}");
+        let ingest_result = execute_command(&ingest_cmd).expect("ingest execute");
+        assert!(ingest_result.contains("raw_staging"));
+
+        let schema_cmd = format!("schema {db_path} raw_staging");
+        let schema_result = execute_command(&schema_cmd).expect("schema execute");
+        assert!(schema_result.contains("\"name\":\"id\""));
+        assert!(schema_result.contains("\"name\":\"city\""));
+
+        cleanup_files(&db_path, &csv_path);
+    }
+
+    #[test]
+    fn parse_schema_with_columns() {
+        let command =
+            parse_command("schema ./spatia.duckdb places --columns id,name").expect("parse");
+        assert_eq!(
+            command,
+            Command::Schema {
+                db_path: "./spatia.duckdb".to_string(),
+                table_name: "places".to_string(),
+                columns: Some(vec!["id".to_string(), "name".to_string()]),
+                samples: false,
+            }
+        );
+    }
+
+    #[test]
+    fn execute_schema_with_samples_adds_values() {
+        let (db_path, csv_path) = setup_files();
+        execute_command(&format!("ingest {db_path} {csv_path}")).expect("ingest execute");
+
+        let plain = execute_command(&format!("schema {db_path} raw_staging")).expect("schema");
+        assert!(!plain.contains("\"samples\""));
+
+        let output =
+            execute_command(&format!("schema {db_path} raw_staging --samples")).expect("schema");
+        let columns: serde_json::Value = serde_json::from_str(&output).expect("json");
+        let columns = columns.as_array().expect("array");
+        assert!(columns
+            .iter()
+            .all(|column| column["samples"].is_array() && column["name"].is_string()));
+
+        let err = parse_command("schema ./db places --columns id --samples").expect_err("fail");
+        assert!(err.to_string().contains("--samples cannot be combined"));
+
+        cleanup_files(&db_path, &csv_path);
+    }
+
+    #[test]
+    fn ingest_skip_unchanged_does_not_reload_the_same_file() {
+        let (db_path, csv_path) = setup_files();
+        let command = format!("ingest {db_path} {csv_path} places --skip-unchanged");
+        let output = execute_command(&command).expect("first ingest");
+        assert!(output.contains("\"status\":\"ok\""), "{output}");
+
+        // A reload would drop this row again.
+        {
+            let conn = duckdb::Connection::open(&db_path).expect("open");
+            conn.execute_batch("INSERT INTO places SELECT * FROM places")
+                .expect("insert");
+        }
+        let output = execute_command(&command).expect("second ingest");
+        let value: serde_json::Value = serde_json::from_str(&output).expect("json");
+        assert_eq!(value["status"], "skipped");
+        assert_eq!(value["table"], "places");
+        assert_eq!(value["row_count"], 2);
+
+        cleanup_files(&db_path, &csv_path);
+    }
+
+    #[test]
+    fn list_tables_on_fresh_db_is_empty_and_schema_of_missing_table_fails() {
+        let (db_path, csv_path) = setup_files();
+        let output = execute_command(&format!("list_tables {db_path}")).expect("list_tables");
+        assert_eq!(output, "[]");
+
+        let err = execute_command(&format!("schema {db_path} raw_staging")).expect_err("missing");
+        assert!(err.to_string().contains("table not found: raw_staging"));
+
+        execute_command(&format!("ingest {db_path} {csv_path}")).expect("ingest execute");
+        let output = execute_command(&format!("list_tables {db_path}")).expect("list_tables");
+        assert_eq!(output, "[\"raw_staging\"]");
+
+        let err = parse_command("list_tables").expect_err("missing db path");
+        assert!(err.to_string().contains("Usage: list_tables"));
+
+        cleanup_files(&db_path, &csv_path);
+    }
+
+    #[test]
+    fn execute_table_preview_returns_typed_rows() {
+        let (db_path, csv_path) = setup_files();
+        execute_command(&format!("ingest {db_path} {csv_path}")).expect("ingest execute");
+
+        let output =
+            execute_command(&format!("table_preview {db_path} raw_staging 5")).expect("preview");
+        let value: serde_json::Value = serde_json::from_str(&output).expect("json");
+        assert_eq!(value["row_count"], 1);
+        assert_eq!(value["columns"][1]["name"], "city");
+        assert_eq!(value["rows"], serde_json::json!([[1, "Oakland"]]));
+
+        let err = parse_command("table_preview ./db raw_staging 0").expect_err("zero limit");
+        assert!(err.to_string().contains("limit must be a positive integer"));
+        let err = parse_command("table_preview ./db").expect_err("missing table");
+        assert!(err.to_string().starts_with("Usage: table_preview"));
+
+        cleanup_files(&db_path, &csv_path);
+    }
+
+    #[test]
+    fn execute_profile_column_reports_top_values() {
+        let (db_path, csv_path) = setup_files();
+        execute_command(&format!("ingest {db_path} {csv_path}")).expect("ingest execute");
+
+        let output = execute_command(&format!("profile_column {db_path} raw_staging city"))
+            .expect("profile");
+        let value: serde_json::Value = serde_json::from_str(&output).expect("json");
+        assert_eq!(value["kind"], "categorical");
+        assert_eq!(value["null_rate"], 0.0);
+        assert_eq!(
+            value["top_values"],
+            serde_json::json!([{ "value": "Oakland", "count": 1 }])
+        );
+
+        let err = execute_command(&format!("profile_column {db_path} raw_staging zip"))
+            .expect_err("missing column");
+        assert!(err
+            .to_string()
+            .contains("column not found: zip in raw_staging"));
+        let err = parse_command("profile_column ./db raw_staging").expect_err("missing column");
+        assert!(err.to_string().starts_with("Usage: profile_column"));
+
+        cleanup_files(&db_path, &csv_path);
+    }
+
+    #[test]
+    fn execute_schema_projection_reports_missing_columns() {
+        let (db_path, csv_path) = setup_files();
+        execute_command(&format!("ingest {db_path} {csv_path}")).expect("ingest execute");
+
+        let result = execute_command(&format!("schema {db_path} raw_staging --columns city,zip"))
+            .expect("schema execute");
+        assert!(result.contains("\"name\":\"city\""));
+        assert!(!result.contains("\"name\":\"id\""));
+        assert!(result.contains("\"missing\":[\"zip\"]"));
+
+        cleanup_files(&db_path, &csv_path);
+    }
+
+    #[test]
+    fn execute_schema_projection_matches_real_column_names() {
+        let (db_path, csv_path) = setup_files();
+        fs::write(&csv_path, "Policy Number,city\nP-1,Oakland\n").expect("write csv");
+        execute_command(&format!("ingest {db_path} {csv_path}")).expect("ingest execute");
+
+        let result = execute_command(&format!(
+            "schema {db_path} raw_staging --columns \"Policy Number,policy_id\""
+        ))
+        .expect("schema execute");
+        assert!(result.contains("\"name\":\"Policy Number\""));
+        assert!(!result.contains("\"name\":\"city\""));
+        assert!(result.contains("\"missing\":[\"policy_id\"]"));
+
+        let err = execute_command(&format!(
+            "schema {db_path} raw_staging --columns \"id;drop\""
+        ))
+        .expect_err("malformed column name");
+        assert!(err.to_string().contains("invalid column name 'id;drop'"));
+
+        cleanup_files(&db_path, &csv_path);
+    }
+
+    #[test]
+    fn parse_geocode_single_address() {
+        let command = parse_command("geocode ./spatia.duckdb \"123 Main St, Springfield, IL\"")
+            .expect("parse");
+        assert_eq!(
+            command,
+            Command::Geocode {
+                db_path: "./spatia.duckdb".to_string(),
+                addresses: vec!["123 Main St, Springfield, IL".to_string()],
+                input_file: None,
+                output_file: None,
+                summary: false,
+                dedupe: false,
+                normalize: false,
+                providers: None,
+                unresolved_only: false,
+                precision: None,
+            }
+        );
+    }
+
+    #[test]
+    fn parse_geocode_multiple_addresses() {
+        let command = parse_command("geocode ./spatia.duckdb \"addr1\" \"addr2\"").expect("parse");
+        assert_eq!(
+            command,
+            Command::Geocode {
+                db_path: "./spatia.duckdb".to_string(),
+                addresses: vec!["addr1".to_string(), "addr2".to_string()],
+                input_file: None,
+                output_file: None,
+                summary: false,
+                dedupe: false,
+                normalize: false,
+                providers: None,
+                unresolved_only: false,
+                precision: None,
+            }
+        );
+    }
+
+    #[test]
+    fn parse_geocode_summary_flag() {
+        let command = parse_command("geocode ./spatia.duckdb \"addr1\" --summary").expect("parse");
+        assert_eq!(
+            command,
+            Command::Geocode {
+                db_path: "./spatia.duckdb".to_string(),
+                addresses: vec!["addr1".to_string()],
+                input_file: None,
+                output_file: None,
+                summary: true,
+                dedupe: false,
+                normalize: false,
+                providers: None,
+                unresolved_only: false,
+                precision: None,
+            }
+        );
+    }
+
+    #[test]
+    fn parse_geocode_dedupe_flag() {
+        let command =
+            parse_command("geocode ./spatia.duckdb \"addr1\" \"ADDR1\" --dedupe").expect("parse");
+        match command {
+            Command::Geocode {
+                addresses, dedupe, ..
+            } => {
+                assert!(dedupe);
+                assert_eq!(addresses, vec!["addr1", "ADDR1"]);
+            }
+            _ => panic!("expected geocode command"),
+        }
+    }
+
+    #[test]
+    fn parse_geocode_normalize_flag() {
+        let command =
+            parse_command("geocode ./spatia.duckdb \"123 N Main St\" --normalize").expect("parse");
+        match command {
+            Command::Geocode {
+                addresses,
+                dedupe,
+                normalize,
+                ..
+            } => {
+                assert!(normalize);
+                assert!(!dedupe);
+                assert_eq!(addresses, vec!["123 N Main St"]);
+            }
+            _ => panic!("expected geocode command"),
+        }
+    }
+
+    #[test]
+    fn parse_geocode_providers_option() {
+        let command = parse_command("geocode ./spatia.duckdb \"addr1\" --providers cache_only")
+            .expect("parse");
+        match command {
+            Command::Geocode { providers, .. } => {
+                assert_eq!(providers, Some(ProviderMode::CacheOnly));
+            }
+            _ => panic!("expected geocode command"),
+        }
+        let err = parse_command("geocode ./spatia.duckdb \"addr1\" --providers sidecar_only")
+            .expect_err("unknown mode");
+        assert!(err
+            .to_string()
+            .contains("unknown geocode provider mode 'sidecar_only'"));
+    }
+
+    #[test]
+    fn parse_geocode_unresolved_only_flag() {
+        let command =
+            parse_command("geocode ./spatia.duckdb \"addr1\" --unresolved-only --summary")
+                .expect("parse");
+        match command {
+            Command::Geocode {
+                addresses,
+                summary,
+                unresolved_only,
+                ..
+            } => {
+                assert!(unresolved_only);
+                assert!(summary);
+                assert_eq!(addresses, vec!["addr1"]);
+            }
+            _ => panic!("expected geocode command"),
+        }
+    }
+
+    #[test]
+    fn parse_geocode_input_and_output_files() {
+        let command = parse_command(
+            "geocode ./spatia.duckdb --input-file ./addresses.txt --output-file ./out.json",
+        )
+        .expect("parse");
+        assert_eq!(
+            command,
+            Command::Geocode {
+                db_path: "./spatia.duckdb".to_string(),
+                addresses: vec![],
+                input_file: Some("./addresses.txt".to_string()),
+                output_file: Some("./out.json".to_string()),
+                summary: false,
+                dedupe: false,
+                normalize: false,
+                providers: None,
+                unresolved_only: false,
+                precision: None,
+            }
+        );
+
+        let err = parse_command("geocode ./spatia.duckdb --input-file").expect_err("fail");
+        assert!(err.to_string().contains("--input-file requires a value"));
+    }
+
+    #[test]
+    fn read_address_file_trims_and_skips_blank_lines() {
+        let path = format!("/tmp/spatia_executor_test_{}.txt", unique_suffix());
+        fs::write(&path, "  123 Main St  \n\n\t\n456 Oak Ave\r\n").expect("write");
+        let addresses = read_address_file(&path).expect("read");
+        assert_eq!(addresses, vec!["123 Main St", "456 Oak Ave"]);
+        let _ = fs::remove_file(&path);
+
+        let err = read_address_file("/nonexistent/addresses.txt").expect_err("missing");
+        assert!(err.to_string().contains("failed to read address file"));
+    }
+
+    #[test]
+    fn parse_reverse_geocode_points() {
+        let command =
+            parse_command("reverse_geocode ./spatia.duckdb 47.6205,-122.3493 \"40.7, -74.0\"")
+                .expect("parse");
+        assert_eq!(
+            command,
+            Command::ReverseGeocode {
+                db_path: "./spatia.duckdb".to_string(),
+                points: vec![(47.6205, -122.3493), (40.7, -74.0)],
+            }
+        );
+    }
+
+    #[test]
+    fn parse_reverse_geocode_rejects_bad_points() {
+        let err = parse_command("reverse_geocode ./spatia.duckdb 47.6").expect_err("fail");
+        assert!(err.to_string().contains("point must be lat,lon"));
+        let err = parse_command("reverse_geocode ./spatia.duckdb 95,10").expect_err("fail");
+        assert!(err.to_string().contains("out of range"));
+        let err = parse_command("reverse_geocode ./spatia.duckdb").expect_err("fail");
+        assert!(err.to_string().contains("Usage: reverse_geocode"));
+    }
+
+    #[test]
+    fn parse_geocode_missing_address_errors() {
         let err = parse_command("geocode ./spatia.duckdb").expect_err("should fail");
         assert!(err.to_string().contains("Usage: geocode"));
     }
 
+    #[test]
+    fn parse_geocode_cache_export_and_import() {
+        let command = parse_command("geocode_cache_export ./spatia.duckdb ./cache.csv")
+            .expect("parse export");
+        assert_eq!(
+            command,
+            Command::GeocodeCacheExport {
+                db_path: "./spatia.duckdb".to_string(),
+                out_path: "./cache.csv".to_string(),
+            }
+        );
+        let command = parse_command("geocode_cache_import ./spatia.duckdb ./cache.json")
+            .expect("parse import");
+        assert_eq!(
+            command,
+            Command::GeocodeCacheImport {
+                db_path: "./spatia.duckdb".to_string(),
+                in_path: "./cache.json".to_string(),
+            }
+        );
+        let err = parse_command("geocode_cache_import ./spatia.duckdb").expect_err("fail");
+        assert!(err.to_string().contains("Usage: geocode_cache_import"));
+    }
+
+    #[test]
+    fn geocode_precision_flag_sets_serialized_digits() {
+        let db_path = format!("/tmp/spatia_executor_test_{}.duckdb", unique_suffix());
+        let mut conn = open_connection(&db_path).expect("open");
+        let cached = [GeocodeResult {
+            address: "cached addr".to_string(),
+            lat: 47.620_422_123_456_78,
+            lon: -122.349_358_987_654_32,
+            source: "geocodio".to_string(),
+        }];
+        cache_store(&mut conn, &cached, "geocodio").expect("seed cache");
+        drop(conn);
+
+        let lat_with = |digits: u32| -> serde_json::Value {
+            let output = execute_command(&format!(
+                "geocode {db_path} \"cached addr\" --summary --providers cache_only \
+                 --precision {digits}"
+            ))
+            .expect("geocode");
+            let payload: serde_json::Value = serde_json::from_str(&output).expect("json");
+            payload["results"][0]["lat"].clone()
+        };
+        assert_eq!(lat_with(2), serde_json::json!(47.62));
+        assert_eq!(lat_with(8), serde_json::json!(47.62042212));
+
+        let _ = fs::remove_file(&db_path);
+        let _ = fs::remove_file(format!("{db_path}.wal"));
+    }
+
+    #[test]
+    fn parse_geocode_cache_refresh_command() {
+        let command = parse_command(
+            "geocode_cache_refresh ./spatia.duckdb --max-age-days 30 --providers free_only",
+        )
+        .expect("parse");
+        assert_eq!(
+            command,
+            Command::GeocodeCacheRefresh {
+                db_path: "./spatia.duckdb".to_string(),
+                max_age_days: Some(30),
+                providers: Some(ProviderMode::FreeOnly),
+            }
+        );
+
+        let err = parse_command("geocode_cache_refresh ./spatia.duckdb --max-age-days soon")
+            .expect_err("fail");
+        assert!(err.to_string().contains("--max-age-days"));
+    }
+
+    #[test]
+    fn parse_db_info_command() {
+        let command = parse_command("db_info ./spatia.duckdb").expect("parse");
+        assert_eq!(
+            command,
+            Command::DbInfo {
+                db_path: "./spatia.duckdb".to_string(),
+            }
+        );
+    }
+
+    #[test]
+    fn execute_db_info_after_ingest() {
+        let (db_path, csv_path) = setup_files();
+        execute_command(&format!("ingest {db_path} {csv_path}")).expect("ingest execute");
+
+        let info = execute_command(&format!("db_info {db_path}")).expect("db_info execute");
+        assert!(info.contains("\"tables\":[\"raw_staging\"]"));
+        assert!(info.contains("\"has_geocode_cache\":false"));
+
+        cleanup_files(&db_path, &csv_path);
+    }
+
+    #[test]
+    fn execute_json_schema_after_ingest() {
+        let (db_path, csv_path) = setup_files();
+        execute_command(&format!("ingest {db_path} {csv_path}")).expect("ingest execute");
+
+        let result =
+            execute_command(&format!("json_schema {db_path} raw_staging")).expect("json_schema");
+        let schema: serde_json::Value = serde_json::from_str(&result).expect("valid json");
+        assert_eq!(schema["title"], "raw_staging");
+        assert!(schema["properties"]["city"].is_object());
+
+        cleanup_files(&db_path, &csv_path);
+    }
+
     #[test]
     fn execute_unknown_command_errors() {
         let err = execute_command("unknown").expect_err("should fail");
@@ -1,22 +1,116 @@
+use std::io::Write;
+
 use duckdb::Connection;
+use flate2::write::GzEncoder;
+use flate2::Compression;
 use serde_json::{json, Map, Value};
 use tracing::info;
 
 use crate::identifiers::validate_table_name;
-use crate::EngineResult;
+use crate::{load_extension, EngineResult};
+
+/// Whether `file_path` asks for gzip output (`.csv.gz`, `.geojson.gz`, ...).
+fn is_gzip_path(file_path: &str) -> bool {
+    std::path::Path::new(file_path)
+        .extension()
+        .and_then(|ext| ext.to_str())
+        .is_some_and(|ext| ext.eq_ignore_ascii_case("gz"))
+}
 
-/// Export a DuckDB table as CSV to the given file path.
+/// Export a DuckDB table as CSV to the given file path. A `.gz` path is
+/// written gzip-compressed.
 pub fn export_table_csv(conn: &Connection, table_name: &str, file_path: &str) -> EngineResult<()> {
     validate_table_name(table_name)?;
     let escaped_path = file_path.replace('\'', "''");
-    let sql = format!(r#"COPY "{table_name}" TO '{escaped_path}' (FORMAT CSV, HEADER)"#);
+    let compression = if is_gzip_path(file_path) {
+        ", COMPRESSION 'gzip'"
+    } else {
+        ""
+    };
+    let sql =
+        format!(r#"COPY "{table_name}" TO '{escaped_path}' (FORMAT CSV, HEADER{compression})"#);
     conn.execute_batch(&sql)?;
     info!(table = %table_name, path = %file_path, "export_table_csv: exported successfully");
     Ok(())
 }
 
+/// Export a table with a GEOMETRY column as GeoParquet to the given file path.
+/// The spatial extension writes the `geo` metadata GIS tools read, so the
+/// geometry survives the round trip. Returns the geometry column's name.
+pub fn export_table_geoparquet(
+    conn: &Connection,
+    table_name: &str,
+    file_path: &str,
+) -> EngineResult<String> {
+    validate_table_name(table_name)?;
+    load_extension(conn, "spatial")?;
+    let Some(geometry_column) = geometry_column(conn, table_name)? else {
+        return Err(
+            format!("table {table_name} has no GEOMETRY column; export it as CSV instead").into(),
+        );
+    };
+    let escaped_path = file_path.replace('\'', "''");
+    let sql = format!(r#"COPY "{table_name}" TO '{escaped_path}' (FORMAT PARQUET)"#);
+    conn.execute_batch(&sql)?;
+    info!(
+        table = %table_name,
+        geometry_column = %geometry_column,
+        path = %file_path,
+        "export_table_geoparquet: exported successfully"
+    );
+    Ok(geometry_column)
+}
+
+/// First GEOMETRY column of `table_name`, preferring one named `geometry`.
+pub(crate) fn geometry_column(conn: &Connection, table_name: &str) -> EngineResult<Option<String>> {
+    let mut stmt = conn.prepare(
+        "SELECT column_name FROM information_schema.columns \
+         WHERE table_schema = 'main' AND table_name = ? AND data_type = 'GEOMETRY' \
+         ORDER BY column_name = 'geometry' DESC, ordinal_position",
+    )?;
+    let mut rows = stmt.query([table_name])?;
+    match rows.next()? {
+        Some(row) => Ok(Some(row.get(0)?)),
+        None => Ok(None),
+    }
+}
+
 /// Export the `analysis_result` view as a GeoJSON FeatureCollection to the given file path.
+/// A `.gz` path is written gzip-compressed.
 pub fn export_analysis_geojson(conn: &Connection, file_path: &str) -> EngineResult<()> {
+    let features = match write_analysis_geojson_file(conn, file_path) {
+        Ok(features) => features,
+        Err(err) => {
+            // Don't leave a truncated file behind.
+            let _ = std::fs::remove_file(file_path);
+            return Err(err);
+        }
+    };
+    info!(features, path = %file_path, "export_analysis_geojson: exported successfully");
+    Ok(())
+}
+
+fn write_analysis_geojson_file(conn: &Connection, file_path: &str) -> EngineResult<usize> {
+    let file = std::fs::File::create(file_path)?;
+    if is_gzip_path(file_path) {
+        let mut encoder = GzEncoder::new(file, Compression::default());
+        let features = write_analysis_geojson(conn, &mut encoder)?;
+        encoder.finish()?;
+        Ok(features)
+    } else {
+        let mut writer = std::io::BufWriter::new(file);
+        let features = write_analysis_geojson(conn, &mut writer)?;
+        writer.flush()?;
+        Ok(features)
+    }
+}
+
+/// Write the `analysis_result` view as a GeoJSON FeatureCollection to `sink`
+/// (a file, stdout or an in-memory buffer) and return the number of features.
+///
+/// Features are written one per line as rows are read, so memory use does
+/// not grow with the result. On error `sink` holds a partial document.
+pub fn write_analysis_geojson<W: Write>(conn: &Connection, mut sink: W) -> EngineResult<usize> {
     // Get column names
     let mut schema_stmt = conn.prepare(
         "SELECT column_name FROM information_schema.columns \
@@ -42,8 +136,9 @@ pub fn export_analysis_geojson(conn: &Connection, file_path: &str) -> EngineResu
     // Query all rows (no LIMIT for export)
     let mut stmt = conn.prepare(&format!("SELECT {cast_select} FROM analysis_result"))?;
     let mut rows = stmt.query([])?;
-    let mut features: Vec<Value> = Vec::new();
+    let mut feature_count = 0;
 
+    sink.write_all(b"{\n  \"type\": \"FeatureCollection\",\n  \"features\": [")?;
     while let Some(row) = rows.next()? {
         let mut props = Map::new();
         for (index, column_name) in col_names.iter().enumerate() {
@@ -66,21 +161,27 @@ pub fn export_analysis_geojson(conn: &Connection, file_path: &str) -> EngineResu
             _ => Value::Null,
         };
 
-        features.push(json!({
+        let feature = json!({
             "type": "Feature",
             "geometry": geometry,
             "properties": Value::Object(props),
-        }));
+        });
+        let separator: &[u8] = if feature_count == 0 {
+            b"\n    "
+        } else {
+            b",\n    "
+        };
+        sink.write_all(separator)?;
+        serde_json::to_writer(&mut sink, &feature)?;
+        feature_count += 1;
     }
-
-    let fc = json!({
-        "type": "FeatureCollection",
-        "features": features,
-    });
-
-    std::fs::write(file_path, serde_json::to_string_pretty(&fc)?)?;
-    info!(features = features.len(), path = %file_path, "export_analysis_geojson: exported successfully");
-    Ok(())
+    let closing: &[u8] = if feature_count == 0 {
+        b"]\n}\n"
+    } else {
+        b"\n  ]\n}\n"
+    };
+    sink.write_all(closing)?;
+    Ok(feature_count)
 }
 
 fn parse_coord(props: &Map<String, Value>, names: &[&str]) -> Option<f64> {
@@ -104,3 +205,151 @@ fn parse_coord(props: &Map<String, Value>, names: &[&str]) -> Option<f64> {
     }
     None
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        export_analysis_geojson, export_table_csv, export_table_geoparquet, write_analysis_geojson,
+    };
+    use crate::load_extension;
+    use duckdb::Connection;
+    use flate2::read::GzDecoder;
+    use std::fs;
+    use std::io::Read;
+    use std::time::{SystemTime, UNIX_EPOCH};
+
+    #[test]
+    fn geoparquet_export_round_trips_geometry() {
+        let suffix = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .expect("time")
+            .as_nanos();
+        let path = format!("/tmp/spatia_export_test_{suffix}.parquet");
+        let conn = Connection::open_in_memory().expect("open");
+        load_extension(&conn, "spatial").expect("spatial");
+        conn.execute_batch(
+            "CREATE TABLE places_wa AS SELECT 'p1' AS id, ST_Point(-122.3, 47.6) AS geometry; \
+             CREATE TABLE plain AS SELECT 1 AS id",
+        )
+        .expect("setup");
+
+        let column = export_table_geoparquet(&conn, "places_wa", &path).expect("export");
+        assert_eq!(column, "geometry");
+        let mut stmt = conn
+            .prepare(&format!(
+                "SELECT ST_AsText(geometry) FROM read_parquet('{path}')"
+            ))
+            .expect("prepare");
+        let wkt: String = stmt.query_row([], |row| row.get(0)).expect("read back");
+        assert_eq!(wkt, "POINT (-122.3 47.6)");
+        let mut stmt = conn
+            .prepare(&format!(
+                "SELECT COUNT(*) FROM parquet_kv_metadata('{path}') WHERE decode(key) = 'geo'"
+            ))
+            .expect("prepare");
+        let geo_keys: i64 = stmt.query_row([], |row| row.get(0)).expect("metadata");
+        assert_eq!(geo_keys, 1);
+
+        let err = export_table_geoparquet(&conn, "plain", &path).expect_err("no geometry");
+        assert!(err.to_string().contains("no GEOMETRY column"));
+        let _ = fs::remove_file(&path);
+    }
+
+    #[test]
+    fn gzip_csv_export_reads_back_through_ingest() {
+        let suffix = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .expect("time")
+            .as_nanos();
+        let csv_path = format!("/tmp/spatia_export_test_{suffix}.csv.gz");
+        let db_path = format!("/tmp/spatia_export_test_{suffix}.duckdb");
+        let conn = Connection::open_in_memory().expect("open");
+        conn.execute_batch(
+            "CREATE TABLE sales AS SELECT * FROM (VALUES (1, 'north'), (2, 'south')) t(id, region)",
+        )
+        .expect("setup");
+
+        export_table_csv(&conn, "sales", &csv_path).expect("export");
+        let mut magic = [0u8; 2];
+        fs::File::open(&csv_path)
+            .and_then(|mut file| file.read_exact(&mut magic))
+            .expect("read header");
+        assert_eq!(magic, [0x1f, 0x8b]);
+
+        spatia_ingest::ingest_csv_to_table(&db_path, &csv_path, "sales_copy").expect("ingest");
+        let copy = Connection::open(&db_path).expect("open copy");
+        let regions: String = copy
+            .query_row(
+                "SELECT string_agg(region, ',' ORDER BY id) FROM sales_copy",
+                [],
+                |row| row.get(0),
+            )
+            .expect("read back");
+        assert_eq!(regions, "north,south");
+        drop(copy);
+
+        let _ = fs::remove_file(&csv_path);
+        let _ = fs::remove_file(&db_path);
+        let _ = fs::remove_file(format!("{db_path}.wal"));
+    }
+
+    #[test]
+    fn gzip_geojson_export_decompresses_to_feature_collection() {
+        let suffix = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .expect("time")
+            .as_nanos();
+        let path = format!("/tmp/spatia_export_test_{suffix}.geojson.gz");
+        let conn = Connection::open_in_memory().expect("open");
+        conn.execute_batch(
+            "CREATE VIEW analysis_result AS SELECT 'a' AS name, 47.6 AS lat, -122.3 AS lon",
+        )
+        .expect("setup");
+
+        export_analysis_geojson(&conn, &path).expect("export");
+        let mut text = String::new();
+        GzDecoder::new(fs::File::open(&path).expect("open export"))
+            .read_to_string(&mut text)
+            .expect("gunzip");
+        let fc: serde_json::Value = serde_json::from_str(&text).expect("json");
+        assert_eq!(
+            fc["features"][0]["geometry"]["coordinates"],
+            serde_json::json!([-122.3, 47.6])
+        );
+
+        let _ = fs::remove_file(&path);
+    }
+
+    #[test]
+    fn analysis_geojson_writes_to_any_sink() {
+        let conn = Connection::open_in_memory().expect("open");
+        let mut buffer = Vec::new();
+        let err = write_analysis_geojson(&conn, &mut buffer).expect_err("no view");
+        assert!(err
+            .to_string()
+            .contains("analysis_result view does not exist"));
+
+        conn.execute_batch(
+            "CREATE VIEW analysis_result AS SELECT * FROM (VALUES \
+                 ('a', 47.6, -122.3), ('b', NULL, NULL)) t(name, lat, lon)",
+        )
+        .expect("setup");
+        let features = write_analysis_geojson(&conn, &mut buffer).expect("write");
+        assert_eq!(features, 2);
+        let fc: serde_json::Value = serde_json::from_slice(&buffer).expect("json");
+        assert_eq!(fc["type"], "FeatureCollection");
+        assert_eq!(fc["features"][1]["geometry"], serde_json::Value::Null);
+        let text = String::from_utf8(buffer).expect("utf8");
+        assert_eq!(text.lines().count(), 7, "one line per feature: {text}");
+
+        conn.execute_batch("CREATE OR REPLACE VIEW analysis_result AS SELECT 1 AS id LIMIT 0")
+            .expect("empty view");
+        let mut buffer = Vec::new();
+        assert_eq!(
+            write_analysis_geojson(&conn, &mut buffer).expect("write"),
+            0
+        );
+        let fc: serde_json::Value = serde_json::from_slice(&buffer).expect("json");
+        assert_eq!(fc["features"], serde_json::json!([]));
+    }
+}
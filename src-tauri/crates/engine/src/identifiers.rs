@@ -1,15 +1,43 @@
 use crate::EngineResult;
 
 pub fn validate_table_name(table_name: &str) -> EngineResult<()> {
-    let mut chars = table_name.chars();
+    validate_identifier(table_name, "table name")
+}
+
+pub fn validate_column_name(column_name: &str) -> EngineResult<()> {
+    validate_identifier(column_name, "column name")
+}
+
+/// Check a column name that is looked up among a table's real columns
+/// rather than spliced into SQL. Ingested names may hold spaces or
+/// punctuation (`Policy Number`), so only names that cannot be a column are
+/// rejected: empty ones and ones with quotes, semicolons or control characters.
+pub(crate) fn validate_column_reference(column_name: &str) -> EngineResult<()> {
+    if column_name.trim().is_empty() {
+        return Err("column name is empty".into());
+    }
+    if column_name
+        .chars()
+        .any(|c| c == '"' || c == '\'' || c == ';' || c.is_control())
+    {
+        return Err(format!(
+            "invalid column name '{column_name}': no quotes, semicolons or control characters"
+        )
+        .into());
+    }
+    Ok(())
+}
+
+pub(crate) fn validate_identifier(value: &str, kind: &str) -> EngineResult<()> {
+    let mut chars = value.chars();
     let Some(first) = chars.next() else {
-        return Err("table name is empty".into());
+        return Err(format!("{kind} is empty").into());
     };
     if !is_ident_start(first) || !chars.all(is_ident_continue) {
-        return Err(
-            "table name must be alphanumeric or underscore and start with a letter or underscore"
-                .into(),
-        );
+        return Err(format!(
+            "{kind} must be alphanumeric or underscore and start with a letter or underscore"
+        )
+        .into());
     }
     Ok(())
 }
@@ -1,45 +1,106 @@
 mod analysis;
+mod clean;
+mod db_info;
 mod db_manager;
 pub mod domain_pack;
 mod executor;
 mod export;
 mod identifiers;
 mod schema;
+mod shell;
+mod spatial_join;
 mod types;
 
 // Re-export geocode crate's public API for backward compatibility
 pub use spatia_geocode::{
-    cache_lookup, cache_store, ensure_cache_table,
-    geocode_addresses, geocode_batch, geocode_batch_with_components, geocode_batch_with_progress,
-    geocode_via_geocodio, geocode_via_nominatim,
-    AddressComponents, components_from_columns, components_from_string, extract_zip,
-    GeocodeBatchResult, GeocodeProgressUpdate, GeocodeResult, GeocodeStats,
+    cache_lookup, cache_store, cache_ttl_days, ensure_cache_table, export_cache, import_cache,
+    local_cache_table_name, refresh_stale_cache, CACHE_DB_ENV, CACHE_TABLE_ENV, CACHE_TTL_ENV,
+    DEFAULT_CACHE_TTL_DAYS,
+    geocode_addresses, geocode_batch, geocode_batch_dedupe, geocode_batch_normalized, geocode_batch_with_components, geocode_batch_with_progress,
+    geocode_batch_with_providers,
+    configure_s3_secret, geocode_via_geocodio, geocode_via_nominatim, http_client_builder, load_extension,
+    coord_precision, parse_coord_precision, partition_results, reverse_geocode_batch, round_coord,
+    user_agent, with_coord_precision, USER_AGENT_SUFFIX_ENV,
+    AddressComponents, canonicalize_address, components_from_columns, components_from_string,
+    edit_distance, extract_zip,
+    CacheEntry, CacheImportSummary, CacheRefreshSummary, GeocodeBatchResult, GeocodeFailure,
+    GeocodePartition, GeocodeProgressUpdate, GeocodeResult, GeocodeSourceCounts, GeocodeStats, GeocodeSummary,
+    ProviderMode, S3Credentials, COORD_PRECISION_ENV,
 };
 pub use spatia_geocode::search_index;
 
 // Re-export ingest crate's public API
-pub use spatia_ingest::{ingest_csv, ingest_csv_to_table, ingest_spatial_file, is_spatial_file};
+pub use spatia_ingest::{
+    add_geometry_from_wkt, ingest_csv, ingest_csv_streamed, ingest_csv_to_table,
+    ingest_csv_to_table_with_types, ingest_csv_with_row_progress, ingest_csv_with_text_fallback,
+    ingest_csv_with_types, ingest_glob, ingest_spatial_file, is_spatial_file, record_ingest_source,
+    unchanged_ingest_row_count, CsvIngestOutcome, GlobIngestFile, IngestOptions,
+    INGEST_PROVENANCE_TABLE,
+};
 
 // Re-export overture crate's public API
 pub use spatia_overture::{
-    fetch_buildings_in_bbox, overture_extract_to_table, overture_geocode,
-    overture_search, BBox, OvertureExtractResult, OvertureGeocodeResult,
-    OvertureSearchResult, OVERTURE_RELEASE,
+    fetch_buildings_in_bbox, overture_extract_explain, overture_extract_to_table,
+    overture_extract_to_table_filtered, overture_extract_to_table_if_stale, overture_geocode,
+    overture_geocode_page, overture_index_extract, overture_materialize_results, overture_query,
+    overture_rebuild_lookup, overture_release, overture_search,
+    overture_search_page, overture_search_with_highlight, overture_themes, BBox, DivisionLevel, ExtractFilters, ExtractRequirement,
+    OvertureExtractPlan, OvertureExtractResult, OvertureGeocodeResult, OvertureLookupResult, OvertureResultPage,
+    OvertureSearchResult, OvertureTheme, OvertureThemeCatalog, SearchWeights, DEFAULT_MAX_RESULTS,
+    OVERTURE_RELEASE, TEMP_DIR_ENV,
 };
 
+pub use analysis::check_analysis_sql;
 pub use analysis::execute_analysis_sql_to_geojson;
+pub use analysis::execute_analysis_sql_to_rows;
+pub use analysis::execute_analysis_sql_with_options;
+pub use analysis::explain_analysis_sql;
+pub use analysis::AnalysisOptions;
 pub use analysis::AnalysisExecutionResult;
+pub use analysis::AnalysisFormat;
+pub use analysis::AnalysisPlan;
+pub use analysis::AnalysisRows;
+pub use analysis::GeometryJoin;
+pub use analysis::NullGeometryMode;
 pub use analysis::TabularResult;
-pub use db_manager::DbManager;
-pub use executor::execute_command;
+pub use clean::{
+    clean_columns, parse_clean_spec, CleanColumnsResult, ColumnCleanOp, ColumnCleanSpec,
+    ColumnCleanSummary,
+};
+pub use db_info::{db_info, DbInfo};
+pub use db_manager::{open_connection, DbManager, SessionSettings};
+pub use executor::{execute_command, execute_command_to, execute_command_with};
+pub use shell::{shell_join, shell_quote, shell_tokenize};
+pub use spatial_join::{spatial_join, SpatialJoinResult};
+pub use schema::drop_view;
 pub use schema::fetch_column_samples;
+pub use schema::list_tables;
+pub use schema::list_views;
+pub use schema::profile_column;
 pub use schema::raw_staging_schema;
+pub use schema::table_json_schema;
+pub use schema::table_preview;
 pub use schema::table_schema;
+pub use schema::table_schema_projection;
+pub use schema::table_schema_with_samples;
+pub use schema::ColumnDistribution;
+pub use schema::ColumnProfile;
+pub use schema::ColumnWithSamples;
+pub use schema::HistogramBucket;
+pub use schema::SchemaProjection;
+pub use schema::TablePreview;
+pub use schema::TABLE_PREVIEW_MAX_ROWS;
 pub use schema::TableColumn;
+pub use schema::TableKind;
+pub use schema::TableListEntry;
+pub use schema::ValueCount;
+pub use schema::PROFILE_TOP_VALUES;
 pub use domain_pack::{
     detect_domain_columns, format_domain_column_annotations, ColumnDetectionRule, DomainPack,
     UiConfig,
 };
-pub use export::{export_analysis_geojson, export_table_csv};
-pub use identifiers::validate_table_name;
-pub use types::EngineResult;
+pub use export::{
+    export_analysis_geojson, export_table_csv, export_table_geoparquet, write_analysis_geojson,
+};
+pub use identifiers::{validate_column_name, validate_table_name};
+pub use types::{classify_error, CommandParseError, EngineResult, ErrorKind};
@@ -1,11 +1,13 @@
 use std::collections::HashMap;
 
 use serde::{Deserialize, Serialize};
+use serde_json::{json, Map, Value};
 use tracing::{debug, info};
 
-use crate::identifiers::validate_table_name;
+use crate::db_manager::open_connection;
+use crate::identifiers::{validate_column_reference, validate_table_name};
 use crate::EngineResult;
-use duckdb::Connection;
+use spatia_ingest::INGEST_PROVENANCE_TABLE;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TableColumn {
@@ -20,8 +22,13 @@ pub struct TableColumn {
 pub fn table_schema(db_path: &str, table_name: &str) -> EngineResult<Vec<TableColumn>> {
     debug!(table = %table_name, "table_schema: fetching schema");
     validate_table_name(table_name)?;
-    let conn = Connection::open(db_path)?;
+    let conn = open_connection(db_path)?;
+    table_columns(&conn, table_name)
+}
 
+/// [`table_schema`] on an open connection; `table_name` must already be
+/// validated.
+fn table_columns(conn: &duckdb::Connection, table_name: &str) -> EngineResult<Vec<TableColumn>> {
     // Use information_schema with query() (not query_map) to avoid DuckDB
     // 1.4.4 Rust driver panic on column_count() before statement execution.
     let sql = format!(
@@ -47,10 +54,529 @@ pub fn table_schema(db_path: &str, table_name: &str) -> EngineResult<Vec<TableCo
             primary_key: false,
         });
     }
+    if columns.is_empty() {
+        return Err(format!("table not found: {table_name}").into());
+    }
     info!(table = %table_name, column_count = columns.len(), "table_schema: fetched successfully");
     Ok(columns)
 }
 
+/// Whether a [`TableListEntry`] is a base table or a view.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TableKind {
+    Table,
+    View,
+}
+
+/// One relation listed by [`list_tables`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct TableListEntry {
+    pub name: String,
+    #[serde(rename = "type")]
+    pub kind: TableKind,
+}
+
+/// Base tables and views in `schema` (usually `main`), sorted by name. A
+/// fresh database, or a schema that does not exist, yields an empty list.
+/// The `ingest_provenance` bookkeeping table is not listed.
+pub fn list_tables(db_path: &str, schema: &str) -> EngineResult<Vec<TableListEntry>> {
+    let conn = open_connection(db_path)?;
+    let mut stmt = conn.prepare(
+        "SELECT table_name, table_type = 'VIEW' FROM information_schema.tables \
+         WHERE table_schema = ? AND table_type IN ('BASE TABLE', 'VIEW') \
+           AND table_name <> ? \
+         ORDER BY table_name",
+    )?;
+    let mut rows = stmt.query([schema, INGEST_PROVENANCE_TABLE])?;
+
+    let mut tables = Vec::new();
+    while let Some(row) = rows.next()? {
+        let is_view: bool = row.get(1)?;
+        tables.push(TableListEntry {
+            name: row.get(0)?,
+            kind: if is_view { TableKind::View } else { TableKind::Table },
+        });
+    }
+    debug!(schema = %schema, count = tables.len(), "list_tables: listed tables");
+    Ok(tables)
+}
+
+/// Names of the views in `main`, such as `analysis_result`, sorted.
+pub fn list_views(db_path: &str) -> EngineResult<Vec<String>> {
+    let conn = open_connection(db_path)?;
+    let mut stmt = conn.prepare(
+        "SELECT table_name FROM information_schema.tables \
+         WHERE table_schema = 'main' AND table_type = 'VIEW' \
+         ORDER BY table_name",
+    )?;
+    let mut rows = stmt.query([])?;
+
+    let mut views = Vec::new();
+    while let Some(row) = rows.next()? {
+        views.push(row.get(0)?);
+    }
+    debug!(count = views.len(), "list_views: listed views");
+    Ok(views)
+}
+
+/// Drop the view `view_name` from `main`. Tables are refused, so this cannot
+/// delete data, and a missing view is an error rather than a no-op.
+pub fn drop_view(db_path: &str, view_name: &str) -> EngineResult<()> {
+    validate_table_name(view_name)?;
+    let conn = open_connection(db_path)?;
+    let table_type: Option<String> = {
+        let mut stmt = conn.prepare(
+            "SELECT table_type FROM information_schema.tables \
+             WHERE table_schema = 'main' AND table_name = ?",
+        )?;
+        let mut rows = stmt.query([view_name])?;
+        match rows.next()? {
+            Some(row) => Some(row.get(0)?),
+            None => None,
+        }
+    };
+    match table_type.as_deref() {
+        None => return Err(format!("view not found: {view_name}").into()),
+        Some("VIEW") => {}
+        Some(_) => return Err(format!("{view_name} is a table, not a view").into()),
+    }
+    conn.execute_batch(&format!(r#"DROP VIEW "{view_name}""#))?;
+    info!(view = %view_name, "drop_view: dropped");
+    Ok(())
+}
+
+/// A column plus a few of its distinct values, rendered as text.
+#[derive(Debug, Clone, Serialize)]
+pub struct ColumnWithSamples {
+    #[serde(flatten)]
+    pub column: TableColumn,
+    /// Up to the requested number of distinct non-NULL values, sorted and
+    /// cut to `SAMPLE_VALUE_MAX_CHARS` characters each.
+    pub samples: Vec<String>,
+}
+
+/// Longest sample value kept before truncation; keeps geometry WKT and long
+/// free text from swamping the output.
+const SAMPLE_VALUE_MAX_CHARS: usize = 80;
+
+/// [`table_schema`] with up to `per_column` distinct sample values for every
+/// column, so callers can see what the data looks like without a second query.
+pub fn table_schema_with_samples(
+    db_path: &str,
+    table_name: &str,
+    per_column: usize,
+) -> EngineResult<Vec<ColumnWithSamples>> {
+    let schema = table_schema(db_path, table_name)?;
+    let conn = open_connection(db_path)?;
+
+    let mut columns = Vec::with_capacity(schema.len());
+    for column in schema {
+        let sql = format!(
+            "SELECT DISTINCT CAST(\"{col}\" AS VARCHAR) AS v FROM \"{table}\" \
+             WHERE \"{col}\" IS NOT NULL ORDER BY v LIMIT {per_column}",
+            col = column.name.replace('"', "\"\""),
+            table = table_name,
+        );
+        let mut stmt = conn.prepare(&sql)?;
+        let mut rows = stmt.query([])?;
+        let mut samples = Vec::new();
+        while let Some(row) = rows.next()? {
+            let value: String = row.get(0)?;
+            samples.push(truncate_sample(value));
+        }
+        columns.push(ColumnWithSamples { column, samples });
+    }
+    debug!(table = %table_name, per_column, "table_schema_with_samples: fetched");
+    Ok(columns)
+}
+
+fn truncate_sample(value: String) -> String {
+    match value.char_indices().nth(SAMPLE_VALUE_MAX_CHARS) {
+        Some((idx, _)) => format!("{}…", &value[..idx]),
+        None => value,
+    }
+}
+
+/// Most rows [`table_preview`] returns, whatever the caller asks for.
+pub const TABLE_PREVIEW_MAX_ROWS: usize = 100;
+
+/// Row count and the first rows of a table, for a quick look at the data.
+#[derive(Debug, Clone, Serialize)]
+pub struct TablePreview {
+    pub row_count: i64,
+    pub columns: Vec<TableColumn>,
+    /// One array per row, in `columns` order. Integer, decimal and boolean
+    /// columns are JSON numbers and booleans; everything else is text.
+    pub rows: Vec<Vec<Value>>,
+}
+
+/// The total row count and the first `limit` rows of `table_name` (capped at
+/// [`TABLE_PREVIEW_MAX_ROWS`]). Unlike [`table_schema_with_samples`] this is
+/// a raw peek: rows come back as stored, nothing is aggregated.
+pub fn table_preview(db_path: &str, table_name: &str, limit: usize) -> EngineResult<TablePreview> {
+    let columns = table_schema(db_path, table_name)?;
+    let conn = open_connection(db_path)?;
+    let limit = limit.clamp(1, TABLE_PREVIEW_MAX_ROWS);
+
+    let row_count: i64 = conn.query_row(
+        &format!("SELECT COUNT(*) FROM \"{table_name}\""),
+        [],
+        |row| row.get(0),
+    )?;
+
+    // Read every value as text and type it from the declared column type;
+    // `row.get::<_, String>` fails on non-VARCHAR columns otherwise.
+    let select_list = columns
+        .iter()
+        .map(|col| format!("CAST(\"{}\" AS VARCHAR)", col.name.replace('"', "\"\"")))
+        .collect::<Vec<_>>()
+        .join(", ");
+    let mut stmt = conn.prepare(&format!(
+        "SELECT {select_list} FROM \"{table_name}\" LIMIT {limit}"
+    ))?;
+    let mut rows = stmt.query([])?;
+
+    let mut preview_rows = Vec::new();
+    while let Some(row) = rows.next()? {
+        let mut cells = Vec::with_capacity(columns.len());
+        for (index, col) in columns.iter().enumerate() {
+            let text: Option<String> = row.get(index)?;
+            cells.push(match text {
+                Some(text) => typed_cell(&col.data_type, text),
+                None => Value::Null,
+            });
+        }
+        preview_rows.push(cells);
+    }
+    debug!(table = %table_name, row_count, returned = preview_rows.len(), "table_preview: fetched");
+    Ok(TablePreview {
+        row_count,
+        columns,
+        rows: preview_rows,
+    })
+}
+
+/// Turn a value read as text back into a JSON number or boolean when its
+/// column type says it is one. Values that do not fit (e.g. a HUGEINT beyond
+/// `i64`) stay text rather than losing precision.
+fn typed_cell(data_type: &str, text: String) -> Value {
+    match json_type_for_duckdb(data_type).0 {
+        "integer" => match text.parse::<i64>() {
+            Ok(value) => Value::from(value),
+            Err(_) => Value::String(text),
+        },
+        "number" => match text.parse::<f64>().ok().and_then(serde_json::Number::from_f64) {
+            Some(value) => Value::Number(value),
+            None => Value::String(text),
+        },
+        "boolean" => match text.as_str() {
+            "true" => Value::Bool(true),
+            "false" => Value::Bool(false),
+            _ => Value::String(text),
+        },
+        _ => Value::String(text),
+    }
+}
+
+/// Most frequent values [`profile_column`] lists for a categorical column.
+pub const PROFILE_TOP_VALUES: usize = 10;
+/// Equal-width buckets in a numeric column's histogram.
+const PROFILE_HISTOGRAM_BUCKETS: usize = 10;
+
+/// Value distribution of a single column, from [`profile_column`].
+#[derive(Debug, Clone, Serialize)]
+pub struct ColumnProfile {
+    pub table: String,
+    pub column: String,
+    pub data_type: String,
+    pub row_count: i64,
+    pub null_count: i64,
+    /// `null_count / row_count`, or 0 for an empty table.
+    pub null_rate: f64,
+    #[serde(flatten)]
+    pub distribution: ColumnDistribution,
+}
+
+/// Numeric columns get summary statistics and a histogram; everything else
+/// (text, booleans, dates, geometry) gets its most frequent values.
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum ColumnDistribution {
+    Numeric {
+        min: Option<f64>,
+        max: Option<f64>,
+        mean: Option<f64>,
+        histogram: Vec<HistogramBucket>,
+    },
+    Categorical {
+        distinct_count: i64,
+        top_values: Vec<ValueCount>,
+    },
+}
+
+/// Non-NULL values in `[lower, upper)`; the last bucket also includes `upper`.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct HistogramBucket {
+    pub lower: f64,
+    pub upper: f64,
+    pub count: i64,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ValueCount {
+    pub value: String,
+    pub count: i64,
+}
+
+/// Profile one column of `table_name`: row and NULL counts, plus min/max/mean
+/// and a histogram for numeric columns or the [`PROFILE_TOP_VALUES`] most
+/// frequent values for everything else. The kind is picked from the column's
+/// declared type in [`table_schema`].
+///
+/// `column_name` only has to name an existing column; any name DuckDB
+/// allows, spaces and quotes included, is quoted in the queries.
+pub fn profile_column(
+    db_path: &str,
+    table_name: &str,
+    column_name: &str,
+) -> EngineResult<ColumnProfile> {
+    validate_table_name(table_name)?;
+    let conn = open_connection(db_path)?;
+    let schema = table_columns(&conn, table_name)?;
+    let Some(column) = schema.into_iter().find(|col| col.name == column_name) else {
+        return Err(format!("column not found: {column_name} in {table_name}").into());
+    };
+    let col = column.name.replace('"', "\"\"");
+
+    let (row_count, non_null): (i64, i64) = conn.query_row(
+        &format!("SELECT COUNT(*), COUNT(\"{col}\") FROM \"{table_name}\""),
+        [],
+        |row| Ok((row.get(0)?, row.get(1)?)),
+    )?;
+    let null_count = row_count - non_null;
+    let null_rate = if row_count == 0 {
+        0.0
+    } else {
+        null_count as f64 / row_count as f64
+    };
+
+    let distribution = match json_type_for_duckdb(&column.data_type).0 {
+        "integer" | "number" => numeric_distribution(&conn, table_name, &col)?,
+        _ => categorical_distribution(&conn, table_name, &col)?,
+    };
+    debug!(
+        table = %table_name,
+        column = %column_name,
+        row_count,
+        null_count,
+        "profile_column: profiled"
+    );
+    Ok(ColumnProfile {
+        table: table_name.to_string(),
+        column: column.name,
+        data_type: column.data_type,
+        row_count,
+        null_count,
+        null_rate,
+        distribution,
+    })
+}
+
+/// `col` is the already-escaped column identifier.
+fn numeric_distribution(
+    conn: &duckdb::Connection,
+    table_name: &str,
+    col: &str,
+) -> EngineResult<ColumnDistribution> {
+    let value = format!("CAST(\"{col}\" AS DOUBLE)");
+    let (min, max, mean): (Option<f64>, Option<f64>, Option<f64>) = conn.query_row(
+        &format!("SELECT MIN({value}), MAX({value}), AVG({value}) FROM \"{table_name}\""),
+        [],
+        |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)),
+    )?;
+
+    // Infinite bounds cannot be split into buckets (or written into the SQL).
+    let mut histogram = Vec::new();
+    let bounds = (min.filter(|v| v.is_finite()), max.filter(|v| v.is_finite()));
+    if let (Some(lo), Some(hi)) = bounds {
+        // A single-valued column would make every bucket zero-width.
+        let buckets = if hi > lo { PROFILE_HISTOGRAM_BUCKETS } else { 1 };
+        let width = (hi - lo) / buckets as f64;
+        let index = if hi > lo {
+            format!(
+                "LEAST(CAST(FLOOR(({value} - ({lo})) / ({width})) AS BIGINT), {})",
+                buckets - 1
+            )
+        } else {
+            "CAST(0 AS BIGINT)".to_string()
+        };
+        let mut counts = vec![0i64; buckets];
+        let mut stmt = conn.prepare(&format!(
+            "SELECT {index} AS bucket, COUNT(*) FROM \"{table_name}\" \
+             WHERE \"{col}\" IS NOT NULL GROUP BY bucket"
+        ))?;
+        let mut rows = stmt.query([])?;
+        while let Some(row) = rows.next()? {
+            let bucket: i64 = row.get(0)?;
+            let count: i64 = row.get(1)?;
+            if let Some(slot) = usize::try_from(bucket).ok().and_then(|b| counts.get_mut(b)) {
+                *slot += count;
+            }
+        }
+        histogram = counts
+            .into_iter()
+            .enumerate()
+            .map(|(i, count)| HistogramBucket {
+                lower: lo + width * i as f64,
+                upper: if i + 1 == buckets { hi } else { lo + width * (i + 1) as f64 },
+                count,
+            })
+            .collect();
+    }
+    Ok(ColumnDistribution::Numeric {
+        min,
+        max,
+        mean,
+        histogram,
+    })
+}
+
+/// `col` is the already-escaped column identifier.
+fn categorical_distribution(
+    conn: &duckdb::Connection,
+    table_name: &str,
+    col: &str,
+) -> EngineResult<ColumnDistribution> {
+    let distinct_count: i64 = conn.query_row(
+        &format!("SELECT COUNT(DISTINCT \"{col}\") FROM \"{table_name}\""),
+        [],
+        |row| row.get(0),
+    )?;
+    let mut stmt = conn.prepare(&format!(
+        "SELECT CAST(\"{col}\" AS VARCHAR) AS v, COUNT(*) AS n FROM \"{table_name}\" \
+         WHERE \"{col}\" IS NOT NULL GROUP BY v ORDER BY n DESC, v LIMIT {PROFILE_TOP_VALUES}"
+    ))?;
+    let mut rows = stmt.query([])?;
+    let mut top_values = Vec::new();
+    while let Some(row) = rows.next()? {
+        let value: String = row.get(0)?;
+        top_values.push(ValueCount {
+            value: truncate_sample(value),
+            count: row.get(1)?,
+        });
+    }
+    Ok(ColumnDistribution::Categorical {
+        distinct_count,
+        top_values,
+    })
+}
+
+/// A subset of a table's schema restricted to explicitly requested columns.
+#[derive(Debug, Clone, Serialize)]
+pub struct SchemaProjection {
+    /// Matching columns, in table order.
+    pub columns: Vec<TableColumn>,
+    /// Requested names that do not exist on the table.
+    pub missing: Vec<String>,
+}
+
+/// Like [`table_schema`] but only returns the requested columns, so wide
+/// tables (e.g. 100+ column Overture extracts) can be trimmed before they are
+/// injected into AI prompts. Requested names are checked against the table's
+/// real columns (case-insensitively, as DuckDB resolves them), so ingested
+/// names such as `Policy Number` match. A malformed name is an error; a
+/// well-formed one the table lacks is reported in `missing`.
+pub fn table_schema_projection(
+    db_path: &str,
+    table_name: &str,
+    requested: &[String],
+) -> EngineResult<SchemaProjection> {
+    for name in requested {
+        validate_column_reference(name)?;
+    }
+    let schema = table_schema(db_path, table_name)?;
+    let is_requested =
+        |col: &TableColumn| requested.iter().any(|name| name.eq_ignore_ascii_case(&col.name));
+
+    let columns: Vec<TableColumn> =
+        schema.iter().filter(|col| is_requested(col)).cloned().collect();
+    let missing: Vec<String> = requested
+        .iter()
+        .filter(|name| !schema.iter().any(|col| col.name.eq_ignore_ascii_case(name)))
+        .cloned()
+        .collect();
+
+    debug!(
+        table = %table_name,
+        requested = requested.len(),
+        matched = columns.len(),
+        missing = missing.len(),
+        "table_schema_projection: projected schema"
+    );
+    Ok(SchemaProjection { columns, missing })
+}
+
+/// Build a Draft-07 JSON Schema describing one row of `table_name`.
+pub fn table_json_schema(db_path: &str, table_name: &str) -> EngineResult<Value> {
+    let schema = table_schema(db_path, table_name)?;
+    Ok(json_schema_for_columns(table_name, &schema))
+}
+
+/// Map column metadata to a Draft-07 object schema. Nullable columns accept
+/// `null` in addition to their base type; NOT NULL columns are `required`.
+fn json_schema_for_columns(table_name: &str, schema: &[TableColumn]) -> Value {
+    let mut properties = Map::new();
+    let mut required = Vec::new();
+
+    for col in schema {
+        let (json_type, format) = json_type_for_duckdb(&col.data_type);
+        let mut property = Map::new();
+        property.insert(
+            "type".to_string(),
+            if col.notnull {
+                json!(json_type)
+            } else {
+                json!([json_type, "null"])
+            },
+        );
+        if let Some(format) = format {
+            property.insert("format".to_string(), json!(format));
+        }
+        property.insert("description".to_string(), json!(col.data_type));
+        properties.insert(col.name.clone(), Value::Object(property));
+
+        if col.notnull {
+            required.push(col.name.clone());
+        }
+    }
+
+    json!({
+        "$schema": "http://json-schema.org/draft-07/schema#",
+        "title": table_name,
+        "type": "object",
+        "properties": properties,
+        "required": required,
+    })
+}
+
+/// DuckDB type name → (JSON Schema type, optional string format). Only
+/// formats defined by Draft-07 are emitted; UUIDs stay plain strings.
+fn json_type_for_duckdb(data_type: &str) -> (&'static str, Option<&'static str>) {
+    let upper = data_type.to_ascii_uppercase();
+    let base = upper.split('(').next().unwrap_or_default().trim();
+    match base {
+        "TINYINT" | "SMALLINT" | "INTEGER" | "BIGINT" | "HUGEINT" | "UTINYINT" | "USMALLINT"
+        | "UINTEGER" | "UBIGINT" | "UHUGEINT" | "INT" => ("integer", None),
+        "DOUBLE" | "FLOAT" | "REAL" | "DECIMAL" | "NUMERIC" => ("number", None),
+        "BOOLEAN" => ("boolean", None),
+        "DATE" => ("string", Some("date")),
+        "TIMESTAMP" | "TIMESTAMP WITH TIME ZONE" | "TIMESTAMPTZ" => ("string", Some("date-time")),
+        "TIME" => ("string", Some("time")),
+        _ => ("string", None),
+    }
+}
+
 pub fn raw_staging_schema(db_path: &str) -> EngineResult<Vec<TableColumn>> {
     table_schema(db_path, "raw_staging")
 }
@@ -71,7 +597,7 @@ pub fn fetch_column_samples(
     table_name: &str,
 ) -> EngineResult<HashMap<String, Vec<String>>> {
     validate_table_name(table_name)?;
-    let conn = Connection::open(db_path)?;
+    let conn = open_connection(db_path)?;
     let schema = table_schema(db_path, table_name)?;
 
     let mut samples: HashMap<String, Vec<String>> = HashMap::new();
@@ -120,3 +646,314 @@ pub fn fetch_column_samples(
     info!(table = %table_name, columns_with_samples = samples.len(), "fetch_column_samples: complete");
     Ok(samples)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        drop_view, json_schema_for_columns, json_type_for_duckdb, list_tables, list_views,
+        profile_column, table_preview, table_schema, table_schema_with_samples,
+        ColumnDistribution, HistogramBucket, TableColumn, TableKind, TableListEntry, ValueCount,
+        TABLE_PREVIEW_MAX_ROWS,
+    };
+    use duckdb::Connection;
+    use std::fs;
+    use std::time::{SystemTime, UNIX_EPOCH};
+
+    fn entry(name: &str, kind: TableKind) -> TableListEntry {
+        TableListEntry {
+            name: name.to_string(),
+            kind,
+        }
+    }
+
+    fn sample_schema() -> Vec<TableColumn> {
+        vec![
+            TableColumn {
+                cid: 0,
+                name: "id".to_string(),
+                data_type: "INTEGER".to_string(),
+                notnull: true,
+                default_value: None,
+                primary_key: true,
+            },
+            TableColumn {
+                cid: 1,
+                name: "city".to_string(),
+                data_type: "VARCHAR".to_string(),
+                notnull: false,
+                default_value: None,
+                primary_key: false,
+            },
+            TableColumn {
+                cid: 2,
+                name: "premium".to_string(),
+                data_type: "DECIMAL(18,3)".to_string(),
+                notnull: false,
+                default_value: None,
+                primary_key: false,
+            },
+            TableColumn {
+                cid: 3,
+                name: "active".to_string(),
+                data_type: "BOOLEAN".to_string(),
+                notnull: true,
+                default_value: None,
+                primary_key: false,
+            },
+            TableColumn {
+                cid: 4,
+                name: "updated_at".to_string(),
+                data_type: "TIMESTAMP".to_string(),
+                notnull: false,
+                default_value: None,
+                primary_key: false,
+            },
+        ]
+    }
+
+    #[test]
+    fn json_schema_maps_duckdb_types() {
+        let schema = json_schema_for_columns("policies", &sample_schema());
+        assert_eq!(schema["$schema"], "http://json-schema.org/draft-07/schema#");
+        assert_eq!(schema["title"], "policies");
+        assert_eq!(schema["type"], "object");
+
+        let props = &schema["properties"];
+        assert_eq!(props["id"]["type"], "integer");
+        assert_eq!(props["city"]["type"], serde_json::json!(["string", "null"]));
+        assert_eq!(props["premium"]["type"], serde_json::json!(["number", "null"]));
+        assert_eq!(props["active"]["type"], "boolean");
+        assert_eq!(props["updated_at"]["format"], "date-time");
+        assert_eq!(json_type_for_duckdb("UUID"), ("string", None));
+    }
+
+    #[test]
+    fn json_schema_requires_not_null_columns() {
+        let schema = json_schema_for_columns("policies", &sample_schema());
+        assert_eq!(schema["required"], serde_json::json!(["id", "active"]));
+    }
+
+    #[test]
+    fn schema_with_samples_lists_distinct_values_per_column() {
+        let suffix = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .expect("time")
+            .as_nanos();
+        let db_path = format!("/tmp/spatia_schema_test_{suffix}.duckdb");
+        {
+            let conn = Connection::open(&db_path).expect("open");
+            conn.execute_batch(
+                "CREATE TABLE policies (id INTEGER, city VARCHAR, note VARCHAR); \
+                 INSERT INTO policies VALUES \
+                     (1, 'Seattle', NULL), (2, 'Austin', NULL), \
+                     (3, 'Seattle', NULL), (4, 'Boston', repeat('x', 200));",
+            )
+            .expect("setup");
+        }
+
+        let columns = table_schema_with_samples(&db_path, "policies", 2).expect("samples");
+        assert_eq!(columns.len(), 3);
+        assert_eq!(columns[0].samples, vec!["1", "2"]);
+        assert_eq!(columns[1].samples, vec!["Austin", "Boston"]);
+        assert_eq!(columns[2].samples.len(), 1);
+        assert!(columns[2].samples[0].ends_with('…'));
+
+        let value = serde_json::to_value(&columns[1]).expect("json");
+        assert_eq!(value["name"], "city");
+        assert_eq!(value["samples"], serde_json::json!(["Austin", "Boston"]));
+
+        let _ = fs::remove_file(&db_path);
+        let _ = fs::remove_file(format!("{db_path}.wal"));
+    }
+
+    #[test]
+    fn preview_returns_typed_first_rows_and_total_count() {
+        let suffix = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .expect("time")
+            .as_nanos();
+        let db_path = format!("/tmp/spatia_schema_preview_test_{suffix}.duckdb");
+        {
+            let conn = Connection::open(&db_path).expect("open");
+            conn.execute_batch(
+                "CREATE TABLE policies (id INTEGER, city VARCHAR, premium DECIMAL(10,2), \
+                     active BOOLEAN); \
+                 INSERT INTO policies \
+                     SELECT i, 'City ' || i, i * 1.5, i % 2 = 0 FROM range(150) t(i); \
+                 UPDATE policies SET city = NULL WHERE id = 1;",
+            )
+            .expect("setup");
+        }
+
+        let preview = table_preview(&db_path, "policies", 2).expect("preview");
+        assert_eq!(preview.row_count, 150);
+        assert_eq!(preview.columns.len(), 4);
+        assert_eq!(preview.rows.len(), 2);
+        assert_eq!(
+            preview.rows[0],
+            vec![
+                serde_json::json!(0),
+                serde_json::json!("City 0"),
+                serde_json::json!(0.0),
+                serde_json::json!(true),
+            ]
+        );
+        assert_eq!(preview.rows[1][1], serde_json::Value::Null);
+        assert_eq!(preview.rows[1][2], serde_json::json!(1.5));
+
+        let capped = table_preview(&db_path, "policies", 1000).expect("preview");
+        assert_eq!(capped.rows.len(), TABLE_PREVIEW_MAX_ROWS);
+
+        assert!(table_preview(&db_path, "policies; DROP TABLE policies", 5).is_err());
+
+        let _ = fs::remove_file(&db_path);
+        let _ = fs::remove_file(format!("{db_path}.wal"));
+    }
+
+    #[test]
+    fn fresh_database_lists_no_tables_and_rejects_missing_schema() {
+        let suffix = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .expect("time")
+            .as_nanos();
+        let db_path = format!("/tmp/spatia_schema_fresh_test_{suffix}.duckdb");
+
+        assert!(list_tables(&db_path, "main").expect("list").is_empty());
+        let err = table_schema(&db_path, "policies").expect_err("missing table");
+        assert_eq!(err.to_string(), "table not found: policies");
+
+        {
+            let conn = Connection::open(&db_path).expect("open");
+            conn.execute_batch(
+                "CREATE TABLE policies (id INTEGER); CREATE VIEW v AS SELECT 1; \
+                 CREATE TABLE ingest_provenance (table_name VARCHAR); \
+                 CREATE SCHEMA overture; CREATE TABLE overture.places (id VARCHAR);",
+            )
+            .expect("setup");
+        }
+        assert_eq!(
+            list_tables(&db_path, "main").expect("list"),
+            vec![entry("policies", TableKind::Table), entry("v", TableKind::View)]
+        );
+        assert_eq!(
+            list_tables(&db_path, "overture").expect("list"),
+            vec![entry("places", TableKind::Table)]
+        );
+        assert_eq!(table_schema(&db_path, "policies").expect("schema").len(), 1);
+
+        let _ = fs::remove_file(&db_path);
+        let _ = fs::remove_file(format!("{db_path}.wal"));
+    }
+
+    #[test]
+    fn lists_and_drops_views_but_not_tables() {
+        let suffix = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .expect("time")
+            .as_nanos();
+        let db_path = format!("/tmp/spatia_schema_views_test_{suffix}.duckdb");
+        {
+            let conn = Connection::open(&db_path).expect("open");
+            conn.execute_batch(
+                "CREATE TABLE policies (id INTEGER); \
+                 CREATE VIEW analysis_result AS SELECT * FROM policies; \
+                 CREATE VIEW claims_by_state AS SELECT 1 AS n;",
+            )
+            .expect("setup");
+        }
+
+        assert_eq!(
+            list_views(&db_path).expect("views"),
+            vec!["analysis_result", "claims_by_state"]
+        );
+        drop_view(&db_path, "analysis_result").expect("drop view");
+        assert_eq!(list_views(&db_path).expect("views"), vec!["claims_by_state"]);
+
+        let err = drop_view(&db_path, "policies").expect_err("table");
+        assert_eq!(err.to_string(), "policies is a table, not a view");
+        let err = drop_view(&db_path, "analysis_result").expect_err("already dropped");
+        assert_eq!(err.to_string(), "view not found: analysis_result");
+        assert!(drop_view(&db_path, "bad-name").is_err());
+
+        let _ = fs::remove_file(&db_path);
+        let _ = fs::remove_file(format!("{db_path}.wal"));
+    }
+
+    #[test]
+    fn profiles_numeric_and_categorical_columns() {
+        let suffix = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .expect("time")
+            .as_nanos();
+        let db_path = format!("/tmp/spatia_schema_profile_test_{suffix}.duckdb");
+        {
+            let conn = Connection::open(&db_path).expect("open");
+            conn.execute_batch(
+                "CREATE TABLE policies \
+                 (premium INTEGER, city VARCHAR, \"home \"\"zone\"\"\" VARCHAR); \
+                 INSERT INTO policies VALUES (0, 'Oakland', 'a'), (5, 'Oakland', 'a'), \
+                 (10, 'Fresno', 'b'), (NULL, NULL, NULL);",
+            )
+            .expect("setup");
+        }
+
+        let profile = profile_column(&db_path, "policies", "premium").expect("numeric");
+        assert_eq!(profile.row_count, 4);
+        assert_eq!(profile.null_count, 1);
+        assert_eq!(profile.null_rate, 0.25);
+        let ColumnDistribution::Numeric {
+            min,
+            max,
+            mean,
+            histogram,
+        } = profile.distribution
+        else {
+            panic!("premium should be numeric");
+        };
+        assert_eq!((min, max, mean), (Some(0.0), Some(10.0), Some(5.0)));
+        assert_eq!(histogram.len(), 10);
+        assert_eq!(
+            histogram[0],
+            HistogramBucket {
+                lower: 0.0,
+                upper: 1.0,
+                count: 1
+            }
+        );
+        assert_eq!(histogram[5].count, 1);
+        assert_eq!(histogram[9].count, 1);
+        assert_eq!(histogram.iter().map(|b| b.count).sum::<i64>(), 3);
+
+        let profile = profile_column(&db_path, "policies", "city").expect("categorical");
+        let json = serde_json::to_value(&profile).expect("json");
+        assert_eq!(json["kind"], "categorical");
+        assert_eq!(json["distinct_count"], 2);
+        let ColumnDistribution::Categorical { top_values, .. } = profile.distribution else {
+            panic!("city should be categorical");
+        };
+        assert_eq!(
+            top_values,
+            vec![
+                ValueCount {
+                    value: "Oakland".to_string(),
+                    count: 2
+                },
+                ValueCount {
+                    value: "Fresno".to_string(),
+                    count: 1
+                },
+            ]
+        );
+
+        let profile =
+            profile_column(&db_path, "policies", "home \"zone\"").expect("quoted column name");
+        assert_eq!(profile.column, "home \"zone\"");
+        assert_eq!(profile.null_count, 1);
+
+        let err = profile_column(&db_path, "policies", "missing").expect_err("missing column");
+        assert_eq!(err.to_string(), "column not found: missing in policies");
+
+        let _ = fs::remove_file(&db_path);
+        let _ = fs::remove_file(format!("{db_path}.wal"));
+    }
+}
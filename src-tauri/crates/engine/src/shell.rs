@@ -0,0 +1,180 @@
+//! Canonical quoting for engine command strings.
+//!
+//! `execute_command` takes a single string, so every caller that builds one
+//! from separate arguments (the CLI, script files) must quote with the same
+//! rules the engine tokenizes with. The rules are a small shell-like subset:
+//!
+//! - Unquoted whitespace separates tokens.
+//! - `'...'` is literal: no escapes inside single quotes.
+//! - `"..."` allows `\"` and `\\`; any other backslash is kept as-is, so
+//!   Windows paths survive without doubling.
+//! - Outside quotes, a backslash escapes whitespace, a quote or another
+//!   backslash; before anything else it is literal.
+//! - `""` and `''` produce an empty token.
+
+use crate::EngineResult;
+
+/// Split `command` into tokens using the rules in the module docs.
+pub fn shell_tokenize(command: &str) -> EngineResult<Vec<String>> {
+    let mut tokens = Vec::new();
+    let mut current = String::new();
+    // Tracks whether a token has started, so `""` yields an empty token.
+    let mut in_token = false;
+    let mut in_quote: Option<char> = None;
+    let mut chars = command.chars().peekable();
+
+    while let Some(ch) = chars.next() {
+        match in_quote {
+            Some('\'') => {
+                if ch == '\'' {
+                    in_quote = None;
+                } else {
+                    current.push(ch);
+                }
+            }
+            Some(_) => match ch {
+                '"' => in_quote = None,
+                '\\' if matches!(chars.peek(), Some('"' | '\\')) => {
+                    current.extend(chars.next());
+                }
+                _ => current.push(ch),
+            },
+            None => match ch {
+                '"' | '\'' => {
+                    in_quote = Some(ch);
+                    in_token = true;
+                }
+                '\\' if chars
+                    .peek()
+                    .is_some_and(|next| next.is_whitespace() || is_escapable(*next)) =>
+                {
+                    current.extend(chars.next());
+                    in_token = true;
+                }
+                _ if ch.is_whitespace() => {
+                    if in_token {
+                        tokens.push(std::mem::take(&mut current));
+                        in_token = false;
+                    }
+                }
+                _ => {
+                    current.push(ch);
+                    in_token = true;
+                }
+            },
+        }
+    }
+
+    if in_quote.is_some() {
+        return Err("Unterminated quoted string".into());
+    }
+    if in_token {
+        tokens.push(current);
+    }
+    Ok(tokens)
+}
+
+/// Quote `value` so that `shell_tokenize` returns it as exactly one token.
+/// Plain values are returned unchanged.
+pub fn shell_quote(value: &str) -> String {
+    let plain = !value.is_empty()
+        && !value
+            .chars()
+            .any(|ch| ch.is_whitespace() || is_escapable(ch));
+    if plain {
+        return value.to_string();
+    }
+    let mut quoted = String::with_capacity(value.len() + 2);
+    quoted.push('"');
+    for ch in value.chars() {
+        if ch == '"' || ch == '\\' {
+            quoted.push('\\');
+        }
+        quoted.push(ch);
+    }
+    quoted.push('"');
+    quoted
+}
+
+/// Join `args` into one command string that tokenizes back to `args`.
+pub fn shell_join<S: AsRef<str>>(args: &[S]) -> String {
+    args.iter()
+        .map(|arg| shell_quote(arg.as_ref()))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+fn is_escapable(ch: char) -> bool {
+    matches!(ch, '"' | '\'' | '\\')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{shell_join, shell_quote, shell_tokenize};
+
+    #[test]
+    fn splits_on_whitespace_and_strips_quotes() {
+        let tokens = shell_tokenize("geocode db \"123 Main St\" 'O Street'").expect("tokenize");
+        assert_eq!(tokens, vec!["geocode", "db", "123 Main St", "O Street"]);
+    }
+
+    #[test]
+    fn double_quotes_accept_escaped_quote_and_backslash() {
+        let tokens = shell_tokenize(r#""say \"hi\" \\ now""#).expect("tokenize");
+        assert_eq!(tokens, vec![r#"say "hi" \ now"#]);
+    }
+
+    #[test]
+    fn windows_paths_keep_backslashes() {
+        let tokens = shell_tokenize(r#"ingest C:\data\spatia.duckdb "C:\data\my file.csv""#)
+            .expect("tokenize");
+        assert_eq!(
+            tokens,
+            vec!["ingest", r"C:\data\spatia.duckdb", r"C:\data\my file.csv"]
+        );
+    }
+
+    #[test]
+    fn empty_quotes_produce_empty_token() {
+        let tokens = shell_tokenize("a \"\" ''").expect("tokenize");
+        assert_eq!(tokens, vec!["a", "", ""]);
+    }
+
+    #[test]
+    fn unterminated_quote_errors() {
+        assert!(shell_tokenize("a \"b").is_err());
+        assert!(shell_tokenize("a 'b").is_err());
+    }
+
+    #[test]
+    fn plain_values_are_not_quoted() {
+        assert_eq!(shell_quote("places_wa"), "places_wa");
+        assert_eq!(
+            shell_quote("-122.4,47.5,-122.2,47.7"),
+            "-122.4,47.5,-122.2,47.7"
+        );
+    }
+
+    #[test]
+    fn quote_round_trips_awkward_values() {
+        let alphabet = ['a', ' ', '"', '\'', '\\', '\t', 'é', ',', '\n'];
+        let mut values = vec![String::new()];
+        // Every string of length 1..=3 over the alphabet.
+        for len in 1..=3u32 {
+            for mut n in 0..alphabet.len().pow(len) {
+                let mut value = String::new();
+                for _ in 0..len {
+                    value.push(alphabet[n % alphabet.len()]);
+                    n /= alphabet.len();
+                }
+                values.push(value);
+            }
+        }
+        for value in &values {
+            let tokens = shell_tokenize(&shell_quote(value)).expect("tokenize");
+            assert_eq!(tokens, vec![value.clone()], "round trip of {value:?}");
+        }
+        let joined = shell_join(&values);
+        assert_eq!(shell_tokenize(&joined).expect("tokenize"), values);
+    }
+}
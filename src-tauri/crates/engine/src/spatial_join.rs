@@ -0,0 +1,239 @@
+//! Point-in-polygon join: tag each point with the polygon that contains it.
+//!
+//! The polygon's `id` and its name (a `name` column, or `names.primary` on
+//! Overture tables) are appended to every point row as `polygon_id` and
+//! `polygon_name`. Points outside every polygon are kept with NULLs.
+
+use duckdb::Connection;
+use serde::Serialize;
+use tracing::info;
+
+use crate::db_manager::open_connection;
+use crate::export::geometry_column;
+use crate::identifiers::validate_table_name;
+use crate::{load_extension, EngineResult};
+
+/// Columns added to the point rows.
+const APPENDED_COLUMNS: [&str; 2] = ["polygon_id", "polygon_name"];
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SpatialJoinResult {
+    pub status: &'static str,
+    pub table: String,
+    /// Rows written; a point inside overlapping polygons appears once per
+    /// polygon.
+    pub rows: usize,
+    /// Points that fell inside at least one polygon.
+    pub matched_points: usize,
+    /// Points that fell inside none.
+    pub unmatched_points: usize,
+}
+
+/// Join `points_table` to `polygons_table` with `ST_Contains` and write the
+/// result to `output_table`, replacing it if it exists. Both inputs need a
+/// GEOMETRY column, and the polygons need an `id` or a name to append.
+pub fn spatial_join(
+    db_path: &str,
+    points_table: &str,
+    polygons_table: &str,
+    output_table: &str,
+) -> EngineResult<SpatialJoinResult> {
+    for table in [points_table, polygons_table, output_table] {
+        validate_table_name(table)?;
+    }
+    if output_table.eq_ignore_ascii_case(points_table)
+        || output_table.eq_ignore_ascii_case(polygons_table)
+    {
+        return Err(format!("output table {output_table} must differ from both inputs").into());
+    }
+
+    let conn = open_connection(db_path)?;
+    load_extension(&conn, "spatial")?;
+    let point_geometry = required_geometry_column(&conn, points_table)?;
+    let polygon_geometry = required_geometry_column(&conn, polygons_table)?;
+
+    let point_columns = column_types(&conn, points_table)?;
+    if let Some((clash, _)) = point_columns.iter().find(|(name, _)| {
+        APPENDED_COLUMNS
+            .iter()
+            .any(|c| name.eq_ignore_ascii_case(c))
+    }) {
+        return Err(format!(
+            "table {points_table} already has a {clash} column; rename it before joining"
+        )
+        .into());
+    }
+
+    let polygon_columns = column_types(&conn, polygons_table)?;
+    let id_expr = polygon_columns
+        .iter()
+        .find(|(name, _)| name.eq_ignore_ascii_case("id"))
+        .map(|(name, _)| format!(r#"g."{name}""#));
+    let name_expr = polygon_name_expr(&polygon_columns);
+    if id_expr.is_none() && name_expr.is_none() {
+        return Err(format!(
+            "table {polygons_table} has no id, name or names column to append to the points"
+        )
+        .into());
+    }
+    let id_expr = id_expr.unwrap_or_else(|| "NULL".to_string());
+    let name_expr = name_expr.unwrap_or_else(|| "NULL".to_string());
+
+    let contains = format!(r#"ST_Contains(g."{polygon_geometry}", p."{point_geometry}")"#);
+    conn.execute_batch(&format!(
+        r#"CREATE OR REPLACE TABLE "{output_table}" AS
+           SELECT p.*, {id_expr} AS polygon_id, {name_expr} AS polygon_name
+           FROM "{points_table}" p
+           LEFT JOIN "{polygons_table}" g ON {contains}"#
+    ))?;
+
+    let rows: i64 = conn.query_row(
+        &format!(r#"SELECT COUNT(*) FROM "{output_table}""#),
+        [],
+        |row| row.get(0),
+    )?;
+    let (total_points, matched_points): (i64, i64) = conn.query_row(
+        &format!(
+            r#"SELECT COUNT(*),
+                      COUNT(*) FILTER (WHERE EXISTS (
+                          SELECT 1 FROM "{polygons_table}" g WHERE {contains}))
+               FROM "{points_table}" p"#
+        ),
+        [],
+        |row| Ok((row.get(0)?, row.get(1)?)),
+    )?;
+
+    info!(
+        points = %points_table,
+        polygons = %polygons_table,
+        output = %output_table,
+        rows,
+        matched_points,
+        "spatial_join: complete"
+    );
+    Ok(SpatialJoinResult {
+        status: "ok",
+        table: output_table.to_string(),
+        rows: rows as usize,
+        matched_points: matched_points as usize,
+        unmatched_points: (total_points - matched_points) as usize,
+    })
+}
+
+fn required_geometry_column(conn: &Connection, table_name: &str) -> EngineResult<String> {
+    geometry_column(conn, table_name)?.ok_or_else(|| {
+        format!(
+            "table {table_name} has no GEOMETRY column; \
+             add one with ingest --wkt or ST_Point(lon, lat) first"
+        )
+        .into()
+    })
+}
+
+/// `(name, data_type)` of every column of `table_name` in `main`.
+fn column_types(conn: &Connection, table_name: &str) -> EngineResult<Vec<(String, String)>> {
+    let mut stmt = conn.prepare(
+        "SELECT column_name, data_type FROM information_schema.columns \
+         WHERE table_schema = 'main' AND table_name = ? \
+         ORDER BY ordinal_position",
+    )?;
+    let mut rows = stmt.query([table_name])?;
+    let mut columns = Vec::new();
+    while let Some(row) = rows.next()? {
+        columns.push((row.get(0)?, row.get(1)?));
+    }
+    Ok(columns)
+}
+
+/// A plain `name` column wins; otherwise Overture's `names` struct supplies
+/// `names.primary`.
+fn polygon_name_expr(columns: &[(String, String)]) -> Option<String> {
+    if let Some((name, _)) = columns
+        .iter()
+        .find(|(name, _)| name.eq_ignore_ascii_case("name"))
+    {
+        return Some(format!(r#"CAST(g."{name}" AS VARCHAR)"#));
+    }
+    columns
+        .iter()
+        .find(|(name, data_type)| {
+            name.eq_ignore_ascii_case("names") && data_type.to_ascii_lowercase().contains("primary")
+        })
+        .map(|(name, _)| format!(r#"g."{name}".primary"#))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::spatial_join;
+    use crate::load_extension;
+    use duckdb::Connection;
+    use std::fs;
+    use std::time::{SystemTime, UNIX_EPOCH};
+
+    fn temp_db_path() -> String {
+        let suffix = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .expect("time")
+            .as_nanos();
+        format!("/tmp/spatia_spatial_join_test_{suffix}.duckdb")
+    }
+
+    fn cleanup(db_path: &str) {
+        let _ = fs::remove_file(db_path);
+        let _ = fs::remove_file(format!("{db_path}.wal"));
+    }
+
+    #[test]
+    fn points_get_the_containing_polygon() {
+        let db_path = temp_db_path();
+        {
+            let conn = Connection::open(&db_path).expect("open");
+            load_extension(&conn, "spatial").expect("spatial");
+            conn.execute_batch(
+                "CREATE TABLE stores AS SELECT * FROM (VALUES \
+                     ('inside', ST_Point(0.5, 0.5)), ('outside', ST_Point(5, 5))) t(label, geom); \
+                 CREATE TABLE regions AS SELECT 'r1' AS id, 'Unit Square' AS name, \
+                     ST_GeomFromText('POLYGON((0 0, 1 0, 1 1, 0 1, 0 0))') AS geometry; \
+                 CREATE TABLE plain AS SELECT 1 AS id;",
+            )
+            .expect("setup");
+        }
+
+        let result = spatial_join(&db_path, "stores", "regions", "stores_by_region").expect("join");
+        assert_eq!(result.rows, 2);
+        assert_eq!((result.matched_points, result.unmatched_points), (1, 1));
+
+        let conn = Connection::open(&db_path).expect("open");
+        load_extension(&conn, "spatial").expect("spatial");
+        let mut stmt = conn
+            .prepare("SELECT label, polygon_id, polygon_name FROM stores_by_region ORDER BY label")
+            .expect("prepare");
+        let mut rows = stmt.query([]).expect("query");
+        let mut joined: Vec<(String, Option<String>, Option<String>)> = Vec::new();
+        while let Some(row) = rows.next().expect("row") {
+            joined.push((
+                row.get(0).expect("label"),
+                row.get(1).expect("id"),
+                row.get(2).expect("name"),
+            ));
+        }
+        assert_eq!(
+            joined,
+            vec![
+                (
+                    "inside".to_string(),
+                    Some("r1".to_string()),
+                    Some("Unit Square".to_string())
+                ),
+                ("outside".to_string(), None, None),
+            ]
+        );
+        drop(stmt);
+        drop(conn);
+
+        let err = spatial_join(&db_path, "plain", "regions", "out").expect_err("no geometry");
+        assert!(err.to_string().contains("no GEOMETRY column"));
+        assert!(spatial_join(&db_path, "stores", "regions", "stores").is_err());
+        cleanup(&db_path);
+    }
+}
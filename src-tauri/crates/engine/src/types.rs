@@ -1,3 +1,88 @@
 use std::error::Error;
+use std::fmt;
 
 pub type EngineResult<T> = Result<T, Box<dyn Error + Send + Sync>>;
+
+/// A command string that could not be parsed (bad usage, malformed bbox,
+/// non-numeric limit, ...). Raised by `execute_command` before any work runs.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CommandParseError(pub String);
+
+impl fmt::Display for CommandParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl Error for CommandParseError {}
+
+/// Coarse failure category for engine errors, used by the CLI exit codes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ErrorKind {
+    Parse,
+    NotFound,
+    Database,
+    Other,
+}
+
+impl ErrorKind {
+    /// 2 = parse error, 3 = not found, 4 = DuckDB error, 1 = anything else.
+    pub fn exit_code(self) -> i32 {
+        match self {
+            ErrorKind::Parse => 2,
+            ErrorKind::NotFound => 3,
+            ErrorKind::Database => 4,
+            ErrorKind::Other => 1,
+        }
+    }
+}
+
+/// Categorise an error returned by the engine.
+///
+/// Missing tables surface from DuckDB as catalog errors, so the "not found"
+/// check runs before the DuckDB downcast to keep them in the same bucket as
+/// missing files.
+pub fn classify_error(err: &(dyn Error + 'static)) -> ErrorKind {
+    if err.downcast_ref::<CommandParseError>().is_some() {
+        return ErrorKind::Parse;
+    }
+    let message = err.to_string().to_ascii_lowercase();
+    if message.contains("not found") || message.contains("does not exist") {
+        return ErrorKind::NotFound;
+    }
+    if err.downcast_ref::<duckdb::Error>().is_some() {
+        return ErrorKind::Database;
+    }
+    ErrorKind::Other
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{classify_error, CommandParseError, ErrorKind};
+    use std::error::Error;
+
+    #[test]
+    fn classifies_parse_not_found_and_other() {
+        let parse: Box<dyn Error + Send + Sync> =
+            Box::new(CommandParseError("Usage: db_info <db_path>".to_string()));
+        assert_eq!(classify_error(parse.as_ref()), ErrorKind::Parse);
+
+        let missing: Box<dyn Error + Send + Sync> =
+            "database file not found: /tmp/nope.duckdb".into();
+        assert_eq!(classify_error(missing.as_ref()), ErrorKind::NotFound);
+
+        let other: Box<dyn Error + Send + Sync> = "Geocodio request failed".into();
+        assert_eq!(classify_error(other.as_ref()), ErrorKind::Other);
+        assert_eq!(ErrorKind::Other.exit_code(), 1);
+    }
+
+    #[test]
+    fn classifies_duckdb_errors() {
+        let conn = duckdb::Connection::open_in_memory().expect("open");
+        let err: Box<dyn Error + Send + Sync> = conn
+            .execute_batch("SELECT * FROM")
+            .expect_err("syntax error")
+            .into();
+        assert_eq!(classify_error(err.as_ref()), ErrorKind::Database);
+    }
+}
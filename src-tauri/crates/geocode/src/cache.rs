@@ -1,18 +1,205 @@
+use std::collections::{HashMap, HashSet};
+use std::path::Path;
+use std::time::{SystemTime, UNIX_EPOCH};
+
 use duckdb::Connection;
+use tracing::{debug, info, warn};
+
+use crate::connection::open_connection;
+use crate::geocode::{geocode_batch_uncached, ProviderMode};
+use crate::identifiers::validate_table_name;
+use crate::types::{
+    CacheEntry, CacheImportSummary, CacheRefreshSummary, GeoResult, GeocodeBatchResult,
+    GeocodeResult, GeocodeStats,
+};
+
+/// Default cache table, created inside the user's data DB.
+const DEFAULT_CACHE_TABLE: &str = "geocode_cache";
+
+/// Environment variable moving the cache into a separate DuckDB file.
+pub const CACHE_DB_ENV: &str = "SPATIA_GEOCODE_CACHE_DB";
+
+/// Environment variable renaming the cache table.
+pub const CACHE_TABLE_ENV: &str = "SPATIA_GEOCODE_CACHE_TABLE";
+
+/// Catalog alias used when the cache lives in a separate attached database.
+const CACHE_DB_ALIAS: &str = "spatia_geocode_cache";
+
+/// Prefix that keeps reverse-geocode keys apart from forward address keys.
+const REVERSE_KEY_PREFIX: &str = "reverse:";
+
+/// Environment variable overriding [`DEFAULT_CACHE_TTL_DAYS`].
+pub const CACHE_TTL_ENV: &str = "SPATIA_GEOCODE_CACHE_TTL_DAYS";
+
+/// Age in days after which `refresh_stale_cache` re-geocodes an entry.
+pub const DEFAULT_CACHE_TTL_DAYS: u32 = 90;
+
+const MILLIS_PER_DAY: i64 = 24 * 60 * 60 * 1000;
+
+/// Table comment marking a cache whose `cached_at` values are UTC. Tables
+/// without it predate the switch and store local time.
+const UTC_CACHE_COMMENT: &str = "spatia geocode cache: cached_at in UTC";
+
+/// Granularity of the local-time offset removed from legacy rows; real zone
+/// offsets are whole quarter hours.
+const OFFSET_STEP_MILLIS: i64 = 15 * 60 * 1000;
+
+/// Cache TTL in days from [`CACHE_TTL_ENV`], or [`DEFAULT_CACHE_TTL_DAYS`]
+/// when unset.
+pub fn cache_ttl_days() -> GeoResult<u32> {
+    let raw = match std::env::var(CACHE_TTL_ENV) {
+        Ok(raw) if !raw.trim().is_empty() => raw,
+        _ => return Ok(DEFAULT_CACHE_TTL_DAYS),
+    };
+    match raw.trim().parse::<u32>() {
+        Ok(days) => Ok(days),
+        Err(_) => Err(format!(
+            "invalid {CACHE_TTL_ENV} '{raw}': expected a whole number of days"
+        )
+        .into()),
+    }
+}
+
+/// `cached_at` values are UTC. They are computed here rather than with
+/// `current_timestamp`, which DuckDB converts to the session's local time
+/// zone when storing it in a plain `TIMESTAMP` column.
+fn unix_millis_now() -> i64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |elapsed| elapsed.as_millis() as i64)
+}
 
-use crate::types::{GeoResult, GeocodeResult};
+/// SQL `TIMESTAMP` literal for `millis` since the Unix epoch, in UTC.
+fn utc_timestamp_sql(millis: i64) -> String {
+    format!("epoch_ms({millis})")
+}
+
+fn utc_now_sql() -> String {
+    utc_timestamp_sql(unix_millis_now())
+}
 
-/// Create the `geocode_cache` table in `conn` if it does not already exist.
+/// Create the geocode cache table if it does not already exist.
+///
+/// The cache defaults to `geocode_cache` in `conn`'s own database.
+/// `SPATIA_GEOCODE_CACHE_DB` moves it into a separate DuckDB file (attached
+/// on demand) so one cache can be shared across projects, and
+/// `SPATIA_GEOCODE_CACHE_TABLE` renames the table.
 pub fn ensure_cache_table(conn: &Connection) -> GeoResult<()> {
-    conn.execute_batch(
-        "CREATE TABLE IF NOT EXISTS geocode_cache (
+    cache_table(conn).map(|_| ())
+}
+
+/// Name of the cache table inside the data DB: `geocode_cache` unless
+/// [`CACHE_TABLE_ENV`] renames it. `None` when [`CACHE_DB_ENV`] keeps the
+/// cache in a separate database, so no table of the data DB is the cache.
+pub fn local_cache_table_name() -> GeoResult<Option<String>> {
+    let (cache_db, table) = cache_settings();
+    if cache_db.is_some() {
+        return Ok(None);
+    }
+    let table = table.unwrap_or_else(|| DEFAULT_CACHE_TABLE.to_string());
+    validate_table_name(&table)?;
+    Ok(Some(table))
+}
+
+/// Non-empty values of [`CACHE_DB_ENV`] and [`CACHE_TABLE_ENV`].
+fn cache_settings() -> (Option<String>, Option<String>) {
+    let setting = |name: &str| std::env::var(name).ok().filter(|v| !v.trim().is_empty());
+    (setting(CACHE_DB_ENV), setting(CACHE_TABLE_ENV))
+}
+
+/// Resolve the configured cache table, attach its database if needed and
+/// create it. Returns the (possibly catalog-qualified) table reference.
+fn cache_table(conn: &Connection) -> GeoResult<String> {
+    let (cache_db, table) = cache_settings();
+    resolve_cache_table(conn, cache_db.as_deref(), table.as_deref())
+}
+
+fn resolve_cache_table(
+    conn: &Connection,
+    cache_db: Option<&str>,
+    table: Option<&str>,
+) -> GeoResult<String> {
+    let table = table.unwrap_or(DEFAULT_CACHE_TABLE);
+    validate_table_name(table)?;
+
+    let (qualified, catalog) = match cache_db {
+        Some(path) => {
+            conn.execute_batch(&format!(
+                "ATTACH IF NOT EXISTS '{}' AS {CACHE_DB_ALIAS}",
+                path.replace('\'', "''")
+            ))?;
+            (format!("{CACHE_DB_ALIAS}.{table}"), format!("'{CACHE_DB_ALIAS}'"))
+        }
+        None => (table.to_string(), "current_database()".to_string()),
+    };
+
+    // `None` when the table does not exist yet, `Some(comment)` otherwise.
+    let existing: Option<Option<String>> = {
+        let mut stmt = conn.prepare(&format!(
+            "SELECT comment FROM duckdb_tables() \
+             WHERE database_name = {catalog} AND schema_name = 'main' AND table_name = ?"
+        ))?;
+        let mut rows = stmt.query([table])?;
+        match rows.next()? {
+            Some(row) => Some(row.get(0)?),
+            None => None,
+        }
+    };
+
+    conn.execute_batch(&format!(
+        "CREATE TABLE IF NOT EXISTS {qualified} (
             address   TEXT PRIMARY KEY,
             lat       REAL NOT NULL,
             lon       REAL NOT NULL,
             source    TEXT NOT NULL,
-            cached_at TIMESTAMP DEFAULT current_timestamp
-        )",
+            cached_at TIMESTAMP DEFAULT current_timestamp,
+            matched_address TEXT
+        );
+        ALTER TABLE {qualified} ADD COLUMN IF NOT EXISTS matched_address TEXT;"
+    ))?;
+
+    match existing {
+        None => conn.execute_batch(&format!(
+            "COMMENT ON TABLE {qualified} IS '{UTC_CACHE_COMMENT}'"
+        ))?,
+        Some(comment) if comment.as_deref() != Some(UTC_CACHE_COMMENT) => {
+            migrate_local_cached_at(conn, &qualified, local_offset_millis(conn)?)?;
+        }
+        Some(_) => {}
+    }
+    Ok(qualified)
+}
+
+/// Offset of the session's local time zone from UTC, in milliseconds,
+/// rounded to [`OFFSET_STEP_MILLIS`].
+fn local_offset_millis(conn: &Connection) -> GeoResult<i64> {
+    let local: i64 = conn.query_row(
+        "SELECT epoch_ms(CAST(current_timestamp AS TIMESTAMP))",
+        [],
+        |row| row.get(0),
     )?;
+    let offset = local - unix_millis_now();
+    Ok((offset as f64 / OFFSET_STEP_MILLIS as f64).round() as i64 * OFFSET_STEP_MILLIS)
+}
+
+/// Shift the local-time `cached_at` values of a cache created before they
+/// were stored in UTC back by `offset_millis`, and mark the table so this
+/// runs once. Uses the current offset, so rows written on the other side
+/// of a daylight-saving change end up an hour off, which is well inside
+/// any TTL.
+fn migrate_local_cached_at(conn: &Connection, table: &str, offset_millis: i64) -> GeoResult<()> {
+    conn.execute_batch("BEGIN TRANSACTION")?;
+    let migrated = conn.execute_batch(&format!(
+        "UPDATE {table} SET cached_at = epoch_ms(epoch_ms(cached_at) - ({offset_millis})) \
+         WHERE cached_at IS NOT NULL; \
+         COMMENT ON TABLE {table} IS '{UTC_CACHE_COMMENT}';"
+    ));
+    if let Err(err) = migrated {
+        conn.execute_batch("ROLLBACK")?;
+        return Err(err.into());
+    }
+    conn.execute_batch("COMMIT")?;
+    info!(table = %table, offset_millis, "migrate_local_cached_at: converted cached_at to UTC");
     Ok(())
 }
 
@@ -24,7 +211,7 @@ pub fn cache_lookup(
     conn: &Connection,
     addresses: &[String],
 ) -> GeoResult<(Vec<GeocodeResult>, Vec<String>)> {
-    ensure_cache_table(conn)?;
+    let table = cache_table(conn)?;
 
     if addresses.is_empty() {
         return Ok((Vec::new(), Vec::new()));
@@ -34,13 +221,12 @@ pub fn cache_lookup(
     // For very large batches we chunk to avoid SQL statement size limits,
     // but for typical geocoding batches (≤10k) a single query is fine.
     const CHUNK_SIZE: usize = 500;
-    let mut hit_map: std::collections::HashMap<String, GeocodeResult> =
-        std::collections::HashMap::with_capacity(addresses.len());
+    let mut hit_map: HashMap<String, GeocodeResult> = HashMap::with_capacity(addresses.len());
 
     for chunk in addresses.chunks(CHUNK_SIZE) {
         let placeholders: String = chunk.iter().map(|_| "?").collect::<Vec<_>>().join(", ");
         let sql = format!(
-            "SELECT address, lat, lon, source FROM geocode_cache WHERE address IN ({placeholders})"
+            "SELECT address, lat, lon, source FROM {table} WHERE address IN ({placeholders})"
         );
         let mut stmt = conn.prepare(&sql)?;
         let params: Vec<&dyn duckdb::ToSql> =
@@ -53,6 +239,16 @@ pub fn cache_lookup(
                 lon: row.get(2)?,
                 source: row.get(3)?,
             };
+            // A corrupt row is treated as a miss so it gets re-geocoded.
+            if !valid_coordinates(result.lat, result.lon) {
+                warn!(
+                    address = %result.address,
+                    lat = result.lat,
+                    lon = result.lon,
+                    "cache_lookup: ignoring out-of-range cache row"
+                );
+                continue;
+            }
             hit_map.insert(result.address.clone(), result);
         }
     }
@@ -67,31 +263,67 @@ pub fn cache_lookup(
         }
     }
 
+    debug!(hits = hits.len(), misses = misses.len(), "cache_lookup: complete");
     Ok((hits, misses))
 }
 
-/// Upsert resolved geocode results into `geocode_cache` using a single
-/// multi-row `INSERT OR REPLACE` statement per chunk.
+/// Upsert resolved geocode results into the cache table using a single
+/// multi-row `INSERT OR REPLACE` statement per chunk, all in one transaction.
+/// When an address appears more than once, its last result wins.
+///
+/// Results whose coordinates fall outside lat [-90, 90] / lon [-180, 180]
+/// are logged and not stored.
+///
+/// Takes the connection mutably so the caller cannot hold a transaction of
+/// its own on it: DuckDB does not nest transactions.
 pub fn cache_store(
-    conn: &Connection,
+    conn: &mut Connection,
     results: &[GeocodeResult],
     source: &str,
 ) -> GeoResult<()> {
+    let valid = results.iter().filter(|result| {
+        let ok = valid_coordinates(result.lat, result.lon);
+        if !ok {
+            warn!(
+                address = %result.address,
+                lat = result.lat,
+                lon = result.lon,
+                "cache_store: skipping out-of-range coordinates"
+            );
+        }
+        ok
+    });
+    // A repeated address keeps its last result, as one upsert per row would;
+    // a single INSERT OR REPLACE cannot touch the same key twice.
+    let mut results: Vec<&GeocodeResult> = Vec::with_capacity(results.len());
+    let mut positions: HashMap<&str, usize> = HashMap::new();
+    for result in valid {
+        match positions.get(result.address.as_str()) {
+            Some(&index) => results[index] = result,
+            None => {
+                positions.insert(result.address.as_str(), results.len());
+                results.push(result);
+            }
+        }
+    }
     if results.is_empty() {
         return Ok(());
     }
-    ensure_cache_table(conn)?;
+    let table = cache_table(conn)?;
 
     // DuckDB handles multi-row VALUES efficiently; chunk to stay within
     // reasonable parameter counts (4 params per row × 250 = 1000 params).
     const CHUNK_SIZE: usize = 250;
+    let now = utc_now_sql();
+    // Dropping the transaction on an early return rolls it back.
+    let tx = conn.transaction()?;
     for chunk in results.chunks(CHUNK_SIZE) {
         let row_placeholders: Vec<String> = chunk
             .iter()
-            .map(|_| "(?, ?, ?, ?, current_timestamp)".to_string())
+            .map(|_| format!("(?, ?, ?, ?, {now})"))
             .collect();
         let sql = format!(
-            "INSERT OR REPLACE INTO geocode_cache (address, lat, lon, source, cached_at) VALUES {}",
+            "INSERT OR REPLACE INTO {table} (address, lat, lon, source, cached_at) VALUES {}",
             row_placeholders.join(", ")
         );
         let mut params_vec: Vec<Box<dyn duckdb::ToSql>> = Vec::with_capacity(chunk.len() * 4);
@@ -102,12 +334,410 @@ pub fn cache_store(
             params_vec.push(Box::new(source.to_string()));
         }
         let params_refs: Vec<&dyn duckdb::ToSql> = params_vec.iter().map(|p| p.as_ref()).collect();
-        conn.execute(&sql, params_refs.as_slice())?;
+        tx.execute(&sql, params_refs.as_slice())?;
     }
+    tx.commit()?;
+
+    debug!(count = results.len(), source = %source, "cache_store: stored results");
+    Ok(())
+}
+
+/// Cache key for a reverse lookup: the point rounded to five decimal places
+/// (about 1 m), so repeated lookups of the same spot share one entry.
+pub(crate) fn reverse_cache_key(lat: f64, lon: f64) -> String {
+    // Adding 0.0 turns a rounded -0.0 into 0.0 so both print the same key.
+    let round = |v: f64| (v * 1e5).round() / 1e5 + 0.0;
+    format!("{REVERSE_KEY_PREFIX}{:.5},{:.5}", round(lat), round(lon))
+}
+
+/// Look up cached reverse-geocode matches for `points`, keyed by
+/// [`reverse_cache_key`]. Each hit's `address` is the matched address.
+pub(crate) fn reverse_cache_lookup(
+    conn: &Connection,
+    points: &[(f64, f64)],
+) -> GeoResult<HashMap<String, GeocodeResult>> {
+    let table = cache_table(conn)?;
+    let keys: Vec<String> = points
+        .iter()
+        .map(|&(lat, lon)| reverse_cache_key(lat, lon))
+        .collect();
+
+    const CHUNK_SIZE: usize = 500;
+    let mut hits = HashMap::new();
+    for chunk in keys.chunks(CHUNK_SIZE) {
+        let placeholders: String = chunk.iter().map(|_| "?").collect::<Vec<_>>().join(", ");
+        let sql = format!(
+            "SELECT address, matched_address, lat, lon, source FROM {table} \
+             WHERE address IN ({placeholders}) AND matched_address IS NOT NULL"
+        );
+        let mut stmt = conn.prepare(&sql)?;
+        let params: Vec<&dyn duckdb::ToSql> =
+            chunk.iter().map(|k| k as &dyn duckdb::ToSql).collect();
+        let mut rows = stmt.query(params.as_slice())?;
+        while let Some(row) = rows.next()? {
+            let key: String = row.get(0)?;
+            hits.insert(
+                key,
+                GeocodeResult {
+                    address: row.get(1)?,
+                    lat: row.get(2)?,
+                    lon: row.get(3)?,
+                    source: row.get(4)?,
+                },
+            );
+        }
+    }
+
+    debug!(hits = hits.len(), points = points.len(), "reverse_cache_lookup: complete");
+    Ok(hits)
+}
+
+/// Store reverse-geocode results, whose `lat`/`lon` are the queried point and
+/// whose `address` is the matched address, under [`reverse_cache_key`].
+pub(crate) fn reverse_cache_store(
+    conn: &Connection,
+    results: &[GeocodeResult],
+    source: &str,
+) -> GeoResult<()> {
+    if results.is_empty() {
+        return Ok(());
+    }
+    let table = cache_table(conn)?;
+    let sql = format!(
+        "INSERT OR REPLACE INTO {table} \
+         (address, lat, lon, source, cached_at, matched_address) \
+         VALUES (?, ?, ?, ?, {}, ?)",
+        utc_now_sql()
+    );
+    let mut stmt = conn.prepare(&sql)?;
+    for result in results {
+        stmt.execute(duckdb::params![
+            reverse_cache_key(result.lat, result.lon),
+            result.lat,
+            result.lon,
+            source,
+            result.address,
+        ])?;
+    }
+
+    debug!(count = results.len(), source = %source, "reverse_cache_store: stored results");
+    Ok(())
+}
+
+/// File formats accepted by [`export_cache`] and [`import_cache`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum CacheFileFormat {
+    Csv,
+    Json,
+}
+
+impl CacheFileFormat {
+    fn from_path(path: &str) -> GeoResult<Self> {
+        let extension = Path::new(path)
+            .extension()
+            .and_then(|ext| ext.to_str())
+            .map(str::to_ascii_lowercase);
+        match extension.as_deref() {
+            Some("csv") => Ok(Self::Csv),
+            Some("json") => Ok(Self::Json),
+            _ => Err(format!(
+                "unsupported cache file {path}: expected a .csv or .json extension"
+            )
+            .into()),
+        }
+    }
+}
+
+/// Whether `lat`/`lon` are finite and on the globe.
+pub(crate) fn valid_coordinates(lat: f64, lon: f64) -> bool {
+    (-90.0..=90.0).contains(&lat) && (-180.0..=180.0).contains(&lon)
+}
+
+/// Write every cache row to `path` as CSV or JSON, picked by its extension.
+/// Returns the number of rows written.
+pub fn export_cache(conn: &Connection, path: &str) -> GeoResult<usize> {
+    let format = CacheFileFormat::from_path(path)?;
+    let table = cache_table(conn)?;
+    let select = format!(
+        "SELECT address, lat, lon, source, matched_address, \
+         CAST(cached_at AS VARCHAR) AS cached_at FROM {table} ORDER BY address"
+    );
+
+    let count = match format {
+        CacheFileFormat::Csv => {
+            conn.execute_batch(&format!(
+                "COPY ({select}) TO '{}' (FORMAT CSV, HEADER)",
+                path.replace('\'', "''")
+            ))?;
+            let count: i64 =
+                conn.query_row(&format!("SELECT COUNT(*) FROM {table}"), [], |row| row.get(0))?;
+            count as usize
+        }
+        CacheFileFormat::Json => {
+            let mut stmt = conn.prepare(&select)?;
+            let mut rows = stmt.query([])?;
+            let mut entries = Vec::new();
+            while let Some(row) = rows.next()? {
+                entries.push(CacheEntry {
+                    address: row.get(0)?,
+                    lat: row.get(1)?,
+                    lon: row.get(2)?,
+                    source: row.get(3)?,
+                    matched_address: row.get(4)?,
+                    cached_at: row.get(5)?,
+                });
+            }
+            std::fs::write(path, serde_json::to_string_pretty(&entries)?)?;
+            entries.len()
+        }
+    };
+
+    info!(path = %path, count, "export_cache: wrote cache rows");
+    Ok(count)
+}
+
+/// Upsert the rows of a CSV or JSON file written by [`export_cache`] into the
+/// cache. Rows whose coordinates do not parse or fall outside
+/// lat [-90, 90] / lon [-180, 180] are skipped and counted.
+pub fn import_cache(conn: &Connection, path: &str) -> GeoResult<CacheImportSummary> {
+    let (entries, mut skipped) = match CacheFileFormat::from_path(path)? {
+        CacheFileFormat::Csv => read_cache_csv(conn, path)?,
+        CacheFileFormat::Json => {
+            let contents = std::fs::read_to_string(path)
+                .map_err(|err| format!("failed to read cache file {path}: {err}"))?;
+            let entries: Vec<CacheEntry> = serde_json::from_str(&contents)
+                .map_err(|err| format!("invalid cache file {path}: {err}"))?;
+            (entries, 0)
+        }
+    };
+
+    let valid: Vec<CacheEntry> = entries
+        .into_iter()
+        .filter(|entry| {
+            let ok = valid_coordinates(entry.lat, entry.lon);
+            if !ok {
+                debug!(
+                    address = %entry.address,
+                    lat = entry.lat,
+                    lon = entry.lon,
+                    "import_cache: skipping out-of-range row"
+                );
+                skipped += 1;
+            }
+            ok
+        })
+        .collect();
+
+    let table = cache_table(conn)?;
+    insert_entries(conn, &table, &valid)?;
+
+    let summary = CacheImportSummary {
+        imported: valid.len(),
+        skipped,
+    };
+    info!(
+        path = %path,
+        imported = summary.imported,
+        skipped = summary.skipped,
+        "import_cache: complete"
+    );
+    Ok(summary)
+}
 
+/// Upsert `entries` in one transaction, keeping each entry's `cached_at` and
+/// stamping entries without one with the current UTC time.
+fn insert_entries(conn: &Connection, table: &str, entries: &[CacheEntry]) -> GeoResult<()> {
+    let sql = format!(
+        "INSERT OR REPLACE INTO {table} \
+         (address, lat, lon, source, cached_at, matched_address) \
+         VALUES (?, ?, ?, ?, COALESCE(TRY_CAST(? AS TIMESTAMP), {}), ?)",
+        utc_now_sql()
+    );
+    let mut stmt = conn.prepare(&sql)?;
+    conn.execute_batch("BEGIN TRANSACTION")?;
+    for entry in entries {
+        let inserted = stmt.execute(duckdb::params![
+            entry.address,
+            entry.lat,
+            entry.lon,
+            entry.source,
+            entry.cached_at,
+            entry.matched_address,
+        ]);
+        if let Err(err) = inserted {
+            conn.execute_batch("ROLLBACK")?;
+            return Err(err.into());
+        }
+    }
+    conn.execute_batch("COMMIT")?;
     Ok(())
 }
 
+/// Re-geocode every forward cache entry older than `max_age_days` (or with
+/// no timestamp) in one batch through `providers`, bypassing the cache, and
+/// upsert each result with a fresh UTC `cached_at`. Nothing is deleted:
+/// entries that no longer resolve, or every entry when the providers fail,
+/// keep their old row and timestamp so the next refresh retries them.
+/// Reverse-geocode entries are left alone.
+pub fn refresh_stale_cache(
+    db_path: &str,
+    max_age_days: u32,
+    providers: ProviderMode,
+) -> GeoResult<CacheRefreshSummary> {
+    refresh_stale_entries(db_path, max_age_days, |addresses| {
+        geocode_batch_uncached(db_path, addresses, providers)
+    })
+}
+
+fn refresh_stale_entries<F>(
+    db_path: &str,
+    max_age_days: u32,
+    geocode: F,
+) -> GeoResult<CacheRefreshSummary>
+where
+    F: FnOnce(&[String]) -> GeoResult<(Vec<GeocodeBatchResult>, GeocodeStats)>,
+{
+    let cutoff = unix_millis_now() - i64::from(max_age_days) * MILLIS_PER_DAY;
+    let predicate = format!(
+        "address NOT LIKE '{REVERSE_KEY_PREFIX}%' \
+         AND (cached_at IS NULL OR cached_at < {})",
+        utc_timestamp_sql(cutoff)
+    );
+
+    // The connection is closed while the pipeline runs because it opens the
+    // database itself.
+    let stale = {
+        let conn = open_connection(db_path)?;
+        let table = cache_table(&conn)?;
+        read_entries(&conn, &table, &predicate)?
+    };
+    if stale.is_empty() {
+        info!(max_age_days, "refresh_stale_cache: no stale entries");
+        return Ok(CacheRefreshSummary {
+            max_age_days,
+            ..Default::default()
+        });
+    }
+
+    let addresses: Vec<String> = stale.iter().map(|entry| entry.address.clone()).collect();
+    let (results, _stats) = geocode(&addresses)
+        .map_err(|err| format!("cache refresh failed, stale entries kept: {err}"))?;
+
+    let mut by_source: HashMap<&str, Vec<GeocodeResult>> = HashMap::new();
+    for result in results.iter().filter(|r| valid_coordinates(r.lat, r.lon)) {
+        by_source
+            .entry(result.source.as_str())
+            .or_default()
+            .push(GeocodeResult::from(result.clone()));
+    }
+    let refreshed: HashSet<&str> = by_source
+        .values()
+        .flatten()
+        .map(|result| result.address.trim())
+        .collect();
+    let mut conn = open_connection(db_path)?;
+    for (source, rows) in &by_source {
+        cache_store(&mut conn, rows, source)?;
+    }
+    let kept = stale
+        .iter()
+        .filter(|entry| !refreshed.contains(entry.address.trim()))
+        .count();
+
+    let summary = CacheRefreshSummary {
+        max_age_days,
+        stale: stale.len(),
+        refreshed: stale.len() - kept,
+        kept,
+    };
+    info!(
+        stale = summary.stale,
+        refreshed = summary.refreshed,
+        kept = summary.kept,
+        "refresh_stale_cache: complete"
+    );
+    Ok(summary)
+}
+
+/// Cache rows matching `predicate`, with `cached_at` as text so it can be
+/// written back unchanged.
+fn read_entries(conn: &Connection, table: &str, predicate: &str) -> GeoResult<Vec<CacheEntry>> {
+    let mut stmt = conn.prepare(&format!(
+        "SELECT address, lat, lon, source, matched_address, CAST(cached_at AS VARCHAR) \
+         FROM {table} WHERE {predicate} ORDER BY address"
+    ))?;
+    let mut rows = stmt.query([])?;
+    let mut entries = Vec::new();
+    while let Some(row) = rows.next()? {
+        entries.push(CacheEntry {
+            address: row.get(0)?,
+            lat: row.get(1)?,
+            lon: row.get(2)?,
+            source: row.get(3)?,
+            matched_address: row.get(4)?,
+            cached_at: row.get(5)?,
+        });
+    }
+    Ok(entries)
+}
+
+/// Read a cache CSV as text so malformed coordinates can be counted instead
+/// of failing the whole file. `matched_address` and `cached_at` are optional.
+fn read_cache_csv(conn: &Connection, path: &str) -> GeoResult<(Vec<CacheEntry>, usize)> {
+    let source = format!(
+        "read_csv('{}', header = true, all_varchar = true)",
+        path.replace('\'', "''")
+    );
+    let mut columns = Vec::new();
+    let mut stmt = conn.prepare(&format!("DESCRIBE SELECT * FROM {source}"))?;
+    let mut rows = stmt.query([])?;
+    while let Some(row) = rows.next()? {
+        columns.push(row.get::<_, String>(0)?);
+    }
+    for required in ["address", "lat", "lon", "source"] {
+        if !columns.iter().any(|column| column == required) {
+            return Err(format!("cache file {path} is missing the {required} column").into());
+        }
+    }
+    let optional = |name: &str| {
+        if columns.iter().any(|column| column == name) {
+            name.to_string()
+        } else {
+            format!("NULL AS {name}")
+        }
+    };
+
+    let sql = format!(
+        "SELECT address, lat, lon, source, {}, {} FROM {source}",
+        optional("matched_address"),
+        optional("cached_at"),
+    );
+    let mut stmt = conn.prepare(&sql)?;
+    let mut rows = stmt.query([])?;
+    let mut entries = Vec::new();
+    let mut skipped = 0;
+    while let Some(row) = rows.next()? {
+        let lat: Option<String> = row.get(1)?;
+        let lon: Option<String> = row.get(2)?;
+        let coordinates = lat
+            .and_then(|lat| lat.trim().parse::<f64>().ok())
+            .zip(lon.and_then(|lon| lon.trim().parse::<f64>().ok()));
+        let Some((lat, lon)) = coordinates else {
+            skipped += 1;
+            continue;
+        };
+        entries.push(CacheEntry {
+            address: row.get(0)?,
+            lat,
+            lon,
+            source: row.get(3)?,
+            matched_address: row.get(4)?,
+            cached_at: row.get(5)?,
+        });
+    }
+    Ok((entries, skipped))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -121,8 +751,56 @@ mod tests {
     }
 
     #[test]
-    fn cache_store_and_lookup_round_trip() {
+    fn resolve_cache_table_defaults_to_in_db_table() {
         let conn = Connection::open_in_memory().expect("open");
+        let table = resolve_cache_table(&conn, None, None).expect("resolve");
+        assert_eq!(table, "geocode_cache");
+    }
+
+    #[test]
+    fn resolve_cache_table_attaches_separate_db() {
+        let suffix = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .expect("time")
+            .as_nanos();
+        let cache_db = format!("/tmp/spatia_geocode_cache_test_{suffix}.duckdb");
+        let conn = Connection::open_in_memory().expect("open");
+
+        let table =
+            resolve_cache_table(&conn, Some(&cache_db), Some("shared_cache")).expect("resolve");
+        assert_eq!(table, "spatia_geocode_cache.shared_cache");
+        // A second call must not fail on the existing attachment.
+        resolve_cache_table(&conn, Some(&cache_db), Some("shared_cache")).expect("re-resolve");
+
+        conn.execute_batch(&format!(
+            "INSERT INTO {table} (address, lat, lon, source) VALUES ('a', 1.0, 2.0, 'test')"
+        ))
+        .expect("insert");
+        let main_tables: i64 = conn
+            .query_row(
+                "SELECT COUNT(*) FROM information_schema.tables \
+                 WHERE table_catalog = 'memory' AND table_name = 'shared_cache'",
+                [],
+                |row| row.get(0),
+            )
+            .expect("count");
+        assert_eq!(main_tables, 0);
+        assert!(std::path::Path::new(&cache_db).exists());
+
+        drop(conn);
+        let _ = std::fs::remove_file(&cache_db);
+        let _ = std::fs::remove_file(format!("{cache_db}.wal"));
+    }
+
+    #[test]
+    fn resolve_cache_table_rejects_invalid_name() {
+        let conn = Connection::open_in_memory().expect("open");
+        assert!(resolve_cache_table(&conn, None, Some("bad;name")).is_err());
+    }
+
+    #[test]
+    fn cache_store_and_lookup_round_trip() {
+        let mut conn = Connection::open_in_memory().expect("open");
         let records = vec![GeocodeResult {
             address: "123 Main St, Springfield, IL".to_string(),
             lat: 39.7817,
@@ -130,7 +808,7 @@ mod tests {
             source: "geocodio".to_string(),
         }];
 
-        cache_store(&conn, &records, "geocodio").expect("store");
+        cache_store(&mut conn, &records, "geocodio").expect("store");
 
         let addresses = vec!["123 Main St, Springfield, IL".to_string()];
         let (hits, misses) = cache_lookup(&conn, &addresses).expect("lookup");
@@ -144,14 +822,14 @@ mod tests {
 
     #[test]
     fn cache_lookup_separates_hits_and_misses() {
-        let conn = Connection::open_in_memory().expect("open");
+        let mut conn = Connection::open_in_memory().expect("open");
         let cached = vec![GeocodeResult {
             address: "cached address".to_string(),
             lat: 1.0,
             lon: 2.0,
             source: "geocodio".to_string(),
         }];
-        cache_store(&conn, &cached, "geocodio").expect("store");
+        cache_store(&mut conn, &cached, "geocodio").expect("store");
 
         let addresses = vec!["cached address".to_string(), "uncached address".to_string()];
         let (hits, misses) = cache_lookup(&conn, &addresses).expect("lookup");
@@ -164,14 +842,14 @@ mod tests {
 
     #[test]
     fn cache_store_upserts_existing_address() {
-        let conn = Connection::open_in_memory().expect("open");
+        let mut conn = Connection::open_in_memory().expect("open");
         let original = vec![GeocodeResult {
             address: "test addr".to_string(),
             lat: 10.0,
             lon: 20.0,
             source: "geocodio".to_string(),
         }];
-        cache_store(&conn, &original, "geocodio").expect("store original");
+        cache_store(&mut conn, &original, "geocodio").expect("store original");
 
         let updated = vec![GeocodeResult {
             address: "test addr".to_string(),
@@ -179,11 +857,298 @@ mod tests {
             lon: 21.0,
             source: "geocodio".to_string(),
         }];
-        cache_store(&conn, &updated, "geocodio").expect("store updated");
+        cache_store(&mut conn, &updated, "geocodio").expect("store updated");
 
         let addresses = vec!["test addr".to_string()];
         let (hits, _) = cache_lookup(&conn, &addresses).expect("lookup");
         assert_eq!(hits.len(), 1);
         assert!((hits[0].lat - 11.0).abs() < 1e-6);
     }
+
+    #[test]
+    fn cache_store_rejects_out_of_range_coordinates() {
+        let mut conn = Connection::open_in_memory().expect("open");
+        let records = vec![
+            GeocodeResult {
+                address: "off globe".to_string(),
+                lat: 123.0,
+                lon: -89.6501,
+                source: "geocodio".to_string(),
+            },
+            GeocodeResult {
+                address: "on globe".to_string(),
+                lat: 39.7817,
+                lon: -89.6501,
+                source: "geocodio".to_string(),
+            },
+        ];
+        cache_store(&mut conn, &records, "geocodio").expect("store");
+
+        let addresses = vec!["off globe".to_string(), "on globe".to_string()];
+        let (hits, misses) = cache_lookup(&conn, &addresses).expect("lookup");
+        assert_eq!(hits.len(), 1);
+        assert_eq!(hits[0].address, "on globe");
+        assert_eq!(misses, vec!["off globe".to_string()]);
+    }
+
+    /// Cache rows as `(address, lat, lon, source)`, sorted by address.
+    fn cache_rows(conn: &Connection) -> Vec<(String, f64, f64, String)> {
+        let mut stmt = conn
+            .prepare("SELECT address, lat, lon, source FROM geocode_cache ORDER BY address")
+            .expect("prepare");
+        let mut rows = stmt.query([]).expect("query");
+        let mut out = Vec::new();
+        while let Some(row) = rows.next().expect("row") {
+            out.push((
+                row.get(0).expect("address"),
+                row.get(1).expect("lat"),
+                row.get(2).expect("lon"),
+                row.get(3).expect("source"),
+            ));
+        }
+        out
+    }
+
+    #[test]
+    fn cache_store_batches_match_per_row_upserts() {
+        // Enough rows to span several chunks, with repeats across chunk
+        // boundaries and a few out-of-range coordinates.
+        let records: Vec<GeocodeResult> = (0..700u32)
+            .map(|i| GeocodeResult {
+                address: format!("{} Main St", i % 600),
+                lat: if i % 97 == 0 { 95.0 } else { f64::from(i % 90) },
+                lon: f64::from(i) / 10.0,
+                source: "geocodio".to_string(),
+            })
+            .collect();
+        let seed = "INSERT INTO geocode_cache (address, lat, lon, source) \
+                    VALUES ('5 Main St', 0.0, 0.0, 'old')";
+
+        let mut batched = Connection::open_in_memory().expect("open");
+        ensure_cache_table(&batched).expect("ensure");
+        batched.execute_batch(seed).expect("seed");
+        cache_store(&mut batched, &records, "geocodio").expect("store");
+
+        let per_row = Connection::open_in_memory().expect("open");
+        ensure_cache_table(&per_row).expect("ensure");
+        per_row.execute_batch(seed).expect("seed");
+        let mut stmt = per_row
+            .prepare(
+                "INSERT OR REPLACE INTO geocode_cache (address, lat, lon, source) \
+                 VALUES (?, ?, ?, ?)",
+            )
+            .expect("prepare");
+        for record in records.iter().filter(|r| valid_coordinates(r.lat, r.lon)) {
+            stmt.execute(duckdb::params![
+                record.address,
+                record.lat,
+                record.lon,
+                "geocodio"
+            ])
+            .expect("insert");
+        }
+
+        let expected = cache_rows(&per_row);
+        assert_eq!(expected.len(), 595);
+        assert_eq!(cache_rows(&batched), expected);
+    }
+
+    #[test]
+    fn cache_lookup_treats_corrupt_rows_as_misses() {
+        let conn = Connection::open_in_memory().expect("open");
+        ensure_cache_table(&conn).expect("ensure");
+        conn.execute_batch(
+            "INSERT INTO geocode_cache (address, lat, lon, source) \
+             VALUES ('corrupt', 45.0, 540.0, 'geocodio')",
+        )
+        .expect("insert");
+
+        let (hits, misses) = cache_lookup(&conn, &["corrupt".to_string()]).expect("lookup");
+        assert!(hits.is_empty());
+        assert_eq!(misses, vec!["corrupt".to_string()]);
+    }
+
+    #[test]
+    fn reverse_cache_key_rounds_to_five_decimals() {
+        assert_eq!(reverse_cache_key(47.620512, -122.349288), "reverse:47.62051,-122.34929");
+        assert_eq!(reverse_cache_key(-0.000001, 0.0), "reverse:0.00000,0.00000");
+    }
+
+    #[test]
+    fn reverse_cache_store_and_lookup_round_trip() {
+        let conn = Connection::open_in_memory().expect("open");
+        let records = vec![GeocodeResult {
+            address: "400 Broad St, Seattle, WA 98109".to_string(),
+            lat: 47.6205,
+            lon: -122.3493,
+            source: "geocodio".to_string(),
+        }];
+        reverse_cache_store(&conn, &records, "geocodio").expect("store");
+
+        let hits = reverse_cache_lookup(&conn, &[(47.620501, -122.349301), (1.0, 1.0)])
+            .expect("lookup");
+        assert_eq!(hits.len(), 1);
+        let hit = &hits[&reverse_cache_key(47.6205, -122.3493)];
+        assert_eq!(hit.address, "400 Broad St, Seattle, WA 98109");
+
+        // Reverse entries never answer a forward lookup for the matched address.
+        let (forward_hits, _) =
+            cache_lookup(&conn, &["400 Broad St, Seattle, WA 98109".to_string()]).expect("lookup");
+        assert!(forward_hits.is_empty());
+    }
+
+    #[test]
+    fn refresh_regeocodes_only_stale_forward_entries() {
+        let suffix = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .expect("time")
+            .as_nanos();
+        let db_path = format!("/tmp/spatia_geocode_cache_test_{suffix}.duckdb");
+        {
+            let conn = Connection::open(&db_path).expect("open");
+            ensure_cache_table(&conn).expect("cache table");
+            conn.execute_batch(&format!(
+                "INSERT INTO geocode_cache (address, lat, lon, source, cached_at) VALUES \
+                     ('1 Main St', 1.0, 1.0, 'geocodio', TIMESTAMP '2020-01-01 00:00:00'), \
+                     ('Nowhere', 3.0, 3.0, 'nominatim', TIMESTAMP '2020-01-01 00:00:00'), \
+                     ('2 Oak Ave', 4.0, 4.0, 'geocodio', {now}); \
+                 INSERT INTO geocode_cache (address, lat, lon, source, cached_at, matched_address) \
+                     VALUES ('reverse:1.00000,1.00000', 1.0, 1.0, 'geocodio', \
+                             TIMESTAMP '2020-01-01 00:00:00', '1 Main St');",
+                now = utc_now_sql()
+            ))
+            .expect("seed");
+        }
+
+        let mut requested: Vec<String> = Vec::new();
+        let summary = refresh_stale_entries(&db_path, 30, |addresses| {
+            requested.extend(addresses.iter().cloned());
+            let result = GeocodeBatchResult {
+                address: "1 Main St".to_string(),
+                lat: 2.0,
+                lon: 2.0,
+                source: "geocodio".to_string(),
+                confidence: 1.0,
+                matched_label: None,
+                matched_table: None,
+                gers_id: None,
+            };
+            Ok((vec![result], GeocodeStats::default()))
+        })
+        .expect("refresh");
+        assert_eq!(requested, vec!["1 Main St", "Nowhere"]);
+        assert_eq!((summary.stale, summary.refreshed, summary.kept), (2, 1, 1));
+
+        let conn = Connection::open(&db_path).expect("reopen");
+        let mut stmt = conn
+            .prepare(
+                "SELECT address, lat, cached_at > TIMESTAMP '2020-01-01 00:00:00' \
+                 FROM geocode_cache ORDER BY address",
+            )
+            .expect("prepare");
+        let mut rows = stmt.query([]).expect("query");
+        let mut cache: Vec<(String, f64, bool)> = Vec::new();
+        while let Some(row) = rows.next().expect("row") {
+            cache.push((
+                row.get(0).expect("address"),
+                row.get(1).expect("lat"),
+                row.get(2).expect("advanced"),
+            ));
+        }
+        assert_eq!(
+            cache,
+            vec![
+                ("1 Main St".to_string(), 2.0, true),
+                ("2 Oak Ave".to_string(), 4.0, true),
+                ("Nowhere".to_string(), 3.0, false),
+                ("reverse:1.00000,1.00000".to_string(), 1.0, false),
+            ]
+        );
+        drop(stmt);
+        drop(conn);
+        let _ = std::fs::remove_file(&db_path);
+        let _ = std::fs::remove_file(format!("{db_path}.wal"));
+    }
+
+    #[test]
+    fn refresh_keeps_stale_entries_when_the_provider_fails() {
+        let suffix = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .expect("time")
+            .as_nanos();
+        let db_path = format!("/tmp/spatia_geocode_cache_test_{suffix}.duckdb");
+        {
+            let conn = Connection::open(&db_path).expect("open");
+            ensure_cache_table(&conn).expect("cache table");
+            conn.execute_batch(
+                "INSERT INTO geocode_cache (address, lat, lon, source, cached_at) VALUES \
+                     ('1 Main St', 1.0, 1.0, 'geocodio', TIMESTAMP '2020-01-01 00:00:00')",
+            )
+            .expect("seed");
+        }
+
+        let err = refresh_stale_entries(&db_path, 30, |_| Err("provider down".into()))
+            .expect_err("refresh should fail");
+        assert!(err.to_string().contains("stale entries kept"), "{err}");
+        assert!(err.to_string().contains("provider down"), "{err}");
+
+        let conn = Connection::open(&db_path).expect("reopen");
+        let row: (String, f64, String) = conn
+            .query_row(
+                "SELECT address, lat, CAST(cached_at AS VARCHAR) FROM geocode_cache",
+                [],
+                |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)),
+            )
+            .expect("row");
+        assert_eq!(row, ("1 Main St".to_string(), 1.0, "2020-01-01 00:00:00".to_string()));
+        drop(conn);
+        let _ = std::fs::remove_file(&db_path);
+        let _ = std::fs::remove_file(format!("{db_path}.wal"));
+    }
+
+    #[test]
+    fn legacy_cache_timestamps_are_migrated_to_utc_once() {
+        let conn = Connection::open_in_memory().expect("open");
+        conn.execute_batch(
+            "CREATE TABLE geocode_cache (
+                 address TEXT PRIMARY KEY, lat REAL NOT NULL, lon REAL NOT NULL,
+                 source TEXT NOT NULL, cached_at TIMESTAMP DEFAULT current_timestamp
+             );
+             INSERT INTO geocode_cache (address, lat, lon, source, cached_at) VALUES
+                 ('a', 1.0, 1.0, 'geocodio', TIMESTAMP '2024-06-01 12:00:00'),
+                 ('b', 1.0, 1.0, 'geocodio', NULL);",
+        )
+        .expect("legacy table");
+
+        // Two hours ahead of UTC.
+        migrate_local_cached_at(&conn, "geocode_cache", 2 * 60 * 60 * 1000).expect("migrate");
+        let cached_at = |address: &str| -> Option<String> {
+            conn.query_row(
+                "SELECT CAST(cached_at AS VARCHAR) FROM geocode_cache WHERE address = ?",
+                [address],
+                |row| row.get(0),
+            )
+            .expect("cached_at")
+        };
+        assert_eq!(cached_at("a").as_deref(), Some("2024-06-01 10:00:00"));
+        assert_eq!(cached_at("b"), None);
+
+        // The table is now marked, so resolving it again leaves the rows alone.
+        resolve_cache_table(&conn, None, None).expect("resolve");
+        assert_eq!(cached_at("a").as_deref(), Some("2024-06-01 10:00:00"));
+    }
+
+    #[test]
+    fn new_cache_tables_are_marked_utc() {
+        let conn = Connection::open_in_memory().expect("open");
+        ensure_cache_table(&conn).expect("ensure");
+        let comment: Option<String> = conn
+            .query_row(
+                "SELECT comment FROM duckdb_tables() WHERE table_name = 'geocode_cache'",
+                [],
+                |row| row.get(0),
+            )
+            .expect("comment");
+        assert_eq!(comment.as_deref(), Some(UTC_CACHE_COMMENT));
+    }
 }
@@ -0,0 +1,614 @@
+use std::fmt::Display;
+use std::io::Read;
+use std::path::PathBuf;
+use std::sync::Mutex;
+use std::thread;
+use std::time::Duration;
+
+use duckdb::Connection;
+use tracing::{debug, warn};
+
+use crate::GeoResult;
+
+/// Environment variable holding a DuckDB `memory_limit` value such as `4GB`.
+pub const MEMORY_LIMIT_ENV: &str = "SPATIA_DUCKDB_MEMORY_LIMIT";
+
+/// Environment variable holding the DuckDB worker thread count.
+pub const THREADS_ENV: &str = "SPATIA_DUCKDB_THREADS";
+
+/// Environment variable pointing DuckDB at a preinstalled extension directory.
+pub const EXTENSION_DIR_ENV: &str = "SPATIA_DUCKDB_EXTENSION_DIR";
+
+/// Environment variable naming a directory DuckDB may spill to once a query
+/// outgrows `memory_limit`; created if missing.
+pub const TEMP_DIR_ENV: &str = "SPATIA_DUCKDB_TEMP_DIR";
+
+/// Environment variable holding `;`-separated `SET`/`PRAGMA` statements run
+/// on every connection after the other settings.
+pub const INIT_SQL_ENV: &str = "SPATIA_DUCKDB_INIT_SQL";
+
+/// Opens tried before a lock-contention error is returned.
+const LOCK_RETRY_ATTEMPTS: u32 = 5;
+
+/// Wait before the first retry; doubled after each, about 750 ms in total.
+const LOCK_RETRY_INITIAL_DELAY: Duration = Duration::from_millis(50);
+
+/// Connections registered through [`share_connection`], keyed by path.
+static SHARED_CONNECTIONS: Mutex<Vec<(PathBuf, Connection)>> = Mutex::new(Vec::new());
+
+/// Size suffixes DuckDB accepts for `memory_limit`.
+const MEMORY_UNITS: &[&str] = &[
+    "B", "KB", "MB", "GB", "TB", "KIB", "MIB", "GIB", "TIB", "BYTES",
+];
+
+/// Per-connection DuckDB settings read from the environment.
+///
+/// Unset fields leave DuckDB's own defaults in place: `memory_limit` is 80%
+/// of system RAM, `threads` is the number of CPU cores, extensions are
+/// looked up under `~/.duckdb/extensions` and file-backed databases spill to
+/// a `.tmp` directory next to the file (in-memory ones do not spill).
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SessionSettings {
+    pub memory_limit: Option<String>,
+    pub threads: Option<usize>,
+    pub extension_directory: Option<String>,
+    pub temp_directory: Option<String>,
+    /// Extra `SET`/`PRAGMA` statements, applied last in order.
+    pub init_sql: Vec<String>,
+}
+
+impl SessionSettings {
+    /// Read and validate `SPATIA_DUCKDB_MEMORY_LIMIT`, `SPATIA_DUCKDB_THREADS`,
+    /// `SPATIA_DUCKDB_EXTENSION_DIR`, `SPATIA_DUCKDB_TEMP_DIR` and
+    /// `SPATIA_DUCKDB_INIT_SQL`. Empty values are treated as unset.
+    pub fn from_env() -> GeoResult<Self> {
+        let memory_limit = match env_value(MEMORY_LIMIT_ENV) {
+            Some(raw) => Some(parse_memory_limit(&raw)?),
+            None => None,
+        };
+        let threads = match env_value(THREADS_ENV) {
+            Some(raw) => Some(parse_threads(&raw)?),
+            None => None,
+        };
+        let init_sql = match env_value(INIT_SQL_ENV) {
+            Some(raw) => parse_init_sql(&raw)?,
+            None => Vec::new(),
+        };
+        Ok(Self {
+            memory_limit,
+            threads,
+            extension_directory: env_value(EXTENSION_DIR_ENV),
+            temp_directory: env_value(TEMP_DIR_ENV),
+            init_sql,
+        })
+    }
+
+    pub fn apply(&self, conn: &Connection) -> GeoResult<()> {
+        if let Some(limit) = &self.memory_limit {
+            debug!(memory_limit = %limit, "apply_session_settings: setting memory_limit");
+            conn.execute_batch(&format!("SET memory_limit = '{limit}'"))?;
+        }
+        if let Some(threads) = self.threads {
+            debug!(threads, "apply_session_settings: setting threads");
+            conn.execute_batch(&format!("SET threads = {threads}"))?;
+        }
+        if let Some(dir) = &self.extension_directory {
+            debug!(extension_directory = %dir, "apply_session_settings: setting extension_directory");
+            conn.execute_batch(&format!(
+                "SET extension_directory = '{}'",
+                dir.replace('\'', "''")
+            ))?;
+        }
+        if let Some(dir) = &self.temp_directory {
+            std::fs::create_dir_all(dir)
+                .map_err(|err| format!("cannot create {TEMP_DIR_ENV} directory {dir}: {err}"))?;
+            debug!(temp_directory = %dir, "apply_session_settings: setting temp_directory");
+            conn.execute_batch(&format!(
+                "SET temp_directory = '{}'",
+                dir.replace('\'', "''")
+            ))?;
+        }
+        for statement in &self.init_sql {
+            debug!(statement = %statement, "apply_session_settings: running init statement");
+            conn.execute_batch(statement)
+                .map_err(|err| format!("{INIT_SQL_ENV} statement '{statement}' failed: {err}"))?;
+        }
+        Ok(())
+    }
+}
+
+/// Open `path` and apply the session settings from the environment.
+///
+/// Every entry point that opens the workspace database, in this crate and in
+/// the engine, overture, ingest and AI crates, should go through here so the
+/// memory and thread caps and the init SQL hold for all of them. An open
+/// that fails because another process holds the file lock is retried with
+/// backoff (see [`retry_on_lock`]); other failures are returned at once.
+///
+/// While a [`share_connection`] guard for `path` is alive this returns a
+/// clone of the shared connection instead, on the same database instance.
+pub fn open_connection(path: &str) -> GeoResult<Connection> {
+    if let Some(conn) = shared_connection(path)? {
+        return Ok(conn);
+    }
+    let settings = SessionSettings::from_env()?;
+    let conn =
+        retry_on_lock(path, || Connection::open(path)).map_err(|err| open_error(path, err))?;
+    settings.apply(&conn)?;
+    Ok(conn)
+}
+
+/// Make [`open_connection`] hand out clones of `conn` for `path` until the
+/// returned guard is dropped. A caller that keeps a database open, like the
+/// CLI session, uses this to run engine commands, which open the database by
+/// path, without a second DuckDB instance on the same file. The clones share
+/// `conn`'s database, so its session settings already apply.
+pub fn share_connection(path: &str, conn: &Connection) -> GeoResult<SharedConnection> {
+    let key = connection_key(path);
+    let clone = conn.try_clone()?;
+    let mut shared = lock_shared();
+    if shared.iter().any(|(shared_path, _)| *shared_path == key) {
+        return Err(format!("a connection to {path} is already shared").into());
+    }
+    shared.push((key.clone(), clone));
+    Ok(SharedConnection { key })
+}
+
+/// Guard returned by [`share_connection`]; dropping it stops the sharing.
+#[derive(Debug)]
+pub struct SharedConnection {
+    key: PathBuf,
+}
+
+impl Drop for SharedConnection {
+    fn drop(&mut self) {
+        lock_shared().retain(|(path, _)| *path != self.key);
+    }
+}
+
+fn shared_connection(path: &str) -> GeoResult<Option<Connection>> {
+    let key = connection_key(path);
+    let shared = lock_shared();
+    match shared.iter().find(|(shared_path, _)| *shared_path == key) {
+        Some((_, conn)) => Ok(Some(conn.try_clone()?)),
+        None => Ok(None),
+    }
+}
+
+/// The registry holds no invariant a panicking holder could break, so a
+/// poisoned lock is still safe to use.
+fn lock_shared() -> std::sync::MutexGuard<'static, Vec<(PathBuf, Connection)>> {
+    SHARED_CONNECTIONS
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+/// `path` made absolute, so `./a.duckdb` and `a.duckdb` match.
+fn connection_key(path: &str) -> PathBuf {
+    std::fs::canonicalize(path).unwrap_or_else(|_| PathBuf::from(path))
+}
+
+/// In-memory counterpart of [`open_connection`] for scratch connections,
+/// with the same session settings.
+pub fn open_in_memory() -> GeoResult<Connection> {
+    let settings = SessionSettings::from_env()?;
+    let conn = Connection::open_in_memory()?;
+    settings.apply(&conn)?;
+    Ok(conn)
+}
+
+/// Run `open` up to [`LOCK_RETRY_ATTEMPTS`] times while it fails with a lock
+/// contention error, sleeping 50 ms, then 100 ms, and so on in between.
+/// DuckDB allows one writing process per file, so a second command that
+/// opens the database while another is finishing gets a transient "Conflicting
+/// lock" error; a short wait usually clears it.
+fn retry_on_lock<T, E: Display>(
+    path: &str,
+    mut open: impl FnMut() -> Result<T, E>,
+) -> Result<T, E> {
+    let mut delay = LOCK_RETRY_INITIAL_DELAY;
+    let mut attempt = 1;
+    loop {
+        match open() {
+            Err(err) if attempt < LOCK_RETRY_ATTEMPTS && is_lock_error(&err.to_string()) => {
+                warn!(
+                    path = %path,
+                    attempt,
+                    delay_ms = delay.as_millis() as u64,
+                    error = %err,
+                    "open_connection: database is locked, retrying"
+                );
+                thread::sleep(delay);
+                delay *= 2;
+                attempt += 1;
+            }
+            result => return result,
+        }
+    }
+}
+
+/// Whether an open error comes from another process holding the file lock.
+fn is_lock_error(message: &str) -> bool {
+    let message = message.to_ascii_lowercase();
+    message.contains("database is locked")
+        || message.contains("conflicting lock")
+        || message.contains("could not set lock")
+}
+
+/// Offset and value of the magic bytes in a DuckDB file's main header.
+const DUCKDB_MAGIC_OFFSET: usize = 8;
+const DUCKDB_MAGIC: &[u8; 4] = b"DUCK";
+
+/// Replace DuckDB's low-level error for an existing file that is not a
+/// database (a CSV passed as the db path, a truncated download) with one
+/// that names the file. Other open failures pass through unchanged.
+fn open_error(path: &str, err: duckdb::Error) -> Box<dyn std::error::Error + Send + Sync> {
+    let message = err.to_string().to_ascii_lowercase();
+    if message.contains("not a valid duckdb database") || is_foreign_file(path) {
+        debug!(path = %path, error = %err, "open_connection: file is not a DuckDB database");
+        return format!("'{path}' is not a valid DuckDB database file").into();
+    }
+    Box::new(err)
+}
+
+/// Whether `path` is a non-empty file without the DuckDB header magic.
+fn is_foreign_file(path: &str) -> bool {
+    let Ok(file) = std::fs::File::open(path) else {
+        return false;
+    };
+    let mut header = Vec::new();
+    let header_len = (DUCKDB_MAGIC_OFFSET + DUCKDB_MAGIC.len()) as u64;
+    if file.take(header_len).read_to_end(&mut header).is_err() {
+        return false;
+    }
+    !header.is_empty() && header.get(DUCKDB_MAGIC_OFFSET..) != Some(DUCKDB_MAGIC.as_slice())
+}
+
+fn env_value(name: &str) -> Option<String> {
+    std::env::var(name)
+        .ok()
+        .map(|value| value.trim().to_string())
+        .filter(|value| !value.is_empty())
+}
+
+/// Accept `<number><unit>` with an optional space, e.g. `4GB`, `512 MiB`,
+/// `1.5GB`. The value is normalized to `<number><UNIT>`.
+fn parse_memory_limit(raw: &str) -> GeoResult<String> {
+    let value = raw.trim();
+    let split = value
+        .find(|c: char| !(c.is_ascii_digit() || c == '.'))
+        .unwrap_or(value.len());
+    let (number, unit) = value.split_at(split);
+    let unit = unit.trim().to_ascii_uppercase();
+    let number_ok = number.parse::<f64>().is_ok_and(|n| n > 0.0);
+    if !number_ok || !MEMORY_UNITS.contains(&unit.as_str()) {
+        return Err(format!(
+            "invalid {MEMORY_LIMIT_ENV} '{raw}': expected a size such as 4GB or 512MiB"
+        )
+        .into());
+    }
+    Ok(format!("{number}{unit}"))
+}
+
+/// Split `raw` on semicolons outside quotes and check that every statement
+/// is a `SET` or `PRAGMA`, so the escape hatch cannot change data or schema.
+/// `--` and `/* */` comments outside quotes are dropped before splitting, so
+/// a quote inside a comment cannot hide a second statement.
+fn parse_init_sql(raw: &str) -> GeoResult<Vec<String>> {
+    let mut statements = Vec::new();
+    let mut current = String::new();
+    let mut quote = None;
+    let mut chars = raw.chars().peekable();
+    while let Some(c) = chars.next() {
+        match (quote, c) {
+            (None, '-') if chars.peek() == Some(&'-') => {
+                while chars.next_if(|&next| next != '\n').is_some() {}
+            }
+            (None, '/') if chars.peek() == Some(&'*') => {
+                chars.next();
+                let mut prev = None;
+                for next in chars.by_ref() {
+                    if prev == Some('*') && next == '/' {
+                        break;
+                    }
+                    prev = Some(next);
+                }
+                current.push(' ');
+            }
+            (None, ';') => statements.push(std::mem::take(&mut current)),
+            (None, '\'' | '"') => {
+                quote = Some(c);
+                current.push(c);
+            }
+            (Some(open), _) if c == open => {
+                quote = None;
+                current.push(c);
+            }
+            _ => current.push(c),
+        }
+    }
+    statements.push(current);
+
+    let statements: Vec<String> = statements
+        .into_iter()
+        .map(|statement| statement.trim().to_string())
+        .filter(|statement| !statement.is_empty())
+        .collect();
+    for statement in &statements {
+        let keyword = statement
+            .split_whitespace()
+            .next()
+            .unwrap_or_default()
+            .to_ascii_uppercase();
+        if keyword != "SET" && keyword != "PRAGMA" {
+            return Err(format!(
+                "invalid {INIT_SQL_ENV} statement '{statement}': only SET and PRAGMA are allowed"
+            )
+            .into());
+        }
+    }
+    Ok(statements)
+}
+
+fn parse_threads(raw: &str) -> GeoResult<usize> {
+    match raw.trim().parse::<usize>() {
+        Ok(n) if n > 0 => Ok(n),
+        _ => Err(format!("invalid {THREADS_ENV} '{raw}': expected a positive integer").into()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        is_lock_error, open_connection, parse_init_sql, parse_memory_limit, parse_threads,
+        retry_on_lock, share_connection, SessionSettings,
+    };
+    use duckdb::Connection;
+    use std::fs;
+    use std::process::{Command, Stdio};
+    use std::thread;
+    use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+    /// Database path handed to [`hold_lock_for_parent_test`] when the lock
+    /// test re-runs this test binary as the process holding the lock.
+    const LOCK_HOLDER_DB_ENV: &str = "SPATIA_TEST_LOCK_HOLDER_DB";
+
+    #[test]
+    fn memory_limit_accepts_common_sizes() {
+        assert_eq!(parse_memory_limit("4GB").unwrap(), "4GB");
+        assert_eq!(parse_memory_limit("512 mib").unwrap(), "512MIB");
+        assert_eq!(parse_memory_limit("1.5gb").unwrap(), "1.5GB");
+    }
+
+    #[test]
+    fn memory_limit_rejects_malformed_values() {
+        for raw in ["4", "GB", "four GB", "4XB", "0GB", "4GB'; DROP TABLE t"] {
+            assert!(parse_memory_limit(raw).is_err(), "{raw} should be rejected");
+        }
+    }
+
+    #[test]
+    fn threads_must_be_positive() {
+        assert_eq!(parse_threads("8").unwrap(), 8);
+        assert!(parse_threads("0").is_err());
+        assert!(parse_threads("-2").is_err());
+        assert!(parse_threads("many").is_err());
+    }
+
+    #[test]
+    fn open_waits_for_a_briefly_held_lock() {
+        let released_at = Instant::now() + Duration::from_millis(120);
+        let mut attempts = 0;
+        let result = retry_on_lock("/tmp/spatia.duckdb", || {
+            attempts += 1;
+            if Instant::now() < released_at {
+                Err(
+                    "IO Error: Could not set lock on file \"/tmp/spatia.duckdb\": \
+                     Conflicting lock is held in /usr/bin/spatia (PID 4242)",
+                )
+            } else {
+                Ok("connection")
+            }
+        });
+        assert_eq!(result, Ok("connection"));
+        assert!(attempts > 1, "expected at least one retry, got {attempts}");
+    }
+
+    #[test]
+    fn open_waits_for_a_lock_held_by_another_process() {
+        let suffix = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .expect("time")
+            .as_nanos();
+        let path = format!("/tmp/spatia_connection_lock_test_{suffix}.duckdb");
+        let ready = format!("{path}.ready");
+
+        let mut holder = Command::new(std::env::current_exe().expect("test binary"))
+            .args([
+                "--ignored",
+                "--exact",
+                "connection::tests::hold_lock_for_parent_test",
+            ])
+            .env(LOCK_HOLDER_DB_ENV, &path)
+            .stdout(Stdio::null())
+            .spawn()
+            .expect("spawn lock holder");
+        let deadline = Instant::now() + Duration::from_secs(30);
+        while fs::metadata(&ready).is_err() && Instant::now() < deadline {
+            thread::sleep(Duration::from_millis(10));
+        }
+        assert!(
+            fs::metadata(&ready).is_ok(),
+            "lock holder never became ready"
+        );
+
+        let conn = open_connection(&path).expect("open once the holder releases the lock");
+        let rows: i64 = conn
+            .query_row("SELECT COUNT(*) FROM held", [], |row| row.get(0))
+            .expect("table written by the holder");
+        assert_eq!(rows, 1);
+        assert!(holder.wait().expect("wait for holder").success());
+
+        drop(conn);
+        for file in [path.clone(), format!("{path}.wal"), ready] {
+            let _ = fs::remove_file(file);
+        }
+    }
+
+    /// Child half of [`open_waits_for_a_lock_held_by_another_process`]: open
+    /// the database, signal readiness, and keep the lock for 300 ms, well
+    /// inside the retry budget of [`retry_on_lock`].
+    #[test]
+    #[ignore = "run as a child process by open_waits_for_a_lock_held_by_another_process"]
+    fn hold_lock_for_parent_test() {
+        let Ok(path) = std::env::var(LOCK_HOLDER_DB_ENV) else {
+            return;
+        };
+        let conn = Connection::open(&path).expect("open in holder");
+        conn.execute_batch("CREATE TABLE held AS SELECT 1 AS id")
+            .expect("write in holder");
+        fs::write(format!("{path}.ready"), "").expect("signal ready");
+        thread::sleep(Duration::from_millis(300));
+        drop(conn);
+    }
+
+    #[test]
+    fn open_fails_fast_on_other_errors() {
+        let mut attempts = 0;
+        let result: Result<(), &str> = retry_on_lock("/tmp/spatia.duckdb", || {
+            attempts += 1;
+            Err("IO Error: Cannot open file: Permission denied")
+        });
+        assert!(result.is_err());
+        assert_eq!(attempts, 1);
+        assert!(is_lock_error("database is locked"));
+        assert!(!is_lock_error(
+            "Catalog Error: Table with name t does not exist"
+        ));
+    }
+
+    #[test]
+    fn opening_a_text_file_reports_it_is_not_a_database() {
+        let suffix = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .expect("time")
+            .as_nanos();
+        let path = format!("/tmp/spatia_connection_test_{suffix}.csv");
+        fs::write(&path, "id,name\n1,Ada\n2,Bo\n").expect("write csv");
+
+        let err = open_connection(&path).err().expect("csv is not a database");
+        assert_eq!(
+            err.to_string(),
+            format!("'{path}' is not a valid DuckDB database file")
+        );
+        let _ = fs::remove_file(&path);
+    }
+
+    #[test]
+    fn apply_sets_duckdb_options() {
+        let suffix = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .expect("time")
+            .as_nanos();
+        let spill_dir = format!("/tmp/spatia_connection_spill_test_{suffix}");
+        let conn = Connection::open_in_memory().expect("open");
+        SessionSettings {
+            memory_limit: Some("1GB".to_string()),
+            threads: Some(2),
+            extension_directory: Some("/tmp/spatia_extensions".to_string()),
+            temp_directory: Some(spill_dir.clone()),
+            init_sql: vec!["SET preserve_insertion_order = false".to_string()],
+        }
+        .apply(&conn)
+        .expect("apply");
+
+        let threads: i64 = conn
+            .query_row("SELECT current_setting('threads')", [], |row| row.get(0))
+            .expect("threads");
+        assert_eq!(threads, 2);
+        let limit: String = conn
+            .query_row("SELECT current_setting('memory_limit')", [], |row| {
+                row.get(0)
+            })
+            .expect("memory_limit");
+        assert!(!limit.is_empty());
+        let dir: String = conn
+            .query_row("SELECT current_setting('extension_directory')", [], |row| {
+                row.get(0)
+            })
+            .expect("extension_directory");
+        assert_eq!(dir, "/tmp/spatia_extensions");
+        let preserve: bool = conn
+            .query_row(
+                "SELECT current_setting('preserve_insertion_order')",
+                [],
+                |row| row.get(0),
+            )
+            .expect("preserve_insertion_order");
+        assert!(!preserve);
+        assert!(std::path::Path::new(&spill_dir).is_dir());
+        let temp: String = conn
+            .query_row("SELECT current_setting('temp_directory')", [], |row| {
+                row.get(0)
+            })
+            .expect("temp_directory");
+        assert_eq!(temp, spill_dir);
+        let _ = fs::remove_dir_all(&spill_dir);
+    }
+
+    #[test]
+    fn init_sql_allows_only_set_and_pragma() {
+        assert_eq!(
+            parse_init_sql("SET enable_progress_bar = false; pragma threads=2;; ").unwrap(),
+            vec!["SET enable_progress_bar = false", "pragma threads=2"]
+        );
+        assert_eq!(
+            parse_init_sql("SET search_path = 'a;b'").unwrap(),
+            vec!["SET search_path = 'a;b'"]
+        );
+        assert_eq!(
+            parse_init_sql("SET threads = 2; -- cap it\n/* note */ PRAGMA version").unwrap(),
+            vec!["SET threads = 2", "PRAGMA version"]
+        );
+        for raw in [
+            "DROP TABLE places",
+            "SET threads = 2; DELETE FROM places",
+            "CREATE TABLE t (id INTEGER)",
+            "SELECT 1",
+            "SET threads=2 -- '\n; DROP TABLE t; -- '",
+            "SET threads=2 /* ' */; DROP TABLE t; /* ' */",
+        ] {
+            assert!(parse_init_sql(raw).is_err(), "{raw} should be rejected");
+        }
+    }
+
+    #[test]
+    fn shared_connections_serve_opens_of_the_same_path() {
+        let suffix = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .expect("time")
+            .as_nanos();
+        let path = format!("/tmp/spatia_connection_test_{suffix}.duckdb");
+        let conn = open_connection(&path).expect("open");
+
+        {
+            let _shared = share_connection(&path, &conn).expect("share");
+            assert!(share_connection(&path, &conn).is_err());
+            // The clone writes to the same database instance.
+            open_connection(&path)
+                .expect("clone")
+                .execute_batch("CREATE TABLE t AS SELECT 1 AS x")
+                .expect("create");
+            let count: i64 = conn
+                .query_row("SELECT COUNT(*) FROM t", [], |row| row.get(0))
+                .expect("count");
+            assert_eq!(count, 1);
+        }
+        // Sharing again works once the guard is gone.
+        drop(share_connection(&path, &conn).expect("share again"));
+
+        drop(conn);
+        let _ = fs::remove_file(&path);
+        let _ = fs::remove_file(format!("{path}.wal"));
+    }
+}
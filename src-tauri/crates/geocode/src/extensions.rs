@@ -0,0 +1,195 @@
+use duckdb::Connection;
+use tracing::{debug, info, warn};
+
+use crate::connection::EXTENSION_DIR_ENV;
+use crate::GeoResult;
+
+/// Environment variables holding credentials for private S3 buckets.
+const S3_KEY_ID_ENV: &str = "SPATIA_S3_KEY_ID";
+const S3_SECRET_ENV: &str = "SPATIA_S3_SECRET";
+const S3_REGION_ENV: &str = "SPATIA_S3_REGION";
+
+/// Name of the DuckDB secret created from those variables.
+const S3_SECRET_NAME: &str = "spatia_s3";
+
+/// Load a DuckDB extension, installing it only when the first `LOAD` fails.
+///
+/// On an air-gapped machine with the extension already present, `INSTALL`
+/// cannot reach the repository; its error is only reported if the extension
+/// still fails to load afterwards.
+pub fn load_extension(conn: &Connection, name: &str) -> GeoResult<()> {
+    if let Some(dir) = std::env::var(EXTENSION_DIR_ENV)
+        .ok()
+        .filter(|dir| !dir.trim().is_empty())
+    {
+        conn.execute_batch(&format!(
+            "SET extension_directory = '{}'",
+            dir.replace('\'', "''")
+        ))?;
+    }
+
+    if conn.execute_batch(&format!("LOAD {name}")).is_ok() {
+        return Ok(());
+    }
+    let install = conn.execute_batch(&format!("INSTALL {name}"));
+    match (conn.execute_batch(&format!("LOAD {name}")), install) {
+        (Ok(()), _) => Ok(()),
+        (Err(_), Err(install_err)) => {
+            Err(format!("failed to install DuckDB extension {name}: {install_err}").into())
+        }
+        (Err(load_err), Ok(())) => {
+            Err(format!("failed to load DuckDB extension {name}: {load_err}").into())
+        }
+    }
+}
+
+/// S3 credentials for httpfs reads of private buckets or mirrors. `Debug`
+/// redacts the secret so the struct can be logged.
+#[derive(Clone, PartialEq, Eq)]
+pub struct S3Credentials {
+    pub key_id: String,
+    pub secret: String,
+    pub region: Option<String>,
+}
+
+impl std::fmt::Debug for S3Credentials {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("S3Credentials")
+            .field("key_id", &self.key_id)
+            .field("secret", &"<redacted>")
+            .field("region", &self.region)
+            .finish()
+    }
+}
+
+impl S3Credentials {
+    /// Read `SPATIA_S3_KEY_ID`, `SPATIA_S3_SECRET` and the optional
+    /// `SPATIA_S3_REGION`. `None` when neither key is set, which keeps
+    /// anonymous access to the public Overture bucket.
+    pub fn from_env() -> GeoResult<Option<Self>> {
+        let var = |name: &str| std::env::var(name).ok().filter(|v| !v.trim().is_empty());
+        Self::from_values(var(S3_KEY_ID_ENV), var(S3_SECRET_ENV), var(S3_REGION_ENV))
+    }
+
+    fn from_values(
+        key_id: Option<String>,
+        secret: Option<String>,
+        region: Option<String>,
+    ) -> GeoResult<Option<Self>> {
+        match (key_id, secret) {
+            (Some(key_id), Some(secret)) => Ok(Some(Self {
+                key_id,
+                secret,
+                region,
+            })),
+            (None, None) => Ok(None),
+            _ => Err(format!("{S3_KEY_ID_ENV} and {S3_SECRET_ENV} must be set together").into()),
+        }
+    }
+
+    /// `CREATE OR REPLACE SECRET` statement for these credentials. It holds
+    /// the secret in clear text, so it must never be logged.
+    pub fn secret_sql(&self) -> String {
+        let quote = |value: &str| format!("'{}'", value.replace('\'', "''"));
+        let mut sql = format!(
+            "CREATE OR REPLACE SECRET {S3_SECRET_NAME} (TYPE S3, KEY_ID {}, SECRET {}",
+            quote(&self.key_id),
+            quote(&self.secret)
+        );
+        if let Some(region) = &self.region {
+            sql.push_str(&format!(", REGION {}", quote(region)));
+        }
+        sql.push(')');
+        sql
+    }
+}
+
+/// Create the S3 secret from the environment on `conn`, if one is
+/// configured, so later httpfs reads are signed. Call after loading `httpfs`.
+pub fn configure_s3_secret(conn: &Connection) -> GeoResult<()> {
+    let Some(credentials) = S3Credentials::from_env()? else {
+        debug!("configure_s3_secret: no S3 credentials set, using anonymous access");
+        return Ok(());
+    };
+    // DuckDB's message can echo the statement, which holds the secret, so
+    // neither the returned error nor the log carries more than its kind.
+    if let Err(err) = conn.execute_batch(&credentials.secret_sql()) {
+        warn!(kind = %error_kind(&err), "configure_s3_secret: CREATE SECRET failed");
+        return Err("failed to configure S3 secret".into());
+    }
+    info!(
+        key_id = %credentials.key_id,
+        region = ?credentials.region,
+        "configure_s3_secret: created S3 secret"
+    );
+    Ok(())
+}
+
+/// The variant (and DuckDB error code) of `err`, without its message.
+fn error_kind(err: &duckdb::Error) -> String {
+    match err {
+        duckdb::Error::DuckDBFailure(failure, _) => format!("DuckDBFailure({:?})", failure.code),
+        other => {
+            let debug = format!("{other:?}");
+            debug
+                .split(|c: char| !c.is_alphanumeric())
+                .next()
+                .unwrap_or_default()
+                .to_string()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{error_kind, S3Credentials};
+
+    fn credentials(region: Option<&str>) -> S3Credentials {
+        S3Credentials::from_values(
+            Some("AKIAEXAMPLE".to_string()),
+            Some("s3cr'et".to_string()),
+            region.map(str::to_string),
+        )
+        .expect("valid")
+        .expect("configured")
+    }
+
+    #[test]
+    fn secret_sql_quotes_values_and_adds_region_when_set() {
+        assert_eq!(
+            credentials(Some("us-west-2")).secret_sql(),
+            "CREATE OR REPLACE SECRET spatia_s3 (TYPE S3, KEY_ID 'AKIAEXAMPLE', \
+             SECRET 's3cr''et', REGION 'us-west-2')"
+        );
+        assert_eq!(
+            credentials(None).secret_sql(),
+            "CREATE OR REPLACE SECRET spatia_s3 (TYPE S3, KEY_ID 'AKIAEXAMPLE', SECRET 's3cr''et')"
+        );
+    }
+
+    #[test]
+    fn error_kind_leaves_out_the_message() {
+        let conn = duckdb::Connection::open_in_memory().expect("open");
+        let err = conn
+            .execute_batch("CREATE SECRET s3cr3t_value (TYPE no_such_type)")
+            .expect_err("unknown secret type");
+        let kind = error_kind(&err);
+        assert!(kind.starts_with("DuckDBFailure"), "{kind}");
+        assert!(!kind.contains("s3cr3t_value"), "{kind}");
+    }
+
+    #[test]
+    fn credentials_need_both_keys_and_debug_hides_the_secret() {
+        assert_eq!(
+            S3Credentials::from_values(None, None, None).expect("anonymous"),
+            None
+        );
+        let err = S3Credentials::from_values(Some("AKIA".to_string()), None, None)
+            .expect_err("half configured");
+        assert!(err.to_string().contains("must be set together"));
+
+        let debug = format!("{:?}", credentials(None));
+        assert!(debug.contains("AKIAEXAMPLE"));
+        assert!(!debug.contains("s3cr"));
+    }
+}
@@ -3,14 +3,25 @@ use std::collections::{HashMap, HashSet};
 use duckdb::Connection;
 use tracing::{debug, error, info, warn};
 
-use crate::cache::{cache_lookup, cache_store};
-use crate::geocodio::{geocode_via_geocodio_inner, GeocodioEnrichedResult};
+use crate::cache::{
+    cache_lookup, cache_store, reverse_cache_key, reverse_cache_lookup, reverse_cache_store,
+    valid_coordinates,
+};
+use crate::connection::open_connection;
+use crate::extensions::load_extension;
+use crate::geocodio::{
+    geocode_via_geocodio_detailed, reverse_geocode_via_geocodio, GeocodioEnrichedResult,
+};
 use crate::identifiers::validate_table_name;
 use crate::nominatim::{geocode_via_nominatim_batch, nominatim_base_url, NominatimEnrichedResult};
 use crate::overture_cache;
+use crate::runtime;
 use crate::scoring::{local_accept_threshold, score_candidate, MIN_SCORE};
-use crate::text::{normalize_address, tokenize_address, AddressComponents, components_from_string};
-use crate::types::{GeoResult, GeocodeBatchResult, GeocodeProgressUpdate, GeocodeResult, GeocodeStats};
+use crate::text::{canonicalize_address, normalize_address, tokenize_address, AddressComponents, components_from_string};
+use crate::types::{
+    GeoResult, GeocodeBatchResult, GeocodePartition, GeocodeProgressUpdate, GeocodeResult,
+    GeocodeStats,
+};
 
 #[derive(Debug, Clone)]
 struct LocalGeocodeCandidate {
@@ -39,12 +50,7 @@ fn has_column(conn: &Connection, table_name: &str, column: &str) -> GeoResult<bo
 }
 
 fn ensure_spatial_loaded(conn: &Connection) -> GeoResult<()> {
-    if conn.execute("LOAD spatial", []).is_ok() {
-        return Ok(());
-    }
-    conn.execute("INSTALL spatial", [])?;
-    conn.execute("LOAD spatial", [])?;
-    Ok(())
+    load_extension(conn, "spatial")
 }
 
 fn find_lookup_tables(conn: &Connection) -> GeoResult<Vec<String>> {
@@ -337,15 +343,50 @@ fn use_geocodio() -> bool {
             .unwrap_or(false)
 }
 
-// ---- Async runner helper ----
+/// Which providers a batch geocode may call, for cost and reliability
+/// control. Set with `SPATIA_GEOCODE_PROVIDERS` or `geocode --providers`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ProviderMode {
+    /// Overture-first with the Nominatim fallback, or the Geocodio fast path
+    /// in Geocodio testing mode (see [`geocode_batch_with_components`]).
+    #[default]
+    Auto,
+    /// Overture-first with the Nominatim fallback; never calls Geocodio,
+    /// even in testing mode, so nothing is spent.
+    FreeOnly,
+    /// Cache, already-downloaded Overture data, then Geocodio. Skips Overture
+    /// downloads and Nominatim.
+    GeocodioOnly,
+    /// Cache hits only. Misses come back unresolved without any provider
+    /// call or download.
+    CacheOnly,
+}
 
-fn run_async<F, T>(f: F) -> GeoResult<T>
-where
-    F: std::future::Future<Output = GeoResult<T>>,
-{
-    match tokio::runtime::Handle::try_current() {
-        Ok(handle) => tokio::task::block_in_place(|| handle.block_on(f)),
-        Err(_) => tokio::runtime::Runtime::new()?.block_on(f),
+impl ProviderMode {
+    /// Mode from `SPATIA_GEOCODE_PROVIDERS`; unset or blank means
+    /// [`ProviderMode::Auto`].
+    pub fn from_env() -> GeoResult<Self> {
+        match std::env::var("SPATIA_GEOCODE_PROVIDERS") {
+            Ok(value) if !value.trim().is_empty() => Ok(value.parse()?),
+            _ => Ok(Self::Auto),
+        }
+    }
+}
+
+impl std::str::FromStr for ProviderMode {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "auto" => Ok(Self::Auto),
+            "free_only" => Ok(Self::FreeOnly),
+            "geocodio_only" => Ok(Self::GeocodioOnly),
+            "cache_only" => Ok(Self::CacheOnly),
+            other => Err(format!(
+                "unknown geocode provider mode '{other}' \
+                 (expected auto, free_only, geocodio_only or cache_only)"
+            )),
+        }
     }
 }
 
@@ -364,11 +405,139 @@ where
 ///
 /// Returns both the ordered results and a [`GeocodeStats`] breakdown by source.
 pub fn geocode_batch(db_path: &str, addresses: &[String]) -> GeoResult<(Vec<GeocodeBatchResult>, GeocodeStats)> {
+    geocode_batch_with_providers(db_path, addresses, ProviderMode::from_env()?)
+}
+
+/// [`geocode_batch`] restricted to the providers allowed by `providers`
+/// instead of `SPATIA_GEOCODE_PROVIDERS`.
+pub fn geocode_batch_with_providers(
+    db_path: &str,
+    addresses: &[String],
+    providers: ProviderMode,
+) -> GeoResult<(Vec<GeocodeBatchResult>, GeocodeStats)> {
     let components: Vec<AddressComponents> = addresses
         .iter()
         .map(|a| components_from_string(a))
         .collect();
-    geocode_batch_with_components(db_path, &components)
+    geocode_components_with_providers(db_path, &components, providers, true)
+}
+
+/// [`geocode_batch_with_providers`] without the cache lookup: every address
+/// goes to the providers, whose results are still written to the cache. The
+/// cache refresh uses this so its stale rows are not returned as hits.
+pub(crate) fn geocode_batch_uncached(
+    db_path: &str,
+    addresses: &[String],
+    providers: ProviderMode,
+) -> GeoResult<(Vec<GeocodeBatchResult>, GeocodeStats)> {
+    let components: Vec<AddressComponents> = addresses
+        .iter()
+        .map(|a| components_from_string(a))
+        .collect();
+    geocode_components_with_providers(db_path, &components, providers, false)
+}
+
+/// Like [`geocode_batch_with_providers`], but geocodes each distinct address
+/// once.
+///
+/// Addresses that differ only in surrounding whitespace or letter case share
+/// one lookup (and at most one provider call), and every input gets that
+/// result back under its own spelling, in input order. In the returned stats
+/// `total`, `geocoded` and `unresolved` count inputs; the per-source counts
+/// count distinct addresses.
+pub fn geocode_batch_dedupe(
+    db_path: &str,
+    addresses: &[String],
+    providers: ProviderMode,
+) -> GeoResult<(Vec<GeocodeBatchResult>, GeocodeStats)> {
+    dedupe_batch(addresses, |address| address.trim().to_string(), |unique| {
+        geocode_batch_with_providers(db_path, unique, providers)
+    })
+}
+
+/// Like [`geocode_batch_dedupe`], but each address is first rewritten with
+/// [`canonicalize_address`], so "123 N Main St" and "123 North Main Street"
+/// share one cache entry and at most one provider call. The canonical
+/// spelling is what gets cached and sent; results still carry the caller's
+/// original address.
+pub fn geocode_batch_normalized(
+    db_path: &str,
+    addresses: &[String],
+    providers: ProviderMode,
+) -> GeoResult<(Vec<GeocodeBatchResult>, GeocodeStats)> {
+    dedupe_batch(addresses, canonicalize_address, |unique| {
+        geocode_batch_with_providers(db_path, unique, providers)
+    })
+}
+
+/// Geocode the distinct values of `canonical(address)` (compared
+/// case-insensitively) and fan the results back out to every input.
+fn dedupe_batch<F>(
+    addresses: &[String],
+    canonical: fn(&str) -> String,
+    geocode: F,
+) -> GeoResult<(Vec<GeocodeBatchResult>, GeocodeStats)>
+where
+    F: FnOnce(&[String]) -> GeoResult<(Vec<GeocodeBatchResult>, GeocodeStats)>,
+{
+    let mut unique: Vec<String> = Vec::new();
+    let mut slot_by_key: HashMap<String, usize> = HashMap::new();
+    let mut slots = Vec::with_capacity(addresses.len());
+    for address in addresses {
+        let spelling = canonical(address);
+        let slot = *slot_by_key
+            .entry(spelling.to_lowercase())
+            .or_insert_with(|| {
+                unique.push(spelling);
+                unique.len() - 1
+            });
+        slots.push(slot);
+    }
+    info!(
+        address_count = addresses.len(),
+        unique_count = unique.len(),
+        "geocode_batch_dedupe: collapsed duplicate addresses"
+    );
+
+    let (results, mut stats) = geocode(&unique)?;
+    let by_address: HashMap<&str, &GeocodeBatchResult> =
+        results.iter().map(|result| (result.address.as_str(), result)).collect();
+    let mut expanded = Vec::new();
+    for (address, slot) in addresses.iter().zip(slots) {
+        if let Some(result) = by_address.get(unique[slot].as_str()) {
+            expanded.push(GeocodeBatchResult {
+                address: address.clone(),
+                ..(*result).clone()
+            });
+        }
+    }
+
+    stats.total = addresses.len();
+    stats.geocoded = expanded.len();
+    stats.unresolved = stats.total - stats.geocoded;
+    Ok((expanded, stats))
+}
+
+/// Split `results` of a batch over `addresses` into resolved rows and the
+/// inputs that got no result. Addresses are compared with surrounding
+/// whitespace ignored, matching how the batch entry points trim their input;
+/// a duplicated unresolved input is listed once per occurrence.
+pub fn partition_results(
+    addresses: &[String],
+    results: Vec<GeocodeBatchResult>,
+) -> GeocodePartition {
+    let returned: HashSet<&str> = results.iter().map(|result| result.address.trim()).collect();
+    let unresolved: Vec<String> = addresses
+        .iter()
+        .filter(|address| !returned.contains(address.trim()))
+        .cloned()
+        .collect();
+    GeocodePartition {
+        resolved_count: results.len(),
+        unresolved_count: unresolved.len(),
+        resolved: results,
+        unresolved,
+    }
 }
 
 /// Maximum batch size for the API-first fast path.
@@ -393,15 +562,23 @@ fn fast_path_limit() -> usize {
 pub fn geocode_batch_api_first(
     db_path: &str,
     components: &[AddressComponents],
+) -> GeoResult<(Vec<GeocodeBatchResult>, GeocodeStats)> {
+    api_first(db_path, components, true)
+}
+
+fn api_first(
+    db_path: &str,
+    components: &[AddressComponents],
+    read_cache: bool,
 ) -> GeoResult<(Vec<GeocodeBatchResult>, GeocodeStats)> {
     let addresses: Vec<String> = components.iter().map(|c| c.full.clone()).collect();
 
     info!(address_count = addresses.len(), "geocode_batch_api_first: starting fast-path geocode");
 
-    let conn = Connection::open(db_path)?;
+    let mut conn = open_connection(db_path)?;
 
     // ---- Step 1: Cache lookup ----
-    let (cached_hits, misses) = cache_lookup(&conn, &addresses)?;
+    let (cached_hits, misses) = lookup_cache(&conn, &addresses, read_cache)?;
     let cache_hit_count = cached_hits.len();
     debug!(cache_hits = cache_hit_count, cache_misses = misses.len(), "geocode_batch_api_first: cache lookup complete");
 
@@ -427,6 +604,7 @@ pub fn geocode_batch_api_first(
     let mut overture_exact_count = 0usize;
     let mut local_fuzzy_count = 0usize;
     let mut api_resolved_count = 0usize;
+    let mut failures = Vec::new();
 
     if !misses.is_empty() {
         let miss_set: HashSet<&str> = misses.iter().map(String::as_str).collect();
@@ -496,14 +674,16 @@ pub fn geocode_batch_api_first(
                 .unwrap_or_else(|_| "https://api.geocod.io".to_string());
 
             info!(unresolved_count = unresolved.len(), "geocode_batch_api_first: calling Geocodio API");
-            let geocodio_results = run_async(geocode_via_geocodio_inner(&api_key, &unresolved, &base_url))?;
+            let outcome = runtime::block_on(geocode_via_geocodio_detailed(&api_key, &unresolved, &base_url))?;
+            let geocodio_results = outcome.resolved;
+            failures = outcome.failures;
             api_resolved_count = geocodio_results.len();
 
             let cache_records: Vec<GeocodeResult> = geocodio_results
                 .iter()
                 .map(|e: &GeocodioEnrichedResult| e.inner.clone())
                 .collect();
-            cache_store(&conn, &cache_records, "geocodio")?;
+            cache_store(&mut conn, &cache_records, "geocodio")?;
 
             // Batch GERS reverse lookup
             let addr_to_zip: HashMap<String, Option<String>> = components
@@ -527,7 +707,10 @@ pub fn geocode_batch_api_first(
                     enriched.inner.lon,
                     zip_hint,
                 )
-                .unwrap_or(None);
+                .unwrap_or_else(|e| {
+                    warn!(error = %e, "geocode_batch: GERS reverse lookup failed");
+                    None
+                });
 
                 resolved_by_address.insert(
                     enriched.inner.address.clone(),
@@ -563,6 +746,7 @@ pub fn geocode_batch_api_first(
         local_fuzzy: local_fuzzy_count,
         api_resolved: api_resolved_count,
         unresolved: total - geocoded,
+        failures,
     };
 
     info!(
@@ -585,10 +769,32 @@ pub fn geocode_batch_api_first(
 /// - If `SPATIA_GEOCODE_USE_GEOCODIO=true` AND `SPATIA_GEOCODIO_API_KEY` is set,
 ///   uses Geocodio instead of Nominatim (for testing/benchmarking only).
 /// - The API-first fast path is only used with Geocodio (testing mode).
+/// - `SPATIA_GEOCODE_PROVIDERS` overrides all of the above (see
+///   [`ProviderMode`]).
 pub fn geocode_batch_with_components(
     db_path: &str,
     components: &[AddressComponents],
 ) -> GeoResult<(Vec<GeocodeBatchResult>, GeocodeStats)> {
+    geocode_components_with_providers(db_path, components, ProviderMode::from_env()?, true)
+}
+
+/// With `read_cache` false the cache is not consulted (providers still write
+/// to it), and [`ProviderMode::CacheOnly`] resolves nothing.
+fn geocode_components_with_providers(
+    db_path: &str,
+    components: &[AddressComponents],
+    providers: ProviderMode,
+    read_cache: bool,
+) -> GeoResult<(Vec<GeocodeBatchResult>, GeocodeStats)> {
+    match providers {
+        ProviderMode::Auto => {}
+        ProviderMode::FreeOnly => return overture_first(db_path, components, read_cache),
+        ProviderMode::GeocodioOnly => return api_first(db_path, components, read_cache),
+        ProviderMode::CacheOnly => {
+            return geocode_batch_cache_only(db_path, components, read_cache)
+        }
+    }
+
     // Geocodio testing mode: API-first fast path
     if use_geocodio() {
         let limit = fast_path_limit();
@@ -598,26 +804,99 @@ pub fn geocode_batch_with_components(
                 fast_path_limit = limit,
                 "geocode_batch: using Geocodio API-first fast path (testing mode)"
             );
-            return geocode_batch_api_first(db_path, components);
+            return api_first(db_path, components, read_cache);
         }
     }
 
-    geocode_batch_overture_first(db_path, components)
+    overture_first(db_path, components, read_cache)
+}
+
+/// [`cache_lookup`], or every address as a miss when `read_cache` is false.
+fn lookup_cache(
+    conn: &Connection,
+    addresses: &[String],
+    read_cache: bool,
+) -> GeoResult<(Vec<GeocodeResult>, Vec<String>)> {
+    if read_cache {
+        cache_lookup(conn, addresses)
+    } else {
+        Ok((Vec::new(), addresses.to_vec()))
+    }
+}
+
+/// Cache-only lookup: hits are returned, misses are counted as unresolved
+/// and never sent anywhere.
+fn geocode_batch_cache_only(
+    db_path: &str,
+    components: &[AddressComponents],
+    read_cache: bool,
+) -> GeoResult<(Vec<GeocodeBatchResult>, GeocodeStats)> {
+    let addresses: Vec<String> = components.iter().map(|c| c.full.clone()).collect();
+    let conn = open_connection(db_path)?;
+    let (cached_hits, _misses) = lookup_cache(&conn, &addresses, read_cache)?;
+
+    let mut by_address: HashMap<String, GeocodeBatchResult> = HashMap::new();
+    for result in cached_hits {
+        by_address.insert(
+            result.address.clone(),
+            GeocodeBatchResult {
+                address: result.address,
+                lat: result.lat,
+                lon: result.lon,
+                confidence: default_confidence(&result.source),
+                source: result.source,
+                matched_label: None,
+                matched_table: None,
+                gers_id: None,
+            },
+        );
+    }
+    let ordered: Vec<GeocodeBatchResult> = addresses
+        .iter()
+        .filter_map(|address| by_address.get(address).cloned())
+        .collect();
+
+    let stats = GeocodeStats {
+        total: addresses.len(),
+        geocoded: ordered.len(),
+        cache_hits: ordered.len(),
+        unresolved: addresses.len() - ordered.len(),
+        ..Default::default()
+    };
+    info!(
+        total = stats.total,
+        cache_hits = stats.cache_hits,
+        unresolved = stats.unresolved,
+        "geocode_batch: cache-only lookup complete"
+    );
+    Ok((ordered, stats))
 }
 
 /// Geocode with a progress callback for real-time UI updates.
 ///
-/// Same strategy as `geocode_batch_with_components` but emits
-/// [`GeocodeProgressUpdate`] events during the Nominatim phase.
+/// Picks the same strategy for `providers` as
+/// [`geocode_batch_with_providers`]. The Overture-first pipeline emits
+/// [`GeocodeProgressUpdate`] events during the Nominatim phase; the
+/// cache-only and Geocodio paths finish without progress events.
 pub fn geocode_batch_with_progress<F>(
     db_path: &str,
     components: &[AddressComponents],
+    providers: ProviderMode,
     progress_cb: F,
 ) -> GeoResult<(Vec<GeocodeBatchResult>, GeocodeStats)>
 where
     F: Fn(GeocodeProgressUpdate) + Send + 'static,
 {
-    geocode_batch_overture_first_with_progress(db_path, components, Some(progress_cb))
+    let overture_first = match providers {
+        ProviderMode::FreeOnly => true,
+        ProviderMode::Auto => !(use_geocodio() && components.len() <= fast_path_limit()),
+        ProviderMode::GeocodioOnly | ProviderMode::CacheOnly => false,
+    };
+    if overture_first {
+        geocode_batch_overture_first_with_progress(db_path, components, Some(progress_cb))
+    } else {
+        geocode_components_with_providers(db_path, components, providers, true)
+    }
 }
 
 /// Full Overture-first geocoding pipeline.
@@ -636,15 +915,23 @@ where
 pub fn geocode_batch_overture_first(
     db_path: &str,
     components: &[AddressComponents],
+) -> GeoResult<(Vec<GeocodeBatchResult>, GeocodeStats)> {
+    overture_first(db_path, components, true)
+}
+
+fn overture_first(
+    db_path: &str,
+    components: &[AddressComponents],
+    read_cache: bool,
 ) -> GeoResult<(Vec<GeocodeBatchResult>, GeocodeStats)> {
     let addresses: Vec<String> = components.iter().map(|c| c.full.clone()).collect();
 
     info!(address_count = addresses.len(), "geocode_batch: starting batch geocode");
 
-    let conn = Connection::open(db_path)?;
+    let mut conn = open_connection(db_path)?;
 
     // ---- Step 1: Cache lookup ----
-    let (cached_hits, misses) = cache_lookup(&conn, &addresses)?;
+    let (cached_hits, misses) = lookup_cache(&conn, &addresses, read_cache)?;
     let cache_hit_count = cached_hits.len();
     debug!(cache_hits = cache_hit_count, cache_misses = misses.len(), "geocode_batch: cache lookup complete");
 
@@ -801,7 +1088,7 @@ pub fn geocode_batch_overture_first(
                             source: r.source.clone(),
                         })
                         .collect();
-                    cache_store(&conn, &local_cache_records, "overture_fuzzy")?;
+                    cache_store(&mut conn, &local_cache_records, "overture_fuzzy")?;
 
                     for result in accepted {
                         resolved_by_address.insert(result.address.clone(), result);
@@ -820,7 +1107,7 @@ pub fn geocode_batch_overture_first(
             let base_url = nominatim_base_url();
             info!(unresolved_count = unresolved.len(), base_url = %base_url, "geocode_batch: falling back to Nominatim");
 
-            let nominatim_results = run_async(geocode_via_nominatim_batch(
+            let nominatim_results = runtime::block_on(geocode_via_nominatim_batch(
                 &unresolved,
                 &base_url,
                 None::<fn(usize, usize)>,
@@ -834,7 +1121,7 @@ pub fn geocode_batch_overture_first(
                 .iter()
                 .map(|e: &NominatimEnrichedResult| e.inner.clone())
                 .collect();
-            cache_store(&conn, &cache_records, "nominatim")?;
+            cache_store(&mut conn, &cache_records, "nominatim")?;
 
             // Build a zip map from address → zip for GERS reverse lookup
             let addr_to_zip: HashMap<String, Option<String>> = components
@@ -859,7 +1146,10 @@ pub fn geocode_batch_overture_first(
                     enriched.inner.lon,
                     zip_hint,
                 )
-                .unwrap_or(None);
+                .unwrap_or_else(|e| {
+                    warn!(error = %e, "geocode_batch: GERS reverse lookup failed");
+                    None
+                });
 
                 if gers_id.is_some() {
                     debug!(
@@ -904,6 +1194,7 @@ pub fn geocode_batch_overture_first(
         local_fuzzy: local_fuzzy_count,
         api_resolved: api_resolved_count,
         unresolved: unresolved_count,
+        failures: Vec::new(),
     };
 
     info!(
@@ -935,7 +1226,7 @@ where
 
     info!(address_count = addresses.len(), "geocode_batch_progress: starting");
 
-    let conn = Connection::open(db_path)?;
+    let mut conn = open_connection(db_path)?;
 
     // ---- Step 1: Cache lookup ----
     let (cached_hits, misses) = cache_lookup(&conn, &addresses)?;
@@ -1064,7 +1355,7 @@ where
                     let local_cache_records: Vec<GeocodeResult> = accepted.iter().map(|r| GeocodeResult {
                         address: r.address.clone(), lat: r.lat, lon: r.lon, source: r.source.clone(),
                     }).collect();
-                    cache_store(&conn, &local_cache_records, "overture_fuzzy")?;
+                    cache_store(&mut conn, &local_cache_records, "overture_fuzzy")?;
                     for result in accepted {
                         resolved_by_address.insert(result.address.clone(), result);
                     }
@@ -1095,7 +1386,7 @@ where
 
             let unresolved_clone = unresolved.clone();
             let progress_cb_ref = progress_cb.as_ref();
-            let nominatim_results = run_async(geocode_via_nominatim_batch(
+            let nominatim_results = runtime::block_on(geocode_via_nominatim_batch(
                 &unresolved,
                 &base_url,
                 Some(move |done: usize, total: usize| {
@@ -1120,7 +1411,7 @@ where
                 .iter()
                 .map(|e| e.inner.clone())
                 .collect();
-            cache_store(&conn, &cache_records, "nominatim")?;
+            cache_store(&mut conn, &cache_records, "nominatim")?;
 
             let addr_to_zip: HashMap<String, Option<String>> = components
                 .iter()
@@ -1135,7 +1426,10 @@ where
                 };
 
                 let zip_hint = addr_to_zip.get(&enriched.inner.address).and_then(|z| z.as_deref());
-                let gers_id = overture_cache::reverse_lookup_gers(&conn, enriched.inner.lat, enriched.inner.lon, zip_hint).unwrap_or(None);
+                let gers_id = overture_cache::reverse_lookup_gers(&conn, enriched.inner.lat, enriched.inner.lon, zip_hint).unwrap_or_else(|e| {
+                    warn!(error = %e, "geocode_batch: GERS reverse lookup failed");
+                    None
+                });
 
                 resolved_by_address.insert(
                     enriched.inner.address.clone(),
@@ -1171,6 +1465,7 @@ where
         local_fuzzy: local_fuzzy_count,
         api_resolved: api_resolved_count,
         unresolved: total - geocoded,
+        failures: Vec::new(),
     };
 
     if let Some(ref cb) = progress_cb {
@@ -1198,10 +1493,81 @@ pub fn geocode_addresses(db_path: &str, addresses: &[String]) -> GeoResult<Vec<G
     Ok(enriched.into_iter().map(GeocodeResult::from).collect())
 }
 
+/// Reverse geocode `points` (`(lat, lon)` pairs) to addresses via Geocodio.
+///
+/// Cache-first like [`geocode_batch`]: points are looked up in the geocode
+/// cache under a key rounded to five decimal places, and only the misses are
+/// sent to the Geocodio reverse endpoint. Results come back in input order,
+/// keep the queried coordinates and carry the matched address; points that
+/// Geocodio cannot match are left out.
+///
+/// Requires `SPATIA_GEOCODIO_API_KEY` when there are cache misses.
+/// `SPATIA_GEOCODIO_BASE_URL` overrides the API host (useful for testing).
+pub fn reverse_geocode_batch(db_path: &str, points: &[(f64, f64)]) -> GeoResult<Vec<GeocodeResult>> {
+    for &(lat, lon) in points {
+        if !valid_coordinates(lat, lon) {
+            return Err(format!(
+                "invalid point {lat},{lon}: expected lat in [-90, 90] and lon in [-180, 180]"
+            )
+            .into());
+        }
+    }
+
+    info!(point_count = points.len(), "reverse_geocode_batch: starting");
+    let conn = open_connection(db_path)?;
+    let mut matches = reverse_cache_lookup(&conn, points)?;
+    let cache_hits = matches.len();
+
+    let mut seen = HashSet::new();
+    let misses: Vec<(f64, f64)> = points
+        .iter()
+        .copied()
+        .filter(|&(lat, lon)| {
+            let key = reverse_cache_key(lat, lon);
+            !matches.contains_key(&key) && seen.insert(key)
+        })
+        .collect();
+
+    if !misses.is_empty() {
+        let api_key = std::env::var("SPATIA_GEOCODIO_API_KEY").map_err(|_| {
+            "SPATIA_GEOCODIO_API_KEY environment variable not set"
+        })?;
+        let base_url = std::env::var("SPATIA_GEOCODIO_BASE_URL")
+            .unwrap_or_else(|_| "https://api.geocod.io".to_string());
+
+        info!(miss_count = misses.len(), "reverse_geocode_batch: calling Geocodio API");
+        let resolved = runtime::block_on(reverse_geocode_via_geocodio(&api_key, &misses, &base_url))?;
+        reverse_cache_store(&conn, &resolved, "geocodio")?;
+        for result in resolved {
+            matches.insert(reverse_cache_key(result.lat, result.lon), result);
+        }
+    }
+
+    let results: Vec<GeocodeResult> = points
+        .iter()
+        .filter_map(|&(lat, lon)| {
+            matches.get(&reverse_cache_key(lat, lon)).map(|hit| GeocodeResult {
+                address: hit.address.clone(),
+                lat,
+                lon,
+                source: hit.source.clone(),
+            })
+        })
+        .collect();
+
+    info!(
+        resolved_count = results.len(),
+        total = points.len(),
+        cache_hits,
+        "reverse_geocode_batch: complete"
+    );
+    Ok(results)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::cache::{cache_lookup, cache_store};
+    use crate::cache::{cache_lookup, cache_store, reverse_cache_store};
     use crate::geocodio::geocode_via_geocodio;
     use crate::scoring::{MIN_LOCAL_ACCEPT_SCORE, MIN_SCORE, local_accept_threshold};
     use duckdb::Connection;
@@ -1224,6 +1590,120 @@ mod tests {
         let _ = std::fs::remove_file(format!("{db_path}.wal.lck"));
     }
 
+    #[test]
+    fn dedupe_batch_geocodes_each_distinct_address_once() {
+        let addresses: Vec<String> =
+            ["85 Pike St", " 85 pike st ", "1 Main St", "85 PIKE ST", "Nowhere"]
+                .iter()
+                .map(|address| address.to_string())
+                .collect();
+        let mut calls: Vec<String> = Vec::new();
+        let (results, stats) = dedupe_batch(&addresses, |address| address.trim().to_string(), |unique| {
+            calls.extend(unique.iter().cloned());
+            let results: Vec<GeocodeBatchResult> = unique
+                .iter()
+                .filter(|address| address.as_str() != "Nowhere")
+                .enumerate()
+                .map(|(index, address)| GeocodeBatchResult {
+                    address: address.clone(),
+                    lat: index as f64,
+                    lon: 0.0,
+                    source: "geocodio".to_string(),
+                    confidence: 1.0,
+                    matched_label: None,
+                    matched_table: None,
+                    gers_id: None,
+                })
+                .collect();
+            let stats = GeocodeStats {
+                total: unique.len(),
+                geocoded: results.len(),
+                api_resolved: results.len(),
+                unresolved: unique.len() - results.len(),
+                ..Default::default()
+            };
+            Ok((results, stats))
+        })
+        .expect("dedupe");
+
+        assert_eq!(calls, vec!["85 Pike St", "1 Main St", "Nowhere"]);
+        let returned: Vec<&str> = results.iter().map(|r| r.address.as_str()).collect();
+        assert_eq!(returned, vec!["85 Pike St", " 85 pike st ", "1 Main St", "85 PIKE ST"]);
+        assert_eq!(results[0].lat, results[1].lat);
+        assert_eq!(results[0].lat, results[3].lat);
+        assert_ne!(results[0].lat, results[2].lat);
+        assert_eq!((stats.total, stats.geocoded, stats.unresolved), (5, 4, 1));
+        assert_eq!(stats.api_resolved, 2);
+    }
+
+    #[test]
+    fn partition_results_separates_unresolved_inputs() {
+        let addresses: Vec<String> = [" 85 Pike St ", "Nowhere", "1 Main St", "Nowhere"]
+            .iter()
+            .map(|address| address.to_string())
+            .collect();
+        let results: Vec<GeocodeBatchResult> = ["85 Pike St", "1 Main St"]
+            .iter()
+            .map(|address| GeocodeBatchResult {
+                address: address.to_string(),
+                lat: 47.6,
+                lon: -122.3,
+                source: "cache".to_string(),
+                confidence: 1.0,
+                matched_label: None,
+                matched_table: None,
+                gers_id: None,
+            })
+            .collect();
+
+        let partition = partition_results(&addresses, results);
+        assert_eq!((partition.resolved_count, partition.unresolved_count), (2, 2));
+        assert_eq!(partition.resolved.len(), 2);
+        assert_eq!(partition.unresolved, vec!["Nowhere", "Nowhere"]);
+
+        let unresolved = partition.unresolved_only();
+        assert!(unresolved.resolved.is_empty());
+        assert_eq!((unresolved.resolved_count, unresolved.unresolved_count), (2, 2));
+    }
+
+    #[test]
+    fn normalized_batch_shares_one_lookup_across_abbreviations() {
+        let addresses: Vec<String> = ["123 N Main St", "123 North  Main Street", "123 n. main st."]
+            .iter()
+            .map(|address| address.to_string())
+            .collect();
+        let mut calls: Vec<String> = Vec::new();
+        let (results, stats) = dedupe_batch(&addresses, canonicalize_address, |unique| {
+            calls.extend(unique.iter().cloned());
+            let results: Vec<GeocodeBatchResult> = unique
+                .iter()
+                .map(|address| GeocodeBatchResult {
+                    address: address.clone(),
+                    lat: 47.6,
+                    lon: -122.3,
+                    source: "cache".to_string(),
+                    confidence: 1.0,
+                    matched_label: None,
+                    matched_table: None,
+                    gers_id: None,
+                })
+                .collect();
+            let stats = GeocodeStats {
+                total: unique.len(),
+                geocoded: results.len(),
+                cache_hits: results.len(),
+                ..Default::default()
+            };
+            Ok((results, stats))
+        })
+        .expect("normalized");
+
+        assert_eq!(calls, vec!["123 North Main Street"]);
+        let returned: Vec<&str> = results.iter().map(|r| r.address.as_str()).collect();
+        assert_eq!(returned, vec!["123 N Main St", "123 North  Main Street", "123 n. main st."]);
+        assert_eq!((stats.total, stats.geocoded, stats.unresolved), (3, 3, 0));
+    }
+
     /// With Nominatim as the default fallback, missing Geocodio key no longer errors.
     /// The pipeline attempts Nominatim instead (which may fail on network, but
     /// doesn't require an API key). This test verifies no panic occurs.
@@ -1340,9 +1820,9 @@ mod tests {
     #[test]
     fn geocode_batch_returns_enriched_cached_results() {
         let db_path = tmp_db_path();
-        let conn = Connection::open(&db_path).expect("open");
+        let mut conn = Connection::open(&db_path).expect("open");
         cache_store(
-            &conn,
+            &mut conn,
             &[GeocodeResult {
                 address: "cached addr".to_string(),
                 lat: 1.5,
@@ -1362,6 +1842,106 @@ mod tests {
         cleanup_db(&db_path);
     }
 
+    fn seed_cached_addr(db_path: &str) {
+        let mut conn = Connection::open(db_path).expect("open");
+        cache_store(
+            &mut conn,
+            &[GeocodeResult {
+                address: "cached addr".to_string(),
+                lat: 1.5,
+                lon: 2.5,
+                source: "geocodio".to_string(),
+            }],
+            "geocodio",
+        )
+        .expect("cache");
+    }
+
+    #[test]
+    fn provider_mode_parses_known_names() {
+        assert_eq!("auto".parse::<ProviderMode>(), Ok(ProviderMode::Auto));
+        assert_eq!(" Free_Only ".parse::<ProviderMode>(), Ok(ProviderMode::FreeOnly));
+        assert_eq!("geocodio_only".parse::<ProviderMode>(), Ok(ProviderMode::GeocodioOnly));
+        assert_eq!("cache_only".parse::<ProviderMode>(), Ok(ProviderMode::CacheOnly));
+        let err = "sidecar".parse::<ProviderMode>().expect_err("unknown mode");
+        assert!(err.contains("unknown geocode provider mode 'sidecar'"));
+    }
+
+    #[test]
+    fn every_provider_mode_serves_cache_hits() {
+        for mode in [
+            ProviderMode::Auto,
+            ProviderMode::FreeOnly,
+            ProviderMode::GeocodioOnly,
+            ProviderMode::CacheOnly,
+        ] {
+            let db_path = tmp_db_path();
+            seed_cached_addr(&db_path);
+            let (results, stats) =
+                geocode_batch_with_providers(&db_path, &["cached addr".to_string()], mode)
+                    .expect("batch");
+            assert_eq!(results.len(), 1, "{mode:?}");
+            assert_eq!((results[0].lat, results[0].lon), (1.5, 2.5), "{mode:?}");
+            assert_eq!((stats.cache_hits, stats.unresolved), (1, 0), "{mode:?}");
+            cleanup_db(&db_path);
+        }
+    }
+
+    #[test]
+    fn cache_only_mode_leaves_misses_unresolved() {
+        let db_path = tmp_db_path();
+        seed_cached_addr(&db_path);
+        let addresses = vec!["never cached".to_string(), "cached addr".to_string()];
+
+        let (results, stats) =
+            geocode_batch_with_providers(&db_path, &addresses, ProviderMode::CacheOnly)
+                .expect("cache-only batch");
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].address, "cached addr");
+        assert_eq!((stats.total, stats.geocoded, stats.unresolved), (2, 1, 1));
+        assert_eq!(stats.api_resolved, 0);
+
+        cleanup_db(&db_path);
+    }
+
+    #[test]
+    fn progress_batch_honours_cache_only_mode() {
+        let db_path = tmp_db_path();
+        seed_cached_addr(&db_path);
+        let components: Vec<AddressComponents> = ["never cached", "cached addr"]
+            .iter()
+            .map(|address| components_from_string(address))
+            .collect();
+
+        // Overture-first would try to download data for the miss; cache-only
+        // must leave it unresolved without any provider call.
+        let (results, stats) =
+            geocode_batch_with_progress(&db_path, &components, ProviderMode::CacheOnly, |_| {})
+                .expect("cache-only batch");
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].address, "cached addr");
+        assert_eq!((stats.total, stats.cache_hits, stats.unresolved), (2, 1, 1));
+        assert_eq!((stats.api_resolved, stats.overture_exact), (0, 0));
+
+        cleanup_db(&db_path);
+    }
+
+    #[test]
+    fn geocodio_only_mode_sends_misses_to_geocodio() {
+        let db_path = tmp_db_path();
+        std::env::remove_var("SPATIA_GEOCODIO_API_KEY");
+        // Without a key the Geocodio step fails, which shows the miss went to
+        // Geocodio rather than the Overture download or Nominatim.
+        let err = geocode_batch_with_providers(
+            &db_path,
+            &["uncached address".to_string()],
+            ProviderMode::GeocodioOnly,
+        )
+        .expect_err("no api key");
+        assert!(err.to_string().contains("SPATIA_GEOCODIO_API_KEY"));
+        cleanup_db(&db_path);
+    }
+
     /// TC-T-001: A local fuzzy match with score < MIN_LOCAL_ACCEPT_SCORE (0.75)
     /// must NOT be accepted as resolved and must NOT be cached.
     #[test]
@@ -1624,4 +2204,36 @@ mod tests {
         assert!(!is_noise_token_smart("seattle", "seattle"));
         assert!(!is_noise_token_smart("98101", "98101"));
     }
+
+    #[test]
+    fn reverse_geocode_batch_serves_cached_points_without_api_key() {
+        let db_path = tmp_db_path();
+        {
+            let conn = Connection::open(&db_path).expect("open");
+            let cached = vec![GeocodeResult {
+                address: "400 Broad St, Seattle, WA 98109".to_string(),
+                lat: 47.6205,
+                lon: -122.3493,
+                source: "geocodio".to_string(),
+            }];
+            reverse_cache_store(&conn, &cached, "geocodio").expect("store");
+        }
+
+        // Both points round to the cached key, so no Geocodio call is needed.
+        let points = [(47.620501, -122.349301), (47.6205, -122.3493)];
+        let results = reverse_geocode_batch(&db_path, &points).expect("reverse geocode");
+        assert_eq!(results.len(), 2);
+        assert_eq!(results[0].address, "400 Broad St, Seattle, WA 98109");
+        assert!((results[0].lat - 47.620501).abs() < 1e-9);
+        assert!((results[1].lon - (-122.3493)).abs() < 1e-9);
+
+        cleanup_db(&db_path);
+    }
+
+    #[test]
+    fn reverse_geocode_batch_rejects_out_of_range_points() {
+        let err = reverse_geocode_batch("/tmp/unused.duckdb", &[(91.0, 0.0)])
+            .expect_err("should fail");
+        assert!(err.to_string().contains("invalid point 91,0"));
+    }
 }
@@ -1,7 +1,9 @@
-use serde::Deserialize;
-use tracing::{debug, error, info};
+use serde::{Deserialize, Serialize};
+use tracing::{debug, error, info, warn};
 
-use crate::types::{GeoResult, GeocodeResult};
+use crate::cache::valid_coordinates;
+use crate::http::http_client_builder;
+use crate::types::{GeoResult, GeocodeFailure, GeocodeResult};
 
 // ---- Geocodio API types ----
 //
@@ -40,7 +42,13 @@ pub(crate) struct GeocodioAddressResponse {
     #[serde(default)]
     #[allow(dead_code)]
     pub(crate) input: Option<serde_json::Value>,
+    /// Empty (or absent) when Geocodio could not match the address.
+    #[serde(default)]
     pub(crate) results: Vec<GeocodioCandidate>,
+    /// Per-address error message, e.g. "Could not geocode address. Postal
+    /// code or city required." Only present on failed entries.
+    #[serde(default)]
+    pub(crate) error: Option<String>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -62,9 +70,9 @@ pub(crate) struct GeocodioCandidate {
     #[serde(default)]
     #[allow(dead_code)]
     pub(crate) source: String,
-    /// Formatted address string returned by Geocodio.
+    /// Formatted address string returned by Geocodio. Only reverse
+    /// geocoding reads it; forward results keep the caller's query string.
     #[serde(default)]
-    #[allow(dead_code)]
     pub(crate) formatted_address: String,
 }
 
@@ -87,6 +95,123 @@ pub(crate) struct GeocodioEnrichedResult {
     pub(crate) accuracy: f64,
 }
 
+/// Resolved and unresolved entries from one Geocodio batch call.
+pub(crate) struct GeocodioOutcome {
+    pub(crate) resolved: Vec<GeocodioEnrichedResult>,
+    /// Entries Geocodio answered with zero candidates, tagged `"no_match"`
+    /// and carrying the per-entry error message when one was returned, plus
+    /// matches with off-globe coordinates, tagged `"invalid_coordinates"`.
+    pub(crate) failures: Vec<GeocodeFailure>,
+}
+
+/// Default cap on a single Geocodio batch response body. A full 10,000-address
+/// batch with address components stays well under this.
+const DEFAULT_MAX_RESPONSE_BYTES: usize = 64 * 1024 * 1024;
+
+/// Response size cap, overridable via `SPATIA_GEOCODIO_MAX_RESPONSE_BYTES`.
+fn max_response_bytes() -> usize {
+    std::env::var("SPATIA_GEOCODIO_MAX_RESPONSE_BYTES")
+        .ok()
+        .and_then(|v| v.parse::<usize>().ok())
+        .filter(|v| *v > 0)
+        .unwrap_or(DEFAULT_MAX_RESPONSE_BYTES)
+}
+
+/// Entries per Geocodio request, overridable via `SPATIA_GEOCODIO_BATCH_SIZE`
+/// and clamped to the API's 10,000-entry limit.
+fn batch_size() -> usize {
+    std::env::var("SPATIA_GEOCODIO_BATCH_SIZE")
+        .ok()
+        .and_then(|v| v.parse::<usize>().ok())
+        .unwrap_or(100)
+        .clamp(1, 10_000)
+}
+
+/// Read `resp` as UTF-8 text, failing as soon as more than `limit` bytes have
+/// arrived instead of buffering an unbounded body.
+async fn read_body_limited(mut resp: reqwest::Response, limit: usize) -> GeoResult<String> {
+    if let Some(len) = resp.content_length() {
+        if len > limit as u64 {
+            return Err(format!(
+                "Geocodio response too large: {len} bytes exceeds limit of {limit} bytes"
+            )
+            .into());
+        }
+    }
+    let mut body = Vec::new();
+    while let Some(chunk) = resp.chunk().await? {
+        if body.len() + chunk.len() > limit {
+            return Err(format!("Geocodio response too large: exceeds limit of {limit} bytes").into());
+        }
+        body.extend_from_slice(&chunk);
+    }
+    Ok(String::from_utf8(body)?)
+}
+
+/// POST one batch to a Geocodio endpoint and decode the response, logging
+/// (with the API key redacted) whatever went wrong.
+async fn post_batch<T: Serialize + ?Sized>(
+    client: &reqwest::Client,
+    url: &str,
+    log_url: &str,
+    api_key: &str,
+    payload: &T,
+    limit: usize,
+) -> GeoResult<GeocodioResponse> {
+    let http_response = client
+        .post(url)
+        .json(payload)
+        .send()
+        .await
+        .inspect_err(|e| {
+            // Classify the error kind so operators know what went wrong
+            // (DNS failure, TLS, timeout, connection refused, etc.)
+            let kind = if e.is_timeout() {
+                "timeout"
+            } else if e.is_connect() {
+                "connection"
+            } else if e.is_request() {
+                "request"
+            } else {
+                "unknown"
+            };
+            let redacted = e.to_string().replace(api_key, "[REDACTED]");
+            error!(
+                url = %log_url,
+                error_kind = %kind,
+                error = %redacted,
+                "post_batch: HTTP request failed"
+            );
+        })?;
+
+    let status = http_response.status();
+    let resp = http_response
+        .error_for_status()
+        .inspect_err(|e| {
+            let redacted = e.to_string().replace(api_key, "[REDACTED]");
+            error!(
+                url = %log_url,
+                status = %status,
+                error = %redacted,
+                "post_batch: API returned error status"
+            );
+        })?;
+
+    // Read raw body first so we can log it on parse failure
+    let body = read_body_limited(resp, limit).await.inspect_err(|e| {
+        error!(url = %log_url, error = %e, "post_batch: rejected response body");
+    })?;
+    serde_json::from_str(&body).map_err(|e| {
+        error!(
+            url = %log_url,
+            error = %e,
+            body_preview = %&body[..body.len().min(500)],
+            "post_batch: failed to decode response body"
+        );
+        e.into()
+    })
+}
+
 /// Core HTTP logic shared by the public `geocode_via_geocodio` wrapper and the
 /// internal `geocode_batch` call-site.  Returns enriched results including the
 /// raw `accuracy` field from the Geocodio response so that callers can
@@ -96,15 +221,23 @@ pub(crate) async fn geocode_via_geocodio_inner(
     addresses: &[String],
     base_url: &str,
 ) -> GeoResult<Vec<GeocodioEnrichedResult>> {
-    let batch_size: usize = std::env::var("SPATIA_GEOCODIO_BATCH_SIZE")
-        .ok()
-        .and_then(|v| v.parse::<usize>().ok())
-        .unwrap_or(100)
-        .clamp(1, 10_000);
+    let outcome = geocode_via_geocodio_detailed(api_key, addresses, base_url).await?;
+    Ok(outcome.resolved)
+}
+
+/// Like [`geocode_via_geocodio_inner`] but also reports the addresses that
+/// Geocodio returned no candidates for, so callers can tell "no match" apart
+/// from "never sent".
+pub(crate) async fn geocode_via_geocodio_detailed(
+    api_key: &str,
+    addresses: &[String],
+    base_url: &str,
+) -> GeoResult<GeocodioOutcome> {
+    let batch_size = batch_size();
 
     info!(address_count = addresses.len(), "geocode_via_geocodio: calling Geocodio API");
 
-    let client = reqwest::Client::new();
+    let client = http_client_builder().build()?;
     let url = format!(
         "{}/v1.10/geocode?api_key={}",
         base_url.trim_end_matches('/'),
@@ -114,78 +247,71 @@ pub(crate) async fn geocode_via_geocodio_inner(
     // appears in log output.
     let log_url = format!("{}/v1.10/geocode", base_url.trim_end_matches('/'));
 
+    let max_response_limit = max_response_bytes();
+
     let mut results = Vec::new();
+    let mut failures = Vec::new();
 
     for (chunk_idx, chunk) in addresses.chunks(batch_size).enumerate() {
         debug!(chunk = chunk_idx, chunk_size = chunk.len(), url = %log_url, "geocode_via_geocodio: sending batch");
-        let http_response = client
-            .post(&url)
-            .json(chunk)
-            .send()
-            .await
-            .inspect_err(|e| {
-                // Classify the error kind so operators know what went wrong
-                // (DNS failure, TLS, timeout, connection refused, etc.)
-                let kind = if e.is_timeout() {
-                    "timeout"
-                } else if e.is_connect() {
-                    "connection"
-                } else if e.is_request() {
-                    "request"
-                } else {
-                    "unknown"
-                };
-                let redacted = e.to_string().replace(api_key, "[REDACTED]");
-                error!(
-                    url = %log_url,
-                    error_kind = %kind,
-                    error = %redacted,
-                    "geocode_via_geocodio: HTTP request failed"
-                );
-            })?;
-
-        let status = http_response.status();
-        let resp = http_response
-            .error_for_status()
-            .inspect_err(|e| {
-                let redacted = e.to_string().replace(api_key, "[REDACTED]");
-                error!(
-                    url = %log_url,
-                    status = %status,
-                    error = %redacted,
-                    "geocode_via_geocodio: API returned error status"
-                );
-            })?;
-
-        // Read raw body first so we can log it on parse failure
-        let body = resp.text().await?;
-        let response: GeocodioResponse = serde_json::from_str(&body).map_err(|e| {
-            error!(
-                url = %log_url,
-                error = %e,
-                body_preview = %&body[..body.len().min(500)],
-                "geocode_via_geocodio: failed to decode response body"
-            );
-            e
-        })?;
+        let response =
+            post_batch(&client, &url, &log_url, api_key, chunk, max_response_limit).await?;
 
         for item in &response.results {
-            if let Some(candidate) = item.response.results.first() {
-                results.push(GeocodioEnrichedResult {
-                    inner: GeocodeResult {
+            match item.response.results.first() {
+                Some(candidate) if valid_coordinates(candidate.location.lat, candidate.location.lng) => {
+                    results.push(GeocodioEnrichedResult {
+                        inner: GeocodeResult {
+                            address: item.query.clone(),
+                            lat: candidate.location.lat,
+                            lon: candidate.location.lng,
+                            source: "geocodio".to_string(),
+                        },
+                        accuracy: candidate.accuracy,
+                    });
+                }
+                Some(candidate) => {
+                    let (lat, lng) = (candidate.location.lat, candidate.location.lng);
+                    warn!(
+                        address = %item.query,
+                        lat,
+                        lng,
+                        "geocode_via_geocodio: candidate has out-of-range coordinates"
+                    );
+                    failures.push(GeocodeFailure {
                         address: item.query.clone(),
-                        lat: candidate.location.lat,
-                        lon: candidate.location.lng,
-                        source: "geocodio".to_string(),
-                    },
-                    accuracy: candidate.accuracy,
-                });
+                        status: "invalid_coordinates".to_string(),
+                        error: Some(format!(
+                            "Geocodio returned out-of-range coordinates {lat},{lng}"
+                        )),
+                    });
+                }
+                None => {
+                    debug!(
+                        address = %item.query,
+                        error = ?item.response.error,
+                        "geocode_via_geocodio: no candidates returned"
+                    );
+                    failures.push(GeocodeFailure {
+                        address: item.query.clone(),
+                        status: "no_match".to_string(),
+                        error: item.response.error.clone(),
+                    });
+                }
             }
         }
     }
 
-    info!(resolved_count = results.len(), total = addresses.len(), "geocode_via_geocodio: completed");
-    Ok(results)
+    info!(
+        resolved_count = results.len(),
+        no_match_count = failures.len(),
+        total = addresses.len(),
+        "geocode_via_geocodio: completed"
+    );
+    Ok(GeocodioOutcome {
+        resolved: results,
+        failures,
+    })
 }
 
 /// Call the Geocodio batch geocoding endpoint.
@@ -205,6 +331,62 @@ pub async fn geocode_via_geocodio(
     Ok(enriched.into_iter().map(|e| e.inner).collect())
 }
 
+/// Call the Geocodio batch reverse endpoint (`POST /v1.10/reverse`).
+///
+/// Points are sent as `"lat,lng"` strings and the response has the same shape
+/// as batch geocoding, in input order. Each resolved entry keeps the queried
+/// coordinates and takes its `address` from the best match's
+/// `formatted_address`; points without a match are left out.
+pub(crate) async fn reverse_geocode_via_geocodio(
+    api_key: &str,
+    points: &[(f64, f64)],
+    base_url: &str,
+) -> GeoResult<Vec<GeocodeResult>> {
+    info!(point_count = points.len(), "reverse_geocode_via_geocodio: calling Geocodio API");
+
+    let client = http_client_builder().build()?;
+    let url = format!(
+        "{}/v1.10/reverse?api_key={}",
+        base_url.trim_end_matches('/'),
+        api_key
+    );
+    let log_url = format!("{}/v1.10/reverse", base_url.trim_end_matches('/'));
+    let max_response_limit = max_response_bytes();
+
+    let mut results = Vec::new();
+    for (chunk_idx, chunk) in points.chunks(batch_size()).enumerate() {
+        let queries: Vec<String> = chunk.iter().map(|(lat, lon)| format!("{lat},{lon}")).collect();
+        debug!(chunk = chunk_idx, chunk_size = chunk.len(), url = %log_url, "reverse_geocode_via_geocodio: sending batch");
+        let response =
+            post_batch(&client, &url, &log_url, api_key, &queries, max_response_limit).await?;
+
+        for (&(lat, lon), item) in chunk.iter().zip(&response.results) {
+            match item.response.results.first() {
+                Some(candidate) if !candidate.formatted_address.is_empty() => {
+                    results.push(GeocodeResult {
+                        address: candidate.formatted_address.clone(),
+                        lat,
+                        lon,
+                        source: "geocodio".to_string(),
+                    });
+                }
+                _ => debug!(
+                    query = %item.query,
+                    error = ?item.response.error,
+                    "reverse_geocode_via_geocodio: no candidates returned"
+                ),
+            }
+        }
+    }
+
+    info!(
+        resolved_count = results.len(),
+        total = points.len(),
+        "reverse_geocode_via_geocodio: completed"
+    );
+    Ok(results)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -376,6 +558,123 @@ mod tests {
         );
     }
 
+    /// TC-G-002b: Zero-result entries are reported as `no_match` failures,
+    /// carrying Geocodio's per-entry error message when one is present.
+    #[tokio::test]
+    async fn geocode_via_geocodio_detailed_reports_no_match_entries() {
+        let mut server = mockito::Server::new_async().await;
+
+        let fixture = r#"{
+            "results": [
+                {
+                    "query": "known address",
+                    "response": {
+                        "results": [
+                            {
+                                "location": {"lat": 39.7817, "lng": -89.6501},
+                                "accuracy": 1
+                            }
+                        ]
+                    }
+                },
+                {
+                    "query": "nowhere",
+                    "response": {
+                        "input": {"formatted_address": "nowhere"},
+                        "results": []
+                    }
+                },
+                {
+                    "query": "main st",
+                    "response": {
+                        "error": "Could not geocode address. Postal code or city required."
+                    }
+                }
+            ]
+        }"#;
+
+        let _mock = server
+            .mock("POST", "/v1.10/geocode?api_key=test_key")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(fixture)
+            .create_async()
+            .await;
+
+        let addresses = vec![
+            "known address".to_string(),
+            "nowhere".to_string(),
+            "main st".to_string(),
+        ];
+        let outcome = geocode_via_geocodio_detailed("test_key", &addresses, &server.url())
+            .await
+            .expect("zero-result entries should not error");
+
+        assert_eq!(outcome.resolved.len(), 1);
+        assert_eq!(
+            outcome.failures,
+            vec![
+                GeocodeFailure {
+                    address: "nowhere".to_string(),
+                    status: "no_match".to_string(),
+                    error: None,
+                },
+                GeocodeFailure {
+                    address: "main st".to_string(),
+                    status: "no_match".to_string(),
+                    error: Some(
+                        "Could not geocode address. Postal code or city required.".to_string()
+                    ),
+                },
+            ]
+        );
+    }
+
+    /// TC-G-002c: A candidate with off-globe coordinates is not returned as
+    /// resolved; it is reported as an `invalid_coordinates` failure instead.
+    #[tokio::test]
+    async fn geocode_via_geocodio_detailed_rejects_out_of_range_coordinates() {
+        let mut server = mockito::Server::new_async().await;
+
+        let fixture = r#"{
+            "results": [
+                {
+                    "query": "bad address",
+                    "response": {
+                        "results": [
+                            {
+                                "location": {"lat": 139.7817, "lng": -89.6501},
+                                "accuracy": 1
+                            }
+                        ]
+                    }
+                }
+            ]
+        }"#;
+
+        let _mock = server
+            .mock("POST", "/v1.10/geocode?api_key=test_key")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(fixture)
+            .create_async()
+            .await;
+
+        let addresses = vec!["bad address".to_string()];
+        let outcome = geocode_via_geocodio_detailed("test_key", &addresses, &server.url())
+            .await
+            .expect("out-of-range candidates should not error");
+
+        assert!(outcome.resolved.is_empty());
+        assert_eq!(outcome.failures.len(), 1);
+        assert_eq!(outcome.failures[0].status, "invalid_coordinates");
+        assert!(outcome.failures[0]
+            .error
+            .as_deref()
+            .unwrap_or_default()
+            .contains("139.7817,-89.6501"));
+    }
+
     /// TC-G-003: An empty address slice must return an empty result list
     /// immediately, without making any HTTP request.
     #[tokio::test]
@@ -421,6 +720,27 @@ mod tests {
         assert!(!err_msg.is_empty(), "error message should not be empty");
     }
 
+    /// An oversized body is rejected with a clear error instead of being
+    /// buffered and parsed.
+    #[tokio::test]
+    async fn read_body_limited_rejects_oversized_body() {
+        let mut server = mockito::Server::new_async().await;
+        let _mock = server
+            .mock("GET", "/big")
+            .with_status(200)
+            .with_body("x".repeat(4096))
+            .create_async()
+            .await;
+
+        let resp = reqwest::get(format!("{}/big", server.url())).await.expect("get");
+        let err = read_body_limited(resp, 1024).await.expect_err("should reject");
+        assert!(err.to_string().contains("response too large"), "got: {err}");
+
+        let resp = reqwest::get(format!("{}/big", server.url())).await.expect("get");
+        let body = read_body_limited(resp, 8192).await.expect("within limit");
+        assert_eq!(body.len(), 4096);
+    }
+
     /// TC-G-005: When the API returns a 422 (unprocessable entity) or 500
     /// (server error) HTTP status, the function propagates a meaningful error.
     #[tokio::test]
@@ -732,4 +1052,64 @@ mod tests {
         assert!((results[0].lon - (-89.6501)).abs() < 1e-6);
         assert_eq!(results[0].source, "geocodio");
     }
+
+    /// Reverse lookups post `"lat,lng"` strings to `/v1.10/reverse`, keep the
+    /// queried coordinates and take the address from the best match.
+    #[tokio::test]
+    async fn reverse_geocode_via_geocodio_fills_address_from_match() {
+        let mut server = mockito::Server::new_async().await;
+
+        let fixture = r#"{
+            "results": [
+                {
+                    "query": "47.6205,-122.3493",
+                    "response": {
+                        "results": [
+                            {
+                                "formatted_address": "400 Broad St, Seattle, WA 98109",
+                                "location": {"lat": 47.62051, "lng": -122.34927},
+                                "accuracy": 1,
+                                "accuracy_type": "rooftop",
+                                "source": "King County"
+                            }
+                        ]
+                    }
+                },
+                {
+                    "query": "0,0",
+                    "response": {
+                        "results": []
+                    }
+                }
+            ]
+        }"#;
+
+        let mock = server
+            .mock("POST", "/v1.10/reverse?api_key=test_key")
+            .match_body(mockito::Matcher::Json(serde_json::json!([
+                "47.6205,-122.3493",
+                "0,0"
+            ])))
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(fixture)
+            .create_async()
+            .await;
+
+        let points = [(47.6205, -122.3493), (0.0, 0.0)];
+        let results = reverse_geocode_via_geocodio("test_key", &points, &server.url())
+            .await
+            .expect("reverse geocode");
+        mock.assert_async().await;
+
+        assert_eq!(
+            results,
+            vec![GeocodeResult {
+                address: "400 Broad St, Seattle, WA 98109".to_string(),
+                lat: 47.6205,
+                lon: -122.3493,
+                source: "geocodio".to_string(),
+            }]
+        );
+    }
 }
@@ -0,0 +1,60 @@
+//! Shared `reqwest` client setup, so every outbound request (Geocodio,
+//! Nominatim, Gemini) identifies itself the same way.
+
+/// Environment variable appended to the User-Agent, e.g. a contact address
+/// for services that ask heavy users to identify themselves.
+pub const USER_AGENT_SUFFIX_ENV: &str = "SPATIA_USER_AGENT_SUFFIX";
+
+/// `spatia/{version} (+repository URL)`, plus `SPATIA_USER_AGENT_SUFFIX` when set.
+pub fn user_agent() -> String {
+    user_agent_with_suffix(std::env::var(USER_AGENT_SUFFIX_ENV).ok().as_deref())
+}
+
+fn user_agent_with_suffix(suffix: Option<&str>) -> String {
+    let base = format!(
+        "spatia/{} (+https://github.com/spatia-app/spatia)",
+        env!("CARGO_PKG_VERSION")
+    );
+    match suffix.map(str::trim).filter(|suffix| !suffix.is_empty()) {
+        Some(suffix) => format!("{base} {suffix}"),
+        None => base,
+    }
+}
+
+/// A `reqwest::ClientBuilder` with the Spatia User-Agent already set. Callers
+/// add their own timeouts before `build()`.
+pub fn http_client_builder() -> reqwest::ClientBuilder {
+    reqwest::Client::builder().user_agent(user_agent())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{http_client_builder, user_agent_with_suffix};
+
+    #[test]
+    fn suffix_is_appended_when_set() {
+        let base = user_agent_with_suffix(None);
+        assert!(base.starts_with(&format!("spatia/{} ", env!("CARGO_PKG_VERSION"))));
+        assert_eq!(user_agent_with_suffix(Some("  ")), base);
+        assert_eq!(
+            user_agent_with_suffix(Some(" ops@example.com ")),
+            format!("{base} ops@example.com")
+        );
+    }
+
+    #[tokio::test]
+    async fn client_sends_spatia_user_agent() {
+        let mut server = mockito::Server::new_async().await;
+        let mock = server
+            .mock("GET", "/")
+            .match_header("user-agent", mockito::Matcher::Regex("^spatia/".to_string()))
+            .with_status(200)
+            .create_async()
+            .await;
+
+        let client = http_client_builder().build().expect("client");
+        let response = client.get(server.url()).send().await.expect("send");
+        assert_eq!(response.status(), 200);
+        mock.assert_async().await;
+    }
+}
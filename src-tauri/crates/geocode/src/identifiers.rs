@@ -14,6 +14,17 @@ pub fn validate_table_name(table_name: &str) -> GeoResult<()> {
     Ok(())
 }
 
+/// Accept `table` or `schema.table`, each part a plain identifier.
+pub fn validate_qualified_table_name(name: &str) -> GeoResult<()> {
+    match name.split_once('.') {
+        Some((schema, table)) => {
+            validate_table_name(schema)?;
+            validate_table_name(table)
+        }
+        None => validate_table_name(name),
+    }
+}
+
 fn is_ident_start(value: char) -> bool {
     value == '_' || value.is_ascii_alphabetic()
 }
@@ -1,21 +1,44 @@
 mod cache;
+mod connection;
+mod extensions;
 mod geocode;
 mod geocodio;
+mod http;
 mod identifiers;
 pub(crate) mod nominatim;
 pub mod overture_cache;
+mod precision;
+mod runtime;
 mod scoring;
 mod text;
 mod types;
 pub mod search_index;
 
-pub use cache::{cache_lookup, cache_store, ensure_cache_table};
-pub use geocode::{geocode_addresses, geocode_batch, geocode_batch_with_components, geocode_batch_with_progress, geocode_batch_api_first, geocode_batch_overture_first, local_fuzzy_geocode};
+pub use cache::{
+    cache_lookup, cache_store, cache_ttl_days, ensure_cache_table, export_cache, import_cache,
+    local_cache_table_name, refresh_stale_cache, CACHE_DB_ENV, CACHE_TABLE_ENV, CACHE_TTL_ENV,
+    DEFAULT_CACHE_TTL_DAYS,
+};
+pub use connection::{
+    open_connection, open_in_memory, share_connection, SessionSettings, SharedConnection,
+    EXTENSION_DIR_ENV, INIT_SQL_ENV, MEMORY_LIMIT_ENV, TEMP_DIR_ENV, THREADS_ENV,
+};
+pub use geocode::{geocode_addresses, geocode_batch, geocode_batch_dedupe, geocode_batch_normalized, geocode_batch_with_components, geocode_batch_with_providers, geocode_batch_with_progress, geocode_batch_api_first, geocode_batch_overture_first, local_fuzzy_geocode, partition_results, reverse_geocode_batch, ProviderMode};
+pub use extensions::{configure_s3_secret, load_extension, S3Credentials};
 pub use geocodio::geocode_via_geocodio;
+pub use http::{http_client_builder, user_agent, USER_AGENT_SUFFIX_ENV};
 pub use nominatim::geocode_via_nominatim;
+pub use precision::{
+    coord_precision, parse_coord_precision, round_coord, serialize_coord, serialize_opt_coord,
+    with_coord_precision, COORD_PRECISION_ENV, MAX_COORD_PRECISION,
+};
 pub use scoring::{score_candidate, MIN_LOCAL_ACCEPT_SCORE, MIN_SCORE};
 pub use text::{
-    components_from_columns, components_from_string, extract_zip, normalize_address,
-    tokenize_address, AddressComponents,
+    canonicalize_address, components_from_columns, components_from_string, edit_distance,
+    extract_zip, normalize_address, tokenize_address, AddressComponents,
+};
+pub use types::{
+    CacheEntry, CacheImportSummary, CacheRefreshSummary, GeoResult, GeocodeBatchResult,
+    GeocodeFailure, GeocodePartition, GeocodeProgressUpdate, GeocodeResult, GeocodeSourceCounts,
+    GeocodeStats, GeocodeSummary,
 };
-pub use types::{GeoResult, GeocodeBatchResult, GeocodeProgressUpdate, GeocodeResult, GeocodeStats};
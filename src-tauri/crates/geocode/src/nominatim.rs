@@ -1,6 +1,7 @@
 use serde::Deserialize;
 use tracing::{debug, error, info, warn};
 
+use crate::http::http_client_builder;
 use crate::types::{GeoResult, GeocodeResult};
 
 // ---- Nominatim API types ----
@@ -33,7 +34,6 @@ pub(crate) struct NominatimEnrichedResult {
 }
 
 const DEFAULT_BASE_URL: &str = "https://nominatim.openstreetmap.org";
-const USER_AGENT: &str = "Spatia/1.0 (https://github.com/spatia-app/spatia)";
 
 /// Return the configured Nominatim base URL or the public instance default.
 pub(crate) fn nominatim_base_url() -> String {
@@ -69,7 +69,6 @@ pub(crate) async fn geocode_via_nominatim_single(
             ("limit", "1"),
             ("addressdetails", "0"),
         ])
-        .header("User-Agent", USER_AGENT)
         .send()
         .await
         .inspect_err(|e| {
@@ -158,7 +157,9 @@ where
         );
     }
 
-    let client = reqwest::Client::builder()
+    // Nominatim's usage policy requires an identifying User-Agent, which the
+    // shared builder sets.
+    let client = http_client_builder()
         .timeout(std::time::Duration::from_secs(30))
         .build()?;
 
@@ -1,8 +1,9 @@
 use std::collections::HashSet;
 
 use duckdb::Connection;
-use tracing::{debug, info};
+use tracing::{debug, info, warn};
 
+use crate::extensions::{configure_s3_secret, load_extension};
 use crate::types::{GeoResult, GeocodeBatchResult};
 
 /// The Overture release to query from S3.
@@ -21,12 +22,10 @@ fn addresses_source() -> String {
 /// Ensure DuckDB extensions needed for remote parquet + spatial ops are loaded.
 fn ensure_extensions(conn: &Connection) -> GeoResult<()> {
     // httpfs is required for reading from S3
-    conn.execute("INSTALL httpfs", []).ok();
-    conn.execute("LOAD httpfs", [])?;
+    load_extension(conn, "httpfs")?;
+    configure_s3_secret(conn)?;
     // spatial is needed for ST_Y / ST_X
-    conn.execute("INSTALL spatial", []).ok();
-    conn.execute("LOAD spatial", [])?;
-    Ok(())
+    load_extension(conn, "spatial")
 }
 
 /// Create the overture address cache table if it doesn't exist.
@@ -139,7 +138,9 @@ pub fn fetch_by_postcodes(
     info!(rows = count, "overture_cache: postcode fetch complete");
 
     for pc in postcodes {
-        log_download(conn, "postcode", pc, count as i64).ok();
+        if let Err(e) = log_download(conn, "postcode", pc, count as i64) {
+            warn!(postcode = %pc, error = %e, "overture_cache: failed to record download log");
+        }
     }
 
     progress(
@@ -0,0 +1,143 @@
+//! Optional rounding of output coordinates.
+//!
+//! Full `f64` precision prints up to 17 significant digits per coordinate,
+//! far beyond what any geocoder resolves. `SPATIA_COORD_PRECISION` caps the
+//! decimal places written when results are serialized; 6 places is about
+//! 0.1 m. Unset keeps full precision. A command-level `--precision` wins
+//! over the variable through [`with_coord_precision`].
+
+use std::cell::Cell;
+use std::sync::OnceLock;
+
+use serde::Serializer;
+
+use crate::types::GeoResult;
+
+/// Environment variable holding the number of decimal places to keep.
+pub const COORD_PRECISION_ENV: &str = "SPATIA_COORD_PRECISION";
+
+/// Beyond 15 places rounding no longer changes an `f64` coordinate.
+pub const MAX_COORD_PRECISION: u32 = 15;
+
+/// Parse a decimal-place count between 0 and [`MAX_COORD_PRECISION`].
+pub fn parse_coord_precision(raw: &str) -> GeoResult<u32> {
+    match raw.trim().parse::<u32>() {
+        Ok(digits) if digits <= MAX_COORD_PRECISION => Ok(digits),
+        _ => Err(format!(
+            "coordinate precision must be an integer from 0 to {MAX_COORD_PRECISION}, got '{raw}'"
+        )
+        .into()),
+    }
+}
+
+/// Precision from [`COORD_PRECISION_ENV`]; unset, empty or invalid values
+/// mean full precision.
+pub fn coord_precision() -> Option<u32> {
+    std::env::var(COORD_PRECISION_ENV)
+        .ok()
+        .filter(|value| !value.trim().is_empty())
+        .and_then(|value| parse_coord_precision(&value).ok())
+}
+
+thread_local! {
+    /// Precision set by [`with_coord_precision`] on this thread, if any.
+    static SCOPED_PRECISION: Cell<Option<Option<u32>>> = const { Cell::new(None) };
+}
+
+/// Run `f` with coordinates serialized at `digits` decimal places, or at
+/// full precision for `None`, on this thread, regardless of
+/// [`COORD_PRECISION_ENV`]. Callers resolve the precision once, typically a
+/// `--precision` flag falling back to [`coord_precision`].
+pub fn with_coord_precision<T>(digits: Option<u32>, f: impl FnOnce() -> T) -> T {
+    struct Restore(Option<Option<u32>>);
+    impl Drop for Restore {
+        fn drop(&mut self) {
+            SCOPED_PRECISION.with(|scoped| scoped.set(self.0));
+        }
+    }
+    let _restore = Restore(SCOPED_PRECISION.with(|scoped| scoped.replace(Some(digits))));
+    f()
+}
+
+/// Precision used by [`serialize_coord`]: the innermost
+/// [`with_coord_precision`] scope, else [`coord_precision`] as read the
+/// first time it was needed.
+fn active_precision() -> Option<u32> {
+    static ENV_PRECISION: OnceLock<Option<u32>> = OnceLock::new();
+    SCOPED_PRECISION
+        .with(Cell::get)
+        .unwrap_or_else(|| *ENV_PRECISION.get_or_init(coord_precision))
+}
+
+/// `value` rounded half away from zero to `digits` decimal places.
+pub fn round_coord(value: f64, digits: u32) -> f64 {
+    let scale = 10f64.powi(digits as i32);
+    let rounded = (value * scale).round() / scale;
+    if rounded.is_finite() {
+        rounded
+    } else {
+        value
+    }
+}
+
+/// `serialize_with` for `f64` coordinates, rounded to the active precision
+/// (see [`with_coord_precision`]).
+pub fn serialize_coord<S: Serializer>(value: &f64, serializer: S) -> Result<S::Ok, S::Error> {
+    match active_precision() {
+        Some(digits) => serializer.serialize_f64(round_coord(*value, digits)),
+        None => serializer.serialize_f64(*value),
+    }
+}
+
+/// `serialize_with` for optional coordinates, like [`serialize_coord`].
+pub fn serialize_opt_coord<S: Serializer>(
+    value: &Option<f64>,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    match value {
+        Some(value) => serialize_coord(value, serializer),
+        None => serializer.serialize_none(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{parse_coord_precision, round_coord, with_coord_precision};
+    use crate::types::GeocodeResult;
+
+    #[test]
+    fn rounds_to_requested_places() {
+        assert_eq!(round_coord(47.606209123456789, 6), 47.606209);
+        assert_eq!(round_coord(-122.33207012345, 4), -122.3321);
+        assert_eq!(round_coord(47.5, 0), 48.0);
+        assert_eq!(parse_coord_precision(" 6 ").unwrap(), 6);
+        assert!(parse_coord_precision("16").is_err());
+        assert!(parse_coord_precision("-1").is_err());
+        assert!(parse_coord_precision("six").is_err());
+    }
+
+    #[test]
+    fn serialization_uses_the_scoped_precision() {
+        let result = GeocodeResult {
+            address: "400 Broad St, Seattle, WA".to_string(),
+            lat: 47.620_422_123_456_78,
+            lon: -122.349_358_987_654_32,
+            source: "cache".to_string(),
+        };
+        let serialize = || serde_json::to_value(&result).expect("serialize");
+
+        let rounded = with_coord_precision(Some(6), serialize);
+        assert_eq!(rounded["lat"], serde_json::json!(47.620422));
+        assert_eq!(rounded["lon"], serde_json::json!(-122.349359));
+
+        // An inner scope wins, including asking for more digits or full
+        // precision, and the outer one is restored afterwards.
+        with_coord_precision(Some(2), || {
+            let finer = with_coord_precision(Some(4), serialize);
+            assert_eq!(finer["lat"], serde_json::json!(47.6204));
+            let full = with_coord_precision(None, serialize);
+            assert_eq!(full["lat"], serde_json::json!(47.620_422_123_456_78));
+            assert_eq!(serialize()["lat"], serde_json::json!(47.62));
+        });
+    }
+}
@@ -0,0 +1,83 @@
+//! Sync-over-async bridge for the blocking geocode entry points.
+//!
+//! The HTTP providers are async, but `geocode_batch` and friends are called
+//! from synchronous code (the CLI executor, Tauri commands). Outside a Tokio
+//! runtime they run on one lazily-built runtime shared by every call, rather
+//! than building and tearing one down per batch.
+
+use std::future::Future;
+use std::sync::OnceLock;
+
+use tokio::runtime::{Handle, Runtime, RuntimeFlavor};
+
+use crate::types::GeoResult;
+
+/// Worker threads of the shared runtime; geocode requests are I/O bound and
+/// issued a batch at a time.
+const WORKER_THREADS: usize = 2;
+
+static RUNTIME: OnceLock<Result<Runtime, String>> = OnceLock::new();
+
+/// The shared runtime, built on first use. A build failure is kept and
+/// reported to every caller.
+fn shared_runtime() -> GeoResult<&'static Runtime> {
+    RUNTIME
+        .get_or_init(|| {
+            tokio::runtime::Builder::new_multi_thread()
+                .worker_threads(WORKER_THREADS)
+                .thread_name("spatia-geocode")
+                .enable_all()
+                .build()
+                .map_err(|err| err.to_string())
+        })
+        .as_ref()
+        .map_err(|err| format!("failed to start the geocode runtime: {err}").into())
+}
+
+/// Run `future` to completion from synchronous code.
+///
+/// Inside a multi-threaded runtime the current worker blocks in place;
+/// outside any runtime the shared runtime drives it. A current-thread runtime
+/// cannot block in place, so that case is an error instead of a panic.
+pub(crate) fn block_on<F, T>(future: F) -> GeoResult<T>
+where
+    F: Future<Output = GeoResult<T>>,
+{
+    match Handle::try_current() {
+        Ok(handle) if handle.runtime_flavor() == RuntimeFlavor::MultiThread => {
+            tokio::task::block_in_place(|| handle.block_on(future))
+        }
+        Ok(_) => Err(
+            "synchronous geocoding cannot run inside a current-thread Tokio runtime; \
+             call it from spawn_blocking or a multi-threaded runtime"
+                .into(),
+        ),
+        Err(_) => shared_runtime()?.block_on(future),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::block_on;
+
+    #[test]
+    fn bridge_reuses_the_shared_runtime_across_calls() {
+        // A task parked on the runtime by the first call is still alive for
+        // the second; a per-call runtime would have cancelled it on drop.
+        let parked =
+            block_on(async { Ok(tokio::spawn(std::future::pending::<()>())) }).expect("first call");
+        let alive = block_on(async move {
+            let alive = !parked.is_finished();
+            parked.abort();
+            Ok(alive)
+        })
+        .expect("second call");
+        assert!(alive);
+    }
+
+    #[tokio::test]
+    async fn bridge_errors_instead_of_panicking_on_current_thread_runtime() {
+        let err = block_on(async { Ok(()) }).expect_err("current-thread runtime");
+        assert!(err.to_string().contains("current-thread"));
+    }
+}
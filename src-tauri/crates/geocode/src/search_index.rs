@@ -55,7 +55,7 @@ pub fn build_index(
     lookup_table: &str,
     index_dir: &Path,
 ) -> GeoResult<usize> {
-    crate::identifiers::validate_table_name(lookup_table)?;
+    crate::identifiers::validate_qualified_table_name(lookup_table)?;
 
     // Clean up any existing index
     if index_dir.exists() {
@@ -109,6 +109,49 @@ pub fn normalize_address(value: &str) -> String {
     out.trim().to_string()
 }
 
+/// Canonical spelling of an address for cache lookups and provider calls.
+///
+/// Whitespace is collapsed and, in the street line (text before the first
+/// comma), USPS-style street-type, directional and unit abbreviations are
+/// spelled out: "123 N Main St." becomes "123 North Main Street". Later
+/// comma-separated parts are only whitespace-collapsed, and a state code
+/// directly before a zip ("Hartford CT 06103") is left alone, so state codes
+/// that double as abbreviations (CT, FL, NE) survive.
+pub fn canonicalize_address(address: &str) -> String {
+    let mut parts = address.split(',');
+    let street_words: Vec<&str> = parts.next().unwrap_or_default().split_whitespace().collect();
+
+    let mut street = Vec::with_capacity(street_words.len());
+    for (index, word) in street_words.iter().enumerate() {
+        let lower = word.trim_end_matches('.').to_ascii_lowercase();
+        let expanded = expand_abbreviation(&lower);
+        let before_zip = street_words
+            .get(index + 1)
+            .is_some_and(|next| ZIP_RE.is_match(next));
+        if expanded == lower || (before_zip && US_STATE_ABBREVS.contains(&lower.as_str())) {
+            street.push(word.to_string());
+        } else {
+            street.push(capitalize(expanded));
+        }
+    }
+
+    let mut canonical = vec![street.join(" ")];
+    canonical.extend(
+        parts
+            .map(|part| part.split_whitespace().collect::<Vec<_>>().join(" "))
+            .filter(|part| !part.is_empty()),
+    );
+    canonical.join(", ")
+}
+
+fn capitalize(word: &str) -> String {
+    let mut chars = word.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => String::new(),
+    }
+}
+
 pub fn tokenize_address(value: &str) -> Vec<String> {
     normalize_address(value)
         .split_whitespace()
@@ -182,6 +225,21 @@ pub fn is_noise_token(token: &str) -> bool {
     US_STATE_ABBREVS.contains(&token) || matches!(token, "us" | "usa")
 }
 
+/// Levenshtein distance over chars, used for "did you mean" suggestions.
+pub fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut prev: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.chars().enumerate() {
+        let mut current = vec![i + 1; b.len() + 1];
+        for (j, cb) in b.iter().enumerate() {
+            let substitution = prev[j] + usize::from(ca != *cb);
+            current[j + 1] = substitution.min(prev[j + 1] + 1).min(current[j] + 1);
+        }
+        prev = current;
+    }
+    prev[b.len()]
+}
+
 /// Pre-process an address string for Tantivy indexing/querying:
 /// normalize, expand abbreviations, remove noise tokens.
 pub fn preprocess_address(address: &str) -> String {
@@ -1,5 +1,7 @@
 use serde::{Deserialize, Serialize};
 
+use crate::precision::serialize_coord;
+
 /// Crate-level result type.
 pub type GeoResult<T> = Result<T, Box<dyn std::error::Error + Send + Sync>>;
 
@@ -7,7 +9,9 @@ pub type GeoResult<T> = Result<T, Box<dyn std::error::Error + Send + Sync>>;
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct GeocodeResult {
     pub address: String,
+    #[serde(serialize_with = "serialize_coord")]
     pub lat: f64,
+    #[serde(serialize_with = "serialize_coord")]
     pub lon: f64,
     pub source: String,
 }
@@ -16,7 +20,9 @@ pub struct GeocodeResult {
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct GeocodeBatchResult {
     pub address: String,
+    #[serde(serialize_with = "serialize_coord")]
     pub lat: f64,
+    #[serde(serialize_with = "serialize_coord")]
     pub lon: f64,
     pub source: String,
     pub confidence: f64,
@@ -37,6 +43,114 @@ pub struct GeocodeStats {
     pub local_fuzzy: usize,
     pub api_resolved: usize,
     pub unresolved: usize,
+    /// Why individual addresses failed at the HTTP fallback, when the service
+    /// reported anything. Addresses not listed here were never sent.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub failures: Vec<GeocodeFailure>,
+}
+
+/// Compact QA view of a batch: how many addresses resolved and which stage
+/// of the pipeline resolved them.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct GeocodeSummary {
+    pub total: usize,
+    pub resolved: usize,
+    pub unresolved: usize,
+    pub by_source: GeocodeSourceCounts,
+}
+
+/// Per-stage resolution counts. `api` covers Geocodio and Nominatim.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct GeocodeSourceCounts {
+    pub cache: usize,
+    pub overture_exact: usize,
+    pub local_fuzzy: usize,
+    pub api: usize,
+}
+
+impl From<&GeocodeStats> for GeocodeSummary {
+    fn from(stats: &GeocodeStats) -> Self {
+        Self {
+            total: stats.total,
+            resolved: stats.geocoded,
+            unresolved: stats.unresolved,
+            by_source: GeocodeSourceCounts {
+                cache: stats.cache_hits,
+                overture_exact: stats.overture_exact,
+                local_fuzzy: stats.local_fuzzy,
+                api: stats.api_resolved,
+            },
+        }
+    }
+}
+
+/// A batch split by outcome. Unresolved addresses never appear in batch
+/// results, so they are recovered by comparing the inputs against the
+/// addresses that came back.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct GeocodePartition {
+    pub resolved_count: usize,
+    pub unresolved_count: usize,
+    pub resolved: Vec<GeocodeBatchResult>,
+    /// Inputs with no result, in input order and as the caller spelled them.
+    pub unresolved: Vec<String>,
+}
+
+impl GeocodePartition {
+    /// Drop the resolved rows but keep both counts, for re-processing only
+    /// the addresses that failed.
+    pub fn unresolved_only(self) -> Self {
+        Self {
+            resolved: Vec::new(),
+            ..self
+        }
+    }
+}
+
+/// An address the HTTP geocoder was asked about but did not resolve.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct GeocodeFailure {
+    pub address: String,
+    /// Machine-readable reason, e.g. `"no_match"`.
+    pub status: String,
+    /// Error or warning text returned by the service, if any.
+    pub error: Option<String>,
+}
+
+/// One geocode cache row as written by `export_cache` and read back by
+/// `import_cache`.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct CacheEntry {
+    pub address: String,
+    pub lat: f64,
+    pub lon: f64,
+    pub source: String,
+    /// Set on reverse-geocode entries only.
+    #[serde(default)]
+    pub matched_address: Option<String>,
+    /// Original cache timestamp; imports without one are stamped with now.
+    #[serde(default)]
+    pub cached_at: Option<String>,
+}
+
+/// Outcome of `import_cache`.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct CacheImportSummary {
+    pub imported: usize,
+    /// Rows dropped for unparseable or off-globe coordinates.
+    pub skipped: usize,
+}
+
+/// Outcome of `refresh_stale_cache`.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct CacheRefreshSummary {
+    /// Forward entries older than `max_age_days` when the refresh started.
+    pub stale: usize,
+    /// Stale entries re-geocoded and stored with a new `cached_at`.
+    pub refreshed: usize,
+    /// Stale entries that no provider resolved; their old rows are kept.
+    pub kept: usize,
+    pub max_age_days: u32,
 }
 
 /// Progress update emitted during geocoding (especially the Nominatim phase).
@@ -1,6 +1,6 @@
 use spatia_geocode::{
-    cache_lookup, cache_store, ensure_cache_table, geocode_batch,
-    geocode_batch_with_components, normalize_address, score_candidate, tokenize_address,
+    cache_lookup, cache_store, ensure_cache_table, export_cache, geocode_batch, import_cache,
+    geocode_batch_with_components, normalize_address, canonicalize_address, score_candidate, tokenize_address,
     components_from_columns, components_from_string, extract_zip,
     GeocodeBatchResult, GeocodeResult,
 };
@@ -42,7 +42,7 @@ fn ingest_test_csv(conn: &Connection) {
 }
 
 /// Pre-populate the geocode cache with known results so tests don't need API keys.
-fn seed_cache(conn: &Connection) {
+fn seed_cache(conn: &mut Connection) {
     let records = vec![
         GeocodeResult {
             address: "85 Pike St, Seattle, WA 98101".to_string(),
@@ -117,6 +117,27 @@ fn normalize_address_empty_input() {
     assert_eq!(normalize_address(""), "");
 }
 
+#[test]
+fn canonicalize_address_expands_street_abbreviations() {
+    assert_eq!(canonicalize_address("123 N Main St"), "123 North Main Street");
+    assert_eq!(canonicalize_address("  123   North Main  Street "), "123 North Main Street");
+    assert_eq!(canonicalize_address("9 sw pine ave. apt 4"), "9 Southwest pine Avenue Apartment 4");
+    assert_eq!(canonicalize_address("1 Elm Blvd Ste 200"), "1 Elm Boulevard Suite 200");
+}
+
+#[test]
+fn canonicalize_address_leaves_city_and_state_alone() {
+    assert_eq!(
+        canonicalize_address("12 Oak Ct ,  Hartford ,CT 06103"),
+        "12 Oak Court, Hartford, CT 06103"
+    );
+    assert_eq!(
+        canonicalize_address("500 E Kennedy Blvd Tampa FL 33602"),
+        "500 East Kennedy Boulevard Tampa FL 33602"
+    );
+    assert_eq!(canonicalize_address(""), "");
+}
+
 #[test]
 fn tokenize_empty_string_returns_empty() {
     let tokens = tokenize_address("");
@@ -181,14 +202,14 @@ fn score_candidate_prefix_match_boosts_score() {
 
 #[test]
 fn cache_handles_special_characters_in_addresses() {
-    let conn = Connection::open_in_memory().expect("open");
+    let mut conn = Connection::open_in_memory().expect("open");
     let records = vec![GeocodeResult {
         address: "123 O'Brien & Sons Rd.".to_string(),
         lat: 47.6090,
         lon: -122.3350,
         source: "geocodio".to_string(),
     }];
-    cache_store(&conn, &records, "geocodio").expect("store");
+    cache_store(&mut conn, &records, "geocodio").expect("store");
 
     let (hits, misses) =
         cache_lookup(&conn, &["123 O'Brien & Sons Rd.".to_string()]).expect("lookup");
@@ -200,14 +221,14 @@ fn cache_handles_special_characters_in_addresses() {
 
 #[test]
 fn cache_handles_unicode_addresses() {
-    let conn = Connection::open_in_memory().expect("open");
+    let mut conn = Connection::open_in_memory().expect("open");
     let records = vec![GeocodeResult {
         address: "123 Nono St".to_string(),
         lat: 47.6100,
         lon: -122.3400,
         source: "geocodio".to_string(),
     }];
-    cache_store(&conn, &records, "geocodio").expect("store");
+    cache_store(&mut conn, &records, "geocodio").expect("store");
 
     let (hits, misses) =
         cache_lookup(&conn, &["123 Nono St".to_string()]).expect("lookup");
@@ -238,7 +259,7 @@ fn cache_lookup_returns_miss_for_unknown_address() {
 
 #[test]
 fn cache_store_multiple_records_and_retrieve_all() {
-    let conn = Connection::open_in_memory().expect("open");
+    let mut conn = Connection::open_in_memory().expect("open");
     let records = vec![
         GeocodeResult {
             address: "addr A".to_string(),
@@ -253,7 +274,7 @@ fn cache_store_multiple_records_and_retrieve_all() {
             source: "geocodio".to_string(),
         },
     ];
-    cache_store(&conn, &records, "geocodio").expect("store");
+    cache_store(&mut conn, &records, "geocodio").expect("store");
 
     let (hits, misses) =
         cache_lookup(&conn, &["addr A".to_string(), "addr B".to_string()]).expect("lookup");
@@ -261,6 +282,94 @@ fn cache_store_multiple_records_and_retrieve_all() {
     assert!(misses.is_empty());
 }
 
+// ---- Cache export/import tests ----
+
+fn assert_cache_round_trip(extension: &str) {
+    let (src_path, mut src) = temp_db();
+    let records = vec![
+        GeocodeResult {
+            address: "123 Main St, Springfield, IL".to_string(),
+            lat: 39.7817,
+            lon: -89.6501,
+            source: "geocodio".to_string(),
+        },
+        GeocodeResult {
+            address: "O'Brien's Pub, 1 Main St".to_string(),
+            lat: -33.8688,
+            lon: 151.2093,
+            source: "overture".to_string(),
+        },
+    ];
+    cache_store(&mut src, &records, "geocodio").expect("seed");
+
+    let file = format!("/tmp/spatia_geocode_cache_export_{}.{extension}", unique_suffix());
+    assert_eq!(export_cache(&src, &file).expect("export"), 2);
+
+    let (dst_path, dst) = temp_db();
+    let summary = import_cache(&dst, &file).expect("import");
+    assert_eq!(summary.imported, 2);
+    assert_eq!(summary.skipped, 0);
+
+    let addresses: Vec<String> = records.iter().map(|r| r.address.clone()).collect();
+    let (hits, misses) = cache_lookup(&dst, &addresses).expect("lookup");
+    assert!(misses.is_empty());
+    for record in &records {
+        let hit = hits.iter().find(|h| h.address == record.address).expect("hit");
+        assert!((hit.lat - record.lat).abs() < 1e-4);
+        assert!((hit.lon - record.lon).abs() < 1e-4);
+        assert_eq!(hit.source, "geocodio");
+    }
+
+    let _ = std::fs::remove_file(&file);
+    cleanup(&src_path);
+    cleanup(&dst_path);
+}
+
+#[test]
+fn cache_export_import_round_trips_csv() {
+    assert_cache_round_trip("csv");
+}
+
+#[test]
+fn cache_export_import_round_trips_json() {
+    assert_cache_round_trip("json");
+}
+
+#[test]
+fn cache_import_skips_out_of_range_rows() {
+    let file = format!("/tmp/spatia_geocode_cache_import_{}.csv", unique_suffix());
+    std::fs::write(
+        &file,
+        "address,lat,lon,source\n\
+         good,47.6,-122.3,geocodio\n\
+         off globe,123.0,10.0,geocodio\n\
+         not a number,abc,10.0,geocodio\n",
+    )
+    .expect("write");
+
+    let (path, conn) = temp_db();
+    let summary = import_cache(&conn, &file).expect("import");
+    assert_eq!(summary.imported, 1);
+    assert_eq!(summary.skipped, 2);
+
+    let (hits, misses) =
+        cache_lookup(&conn, &["good".to_string(), "off globe".to_string()]).expect("lookup");
+    assert_eq!(hits.len(), 1);
+    assert_eq!(misses, vec!["off globe".to_string()]);
+
+    let _ = std::fs::remove_file(&file);
+    cleanup(&path);
+}
+
+#[test]
+fn cache_export_rejects_unknown_extension() {
+    let (path, conn) = temp_db();
+    ensure_cache_table(&conn).expect("ensure");
+    let err = export_cache(&conn, "/tmp/cache.parquet").expect_err("should fail");
+    assert!(err.to_string().contains("expected a .csv or .json extension"));
+    cleanup(&path);
+}
+
 // ---- End-to-end geocode_batch tests ----
 
 #[test]
@@ -273,7 +382,7 @@ fn geocode_batch_with_empty_addresses_returns_empty() {
 
 #[test]
 fn geocode_batch_deduplicates_cached_results() {
-    let (path, conn) = temp_db();
+    let (path, mut conn) = temp_db();
     // Pre-populate cache
     let records = vec![GeocodeResult {
         address: "85 Pike St".to_string(),
@@ -281,7 +390,7 @@ fn geocode_batch_deduplicates_cached_results() {
         lon: -122.3404,
         source: "geocodio".to_string(),
     }];
-    cache_store(&conn, &records, "geocodio").expect("seed");
+    cache_store(&mut conn, &records, "geocodio").expect("seed");
     drop(conn);
 
     // Pass the same address twice
@@ -300,7 +409,7 @@ fn geocode_batch_deduplicates_cached_results() {
 
 #[test]
 fn geocode_batch_preserves_input_order() {
-    let (path, conn) = temp_db();
+    let (path, mut conn) = temp_db();
     let records = vec![
         GeocodeResult {
             address: "addr alpha".to_string(),
@@ -321,7 +430,7 @@ fn geocode_batch_preserves_input_order() {
             source: "test".to_string(),
         },
     ];
-    cache_store(&conn, &records, "test").expect("seed");
+    cache_store(&mut conn, &records, "test").expect("seed");
     drop(conn);
 
     let (results, _stats) = geocode_batch(
@@ -343,7 +452,7 @@ fn geocode_batch_preserves_input_order() {
 
 #[test]
 fn geocode_batch_returns_only_resolved_addresses() {
-    let (path, conn) = temp_db();
+    let (path, mut conn) = temp_db();
     // Cache only one of two addresses
     let records = vec![GeocodeResult {
         address: "valid address".to_string(),
@@ -351,7 +460,7 @@ fn geocode_batch_returns_only_resolved_addresses() {
         lon: 2.0,
         source: "test".to_string(),
     }];
-    cache_store(&conn, &records, "test").expect("seed");
+    cache_store(&mut conn, &records, "test").expect("seed");
     drop(conn);
 
     // Only pass the cached address -- unresolved addresses without API key would error
@@ -365,14 +474,14 @@ fn geocode_batch_returns_only_resolved_addresses() {
 
 #[test]
 fn geocode_batch_returns_cached_confidence() {
-    let (path, conn) = temp_db();
+    let (path, mut conn) = temp_db();
     let records = vec![GeocodeResult {
         address: "cached addr".to_string(),
         lat: 1.5,
         lon: 2.5,
         source: "geocodio".to_string(),
     }];
-    cache_store(&conn, &records, "geocodio").expect("seed");
+    cache_store(&mut conn, &records, "geocodio").expect("seed");
     drop(conn);
 
     let (results, _stats) = geocode_batch(&path, &["cached addr".to_string()]).expect("batch");
@@ -680,7 +789,7 @@ fn geocode_empty_results_does_not_error() {
 
 #[test]
 fn end_to_end_ingest_and_geocode_with_seeded_cache() {
-    let (path, conn) = temp_db();
+    let (path, mut conn) = temp_db();
 
     // Ingest the test CSV
     ingest_test_csv(&conn);
@@ -694,7 +803,7 @@ fn end_to_end_ingest_and_geocode_with_seeded_cache() {
     assert_eq!(count, 10, "CSV should have 10 rows");
 
     // Seed cache with known addresses
-    seed_cache(&conn);
+    seed_cache(&mut conn);
     drop(conn); // geocode_batch opens its own connection
 
     // Geocode a subset of addresses from the CSV
@@ -811,14 +920,14 @@ fn overture_cache_cached_postcodes_empty() {
 
 #[test]
 fn geocode_batch_with_components_uses_cache() {
-    let (path, conn) = temp_db();
+    let (path, mut conn) = temp_db();
     let records = vec![GeocodeResult {
         address: "123 Main St, Tampa, FL, 33603".to_string(),
         lat: 27.9506,
         lon: -82.4572,
         source: "geocodio".to_string(),
     }];
-    cache_store(&conn, &records, "geocodio").expect("seed");
+    cache_store(&mut conn, &records, "geocodio").expect("seed");
     drop(conn);
 
     let components = vec![components_from_columns("123 Main St", Some("Tampa"), Some("FL"), Some("33603"))];
@@ -840,7 +949,7 @@ fn geocode_batch_with_components_empty_input() {
 
 #[test]
 fn geocode_batch_with_components_stats_tracking() {
-    let (path, conn) = temp_db();
+    let (path, mut conn) = temp_db();
     let records = vec![
         GeocodeResult {
             address: "addr A".to_string(),
@@ -855,7 +964,7 @@ fn geocode_batch_with_components_stats_tracking() {
             source: "geocodio".to_string(),
         },
     ];
-    cache_store(&conn, &records, "geocodio").expect("seed");
+    cache_store(&mut conn, &records, "geocodio").expect("seed");
     drop(conn);
 
     let components = vec![
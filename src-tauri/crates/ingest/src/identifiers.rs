@@ -14,6 +14,26 @@ pub fn validate_table_name(table_name: &str) -> IngestResult<()> {
     Ok(())
 }
 
+/// Turn arbitrary text (e.g. a file stem) into a lowercase identifier
+/// fragment: runs of other characters become a single `_`, and leading or
+/// trailing underscores are dropped. Returns `"file"` if nothing is left.
+pub fn sanitize_identifier(value: &str) -> String {
+    let mut out = String::with_capacity(value.len());
+    for ch in value.chars() {
+        if ch.is_ascii_alphanumeric() {
+            out.push(ch.to_ascii_lowercase());
+        } else if !out.ends_with('_') {
+            out.push('_');
+        }
+    }
+    let trimmed = out.trim_matches('_');
+    if trimmed.is_empty() {
+        "file".to_string()
+    } else {
+        trimmed.to_string()
+    }
+}
+
 fn is_ident_start(value: char) -> bool {
     value == '_' || value.is_ascii_alphabetic()
 }
@@ -21,3 +41,21 @@ fn is_ident_start(value: char) -> bool {
 fn is_ident_continue(value: char) -> bool {
     is_ident_start(value) || value.is_ascii_digit()
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{sanitize_identifier, validate_table_name};
+
+    #[test]
+    fn sanitize_identifier_collapses_separators() {
+        assert_eq!(sanitize_identifier("Sales Q1-2024 (final)"), "sales_q1_2024_final");
+        assert_eq!(sanitize_identifier("--"), "file");
+        assert_eq!(sanitize_identifier("2024"), "2024");
+    }
+
+    #[test]
+    fn sanitized_fragment_is_valid_after_prefix() {
+        let table = format!("imports_{}", sanitize_identifier("été 2024.v2"));
+        assert!(validate_table_name(&table).is_ok(), "{table}");
+    }
+}
@@ -1,12 +1,25 @@
 use std::path::Path;
 
-use duckdb::Connection;
+use duckdb::types::Value;
+use duckdb::{appender_params_from_iter, Connection};
+use spatia_geocode::{load_extension, open_connection};
 
-use crate::identifiers::validate_table_name;
+use crate::identifiers::{sanitize_identifier, validate_table_name};
 use crate::IngestResult;
 
 const RAW_STAGING_TABLE: &str = "raw_staging";
 
+/// CSVs at least this large are streamed by [`ingest_csv_with_row_progress`];
+/// smaller files keep the single `CREATE TABLE AS` load, which is faster.
+const STREAMED_INGEST_MIN_BYTES: u64 = 256 * 1024 * 1024;
+
+/// Rows appended between appender flushes and progress callbacks.
+const STREAMED_INGEST_CHUNK_ROWS: u64 = 100_000;
+
+/// Source file size, mtime and load options of each table loaded with
+/// [`record_ingest_source`], so an unchanged CSV need not be loaded again.
+pub const INGEST_PROVENANCE_TABLE: &str = "ingest_provenance";
+
 /// Supported spatial file extensions (case-insensitive check).
 const SPATIAL_EXTENSIONS: &[&str] = &["geojson", "json", "shp", "gpkg", "fgb"];
 
@@ -19,66 +32,607 @@ pub fn is_spatial_file(file_path: &str) -> bool {
         .unwrap_or(false)
 }
 
+/// DuckDB type names accepted for explicit column type overrides.
+const ALLOWED_COLUMN_TYPES: &[&str] = &[
+    "VARCHAR", "TEXT", "BOOLEAN", "TINYINT", "SMALLINT", "INTEGER", "BIGINT", "HUGEINT",
+    "FLOAT", "REAL", "DOUBLE", "DECIMAL", "DATE", "TIME", "TIMESTAMP", "UUID",
+];
+
 pub fn ingest_csv(db_path: &str, csv_path: &str) -> IngestResult<()> {
-    let conn = Connection::open(db_path)?;
-    ensure_spatial_extension(&conn)?;
-    load_csv_to_table(&conn, csv_path, RAW_STAGING_TABLE, true)?;
+    ingest_csv_with_types(db_path, csv_path, &[])
+}
+
+/// Like [`ingest_csv`], forcing the listed `(column, TYPE)` pairs instead of
+/// relying on `read_csv_auto` inference (e.g. keep ZIP codes as VARCHAR).
+pub fn ingest_csv_with_types(
+    db_path: &str,
+    csv_path: &str,
+    column_types: &[(String, String)],
+) -> IngestResult<()> {
+    let types_sql = column_types_sql(column_types)?;
+    let conn = open_connection(db_path)?;
+    load_extension(&conn, "spatial")?;
+    load_csv_to_table(&conn, csv_path, RAW_STAGING_TABLE, true, types_sql.as_deref(), false)?;
     Ok(())
 }
 
 pub fn ingest_csv_to_table(db_path: &str, csv_path: &str, table_name: &str) -> IngestResult<()> {
+    ingest_csv_to_table_with_types(db_path, csv_path, table_name, &[])
+}
+
+/// Like [`ingest_csv_to_table`], forcing the listed `(column, TYPE)` pairs.
+pub fn ingest_csv_to_table_with_types(
+    db_path: &str,
+    csv_path: &str,
+    table_name: &str,
+    column_types: &[(String, String)],
+) -> IngestResult<()> {
+    validate_table_name(table_name)?;
+    let types_sql = column_types_sql(column_types)?;
+    let conn = open_connection(db_path)?;
+    load_extension(&conn, "spatial")?;
+    load_csv_to_table(&conn, csv_path, table_name, false, types_sql.as_deref(), false)?;
+    Ok(())
+}
+
+/// Outcome of [`ingest_csv_with_text_fallback`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct CsvIngestOutcome {
+    pub table: String,
+    /// `true` when type inference failed and the CSV was reloaded with every
+    /// column (except explicit overrides) as VARCHAR.
+    pub loaded_as_text: bool,
+    pub warnings: Vec<String>,
+}
+
+/// Load options that change what an ingest produces from the same CSV.
+/// [`record_ingest_source`] stores them and [`unchanged_ingest_row_count`]
+/// only matches a record made with equal options.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct IngestOptions {
+    /// `--type column=TYPE` overrides.
+    pub column_types: Vec<(String, String)>,
+    /// `--wkt` column parsed into `geometry` after loading.
+    pub wkt_column: Option<String>,
+    pub text_fallback: bool,
+}
+
+impl IngestOptions {
+    /// Canonical text form: column names (the WKT column too) lowercased,
+    /// types uppercased and overrides sorted, so `--type b=int --type A=text`
+    /// and `--type a=TEXT --type B=INT` compare equal.
+    fn normalized(&self) -> String {
+        let mut types: Vec<String> = self
+            .column_types
+            .iter()
+            .map(|(column, ty)| {
+                format!("{}={}", column.trim().to_lowercase(), ty.trim().to_uppercase())
+            })
+            .collect();
+        types.sort();
+        format!(
+            "types={};wkt={};text_fallback={}",
+            types.join(","),
+            self.wkt_column.as_deref().unwrap_or_default().trim().to_lowercase(),
+            self.text_fallback
+        )
+    }
+}
+
+/// Like [`ingest_csv_to_table_with_types`] (or [`ingest_csv_with_types`] when
+/// `table_name` is `None`), but a value that does not fit the type
+/// `read_csv_auto` inferred for its column, such as "N/A" in a numeric column
+/// past the sniffing sample, no longer fails the ingest. The CSV is loaded
+/// again with `all_varchar=true` and the outcome carries a warning naming the
+/// column, so types can be fixed afterwards with the cleaner.
+pub fn ingest_csv_with_text_fallback(
+    db_path: &str,
+    csv_path: &str,
+    table_name: Option<&str>,
+    column_types: &[(String, String)],
+) -> IngestResult<CsvIngestOutcome> {
+    let (table_name, replace) = match table_name {
+        Some(table_name) => {
+            validate_table_name(table_name)?;
+            (table_name, false)
+        }
+        None => (RAW_STAGING_TABLE, true),
+    };
+    let types_sql = column_types_sql(column_types)?;
+    let conn = open_connection(db_path)?;
+    load_extension(&conn, "spatial")?;
+
+    let message = match load_csv_to_table(
+        &conn,
+        csv_path,
+        table_name,
+        replace,
+        types_sql.as_deref(),
+        false,
+    ) {
+        Ok(()) => {
+            return Ok(CsvIngestOutcome {
+                table: table_name.to_string(),
+                loaded_as_text: false,
+                warnings: Vec::new(),
+            });
+        }
+        Err(err) if is_type_conversion_error(&err.to_string()) => err.to_string(),
+        Err(err) => return Err(err),
+    };
+    tracing::warn!(
+        table = %table_name,
+        error = %message,
+        "ingest_csv_with_text_fallback: type inference failed, reloading as text"
+    );
+    load_csv_to_table(&conn, csv_path, table_name, replace, types_sql.as_deref(), true)?;
+    let warning = match converting_column(&message) {
+        Some(column) => format!(
+            "column {column} has values that do not match its inferred type; \
+             all columns were loaded as text"
+        ),
+        None => "type inference failed; all columns were loaded as text".to_string(),
+    };
+    Ok(CsvIngestOutcome {
+        table: table_name.to_string(),
+        loaded_as_text: true,
+        warnings: vec![warning],
+    })
+}
+
+/// Row count of `table_name` (`raw_staging` when `None`) if it was last
+/// loaded from `csv_path` with [`record_ingest_source`] using the same
+/// `options`, and the file's size and modification time have not changed
+/// since. Any difference, a missing record or a missing table returns
+/// `None`, meaning the CSV should be loaded again.
+pub fn unchanged_ingest_row_count(
+    db_path: &str,
+    csv_path: &str,
+    table_name: Option<&str>,
+    options: &IngestOptions,
+) -> IngestResult<Option<i64>> {
+    let table_name = table_name.unwrap_or(RAW_STAGING_TABLE);
+    validate_table_name(table_name)?;
+    let (size, mtime) = source_fingerprint(csv_path)?;
+    let conn = open_connection(db_path)?;
+    if !has_table(&conn, INGEST_PROVENANCE_TABLE)? || !has_table(&conn, table_name)? {
+        return Ok(None);
+    }
+    ensure_provenance_table(&conn)?;
+
+    let matches: i64 = conn.query_row(
+        &format!(
+            "SELECT count(*) FROM {INGEST_PROVENANCE_TABLE} \
+             WHERE lower(table_name) = lower(?) AND source_path = ? AND size_bytes = ? \
+               AND modified_ms = ? AND options = ?"
+        ),
+        duckdb::params![table_name, csv_path, size, mtime, options.normalized()],
+        |row| row.get(0),
+    )?;
+    if matches == 0 {
+        return Ok(None);
+    }
+    let rows: i64 =
+        conn.query_row(&format!("SELECT COUNT(*) FROM {table_name}"), [], |row| row.get(0))?;
+    Ok(Some(rows))
+}
+
+/// Record that `table_name` (`raw_staging` when `None`) now holds `csv_path`
+/// as it is on disk, loaded with `options`, for
+/// [`unchanged_ingest_row_count`]. Call it after a successful load; any
+/// later load into the table drops the record again.
+pub fn record_ingest_source(
+    db_path: &str,
+    csv_path: &str,
+    table_name: Option<&str>,
+    options: &IngestOptions,
+) -> IngestResult<()> {
+    let table_name = table_name.unwrap_or(RAW_STAGING_TABLE);
     validate_table_name(table_name)?;
-    let conn = Connection::open(db_path)?;
-    ensure_spatial_extension(&conn)?;
-    load_csv_to_table(&conn, csv_path, table_name, false)?;
+    let (size, mtime) = source_fingerprint(csv_path)?;
+    let conn = open_connection(db_path)?;
+    ensure_provenance_table(&conn)?;
+    forget_ingest_source(&conn, table_name)?;
+    conn.execute(
+        &format!(
+            "INSERT INTO {INGEST_PROVENANCE_TABLE} \
+             (table_name, source_path, size_bytes, modified_ms, options, ingested_at) \
+             VALUES (?, ?, ?, ?, ?, current_timestamp)"
+        ),
+        duckdb::params![table_name, csv_path, size, mtime, options.normalized()],
+    )?;
     Ok(())
 }
 
+/// Create the provenance table, adding the `options` column to one written
+/// before it existed. Such older records have no options and never match.
+fn ensure_provenance_table(conn: &Connection) -> IngestResult<()> {
+    conn.execute_batch(&format!(
+        "CREATE TABLE IF NOT EXISTS {INGEST_PROVENANCE_TABLE} ( \
+             table_name VARCHAR PRIMARY KEY, \
+             source_path VARCHAR NOT NULL, \
+             size_bytes BIGINT NOT NULL, \
+             modified_ms BIGINT NOT NULL, \
+             options VARCHAR, \
+             ingested_at TIMESTAMP NOT NULL DEFAULT current_timestamp \
+         ); \
+         ALTER TABLE {INGEST_PROVENANCE_TABLE} ADD COLUMN IF NOT EXISTS options VARCHAR;"
+    ))?;
+    Ok(())
+}
+
+/// Drop the provenance record of `table_name`, if any, because its rows were
+/// just replaced. Loads that are not recorded must not leave an old match.
+/// Table names are matched case-insensitively, as DuckDB resolves them.
+fn forget_ingest_source(conn: &Connection, table_name: &str) -> IngestResult<()> {
+    if has_table(conn, INGEST_PROVENANCE_TABLE)? {
+        conn.execute(
+            &format!("DELETE FROM {INGEST_PROVENANCE_TABLE} WHERE lower(table_name) = lower(?)"),
+            [table_name],
+        )?;
+    }
+    Ok(())
+}
+
+/// Size in bytes and modification time (ms since the Unix epoch) of `path`.
+fn source_fingerprint(path: &str) -> IngestResult<(i64, i64)> {
+    let metadata = std::fs::metadata(path)
+        .map_err(|err| format!("failed to read CSV file {path}: {err}"))?;
+    let modified = metadata
+        .modified()
+        .map_err(|err| format!("failed to read modification time of {path}: {err}"))?;
+    let mtime = modified
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|elapsed| elapsed.as_millis() as i64)
+        .unwrap_or_default();
+    Ok((metadata.len() as i64, mtime))
+}
+
+fn has_table(conn: &Connection, table_name: &str) -> IngestResult<bool> {
+    let count: i64 = conn.query_row(
+        "SELECT COUNT(*) FROM information_schema.tables \
+         WHERE table_schema = 'main' AND table_name = ?",
+        [table_name],
+        |row| row.get(0),
+    )?;
+    Ok(count > 0)
+}
+
+/// Whether a `read_csv` error comes from a value that could not be cast to
+/// its column's type, as opposed to a missing file or a malformed CSV.
+fn is_type_conversion_error(message: &str) -> bool {
+    message.contains("Could not convert") || message.contains("Error when converting column")
+}
+
+/// Column named by DuckDB's `Error when converting column "name"` message.
+fn converting_column(message: &str) -> Option<String> {
+    let rest = message.split_once("converting column ")?.1;
+    let quote = rest.chars().next().filter(|c| *c == '"' || *c == '\'')?;
+    let rest = &rest[1..];
+    rest.find(quote).map(|end| rest[..end].to_string())
+}
+
+/// Load a CSV into `table_name` (or `raw_staging`, replacing it, when `None`),
+/// reporting the number of rows loaded so far through `on_progress`. Returns
+/// the final row count.
+///
+/// Files of at least 256 MiB are streamed: the table is created from a
+/// `LIMIT 0` read, then rows are appended in chunks of 100k with a callback
+/// after each, so memory stays bounded and the caller sees real progress.
+/// Smaller files use the same single-statement load as
+/// [`ingest_csv_to_table_with_types`] and report once at the end.
+pub fn ingest_csv_with_row_progress<F>(
+    db_path: &str,
+    csv_path: &str,
+    table_name: Option<&str>,
+    column_types: &[(String, String)],
+    on_progress: F,
+) -> IngestResult<u64>
+where
+    F: FnMut(u64),
+{
+    load_csv_with_row_progress(
+        db_path,
+        csv_path,
+        table_name,
+        column_types,
+        STREAMED_INGEST_MIN_BYTES,
+        on_progress,
+    )
+}
+
+/// Like [`ingest_csv_with_row_progress`], but streams the file whatever its
+/// size. Used to measure where streaming starts to pay off.
+pub fn ingest_csv_streamed<F>(
+    db_path: &str,
+    csv_path: &str,
+    table_name: Option<&str>,
+    column_types: &[(String, String)],
+    on_progress: F,
+) -> IngestResult<u64>
+where
+    F: FnMut(u64),
+{
+    load_csv_with_row_progress(db_path, csv_path, table_name, column_types, 0, on_progress)
+}
+
+fn load_csv_with_row_progress<F>(
+    db_path: &str,
+    csv_path: &str,
+    table_name: Option<&str>,
+    column_types: &[(String, String)],
+    streamed_min_bytes: u64,
+    mut on_progress: F,
+) -> IngestResult<u64>
+where
+    F: FnMut(u64),
+{
+    let (table_name, replace) = match table_name {
+        Some(table_name) => {
+            validate_table_name(table_name)?;
+            (table_name, false)
+        }
+        None => (RAW_STAGING_TABLE, true),
+    };
+    let types_sql = column_types_sql(column_types)?;
+    let file_size = std::fs::metadata(csv_path)
+        .map_err(|err| format!("failed to read CSV file {csv_path}: {err}"))?
+        .len();
+    let conn = open_connection(db_path)?;
+    load_extension(&conn, "spatial")?;
+
+    if file_size >= streamed_min_bytes {
+        return stream_csv_to_table(
+            &conn,
+            csv_path,
+            table_name,
+            replace,
+            types_sql.as_deref(),
+            STREAMED_INGEST_CHUNK_ROWS,
+            on_progress,
+        );
+    }
+    load_csv_to_table(&conn, csv_path, table_name, replace, types_sql.as_deref(), false)?;
+    let rows: i64 =
+        conn.query_row(&format!("SELECT COUNT(*) FROM {table_name}"), [], |row| row.get(0))?;
+    on_progress(rows as u64);
+    Ok(rows as u64)
+}
+
+/// Validate a type override against [`ALLOWED_COLUMN_TYPES`], returning the
+/// normalized (uppercase) type. `DECIMAL(p,s)` is accepted with numeric
+/// precision and scale.
+fn normalize_column_type(data_type: &str) -> IngestResult<String> {
+    let upper = data_type.trim().to_ascii_uppercase().replace(' ', "");
+    let (base, args) = match upper.split_once('(') {
+        Some((base, rest)) => (base, rest.strip_suffix(')')),
+        None => (upper.as_str(), Some("")),
+    };
+    let args_ok = match args {
+        Some("") => !upper.contains('('),
+        Some(args) => {
+            base == "DECIMAL"
+                && args
+                    .split(',')
+                    .all(|part| !part.is_empty() && part.chars().all(|c| c.is_ascii_digit()))
+        }
+        None => false,
+    };
+    if !ALLOWED_COLUMN_TYPES.contains(&base) || !args_ok {
+        return Err(format!(
+            "unsupported column type '{data_type}' (allowed: {})",
+            ALLOWED_COLUMN_TYPES.join(", ")
+        )
+        .into());
+    }
+    Ok(upper)
+}
+
+/// Render column type overrides as a DuckDB struct literal for the `types`
+/// option of `read_csv`, or `None` when there are no overrides.
+fn column_types_sql(column_types: &[(String, String)]) -> IngestResult<Option<String>> {
+    if column_types.is_empty() {
+        return Ok(None);
+    }
+    let mut entries = Vec::with_capacity(column_types.len());
+    for (column, data_type) in column_types {
+        if column.trim().is_empty() {
+            return Err("column type override has an empty column name".into());
+        }
+        let data_type = normalize_column_type(data_type)?;
+        entries.push(format!("'{}': '{}'", column.replace('\'', "''"), data_type));
+    }
+    Ok(Some(format!("{{{}}}", entries.join(", "))))
+}
+
+/// Outcome of loading one file matched by [`ingest_glob`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct GlobIngestFile {
+    pub path: String,
+    pub table: String,
+    /// `None` when the file loaded; otherwise why it did not.
+    pub error: Option<String>,
+}
+
+/// Ingest every file matching `pattern` into its own `{table_prefix}_{stem}`
+/// table over a single connection.
+///
+/// Stems are sanitized into identifiers, and a name that collides with an
+/// earlier file in the batch or with an existing table or view gets a numeric
+/// suffix (`_2`, `_3`, ...), so nothing already in the database is replaced.
+/// Spatial files go through `ST_Read`, everything
+/// else through the CSV loader. A file that fails to load is reported in its
+/// entry without stopping the rest.
+pub fn ingest_glob(
+    db_path: &str,
+    pattern: &str,
+    table_prefix: &str,
+) -> IngestResult<Vec<GlobIngestFile>> {
+    validate_table_name(table_prefix)?;
+    let conn = open_connection(db_path)?;
+    load_extension(&conn, "spatial")?;
+
+    let mut stmt = conn.prepare("SELECT file FROM glob(?) ORDER BY file")?;
+    let mut rows = stmt.query([pattern])?;
+    let mut paths = Vec::new();
+    while let Some(row) = rows.next()? {
+        paths.push(row.get::<_, String>(0)?);
+    }
+    if paths.is_empty() {
+        return Err(format!("no files match {pattern}").into());
+    }
+
+    // DuckDB identifiers are case-insensitive, so compare lowercased names.
+    let mut used = std::collections::HashSet::new();
+    let mut existing = conn.prepare(
+        "SELECT table_name FROM information_schema.tables WHERE table_schema = 'main'",
+    )?;
+    let mut rows = existing.query([])?;
+    while let Some(row) = rows.next()? {
+        used.insert(row.get::<_, String>(0)?.to_lowercase());
+    }
+
+    let mut results = Vec::with_capacity(paths.len());
+    for path in paths {
+        let stem = Path::new(&path)
+            .file_stem()
+            .and_then(|stem| stem.to_str())
+            .unwrap_or_default();
+        let base = format!("{table_prefix}_{}", sanitize_identifier(stem));
+        let mut table = base.clone();
+        let mut suffix = 2;
+        while !used.insert(table.to_lowercase()) {
+            table = format!("{base}_{suffix}");
+            suffix += 1;
+        }
+
+        let loaded = if is_spatial_file(&path) {
+            load_spatial_to_table(&conn, &path, &table)
+        } else {
+            load_csv_to_table(&conn, &path, &table, true, None, false)
+        };
+        if let Err(err) = &loaded {
+            tracing::warn!(file = %path, table = %table, error = %err, "ingest_glob: file failed");
+        }
+        results.push(GlobIngestFile {
+            path,
+            table,
+            error: loaded.err().map(|err| err.to_string()),
+        });
+    }
+
+    tracing::info!(
+        pattern = %pattern,
+        files = results.len(),
+        failed = results.iter().filter(|r| r.error.is_some()).count(),
+        "ingest_glob: complete"
+    );
+    Ok(results)
+}
+
 /// Ingest a spatial file (GeoJSON, Shapefile, GeoPackage, FlatGeobuf) into DuckDB
 /// using `ST_Read()` from the spatial extension. The geometry column is stored as
 /// DuckDB's native GEOMETRY type.
 pub fn ingest_spatial_file(db_path: &str, file_path: &str, table_name: &str) -> IngestResult<()> {
     validate_table_name(table_name)?;
-    let conn = Connection::open(db_path)?;
-    ensure_spatial_extension(&conn)?;
+    let conn = open_connection(db_path)?;
+    load_extension(&conn, "spatial")?;
     load_spatial_to_table(&conn, file_path, table_name)?;
     Ok(())
 }
 
-fn ensure_spatial_extension(conn: &Connection) -> IngestResult<()> {
-    conn.execute("INSTALL spatial", [])?;
-    conn.execute("LOAD spatial", [])?;
+/// Add a `geometry` column to `table_name` parsed with `ST_GeomFromText` from
+/// the WKT text in `wkt_column`, for data that carries its own shapes rather
+/// than lat/lon pairs. The WKT column is kept. The column must exist and be
+/// text, the table must not already have a `geometry` column, and one
+/// unparseable row fails the whole update without changing the table.
+pub fn add_geometry_from_wkt(
+    db_path: &str,
+    table_name: &str,
+    wkt_column: &str,
+) -> IngestResult<()> {
+    validate_table_name(table_name)?;
+    let conn = open_connection(db_path)?;
+    load_extension(&conn, "spatial")?;
+
+    match column_type(&conn, table_name, wkt_column)?.as_deref() {
+        None => {
+            return Err(format!("column {wkt_column} not found in table {table_name}").into());
+        }
+        Some("VARCHAR") => {}
+        Some(other) => {
+            return Err(format!(
+                "WKT column {wkt_column} must be text, but {table_name}.{wkt_column} is {other}"
+            )
+            .into());
+        }
+    }
+    if column_type(&conn, table_name, "geometry")?.is_some() {
+        return Err(format!("table {table_name} already has a geometry column").into());
+    }
+
+    let column = format!("\"{}\"", wkt_column.replace('"', "\"\""));
+    conn.execute_batch("BEGIN TRANSACTION")?;
+    let converted = conn.execute_batch(&format!(
+        "ALTER TABLE {table_name} ADD COLUMN geometry GEOMETRY; \
+         UPDATE {table_name} SET geometry = ST_GeomFromText({column});"
+    ));
+    if let Err(err) = converted {
+        conn.execute_batch("ROLLBACK")?;
+        return Err(format!("failed to parse WKT in {table_name}.{wkt_column}: {err}").into());
+    }
+    conn.execute_batch("COMMIT")?;
+
+    tracing::info!(
+        table = %table_name,
+        wkt_column = %wkt_column,
+        "add_geometry_from_wkt: added geometry column"
+    );
     Ok(())
 }
 
+/// DuckDB type of `table_name.column`, or `None` when there is no such column.
+fn column_type(conn: &Connection, table_name: &str, column: &str) -> IngestResult<Option<String>> {
+    let mut stmt = conn.prepare(
+        "SELECT data_type FROM information_schema.columns \
+         WHERE table_schema = 'main' AND table_name = ? AND column_name = ?",
+    )?;
+    let mut rows = stmt.query(duckdb::params![table_name, column])?;
+    match rows.next()? {
+        Some(row) => Ok(Some(row.get(0)?)),
+        None => Ok(None),
+    }
+}
+
 fn load_csv_to_table(
     conn: &Connection,
     csv_path: &str,
     table_name: &str,
     replace: bool,
+    types_sql: Option<&str>,
+    all_varchar: bool,
 ) -> IngestResult<()> {
     let escaped_csv_path = csv_path.replace('\'', "''");
     let create = if replace { "CREATE OR REPLACE TABLE" } else { "CREATE TABLE" };
+    let mut types_option = types_sql
+        .map(|types| format!(", types={types}"))
+        .unwrap_or_default();
+    if all_varchar {
+        types_option.push_str(", all_varchar=true");
+    }
 
     // Try read_csv_auto first; if it produces only 1 column (delimiter
     // mis-detection), fall back to read_csv with explicit comma delimiter
     // and null_padding for ragged rows.
     let auto_sql = format!(
-        "{create} {table} AS SELECT * FROM read_csv_auto('{csv}')",
-        create = create, table = table_name, csv = escaped_csv_path,
+        "{create} {table} AS SELECT * FROM {source}",
+        create = create,
+        table = table_name,
+        source = read_csv_source(&escaped_csv_path, &types_option, false),
     );
     conn.execute(&auto_sql, [])?;
 
-    let col_count: i64 = conn.query_row(
-        &format!(
-            "SELECT COUNT(*) FROM information_schema.columns \
-             WHERE table_schema = 'main' AND table_name = '{}'",
-            table_name.replace('\'', "''")
-        ),
-        [],
-        |row| row.get(0),
-    )?;
-
+    let col_count = table_column_count(conn, table_name)?;
     if col_count <= 1 {
         // read_csv_auto failed to detect delimiter; retry with explicit options
         tracing::warn!(
@@ -87,14 +641,150 @@ fn load_csv_to_table(
             "load_csv_to_table: read_csv_auto produced single column, retrying with explicit delimiter"
         );
         let fallback_sql = format!(
-            "CREATE OR REPLACE TABLE {table} AS SELECT * FROM read_csv('{csv}', \
-             delim=',', header=true, auto_detect=true, null_padding=true)",
-            table = table_name, csv = escaped_csv_path,
+            "CREATE OR REPLACE TABLE {table} AS SELECT * FROM {source}",
+            table = table_name,
+            source = read_csv_source(&escaped_csv_path, &types_option, true),
         );
         conn.execute(&fallback_sql, [])?;
     }
 
-    Ok(())
+    forget_ingest_source(conn, table_name)
+}
+
+/// Like [`load_csv_to_table`], but creates the table from a `LIMIT 0` read
+/// and then appends the rows through a DuckDB appender, flushing and calling
+/// `on_progress` every `chunk_rows` rows and once at the end.
+///
+/// The whole load runs in one transaction: a read or append error rolls it
+/// back, so no partly filled table is left behind (and a replaced
+/// `raw_staging` keeps its previous rows).
+fn stream_csv_to_table<F>(
+    conn: &Connection,
+    csv_path: &str,
+    table_name: &str,
+    replace: bool,
+    types_sql: Option<&str>,
+    chunk_rows: u64,
+    on_progress: F,
+) -> IngestResult<u64>
+where
+    F: FnMut(u64),
+{
+    conn.execute_batch("BEGIN TRANSACTION")?;
+    let streamed =
+        append_csv_rows(conn, csv_path, table_name, replace, types_sql, chunk_rows, on_progress);
+    match streamed {
+        Ok(loaded) => {
+            conn.execute_batch("COMMIT")?;
+            tracing::info!(
+                table = %table_name,
+                file = %csv_path,
+                rows = loaded,
+                "stream_csv_to_table: appended CSV rows"
+            );
+            Ok(loaded)
+        }
+        Err(err) => {
+            conn.execute_batch("ROLLBACK")?;
+            tracing::warn!(
+                table = %table_name,
+                file = %csv_path,
+                error = %err,
+                "stream_csv_to_table: load failed, rolled back"
+            );
+            Err(err)
+        }
+    }
+}
+
+/// Body of [`stream_csv_to_table`], run inside its transaction.
+fn append_csv_rows<F>(
+    conn: &Connection,
+    csv_path: &str,
+    table_name: &str,
+    replace: bool,
+    types_sql: Option<&str>,
+    chunk_rows: u64,
+    mut on_progress: F,
+) -> IngestResult<u64>
+where
+    F: FnMut(u64),
+{
+    let escaped_csv_path = csv_path.replace('\'', "''");
+    let create = if replace { "CREATE OR REPLACE TABLE" } else { "CREATE TABLE" };
+    let types_option = types_sql
+        .map(|types| format!(", types={types}"))
+        .unwrap_or_default();
+
+    let mut source = read_csv_source(&escaped_csv_path, &types_option, false);
+    conn.execute(&format!("{create} {table_name} AS SELECT * FROM {source} LIMIT 0"), [])?;
+    let mut col_count = table_column_count(conn, table_name)?;
+    if col_count <= 1 {
+        tracing::warn!(
+            table = %table_name,
+            auto_col_count = col_count,
+            "stream_csv_to_table: read_csv_auto produced single column, retrying with explicit delimiter"
+        );
+        source = read_csv_source(&escaped_csv_path, &types_option, true);
+        conn.execute(
+            &format!("CREATE OR REPLACE TABLE {table_name} AS SELECT * FROM {source} LIMIT 0"),
+            [],
+        )?;
+        col_count = table_column_count(conn, table_name)?;
+    }
+    let col_count = col_count as usize;
+
+    // Read on a second connection to the same database so appender flushes
+    // do not run while this connection has an open result.
+    let reader = conn.try_clone()?;
+    let mut stmt = reader.prepare(&format!("SELECT * FROM {source}"))?;
+    let mut rows = stmt.query([])?;
+    let mut appender = conn.appender(table_name)?;
+    let mut loaded: u64 = 0;
+    while let Some(row) = rows.next()? {
+        let mut values = Vec::with_capacity(col_count);
+        for index in 0..col_count {
+            values.push(row.get::<_, Value>(index)?);
+        }
+        appender.append_row(appender_params_from_iter(values))?;
+        loaded += 1;
+        if loaded % chunk_rows == 0 {
+            appender.flush()?;
+            on_progress(loaded);
+        }
+    }
+    appender.flush()?;
+    if loaded == 0 || loaded % chunk_rows != 0 {
+        on_progress(loaded);
+    }
+    drop(appender);
+    forget_ingest_source(conn, table_name)?;
+    Ok(loaded)
+}
+
+/// `read_csv_auto(...)`, or with `explicit_delimiter` the comma-delimited,
+/// null-padded `read_csv(...)` used when sniffing finds a single column.
+fn read_csv_source(escaped_csv_path: &str, types_option: &str, explicit_delimiter: bool) -> String {
+    if explicit_delimiter {
+        format!(
+            "read_csv('{escaped_csv_path}', delim=',', header=true, auto_detect=true, \
+             null_padding=true{types_option})"
+        )
+    } else {
+        format!("read_csv_auto('{escaped_csv_path}'{types_option})")
+    }
+}
+
+fn table_column_count(conn: &Connection, table_name: &str) -> IngestResult<i64> {
+    Ok(conn.query_row(
+        &format!(
+            "SELECT COUNT(*) FROM information_schema.columns \
+             WHERE table_schema = 'main' AND table_name = '{}'",
+            table_name.replace('\'', "''")
+        ),
+        [],
+        |row| row.get(0),
+    )?)
 }
 
 fn load_spatial_to_table(
@@ -119,12 +809,17 @@ fn load_spatial_to_table(
         "ingest_spatial_file: loaded spatial file via ST_Read"
     );
 
-    Ok(())
+    forget_ingest_source(conn, table_name)
 }
 
 #[cfg(test)]
 mod tests {
-    use super::{ingest_csv, ingest_csv_to_table, ingest_spatial_file, is_spatial_file};
+    use super::{
+        add_geometry_from_wkt, column_types_sql, converting_column, ingest_csv,
+        ingest_csv_to_table, ingest_csv_to_table_with_types, ingest_csv_with_row_progress,
+        ingest_csv_with_text_fallback, ingest_glob, ingest_spatial_file, is_spatial_file,
+        record_ingest_source, stream_csv_to_table, unchanged_ingest_row_count, IngestOptions,
+    };
     use std::fs;
     use std::io::Write;
     use std::time::{SystemTime, UNIX_EPOCH};
@@ -164,6 +859,292 @@ mod tests {
         cleanup_files(&db_path, &csv_path);
     }
 
+    #[test]
+    fn unchanged_ingest_matches_until_the_file_or_table_changes() {
+        let (db_path, csv_path) = setup_files();
+        let plain = IngestOptions::default();
+        ingest_csv_to_table(&db_path, &csv_path, "places").expect("ingest");
+        assert_eq!(
+            unchanged_ingest_row_count(&db_path, &csv_path, Some("places"), &plain)
+                .expect("check"),
+            None,
+            "loads are only matched once recorded"
+        );
+        record_ingest_source(&db_path, &csv_path, Some("places"), &plain).expect("record");
+        assert_eq!(
+            unchanged_ingest_row_count(&db_path, &csv_path, Some("places"), &plain)
+                .expect("check"),
+            Some(1)
+        );
+        assert_eq!(
+            unchanged_ingest_row_count(&db_path, &csv_path, None, &plain).expect("check"),
+            None
+        );
+
+        let mut file = fs::OpenOptions::new()
+            .append(true)
+            .open(&csv_path)
+            .expect("open csv");
+        writeln!(file, "2,Ferry Building,37.7955,-122.3937").expect("append row");
+        drop(file);
+        assert_eq!(
+            unchanged_ingest_row_count(&db_path, &csv_path, Some("places"), &plain)
+                .expect("check"),
+            None
+        );
+
+        // Any reload drops the record, even when it is not recorded again.
+        ingest_csv(&db_path, &csv_path).expect("ingest staging");
+        record_ingest_source(&db_path, &csv_path, None, &plain).expect("record staging");
+        assert_eq!(
+            unchanged_ingest_row_count(&db_path, &csv_path, None, &plain).expect("check"),
+            Some(2)
+        );
+        ingest_csv(&db_path, &csv_path).expect("reload staging");
+        assert_eq!(
+            unchanged_ingest_row_count(&db_path, &csv_path, None, &plain).expect("check"),
+            None
+        );
+        cleanup_files(&db_path, &csv_path);
+    }
+
+    #[test]
+    fn unchanged_ingest_requires_the_same_options() {
+        let (db_path, csv_path) = setup_files();
+        ingest_csv_to_table(&db_path, &csv_path, "places").expect("ingest");
+        let typed = IngestOptions {
+            column_types: vec![("Name".to_string(), "varchar".to_string())],
+            ..IngestOptions::default()
+        };
+        record_ingest_source(&db_path, &csv_path, Some("places"), &typed).expect("record");
+
+        let reordered = IngestOptions {
+            column_types: vec![("name".to_string(), "VARCHAR".to_string())],
+            ..IngestOptions::default()
+        };
+        assert_eq!(
+            unchanged_ingest_row_count(&db_path, &csv_path, Some("PLACES"), &reordered)
+                .expect("check"),
+            Some(1),
+            "options and table names compare after normalization"
+        );
+        for changed in [
+            IngestOptions::default(),
+            IngestOptions { text_fallback: true, ..typed.clone() },
+            IngestOptions { wkt_column: Some("wkt".to_string()), ..typed.clone() },
+        ] {
+            assert_eq!(
+                unchanged_ingest_row_count(&db_path, &csv_path, Some("places"), &changed)
+                    .expect("check"),
+                None,
+                "{changed:?} must reload"
+            );
+        }
+        cleanup_files(&db_path, &csv_path);
+    }
+
+    #[test]
+    fn streamed_csv_load_reports_progress_per_chunk() {
+        let suffix = unique_suffix();
+        let db_path = format!("/tmp/spatia_ingest_stream_test_{suffix}.duckdb");
+        let csv_path = format!("/tmp/spatia_ingest_stream_test_{suffix}.csv");
+        let mut file = fs::File::create(&csv_path).expect("create csv");
+        writeln!(file, "id,name,zip").expect("write header");
+        for id in 1..=5 {
+            writeln!(file, "{id},place {id},0210{id}").expect("write row");
+        }
+        drop(file);
+
+        let conn = duckdb::Connection::open(&db_path).expect("open db");
+        let types = Some("{'zip': 'VARCHAR'}");
+        let mut progress = Vec::new();
+        let rows = stream_csv_to_table(&conn, &csv_path, "places", false, types, 2, |rows| {
+            progress.push(rows)
+        })
+        .expect("stream csv");
+        assert_eq!(rows, 5);
+        assert_eq!(progress, vec![2, 4, 5]);
+
+        let (count, zip): (i64, String) = conn
+            .query_row("SELECT COUNT(*), max(zip) FROM places", [], |row| {
+                Ok((row.get(0)?, row.get(1)?))
+            })
+            .expect("query places");
+        assert_eq!((count, zip.as_str()), (5, "02105"));
+        drop(conn);
+
+        let mut progress = Vec::new();
+        let rows = ingest_csv_with_row_progress(&db_path, &csv_path, None, &[], |rows| {
+            progress.push(rows)
+        })
+        .expect("ingest with progress");
+        assert_eq!(rows, 5);
+        assert_eq!(progress, vec![5]);
+
+        cleanup_files(&db_path, &csv_path);
+    }
+
+    #[test]
+    fn failed_streamed_load_leaves_no_partial_table() {
+        let suffix = unique_suffix();
+        let db_path = format!("/tmp/spatia_ingest_stream_fail_test_{suffix}.duckdb");
+        let csv_path = format!("/tmp/spatia_ingest_stream_fail_test_{suffix}.csv");
+        let mut file = fs::File::create(&csv_path).expect("create csv");
+        writeln!(file, "id,zip").expect("write header");
+        for id in 1..=5000 {
+            writeln!(file, "{id},{}", 10000 + id).expect("write row");
+        }
+        writeln!(file, "5001,N/A").expect("write bad row");
+        drop(file);
+
+        let conn = duckdb::Connection::open(&db_path).expect("open db");
+        conn.execute_batch("CREATE TABLE raw_staging AS SELECT 1 AS id")
+            .expect("seed staging");
+        let types = Some("{'zip': 'INTEGER'}");
+        stream_csv_to_table(&conn, &csv_path, "places", false, types, 1000, |_| {})
+            .expect_err("N/A is not an INTEGER");
+        stream_csv_to_table(&conn, &csv_path, "raw_staging", true, types, 1000, |_| {})
+            .expect_err("N/A is not an INTEGER");
+
+        let places: i64 = conn
+            .query_row(
+                "SELECT COUNT(*) FROM information_schema.tables WHERE table_name = 'places'",
+                [],
+                |row| row.get(0),
+            )
+            .expect("count tables");
+        assert_eq!(places, 0, "a failed load must not leave a partial table");
+        let staged: i64 = conn
+            .query_row("SELECT COUNT(*) FROM raw_staging", [], |row| row.get(0))
+            .expect("count staging");
+        assert_eq!(staged, 1, "a failed replace keeps the previous rows");
+        drop(conn);
+
+        cleanup_files(&db_path, &csv_path);
+    }
+
+    #[test]
+    fn ingest_with_varchar_type_keeps_leading_zero_zip() {
+        let suffix = unique_suffix();
+        let db_path = format!("/tmp/spatia_ingest_types_test_{suffix}.duckdb");
+        let csv_path = format!("/tmp/spatia_ingest_types_test_{suffix}.csv");
+        fs::write(&csv_path, "id,zip\n1,02134\n2,00501\n").expect("write csv");
+
+        let types = vec![("zip".to_string(), "varchar".to_string())];
+        ingest_csv_to_table_with_types(&db_path, &csv_path, "zips", &types)
+            .expect("ingest with types");
+
+        let conn = duckdb::Connection::open(&db_path).expect("open db");
+        let data_type: String = conn
+            .query_row(
+                "SELECT data_type FROM information_schema.columns \
+                 WHERE table_name = 'zips' AND column_name = 'zip'",
+                [],
+                |row| row.get(0),
+            )
+            .expect("zip type");
+        assert_eq!(data_type, "VARCHAR");
+        let first_zip: String = conn
+            .query_row("SELECT zip FROM zips ORDER BY id LIMIT 1", [], |row| row.get(0))
+            .expect("zip value");
+        assert_eq!(first_zip, "02134");
+
+        cleanup_files(&db_path, &csv_path);
+    }
+
+    #[test]
+    fn mixed_type_column_falls_back_to_text() {
+        let suffix = unique_suffix();
+        let db_path = format!("/tmp/spatia_ingest_mixed_test_{suffix}.duckdb");
+        let csv_path = format!("/tmp/spatia_ingest_mixed_test_{suffix}.csv");
+        // The text value sits past read_csv_auto's 20480-row sample, so the
+        // column is inferred as an integer and the full read fails.
+        let mut file = fs::File::create(&csv_path).expect("create csv");
+        writeln!(file, "id,units").expect("write header");
+        for id in 1..=30_000 {
+            writeln!(file, "{id},{}", id % 40).expect("write row");
+        }
+        writeln!(file, "30001,N/A").expect("write dirty row");
+        drop(file);
+
+        assert!(ingest_csv_to_table(&db_path, &csv_path, "strict").is_err());
+        let outcome = ingest_csv_with_text_fallback(&db_path, &csv_path, Some("units"), &[])
+            .expect("fallback ingest");
+        assert!(outcome.loaded_as_text);
+        assert_eq!(outcome.table, "units");
+        assert!(outcome.warnings[0].contains("units"), "{:?}", outcome.warnings);
+
+        let conn = duckdb::Connection::open(&db_path).expect("open db");
+        let (rows, data_type): (i64, String) = conn
+            .query_row(
+                "SELECT (SELECT COUNT(*) FROM units), (SELECT data_type \
+                 FROM information_schema.columns \
+                 WHERE table_name = 'units' AND column_name = 'units')",
+                [],
+                |row| Ok((row.get(0)?, row.get(1)?)),
+            )
+            .expect("query units");
+        assert_eq!((rows, data_type.as_str()), (30_001, "VARCHAR"));
+        drop(conn);
+
+        assert_eq!(
+            converting_column(r#"Error when converting column "units". Could not convert"#),
+            Some("units".to_string())
+        );
+        cleanup_files(&db_path, &csv_path);
+    }
+
+    #[test]
+    fn add_geometry_from_wkt_parses_point_column() {
+        let suffix = unique_suffix();
+        let db_path = format!("/tmp/spatia_ingest_wkt_test_{suffix}.duckdb");
+        let csv_path = format!("/tmp/spatia_ingest_wkt_test_{suffix}.csv");
+        fs::write(
+            &csv_path,
+            "id,name,geom_wkt\n1,City Hall,POINT(-122.4192 37.7793)\n2,Ferry Building,POINT(-122.3937 37.7955)\n",
+        )
+        .expect("write csv");
+        ingest_csv_to_table(&db_path, &csv_path, "sites").expect("ingest");
+
+        let err = add_geometry_from_wkt(&db_path, "sites", "id").expect_err("not text");
+        assert!(err.to_string().contains("must be text"));
+        let err = add_geometry_from_wkt(&db_path, "sites", "wkt").expect_err("missing");
+        assert!(err.to_string().contains("not found"));
+
+        add_geometry_from_wkt(&db_path, "sites", "geom_wkt").expect("add geometry");
+        let conn = duckdb::Connection::open(&db_path).expect("open db");
+        conn.execute_batch("LOAD spatial").expect("load spatial");
+        let (lon, lat): (f64, f64) = conn
+            .query_row(
+                "SELECT ST_X(geometry), ST_Y(geometry) FROM sites WHERE id = 1",
+                [],
+                |row| Ok((row.get(0)?, row.get(1)?)),
+            )
+            .expect("point");
+        assert!((lon + 122.4192).abs() < 1e-9);
+        assert!((lat - 37.7793).abs() < 1e-9);
+        drop(conn);
+
+        let err = add_geometry_from_wkt(&db_path, "sites", "geom_wkt").expect_err("twice");
+        assert!(err.to_string().contains("already has a geometry column"));
+
+        cleanup_files(&db_path, &csv_path);
+    }
+
+    #[test]
+    fn column_types_reject_unknown_type() {
+        let types = vec![("zip".to_string(), "VARCHAR); DROP TABLE x; --".to_string())];
+        let err = column_types_sql(&types).expect_err("should reject");
+        assert!(err.to_string().contains("unsupported column type"));
+    }
+
+    #[test]
+    fn column_types_accept_decimal_precision() {
+        let types = vec![("premium".to_string(), "decimal(18, 2)".to_string())];
+        let sql = column_types_sql(&types).expect("valid").expect("some");
+        assert_eq!(sql, "{'premium': 'DECIMAL(18,2)'}");
+    }
+
     fn setup_files() -> (String, String) {
         let suffix = unique_suffix();
         let db_path = format!("/tmp/spatia_ingest_test_{suffix}.duckdb");
@@ -252,6 +1233,68 @@ mod tests {
         let _ = fs::remove_file(&geojson_path);
     }
 
+    #[test]
+    fn ingest_glob_loads_each_file_into_prefixed_table() {
+        let suffix = unique_suffix();
+        let dir = format!("/tmp/spatia_ingest_glob_test_{suffix}");
+        fs::create_dir_all(&dir).expect("create dir");
+        fs::write(format!("{dir}/Sales 2024.csv"), "id,amount\n1,10\n2,20\n").expect("write a");
+        fs::write(format!("{dir}/sales-2024.csv"), "id,amount\n3,30\n").expect("write b");
+        fs::write(format!("{dir}/notes.txt"), "ignored").expect("write txt");
+        let db_path = format!("{dir}/spatia.duckdb");
+
+        let results =
+            ingest_glob(&db_path, &format!("{dir}/*.csv"), "imports").expect("ingest_glob");
+        let tables: Vec<&str> = results.iter().map(|r| r.table.as_str()).collect();
+        assert_eq!(tables, vec!["imports_sales_2024", "imports_sales_2024_2"]);
+        assert!(results.iter().all(|r| r.error.is_none()));
+
+        let conn = duckdb::Connection::open(&db_path).expect("open db");
+        let count: i64 = conn
+            .query_row("SELECT COUNT(*) FROM imports_sales_2024", [], |row| row.get(0))
+            .expect("count");
+        assert_eq!(count, 2);
+        drop(conn);
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn ingest_glob_does_not_replace_existing_tables() {
+        let suffix = unique_suffix();
+        let dir = format!("/tmp/spatia_ingest_glob_existing_test_{suffix}");
+        fs::create_dir_all(&dir).expect("create dir");
+        fs::write(format!("{dir}/sales.csv"), "id,amount\n1,10\n").expect("write csv");
+        let db_path = format!("{dir}/spatia.duckdb");
+        let conn = duckdb::Connection::open(&db_path).expect("open db");
+        conn.execute_batch("CREATE TABLE imports_sales AS SELECT 'kept' AS note")
+            .expect("seed table");
+        drop(conn);
+
+        let results =
+            ingest_glob(&db_path, &format!("{dir}/*.csv"), "imports").expect("ingest_glob");
+        assert_eq!(results[0].table, "imports_sales_2");
+
+        let conn = duckdb::Connection::open(&db_path).expect("open db");
+        let note: String = conn
+            .query_row("SELECT note FROM imports_sales", [], |row| row.get(0))
+            .expect("existing table untouched");
+        assert_eq!(note, "kept");
+        drop(conn);
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn ingest_glob_errors_when_nothing_matches() {
+        let suffix = unique_suffix();
+        let db_path = format!("/tmp/spatia_ingest_glob_empty_{suffix}.duckdb");
+        let err = ingest_glob(&db_path, &format!("/tmp/spatia_no_such_dir_{suffix}/*.csv"), "t")
+            .expect_err("should fail");
+        assert!(err.to_string().contains("no files match"));
+        cleanup_files(&db_path, "");
+    }
+
     fn unique_suffix() -> u128 {
         SystemTime::now()
             .duration_since(UNIX_EPOCH)
@@ -2,9 +2,22 @@ mod identifiers;
 mod ingest;
 mod types;
 
-pub use identifiers::validate_table_name;
+pub use identifiers::{sanitize_identifier, validate_table_name};
+pub use ingest::add_geometry_from_wkt;
 pub use ingest::ingest_csv;
+pub use ingest::ingest_csv_streamed;
 pub use ingest::ingest_csv_to_table;
+pub use ingest::ingest_csv_to_table_with_types;
+pub use ingest::ingest_csv_with_row_progress;
+pub use ingest::ingest_csv_with_text_fallback;
+pub use ingest::ingest_csv_with_types;
+pub use ingest::ingest_glob;
 pub use ingest::ingest_spatial_file;
 pub use ingest::is_spatial_file;
+pub use ingest::record_ingest_source;
+pub use ingest::unchanged_ingest_row_count;
+pub use ingest::CsvIngestOutcome;
+pub use ingest::GlobIngestFile;
+pub use ingest::IngestOptions;
+pub use ingest::INGEST_PROVENANCE_TABLE;
 pub use types::IngestResult;
@@ -14,6 +14,23 @@ pub fn validate_table_name(table_name: &str) -> OvertureResult<()> {
     Ok(())
 }
 
+/// Accept `table` or `schema.table`, each part a plain identifier.
+pub fn validate_qualified_table_name(name: &str) -> OvertureResult<()> {
+    let (schema, table) = split_qualified_name(name);
+    if validate_table_name(schema).is_err() {
+        return Err(format!(
+            "schema name '{schema}' must be alphanumeric or underscore and start with a letter or underscore"
+        )
+        .into());
+    }
+    validate_table_name(table)
+}
+
+/// Split `schema.table` into its parts; a bare name is in `main`.
+pub fn split_qualified_name(name: &str) -> (&str, &str) {
+    name.split_once('.').unwrap_or(("main", name))
+}
+
 fn is_ident_start(value: char) -> bool {
     value == '_' || value.is_ascii_alphabetic()
 }
@@ -1,14 +1,38 @@
 mod identifiers;
 mod overture;
+mod themes;
 mod types;
 
 pub use overture::fetch_buildings_in_bbox;
+pub use overture::overture_extract_explain;
 pub use overture::overture_extract_to_table;
+pub use overture::overture_extract_to_table_filtered;
+pub use overture::overture_extract_to_table_if_stale;
 pub use overture::overture_geocode;
+pub use overture::overture_geocode_page;
+pub use overture::overture_index_extract;
+pub use overture::overture_materialize_results;
+pub use overture::overture_query;
+pub use overture::overture_rebuild_lookup;
+pub use overture::overture_release;
 pub use overture::overture_search;
+pub use overture::overture_search_page;
+pub use overture::overture_search_with_highlight;
 pub use overture::BBox;
+pub use overture::DivisionLevel;
+pub use overture::ExtractFilters;
+pub use overture::ExtractRequirement;
+pub use overture::OvertureExtractPlan;
 pub use overture::OvertureExtractResult;
 pub use overture::OvertureGeocodeResult;
+pub use overture::OvertureLookupResult;
+pub use overture::OvertureResultPage;
 pub use overture::OvertureSearchResult;
+pub use overture::SearchWeights;
+pub use overture::DEFAULT_MAX_RESULTS;
 pub use overture::OVERTURE_RELEASE;
+pub use spatia_geocode::TEMP_DIR_ENV;
+pub use themes::overture_themes;
+pub use themes::OvertureTheme;
+pub use themes::OvertureThemeCatalog;
 pub use types::OvertureResult;
@@ -1,12 +1,27 @@
 use duckdb::Connection;
 use serde::Serialize;
+use spatia_geocode::{
+    load_extension, open_connection, open_in_memory, serialize_opt_coord, SessionSettings,
+    MEMORY_LIMIT_ENV, TEMP_DIR_ENV,
+};
 
-use crate::identifiers::validate_table_name;
+use crate::identifiers::{split_qualified_name, validate_qualified_table_name};
+use crate::themes::{validate_theme, validate_theme_type};
 use crate::OvertureResult;
 
 pub const OVERTURE_RELEASE: &str = "2026-02-18.0";
 
-#[derive(Debug, Clone, Copy, PartialEq)]
+/// Hard cap on search/geocode rows when `SPATIA_OVERTURE_MAX_RESULTS` is unset.
+pub const DEFAULT_MAX_RESULTS: usize = 1000;
+
+/// One row per extracted table recording the parameters it was built from,
+/// so a repeat extract can tell whether the table is still current.
+const PROVENANCE_TABLE: &str = "overture_extract_provenance";
+
+/// Temp table holding the changed rows of a `since` extract before the merge.
+const EXTRACT_DELTA_TABLE: &str = "_spatia_extract_delta";
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
 pub struct BBox {
     pub xmin: f64,
     pub ymin: f64,
@@ -14,18 +29,90 @@ pub struct BBox {
     pub ymax: f64,
 }
 
+const BBOX_FIELDS: [&str; 4] = ["xmin", "ymin", "xmax", "ymax"];
+
 impl BBox {
+    /// Parse `xmin,ymin,xmax,ymax` and [`validate`](Self::validate) it.
+    /// Errors name the field that failed, e.g. `bbox ymax '47.x' is not a
+    /// valid number`.
     pub fn parse(input: &str) -> OvertureResult<Self> {
+        let [xmin, ymin, xmax, ymax] = Self::parse_coords(input)?;
+        Self::validate(xmin, ymin, xmax, ymax)
+    }
+
+    /// Like [`parse`](Self::parse) but without the lon/lat range check, for
+    /// bboxes in a projected (non-geographic) CRS.
+    pub fn parse_projected(input: &str) -> OvertureResult<Self> {
+        let [xmin, ymin, xmax, ymax] = Self::parse_coords(input)?;
+        Self::validate_projected(xmin, ymin, xmax, ymax)
+    }
+
+    fn parse_coords(input: &str) -> OvertureResult<[f64; 4]> {
         let parts: Vec<&str> = input.split(',').map(str::trim).collect();
         if parts.len() != 4 {
-            return Err("bbox must be: xmin,ymin,xmax,ymax".into());
+            return Err(format!(
+                "bbox must be: xmin,ymin,xmax,ymax (got {} value{})",
+                parts.len(),
+                if parts.len() == 1 { "" } else { "s" }
+            )
+            .into());
+        }
+        let mut coords = [0.0; 4];
+        for ((coord, part), name) in coords.iter_mut().zip(&parts).zip(BBOX_FIELDS) {
+            if part.is_empty() {
+                return Err(format!("bbox {name} is missing").into());
+            }
+            *coord = part
+                .parse::<f64>()
+                .map_err(|_| format!("bbox {name} '{part}' is not a valid number"))?;
+        }
+        Ok(coords)
+    }
+
+    /// Build a geographic bbox, checking what
+    /// [`validate_projected`](Self::validate_projected) does and that x is a
+    /// longitude in [-180, 180] and y a latitude in [-90, 90], as Overture
+    /// data is. When the values fit the other way round the error says lon
+    /// and lat look swapped.
+    pub fn validate(xmin: f64, ymin: f64, xmax: f64, ymax: f64) -> OvertureResult<Self> {
+        let bbox = Self::validate_projected(xmin, ymin, xmax, ymax)?;
+        let lon_ok = |value: f64| (-180.0..=180.0).contains(&value);
+        let lat_ok = |value: f64| (-90.0..=90.0).contains(&value);
+        let checks = [
+            (xmin, "xmin", lon_ok(xmin), "longitude", "[-180, 180]"),
+            (ymin, "ymin", lat_ok(ymin), "latitude", "[-90, 90]"),
+            (xmax, "xmax", lon_ok(xmax), "longitude", "[-180, 180]"),
+            (ymax, "ymax", lat_ok(ymax), "latitude", "[-90, 90]"),
+        ];
+        let Some((value, name, _, axis, range)) = checks.into_iter().find(|check| !check.2) else {
+            return Ok(bbox);
+        };
+        let swapped = [xmin, xmax].into_iter().all(lat_ok) && [ymin, ymax].into_iter().all(lon_ok);
+        let hint = if swapped {
+            "; the values look like lat,lon, but the order is xmin,ymin,xmax,ymax (lon,lat)"
+        } else {
+            ""
+        };
+        Err(format!("bbox {name} {value} is outside the {axis} range {range}{hint}").into())
+    }
+
+    /// Build a bbox from four coordinates in any CRS, checking only that each
+    /// is finite and that `xmin < xmax` and `ymin < ymax`.
+    pub fn validate_projected(xmin: f64, ymin: f64, xmax: f64, ymax: f64) -> OvertureResult<Self> {
+        for (value, name) in [xmin, ymin, xmax, ymax].into_iter().zip(BBOX_FIELDS) {
+            if !value.is_finite() {
+                return Err(format!("bbox {name} must be a finite number, got {value}").into());
+            }
         }
-        let xmin = parts[0].parse::<f64>()?;
-        let ymin = parts[1].parse::<f64>()?;
-        let xmax = parts[2].parse::<f64>()?;
-        let ymax = parts[3].parse::<f64>()?;
-        if !(xmin < xmax && ymin < ymax) {
-            return Err("bbox must satisfy xmin < xmax and ymin < ymax".into());
+        if xmin >= xmax {
+            return Err(
+                format!("bbox must satisfy xmin < xmax, got xmin {xmin} and xmax {xmax}").into(),
+            );
+        }
+        if ymin >= ymax {
+            return Err(
+                format!("bbox must satisfy ymin < ymax, got ymin {ymin} and ymax {ymax}").into(),
+            );
         }
         Ok(Self {
             xmin,
@@ -34,6 +121,25 @@ impl BBox {
             ymax,
         })
     }
+
+    /// Area in square degrees. Only meaningful for comparing bboxes, not as a
+    /// ground area.
+    pub fn area_deg2(&self) -> f64 {
+        (self.xmax - self.xmin) * (self.ymax - self.ymin)
+    }
+
+    /// The bbox as a closed WKT `POLYGON`, counter-clockwise from `(xmin ymin)`.
+    pub fn to_wkt(&self) -> String {
+        let Self {
+            xmin,
+            ymin,
+            xmax,
+            ymax,
+        } = self;
+        format!(
+            "POLYGON(({xmin} {ymin}, {xmax} {ymin}, {xmax} {ymax}, {xmin} {ymax}, {xmin} {ymin}))"
+        )
+    }
 }
 
 #[derive(Debug, Clone, Serialize)]
@@ -42,22 +148,304 @@ pub struct OvertureExtractResult {
     pub table: String,
     pub release: String,
     pub row_count: i64,
+    /// Extent of the extracted rows, for fitting the map to them. Absent for
+    /// tables without geometry and for empty extracts.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub bbox: Option<BBox>,
+    /// Spill directory from `SPATIA_DUCKDB_TEMP_DIR`, when one was set.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub temp_directory: Option<String>,
+}
+
+/// Outcome of [`overture_rebuild_lookup`].
+#[derive(Debug, Clone, Serialize)]
+pub struct OvertureLookupResult {
+    pub status: &'static str,
+    pub lookup_table: String,
+    pub row_count: i64,
+}
+
+/// What `overture_extract_to_table` would run, without touching the database
+/// or the network.
+#[derive(Debug, Clone, Serialize)]
+pub struct OvertureExtractPlan {
+    pub table: String,
+    pub release: String,
+    pub source_path: String,
+    pub bbox_filter: String,
+    pub sql: String,
 }
 
 #[derive(Debug, Clone, Serialize)]
 pub struct OvertureSearchResult {
     pub id: Option<String>,
     pub label: String,
+    /// HTML-escaped `label` with the matched parts wrapped in `<mark>`.
+    /// Only populated by [`overture_search_with_highlight`].
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub highlight: Option<String>,
+    /// Only populated when coordinates were requested from
+    /// [`overture_search_page`] and the base table has a geometry column, or
+    /// by [`overture_query`] when the source has one.
+    #[serde(
+        skip_serializing_if = "Option::is_none",
+        serialize_with = "serialize_opt_coord"
+    )]
+    pub lat: Option<f64>,
+    #[serde(
+        skip_serializing_if = "Option::is_none",
+        serialize_with = "serialize_opt_coord"
+    )]
+    pub lon: Option<f64>,
+}
+
+/// Ranking for `overture_search` hits. A hit scores the weight of the first
+/// tier its label matches (exact, prefix, word prefix, substring, or only
+/// all tokens), plus `token_word_start` per query token that starts a word.
+/// Higher scores rank first; ties go to the shorter label, then
+/// alphabetically.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SearchWeights {
+    pub exact: i64,
+    pub prefix: i64,
+    pub word_prefix: i64,
+    pub substring: i64,
+    pub tokens: i64,
+    pub token_word_start: i64,
+}
+
+impl SearchWeights {
+    /// Exact, then prefix, then word-prefix, then substring matches, with
+    /// token word starts breaking ties within a tier.
+    pub const BALANCED: Self = Self {
+        exact: 4000,
+        prefix: 3000,
+        word_prefix: 2000,
+        substring: 1000,
+        tokens: 0,
+        token_word_start: 1,
+    };
+
+    /// Any label with a word starting with the query ranks like an exact
+    /// match, so shorter word-prefix labels beat longer prefix labels.
+    pub const PREFIX_HEAVY: Self = Self {
+        exact: 3000,
+        prefix: 3000,
+        word_prefix: 3000,
+        substring: 1000,
+        tokens: 0,
+        token_word_start: 1,
+    };
+
+    /// Only an exact label match is boosted; every other hit is ordered by
+    /// label length.
+    pub const EXACT_ONLY: Self = Self {
+        exact: 1,
+        prefix: 0,
+        word_prefix: 0,
+        substring: 0,
+        tokens: 0,
+        token_word_start: 0,
+    };
+}
+
+impl Default for SearchWeights {
+    fn default() -> Self {
+        Self::BALANCED
+    }
+}
+
+impl std::str::FromStr for SearchWeights {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "balanced" => Ok(Self::BALANCED),
+            "prefix_heavy" => Ok(Self::PREFIX_HEAVY),
+            "exact_only" => Ok(Self::EXACT_ONLY),
+            other => Err(format!(
+                "unknown search ranking '{other}' (expected balanced, prefix_heavy or exact_only)"
+            )),
+        }
+    }
+}
+
+/// Search or geocode rows plus whether more rows matched than were returned,
+/// either because of the caller's limit or the `SPATIA_OVERTURE_MAX_RESULTS`
+/// cap.
+#[derive(Debug, Clone, Serialize)]
+pub struct OvertureResultPage<T> {
+    pub results: Vec<T>,
+    pub truncated: bool,
 }
 
 #[derive(Debug, Clone, Serialize)]
 pub struct OvertureGeocodeResult {
     pub id: Option<String>,
     pub label: String,
+    #[serde(serialize_with = "serialize_opt_coord")]
     pub lat: Option<f64>,
+    #[serde(serialize_with = "serialize_opt_coord")]
     pub lon: Option<f64>,
 }
 
+/// Column that must be non-null for a row to be kept by an extract.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExtractRequirement {
+    Names,
+    Geometry,
+}
+
+impl ExtractRequirement {
+    fn column(self) -> &'static str {
+        match self {
+            ExtractRequirement::Names => "names",
+            ExtractRequirement::Geometry => "geometry",
+        }
+    }
+
+    /// Expression that must be non-null. A `names` struct can be present
+    /// with a NULL `primary`, which would still give the lookup a blank
+    /// label, so names are required through their primary name.
+    fn not_null_sql(self) -> &'static str {
+        match self {
+            ExtractRequirement::Names => "names.primary",
+            ExtractRequirement::Geometry => "geometry",
+        }
+    }
+}
+
+impl std::str::FromStr for ExtractRequirement {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "names" => Ok(ExtractRequirement::Names),
+            "geometry" => Ok(ExtractRequirement::Geometry),
+            other => Err(format!(
+                "unknown extract requirement '{other}' (expected names or geometry)"
+            )),
+        }
+    }
+}
+
+/// Admin level kept by a `divisions` extract, matched against `subtype`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DivisionLevel {
+    Country,
+    Region,
+    County,
+    Locality,
+}
+
+impl DivisionLevel {
+    fn subtype(self) -> &'static str {
+        match self {
+            DivisionLevel::Country => "country",
+            DivisionLevel::Region => "region",
+            DivisionLevel::County => "county",
+            DivisionLevel::Locality => "locality",
+        }
+    }
+}
+
+impl std::str::FromStr for DivisionLevel {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "country" => Ok(DivisionLevel::Country),
+            "region" => Ok(DivisionLevel::Region),
+            "county" => Ok(DivisionLevel::County),
+            "locality" => Ok(DivisionLevel::Locality),
+            other => Err(format!(
+                "unknown division level '{other}' (expected country, region, county or locality)"
+            )),
+        }
+    }
+}
+
+/// Row filters applied on top of the bbox in an extract.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ExtractFilters {
+    /// Columns that must be non-null (for `names`, its `primary` name).
+    pub require: Vec<ExtractRequirement>,
+    /// Admin level to keep; only valid for the `divisions` theme.
+    pub level: Option<DivisionLevel>,
+    /// Skip the known theme/type check, for types added after the pinned
+    /// release. Not part of the provenance fingerprint.
+    pub force: bool,
+    /// Schema to extract into, created if missing, so reference data stays
+    /// out of `main`. `None` keeps `main`. The schema-qualified table name is
+    /// what provenance records, so it is not part of the fingerprint either.
+    pub schema: Option<String>,
+    /// `YYYY-MM-DD`: keep only rows whose `update_time` is on or after this
+    /// date and merge them into an existing table by `id` instead of
+    /// replacing it. Ignored, with a warning, for themes without an
+    /// `update_time` column. Part of the fingerprint, so a new date is not
+    /// skipped as current.
+    pub since: Option<String>,
+}
+
+impl ExtractFilters {
+    /// `table_name` (or the theme/type default) qualified with [`Self::schema`].
+    fn target_table(
+        &self,
+        theme: &str,
+        item_type: &str,
+        table_name: Option<&str>,
+    ) -> OvertureResult<String> {
+        let table = table_name
+            .map(str::to_string)
+            .unwrap_or_else(|| default_table_name(theme, item_type));
+        let table = match self.schema.as_deref() {
+            Some(schema) if table.contains('.') => {
+                return Err(format!(
+                    "table {table} is already schema-qualified; drop the schema option {schema}"
+                )
+                .into());
+            }
+            Some(schema) if schema != "main" => format!("{schema}.{table}"),
+            _ => table,
+        };
+        validate_qualified_table_name(&table)?;
+        Ok(table)
+    }
+
+    fn validate(&self, theme: &str, item_type: &str) -> OvertureResult<()> {
+        if !self.force {
+            validate_theme_type(theme, item_type)?;
+        }
+        if self.level.is_some() && theme != "divisions" {
+            return Err(
+                format!("--level only applies to the divisions theme, not '{theme}'").into(),
+            );
+        }
+        if let Some(since) = self.since.as_deref() {
+            validate_since_date(since)?;
+        }
+        Ok(())
+    }
+
+    /// Stable text form stored in the provenance table. `since` is only
+    /// appended when set, so full extracts keep the fingerprint they were
+    /// recorded with.
+    fn fingerprint(&self) -> String {
+        let mut columns: Vec<&str> = self.require.iter().map(|r| r.column()).collect();
+        columns.sort_unstable();
+        columns.dedup();
+        let mut fingerprint = format!(
+            "require={};level={}",
+            columns.join(","),
+            self.level.map(DivisionLevel::subtype).unwrap_or_default()
+        );
+        if let Some(since) = self.since.as_deref() {
+            fingerprint.push_str(&format!(";since={since}"));
+        }
+        fingerprint
+    }
+}
+
 pub fn overture_extract_to_table(
     db_path: &str,
     theme: &str,
@@ -65,97 +453,756 @@ pub fn overture_extract_to_table(
     bbox: BBox,
     table_name: Option<&str>,
 ) -> OvertureResult<OvertureExtractResult> {
-    let table = table_name
-        .map(str::to_string)
-        .unwrap_or_else(|| default_table_name(theme, item_type));
-    validate_table_name(&table)?;
+    overture_extract_to_table_filtered(
+        db_path,
+        theme,
+        item_type,
+        bbox,
+        table_name,
+        &ExtractFilters::default(),
+    )
+}
 
-    let conn = Connection::open(db_path)?;
+/// Like [`overture_extract_to_table`] but applies `filters`: rows whose
+/// required columns are NULL are dropped, keeping extracts meant for
+/// search/geocoding small, and a division level narrows admin boundaries
+/// to one `subtype`.
+pub fn overture_extract_to_table_filtered(
+    db_path: &str,
+    theme: &str,
+    item_type: &str,
+    bbox: BBox,
+    table_name: Option<&str>,
+    filters: &ExtractFilters,
+) -> OvertureResult<OvertureExtractResult> {
+    let table = filters.target_table(theme, item_type, table_name)?;
+    filters.validate(theme, item_type)?;
+
+    // open_connection applies the memory limit and spill directory; read the
+    // latter back only to report it and to word an out-of-memory error.
+    let temp_directory = SessionSettings::from_env()?.temp_directory;
+    let conn = open_connection(db_path)?;
     ensure_extensions(&conn)?;
+    let (schema, _) = split_qualified_name(&table);
+    if schema != "main" {
+        conn.execute_batch(&format!("CREATE SCHEMA IF NOT EXISTS {schema}"))?;
+    }
 
     let release = overture_release();
     let source_path = overture_source_path(&release, theme, item_type);
+    run_extract(&conn, &table, &source_path, bbox, filters)
+        .and_then(|()| create_lookup_table(&conn, &table, theme))
+        .map_err(|err| out_of_memory_hint(err, temp_directory.as_deref()))?;
+    build_search_index(&conn, db_path, &lookup_table_name(&table));
+
+    let count_sql = format!("SELECT COUNT(*) FROM {table}", table = table);
+    let mut stmt = conn.prepare(&count_sql)?;
+    let row_count: i64 = stmt.query_row([], |row| row.get(0))?;
+    let extent = table_extent(&conn, &table)?;
+
+    let key = ProvenanceKey {
+        table: &table,
+        theme,
+        item_type,
+        bbox,
+        release: &release,
+        filters,
+    };
+    record_provenance(&conn, &key)?;
+
+    Ok(OvertureExtractResult {
+        status: "ok",
+        table,
+        release,
+        row_count,
+        bbox: extent,
+        temp_directory,
+    })
+}
+
+/// Replace DuckDB's out-of-memory error with one that says what to change.
+/// Other errors pass through unchanged.
+fn out_of_memory_hint(
+    err: Box<dyn std::error::Error + Send + Sync>,
+    temp_directory: Option<&str>,
+) -> Box<dyn std::error::Error + Send + Sync> {
+    let message = err.to_string();
+    if !message.to_ascii_lowercase().contains("out of memory") {
+        return err;
+    }
+    let spill = match temp_directory {
+        Some(dir) => format!("even with spilling to {dir}"),
+        None => format!("without a spill directory; set {TEMP_DIR_ENV} so DuckDB can use disk"),
+    };
+    format!(
+        "overture extract ran out of memory {spill}. Try a smaller bbox or raise \
+         {MEMORY_LIMIT_ENV}. DuckDB said: {message}"
+    )
+    .into()
+}
+
+/// Create `table` from `source_path`, or with an effective `since` date and an
+/// existing table, replace its rows that share an `id` with the changed ones
+/// and append the rest.
+fn run_extract(
+    conn: &Connection,
+    table: &str,
+    source_path: &str,
+    bbox: BBox,
+    filters: &ExtractFilters,
+) -> OvertureResult<()> {
+    let since = match filters.since.as_deref() {
+        Some(date) if source_has_column(conn, source_path, "update_time")? => Some(date),
+        Some(date) => {
+            tracing::warn!(
+                since = date,
+                source_path,
+                "overture_extract: source has no update_time column, ignoring --since"
+            );
+            None
+        }
+        None => None,
+    };
+    let merge = since.is_some() && has_table(conn, table)? && has_column(conn, table, "id")?;
+    if !merge {
+        let sql = build_extract_sql(table, source_path, bbox, filters, since);
+        conn.execute(&sql, [])?;
+        return Ok(());
+    }
+
+    let filter = extract_filter_sql(bbox, filters, since);
+    conn.execute_batch(&format!(
+        "BEGIN TRANSACTION; \
+         CREATE OR REPLACE TEMP TABLE {EXTRACT_DELTA_TABLE} AS \
+         SELECT * FROM read_parquet('{source_path}') WHERE {filter}; \
+         DELETE FROM {table} WHERE id IN (SELECT id FROM {EXTRACT_DELTA_TABLE}); \
+         INSERT INTO {table} BY NAME SELECT * FROM {EXTRACT_DELTA_TABLE}; \
+         DROP TABLE {EXTRACT_DELTA_TABLE}; \
+         COMMIT;"
+    ))?;
+    tracing::info!(table, since, "overture_extract: merged rows changed since date");
+    Ok(())
+}
+
+/// Whether the parquet at `source_path` has `column`; reads only the schema.
+fn source_has_column(conn: &Connection, source_path: &str, column: &str) -> OvertureResult<bool> {
+    let mut stmt = conn.prepare(&format!(
+        "SELECT column_name FROM (DESCRIBE SELECT * FROM read_parquet('{source_path}'))"
+    ))?;
+    let mut rows = stmt.query([])?;
+    while let Some(row) = rows.next()? {
+        let name: String = row.get(0)?;
+        if name.eq_ignore_ascii_case(column) {
+            return Ok(true);
+        }
+    }
+    Ok(false)
+}
+
+/// Check that `date` is a `YYYY-MM-DD` calendar date, so it can be compared
+/// with `update_time` as text or as a timestamp.
+fn validate_since_date(date: &str) -> OvertureResult<()> {
+    let shaped = date.len() == 10
+        && date.bytes().enumerate().all(|(i, b)| match i {
+            4 | 7 => b == b'-',
+            _ => b.is_ascii_digit(),
+        });
+    let month = date.get(5..7).and_then(|m| m.parse::<u32>().ok());
+    let day = date.get(8..10).and_then(|d| d.parse::<u32>().ok());
+    if !(shaped && matches!(month, Some(1..=12)) && matches!(day, Some(1..=31))) {
+        return Err(format!("--since must be a YYYY-MM-DD date, got '{date}'").into());
+    }
+    Ok(())
+}
+
+/// Extent of `table` from the per-row Overture `bbox` struct, so no spatial
+/// extension is needed. `None` when the table has no `geometry` (or `bbox`)
+/// column or no rows.
+fn table_extent(conn: &Connection, table: &str) -> OvertureResult<Option<BBox>> {
+    if !has_column(conn, table, "geometry")? || !has_column(conn, table, "bbox")? {
+        return Ok(None);
+    }
     let sql = format!(
-        "CREATE OR REPLACE TABLE {table} AS \
-         SELECT * FROM read_parquet('{source}') \
-         WHERE bbox.xmin <= {xmax} AND bbox.xmax >= {xmin} \
-           AND bbox.ymin <= {ymax} AND bbox.ymax >= {ymin}",
-        table = table,
-        source = source_path,
-        xmin = bbox.xmin,
-        ymin = bbox.ymin,
-        xmax = bbox.xmax,
-        ymax = bbox.ymax,
+        "SELECT min(bbox.xmin), min(bbox.ymin), max(bbox.xmax), max(bbox.ymax) FROM {table}"
     );
-    conn.execute(&sql, [])?;
-    create_lookup_table(&conn, &table, theme)?;
+    let mut stmt = conn.prepare(&sql)?;
+    let extent = stmt.query_row([], |row| {
+        Ok((
+            row.get::<_, Option<f64>>(0)?,
+            row.get::<_, Option<f64>>(1)?,
+            row.get::<_, Option<f64>>(2)?,
+            row.get::<_, Option<f64>>(3)?,
+        ))
+    })?;
+    Ok(match extent {
+        (Some(xmin), Some(ymin), Some(xmax), Some(ymax)) => Some(BBox {
+            xmin,
+            ymin,
+            xmax,
+            ymax,
+        }),
+        _ => None,
+    })
+}
 
-    // Build Tantivy search index for the lookup table
-    let lookup = lookup_table_name(&table);
-    let index_dir = spatia_geocode::search_index::index_dir_for_table(db_path, &lookup);
-    match spatia_geocode::search_index::build_index(&conn, &lookup, &index_dir) {
+/// Build the Tantivy search index for `lookup`. A failure is only logged:
+/// search falls back to `LIKE` over the lookup table.
+fn build_search_index(conn: &Connection, db_path: &str, lookup: &str) {
+    let index_dir = spatia_geocode::search_index::index_dir_for_table(db_path, lookup);
+    match spatia_geocode::search_index::build_index(conn, lookup, &index_dir) {
         Ok(count) => {
             tracing::info!(
                 doc_count = count,
-                lookup_table = lookup.as_str(),
+                lookup_table = lookup,
                 "overture_extract: built Tantivy search index"
             );
         }
         Err(e) => {
             tracing::warn!(
                 error = %e,
-                lookup_table = lookup.as_str(),
+                lookup_table = lookup,
                 "overture_extract: failed to build Tantivy index, LIKE fallback will be used"
             );
         }
     }
+}
 
-    let count_sql = format!("SELECT COUNT(*) FROM {table}", table = table);
-    let mut stmt = conn.prepare(&count_sql)?;
-    let row_count: i64 = stmt.query_row([], |row| row.get(0))?;
+/// Regenerate `{table_name}_lookup` (and its search index) from an existing
+/// extract, so lookup improvements reach old extracts without downloading
+/// them again. `theme` picks the label source as in `overture_extract`; the
+/// base table must exist and have the columns that theme's labels read.
+pub fn overture_rebuild_lookup(
+    db_path: &str,
+    table_name: &str,
+    theme: &str,
+) -> OvertureResult<OvertureLookupResult> {
+    validate_qualified_table_name(table_name)?;
+    validate_theme(theme)?;
+    let conn = open_connection(db_path)?;
+    if !has_table(&conn, table_name)? {
+        return Err(
+            format!("table {table_name} does not exist; run overture_extract first").into(),
+        );
+    }
+    let mut missing = Vec::new();
+    for column in lookup_source_columns(theme) {
+        if !has_column(&conn, table_name, column)? {
+            missing.push(*column);
+        }
+    }
+    if !missing.is_empty() {
+        return Err(format!(
+            "table {table_name} has no {} column(s), which a {theme} lookup needs",
+            missing.join(", ")
+        )
+        .into());
+    }
 
-    Ok(OvertureExtractResult {
+    create_lookup_table(&conn, table_name, theme)?;
+    let lookup_table = lookup_table_name(table_name);
+    build_search_index(&conn, db_path, &lookup_table);
+
+    let mut stmt = conn.prepare(&format!("SELECT COUNT(*) FROM {lookup_table}"))?;
+    let row_count: i64 = stmt.query_row([], |row| row.get(0))?;
+    tracing::info!(
+        lookup_table = lookup_table.as_str(),
+        row_count,
+        "overture_rebuild_lookup: rebuilt"
+    );
+    Ok(OvertureLookupResult {
         status: "ok",
+        lookup_table,
+        row_count,
+    })
+}
+
+/// Base-table columns [`create_lookup_table`] reads for `theme`.
+fn lookup_source_columns(theme: &str) -> &'static [&'static str] {
+    match theme {
+        "addresses" => &[
+            "id",
+            "number",
+            "street",
+            "postal_city",
+            "postcode",
+            "country",
+        ],
+        "divisions" => &["id", "names"],
+        _ => &["id"],
+    }
+}
+
+/// Like [`overture_extract_to_table_filtered`], but returns the existing
+/// table's row count with status `"skipped"` when the provenance table shows
+/// it was last extracted with the same theme, type, bbox, filters and
+/// release. Any difference, or a missing table, triggers a fresh extract.
+pub fn overture_extract_to_table_if_stale(
+    db_path: &str,
+    theme: &str,
+    item_type: &str,
+    bbox: BBox,
+    table_name: Option<&str>,
+    filters: &ExtractFilters,
+) -> OvertureResult<OvertureExtractResult> {
+    let table = filters.target_table(theme, item_type, table_name)?;
+    filters.validate(theme, item_type)?;
+
+    let release = overture_release();
+    let key = ProvenanceKey {
+        table: &table,
+        theme,
+        item_type,
+        bbox,
+        release: &release,
+        filters,
+    };
+    let current = {
+        let conn = open_connection(db_path)?;
+        match current_row_count(&conn, &key)? {
+            Some(row_count) => Some((row_count, table_extent(&conn, &table)?)),
+            None => None,
+        }
+    };
+    if let Some((row_count, extent)) = current {
+        tracing::info!(
+            table = table.as_str(),
+            row_count,
+            "overture_extract: table is current, skipping download"
+        );
+        return Ok(OvertureExtractResult {
+            status: "skipped",
+            table,
+            release,
+            row_count,
+            bbox: extent,
+            temp_directory: None,
+        });
+    }
+
+    overture_extract_to_table_filtered(db_path, theme, item_type, bbox, table_name, filters)
+}
+
+/// Index an extracted table for `overture_geocode` and spatial filters: an
+/// ART index on `id`, which the lookup joins compare uncast (see
+/// `id_join_key`), and, when the spatial extension can build one, an R-tree
+/// on `geometry`. Existing indexes are kept, so this is safe to re-run.
+/// Returns the names of the indexes present on the table afterwards.
+///
+/// This costs time and space proportional to the table, so it is opt-in
+/// (`overture_extract --index`) rather than part of every extract.
+pub fn overture_index_extract(db_path: &str, table_name: &str) -> OvertureResult<Vec<String>> {
+    validate_qualified_table_name(table_name)?;
+    let conn = open_connection(db_path)?;
+    if !has_table(&conn, table_name)? {
+        return Err(format!("table {table_name} does not exist").into());
+    }
+
+    // Index names live in the table's schema and cannot be qualified.
+    let (schema, bare_table) = split_qualified_name(table_name);
+    if has_column(&conn, table_name, "id")? {
+        conn.execute_batch(&format!(
+            "CREATE INDEX IF NOT EXISTS {bare_table}_id_idx ON {table_name} (id)"
+        ))?;
+    }
+    if has_column(&conn, table_name, "geometry")? {
+        let rtree = load_extension(&conn, "spatial").and_then(|()| {
+            conn.execute_batch(&format!(
+                "CREATE INDEX IF NOT EXISTS {bare_table}_geometry_rtree \
+                 ON {table_name} USING RTREE (geometry)"
+            ))
+            .map_err(Into::into)
+        });
+        if let Err(e) = rtree {
+            tracing::warn!(
+                error = %e,
+                table = table_name,
+                "overture_index_extract: R-tree index unavailable, skipping"
+            );
+        }
+    }
+
+    let mut stmt = conn.prepare(
+        "SELECT index_name FROM duckdb_indexes() \
+         WHERE schema_name = ? AND table_name = ? ORDER BY index_name",
+    )?;
+    let mut rows = stmt.query([schema, bare_table])?;
+    let mut indexes = Vec::new();
+    while let Some(row) = rows.next()? {
+        indexes.push(row.get::<_, String>(0)?);
+    }
+    tracing::info!(
+        table = table_name,
+        index_count = indexes.len(),
+        "overture_index_extract: indexes ready"
+    );
+    Ok(indexes)
+}
+
+/// Parameters that identify an extract in the provenance table.
+struct ProvenanceKey<'a> {
+    table: &'a str,
+    theme: &'a str,
+    item_type: &'a str,
+    bbox: BBox,
+    release: &'a str,
+    filters: &'a ExtractFilters,
+}
+
+impl ProvenanceKey<'_> {
+    fn bbox_text(&self) -> String {
+        format!(
+            "{},{},{},{}",
+            self.bbox.xmin, self.bbox.ymin, self.bbox.xmax, self.bbox.ymax
+        )
+    }
+}
+
+fn ensure_provenance_table(conn: &Connection) -> OvertureResult<()> {
+    conn.execute_batch(&format!(
+        "CREATE TABLE IF NOT EXISTS {PROVENANCE_TABLE} ( \
+             table_name VARCHAR PRIMARY KEY, \
+             theme VARCHAR NOT NULL, \
+             item_type VARCHAR NOT NULL, \
+             bbox VARCHAR NOT NULL, \
+             release VARCHAR NOT NULL, \
+             filters VARCHAR NOT NULL, \
+             extracted_at TIMESTAMP NOT NULL DEFAULT current_timestamp \
+         )"
+    ))?;
+    Ok(())
+}
+
+fn record_provenance(conn: &Connection, key: &ProvenanceKey) -> OvertureResult<()> {
+    ensure_provenance_table(conn)?;
+    conn.execute(
+        &format!(
+            "INSERT OR REPLACE INTO {PROVENANCE_TABLE} \
+             (table_name, theme, item_type, bbox, release, filters, extracted_at) \
+             VALUES (?, ?, ?, ?, ?, ?, current_timestamp)"
+        ),
+        duckdb::params![
+            key.table,
+            key.theme,
+            key.item_type,
+            key.bbox_text(),
+            key.release,
+            key.filters.fingerprint()
+        ],
+    )?;
+    Ok(())
+}
+
+/// Row count of `key.table` if it exists and its provenance matches `key`.
+fn current_row_count(conn: &Connection, key: &ProvenanceKey) -> OvertureResult<Option<i64>> {
+    ensure_provenance_table(conn)?;
+    let mut stmt = conn.prepare(&format!(
+        "SELECT count(*) FROM {PROVENANCE_TABLE} \
+         WHERE table_name = ? AND theme = ? AND item_type = ? \
+             AND bbox = ? AND release = ? AND filters = ?"
+    ))?;
+    let matches: i64 = stmt.query_row(
+        duckdb::params![
+            key.table,
+            key.theme,
+            key.item_type,
+            key.bbox_text(),
+            key.release,
+            key.filters.fingerprint()
+        ],
+        |row| row.get(0),
+    )?;
+    if matches == 0 || !has_table(conn, key.table)? {
+        return Ok(None);
+    }
+
+    let mut stmt = conn.prepare(&format!("SELECT COUNT(*) FROM {}", key.table))?;
+    let row_count: i64 = stmt.query_row([], |row| row.get(0))?;
+    Ok(Some(row_count))
+}
+
+fn has_table(conn: &Connection, table_name: &str) -> OvertureResult<bool> {
+    let (schema, table) = split_qualified_name(table_name);
+    let mut stmt = conn.prepare(
+        "SELECT count(*) FROM information_schema.tables \
+         WHERE table_schema = ? AND table_name = ?",
+    )?;
+    let count: i64 = stmt.query_row(duckdb::params![schema, table], |row| row.get(0))?;
+    Ok(count > 0)
+}
+
+/// Resolve the source path and SQL that `overture_extract_to_table` would
+/// execute for the same arguments.
+pub fn overture_extract_explain(
+    theme: &str,
+    item_type: &str,
+    bbox: BBox,
+    table_name: Option<&str>,
+    filters: &ExtractFilters,
+) -> OvertureResult<OvertureExtractPlan> {
+    let table = filters.target_table(theme, item_type, table_name)?;
+    filters.validate(theme, item_type)?;
+
+    let release = overture_release();
+    let source_path = overture_source_path(&release, theme, item_type);
+    // The source is not read here, so a `since` filter is shown even for
+    // themes whose parquet turns out to lack `update_time`.
+    let sql = build_extract_sql(&table, &source_path, bbox, filters, filters.since.as_deref());
+    Ok(OvertureExtractPlan {
         table,
         release,
-        row_count,
+        source_path,
+        bbox_filter: bbox_filter_sql(bbox),
+        sql,
     })
 }
 
+fn build_extract_sql(
+    table: &str,
+    source_path: &str,
+    bbox: BBox,
+    filters: &ExtractFilters,
+    since: Option<&str>,
+) -> String {
+    let filter = extract_filter_sql(bbox, filters, since);
+    format!(
+        "CREATE OR REPLACE TABLE {table} AS \
+         SELECT * FROM read_parquet('{source_path}') \
+         WHERE {filter}"
+    )
+}
+
+/// WHERE clause of an extract: the bbox plus `filters` and the `since` date.
+fn extract_filter_sql(bbox: BBox, filters: &ExtractFilters, since: Option<&str>) -> String {
+    let mut filter = bbox_filter_sql(bbox);
+    for requirement in &filters.require {
+        filter.push_str(&format!(" AND {} IS NOT NULL", requirement.not_null_sql()));
+    }
+    if let Some(level) = filters.level {
+        filter.push_str(&format!(" AND subtype = '{}'", level.subtype()));
+    }
+    if let Some(date) = since {
+        filter.push_str(&format!(" AND update_time >= '{date}'"));
+    }
+    filter
+}
+
+fn bbox_filter_sql(bbox: BBox) -> String {
+    format!(
+        "bbox.xmin <= {xmax} AND bbox.xmax >= {xmin} \
+         AND bbox.ymin <= {ymax} AND bbox.ymax >= {ymin}",
+        xmin = bbox.xmin,
+        ymin = bbox.ymin,
+        xmax = bbox.xmax,
+        ymax = bbox.ymax,
+    )
+}
+
+/// Search the `{table}_lookup` labels for `query`.
+///
+/// Every whitespace-separated query token must appear somewhere in the label,
+/// so "park lincoln" finds "Lincoln Park". Contiguous matches still rank first
+/// (exact, then prefix, then word-boundary, then substring); reordered matches
+/// follow, ordered by how many tokens start a word in the label.
 pub fn overture_search(
     db_path: &str,
     table_name: &str,
     query: &str,
     limit: usize,
 ) -> OvertureResult<Vec<OvertureSearchResult>> {
-    validate_table_name(table_name)?;
+    overture_search_with_highlight(db_path, table_name, query, limit, false)
+}
+
+/// [`overture_search`], optionally marking up which part of each label
+/// matched: the whole query when it appears contiguously, otherwise each
+/// query token.
+pub fn overture_search_with_highlight(
+    db_path: &str,
+    table_name: &str,
+    query: &str,
+    limit: usize,
+    highlight: bool,
+) -> OvertureResult<Vec<OvertureSearchResult>> {
+    overture_search_page(
+        db_path,
+        table_name,
+        query,
+        limit,
+        highlight,
+        false,
+        SearchWeights::default(),
+    )
+    .map(|page| page.results)
+}
+
+/// [`overture_search_with_highlight`], also reporting whether more labels
+/// matched than `limit` (after the max-results cap) allowed.
+///
+/// With `include_coords`, each hit also gets `lat`/`lon` from the base
+/// table's geometry, placed the same way as [`overture_geocode`]. Tables
+/// that cannot be geocoded (no `id`/`geometry` column) still return their
+/// hits, just without coordinates.
+///
+/// `weights` orders the hits; [`SearchWeights::default`] is the balanced
+/// ranking used by [`overture_search`].
+pub fn overture_search_page(
+    db_path: &str,
+    table_name: &str,
+    query: &str,
+    limit: usize,
+    highlight: bool,
+    include_coords: bool,
+    weights: SearchWeights,
+) -> OvertureResult<OvertureResultPage<OvertureSearchResult>> {
+    validate_qualified_table_name(table_name)?;
     if query.trim().is_empty() {
         return Err("search query cannot be empty".into());
     }
-    let safe_limit = limit.clamp(1, 1000);
+    let safe_limit = limit.clamp(1, max_results());
 
-    let conn = Connection::open(db_path)?;
-        let lookup_table = lookup_table_name(table_name);
-        validate_table_name(&lookup_table)?;
+    let conn = open_connection(db_path)?;
+    let lookup_table = lookup_table_name(table_name);
+    validate_qualified_table_name(&lookup_table)?;
+
+    let coords_source = if include_coords {
+        match geocode_source(&conn, table_name, &lookup_table) {
+            Ok(source) => {
+                ensure_extensions(&conn)?;
+                Some(source)
+            }
+            Err(err) => {
+                tracing::info!("overture_search_page: returning hits without coordinates: {err}");
+                None
+            }
+        }
+    } else {
+        None
+    };
+    let normalized = query.split_whitespace().collect::<Vec<_>>().join(" ");
+    let (matches, ranking) = label_match_sql(&normalized, weights);
+    // One extra row tells us whether the result was cut off.
+    let sql = match coords_source {
+        // The hits are ranked and cut to the limit before the join, so only
+        // those ids are looked up in the base table.
+        Some(source) => format!(
+            "WITH hits AS ( \
+                 SELECT l.source_id, l.label, row_number() OVER (ORDER BY {ranking}) AS hit_rank \
+                 FROM {lookup_table} l \
+                 WHERE {matches} \
+                 ORDER BY hit_rank \
+                 LIMIT {limit} \
+             ) \
+             SELECT h.source_id AS id, h.label, \
+                    CAST(ST_Y({point}) AS DOUBLE) AS lat, CAST(ST_X({point}) AS DOUBLE) AS lon \
+             FROM hits h LEFT JOIN {table_name} t ON {key} = h.source_id \
+             ORDER BY h.hit_rank",
+            point = source.point_expr(),
+            key = id_join_key(&conn, table_name)?,
+            limit = safe_limit + 1,
+        ),
+        None => format!(
+            "SELECT l.source_id AS id, l.label \
+             FROM {lookup_table} l \
+             WHERE {matches} \
+             ORDER BY {ranking} \
+             LIMIT {limit}",
+            limit = safe_limit + 1,
+        ),
+    };
+
+    let mut stmt = conn.prepare(&sql)?;
+    let mut rows = stmt.query([])?;
 
-        let escaped_query = query.replace('\'', "''").to_lowercase();
+    let mut out = Vec::new();
+    while let Some(row) = rows.next()? {
+        let label = row.get::<_, String>(1).unwrap_or_default();
+        let (lat, lon) = if coords_source.is_some() {
+            (row.get(2).ok(), row.get(3).ok())
+        } else {
+            (None, None)
+        };
+        out.push(OvertureSearchResult {
+            id: row.get(0).ok(),
+            highlight: highlight.then(|| highlight_label(&label, &normalized)),
+            label,
+            lat,
+            lon,
+        });
+    }
+    Ok(into_page(out, safe_limit))
+}
+
+/// Search `theme`/`item_type` labels inside `bbox` straight from the Overture
+/// parquet on S3, without creating a local table.
+///
+/// Labels and ranking match [`overture_search_page`] with default weights,
+/// and hits carry coordinates when the source has geometry. Every call scans
+/// the remote source again, so it is slower per query than
+/// `overture_extract` followed by `overture_search`; use it for one-off
+/// lookups.
+pub fn overture_query(
+    theme: &str,
+    item_type: &str,
+    bbox: BBox,
+    query: &str,
+    limit: usize,
+) -> OvertureResult<OvertureResultPage<OvertureSearchResult>> {
+    validate_theme_type(theme, item_type)?;
+    if query.trim().is_empty() {
+        return Err("search query cannot be empty".into());
+    }
+    let safe_limit = limit.clamp(1, max_results());
+
+    let conn = open_in_memory()?;
+    ensure_extensions(&conn)?;
+    let source_path = overture_source_path(&overture_release(), theme, item_type);
+    tracing::warn!(
+        source_path,
+        "overture_query: scanning remote parquet; this is slower per call than \
+         overture_extract + overture_search"
+    );
+    let out = query_source(&conn, &source_path, theme, bbox, query, safe_limit)?;
+    Ok(into_page(out, safe_limit))
+}
+
+/// Run the single-pass bbox + label query of [`overture_query`] against
+/// `source_path`, returning up to `limit + 1` rows so the caller can tell
+/// whether the result was cut off.
+fn query_source(
+    conn: &Connection,
+    source_path: &str,
+    theme: &str,
+    bbox: BBox,
+    query: &str,
+    limit: usize,
+) -> OvertureResult<Vec<OvertureSearchResult>> {
+    let has_names = source_has_column(conn, source_path, "names")?;
+    let label = lookup_label_sql(theme, has_names);
+    let coords = if source_has_column(conn, source_path, "geometry")? {
+        let point = if theme == "addresses" {
+            GeocodeSource::Addresses.point_expr()
+        } else {
+            GeocodeSource::Names.point_expr()
+        };
+        format!("CAST(ST_Y({point}) AS DOUBLE) AS lat, CAST(ST_X({point}) AS DOUBLE) AS lon")
+    } else {
+        "CAST(NULL AS DOUBLE) AS lat, CAST(NULL AS DOUBLE) AS lon".to_string()
+    };
+
+    let normalized = query.split_whitespace().collect::<Vec<_>>().join(" ");
+    let (matches, ranking) = label_match_sql(&normalized, SearchWeights::default());
     let sql = format!(
-                "SELECT source_id AS id, label \
-                 FROM {table} \
-                 WHERE label_norm LIKE '%{query}%' \
-                 ORDER BY \
-                     CASE \
-                         WHEN label_norm = '{query}' THEN 0 \
-                         WHEN label_norm LIKE '{query}%' THEN 1 \
-                         WHEN label_norm LIKE '% {query}%' THEN 2 \
-                         ELSE 3 \
-                     END, \
-                     length(label_norm), \
-                     label \
+        "WITH l AS ( \
+             SELECT CAST(t.id AS VARCHAR) AS source_id, {label} AS label, {coords} \
+             FROM read_parquet('{source_path}') t \
+             WHERE {bbox_filter} \
+         ) \
+         SELECT l.source_id AS id, l.label, l.lat, l.lon \
+         FROM (SELECT *, lower(label) AS label_norm FROM l) l \
+         WHERE l.label IS NOT NULL AND l.label != '' AND {matches} \
+         ORDER BY {ranking} \
          LIMIT {limit}",
-                table = lookup_table,
-        query = escaped_query,
-        limit = safe_limit,
+        bbox_filter = bbox_filter_sql(bbox),
+        limit = limit + 1,
     );
 
     let mut stmt = conn.prepare(&sql)?;
@@ -166,53 +1213,181 @@ pub fn overture_search(
         out.push(OvertureSearchResult {
             id: row.get(0).ok(),
             label: row.get::<_, String>(1).unwrap_or_default(),
+            highlight: None,
+            lat: row.get(2).ok(),
+            lon: row.get(3).ok(),
         });
     }
     Ok(out)
 }
 
+/// WHERE and ORDER BY clauses matching `l.label_norm` against the
+/// whitespace-normalized `query`, ranked by `weights` as described on
+/// [`overture_search`].
+fn label_match_sql(query: &str, weights: SearchWeights) -> (String, String) {
+    let escaped_query = query.replace('\'', "''").to_lowercase();
+    let tokens: Vec<&str> = escaped_query.split(' ').collect();
+    let all_tokens = tokens
+        .iter()
+        .map(|token| format!("l.label_norm LIKE '%{token}%'"))
+        .collect::<Vec<_>>()
+        .join(" AND ");
+    let word_start_hits = tokens
+        .iter()
+        .map(|token| {
+            format!(
+                "CASE WHEN l.label_norm LIKE '{token}%' OR l.label_norm LIKE '% {token}%' \
+                 THEN 1 ELSE 0 END"
+            )
+        })
+        .collect::<Vec<_>>()
+        .join(" + ");
+
+    let matches = format!("(l.label_norm LIKE '%{escaped_query}%' OR ({all_tokens}))");
+    let ranking = format!(
+        "CASE \
+             WHEN l.label_norm = '{query}' THEN {exact} \
+             WHEN l.label_norm LIKE '{query}%' THEN {prefix} \
+             WHEN l.label_norm LIKE '% {query}%' THEN {word_prefix} \
+             WHEN l.label_norm LIKE '%{query}%' THEN {substring} \
+             ELSE {tokens} \
+         END + ({word_start_hits}) * {token_word_start} DESC, \
+         length(l.label_norm), \
+         l.label",
+        query = escaped_query,
+        exact = weights.exact,
+        prefix = weights.prefix,
+        word_prefix = weights.word_prefix,
+        substring = weights.substring,
+        tokens = weights.tokens,
+        token_word_start = weights.token_word_start,
+    );
+    (matches, ranking)
+}
+
+/// Wrap the parts of `label` matching `query` (case-insensitively) in
+/// `<mark>`, HTML-escaping everything else.
+fn highlight_label(label: &str, query: &str) -> String {
+    // Lowercase per char so each position in `lowered` maps back to a char
+    // of `label`, even when lowercasing changes the byte length.
+    let chars: Vec<char> = label.chars().collect();
+    let mut lowered = String::new();
+    let mut owner = Vec::new();
+    for (index, ch) in chars.iter().enumerate() {
+        for lower in ch.to_lowercase() {
+            lowered.push(lower);
+            owner.resize(lowered.len(), index);
+        }
+    }
+
+    let query = query.to_lowercase();
+    let needles: Vec<&str> = if lowered.contains(query.as_str()) {
+        vec![query.as_str()]
+    } else {
+        query.split_whitespace().collect()
+    };
+
+    let mut marked = vec![false; chars.len()];
+    for needle in needles {
+        if let Some(start) = lowered.find(needle) {
+            let end = start + needle.len();
+            for index in owner[start]..=owner[end - 1] {
+                marked[index] = true;
+            }
+        }
+    }
+
+    let mut out = String::with_capacity(label.len() + 16);
+    let mut open = false;
+    for (ch, is_marked) in chars.iter().zip(&marked) {
+        if *is_marked != open {
+            out.push_str(if *is_marked { "<mark>" } else { "</mark>" });
+            open = *is_marked;
+        }
+        match ch {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            _ => out.push(*ch),
+        }
+    }
+    if open {
+        out.push_str("</mark>");
+    }
+    out
+}
+
 pub fn overture_geocode(
     db_path: &str,
     table_name: &str,
     query: &str,
     limit: usize,
 ) -> OvertureResult<Vec<OvertureGeocodeResult>> {
-    validate_table_name(table_name)?;
+    overture_geocode_page(db_path, table_name, query, limit).map(|page| page.results)
+}
+
+/// [`overture_geocode`], also reporting whether more rows matched than were
+/// returned.
+pub fn overture_geocode_page(
+    db_path: &str,
+    table_name: &str,
+    query: &str,
+    limit: usize,
+) -> OvertureResult<OvertureResultPage<OvertureGeocodeResult>> {
+    validate_qualified_table_name(table_name)?;
     if query.trim().is_empty() {
         return Err("geocode query cannot be empty".into());
     }
-    let safe_limit = limit.clamp(1, 1000);
+    let safe_limit = limit.clamp(1, max_results());
 
-    let conn = Connection::open(db_path)?;
+    let conn = open_connection(db_path)?;
+    let lookup_table = lookup_table_name(table_name);
+    validate_qualified_table_name(&lookup_table)?;
+    let source = geocode_source(&conn, table_name, &lookup_table)?;
     ensure_extensions(&conn)?;
 
-    let lookup_table = lookup_table_name(table_name);
-    validate_table_name(&lookup_table)?;
+    let point = source.point_expr();
     let escaped_query = query.replace('\'', "''").to_lowercase();
 
+    // Matches are ranked and cut to the limit before the join, so only those
+    // ids are looked up in the base table. A lookup row whose id is no longer
+    // in the table (rebuild it with overture_rebuild_lookup) is dropped after
+    // the cut and leaves the page one row short.
     let sql = format!(
-        "SELECT \
-           l.source_id AS id, \
-           l.label, \
-           CAST(ST_Y(t.geometry) AS DOUBLE) AS lat, \
-           CAST(ST_X(t.geometry) AS DOUBLE) AS lon \
-         FROM {lookup} l \
-         JOIN {table} t ON CAST(t.id AS VARCHAR) = l.source_id \
-         WHERE l.label_norm LIKE '%{query}%' \
-         ORDER BY \
-           CASE \
-             WHEN l.label_norm = '{query}' THEN 0 \
-             WHEN l.label_norm LIKE '{query}%' THEN 1 \
-             WHEN l.label_norm LIKE '% {query}%' THEN 2 \
-             ELSE 3 \
-           END, \
-           length(l.label_norm), \
-           l.label \
-         LIMIT {limit}",
+        "WITH hits AS ( \
+           SELECT \
+             l.source_id, \
+             l.label, \
+             row_number() OVER ( \
+               ORDER BY \
+                 CASE \
+                   WHEN l.label_norm = '{query}' THEN 0 \
+                   WHEN l.label_norm LIKE '{query}%' THEN 1 \
+                   WHEN l.label_norm LIKE '% {query}%' THEN 2 \
+                   ELSE 3 \
+                 END, \
+                 length(l.label_norm), \
+                 l.label \
+             ) AS hit_rank \
+           FROM {lookup} l \
+           WHERE l.label_norm LIKE '%{query}%' \
+           ORDER BY hit_rank \
+           LIMIT {limit} \
+         ) \
+         SELECT \
+           h.source_id AS id, \
+           h.label, \
+           CAST(ST_Y({point}) AS DOUBLE) AS lat, \
+           CAST(ST_X({point}) AS DOUBLE) AS lon \
+         FROM hits h \
+         JOIN {table} t ON {key} = h.source_id \
+         ORDER BY h.hit_rank",
         lookup = lookup_table,
         table = table_name,
+        key = id_join_key(&conn, table_name)?,
         query = escaped_query,
-        limit = safe_limit,
+        limit = safe_limit + 1,
     );
 
     let mut stmt = conn.prepare(&sql)?;
@@ -227,92 +1402,229 @@ pub fn overture_geocode(
             lon: row.get(3).ok(),
         });
     }
-    Ok(out)
+    Ok(into_page(out, safe_limit))
+}
+
+/// How an extract's lookup labels were built, which decides how
+/// `overture_geocode` turns the matched row into coordinates.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum GeocodeSource {
+    /// `addresses` theme: labels come from number/street/postcode columns.
+    Addresses,
+    /// Any theme with a `names` column (places, buildings, divisions).
+    Names,
+}
+
+impl GeocodeSource {
+    /// Point expression for the joined base row `t`. Address rows are points;
+    /// named features (places, buildings, division areas) may be polygons, so
+    /// they are placed at their centroid.
+    fn point_expr(self) -> &'static str {
+        match self {
+            GeocodeSource::Addresses => "t.geometry",
+            GeocodeSource::Names => "ST_Centroid(t.geometry)",
+        }
+    }
+}
+
+/// Check that `table_name` can be geocoded and say which kind of extract it
+/// is. Tables without `id`/`geometry`, without address or `names` columns,
+/// or without a lookup table get an error naming the fix instead of an
+/// empty or meaningless result.
+fn geocode_source(
+    conn: &Connection,
+    table_name: &str,
+    lookup_table: &str,
+) -> OvertureResult<GeocodeSource> {
+    if !has_table(conn, table_name)? {
+        return Err(format!("table {table_name} does not exist").into());
+    }
+    if !has_column(conn, table_name, "id")? {
+        return Err(format!(
+            "table {table_name} has no id column and cannot be geocoded; \
+             geocode an overture_extract table from the addresses or places theme"
+        )
+        .into());
+    }
+    // Checked here because the geocode SQL reads t.geometry, and DuckDB's
+    // own binder error for a missing column does not say how to fix it.
+    if !has_column(conn, table_name, "geometry")? {
+        return Err(format!(
+            "table '{table_name}' has no geometry column; re-extract it with geometry \
+             (overture_extract keeps it unless the source theme has none)"
+        )
+        .into());
+    }
+
+    let has_address =
+        has_column(conn, table_name, "street")? && has_column(conn, table_name, "number")?;
+    let source = if has_address {
+        GeocodeSource::Addresses
+    } else if has_column(conn, table_name, "names")? {
+        GeocodeSource::Names
+    } else {
+        return Err(format!(
+            "table {table_name} has neither address (number, street) nor names columns, \
+             so it has no labels to geocode against; extract the addresses theme for \
+             street addresses or the places theme for named places"
+        )
+        .into());
+    };
+
+    if !has_table(conn, lookup_table)? {
+        return Err(format!(
+            "lookup table {lookup_table} does not exist; re-run overture_extract for {table_name} to build it"
+        )
+        .into());
+    }
+    Ok(source)
+}
+
+/// Trim rows fetched with `LIMIT limit + 1` back to `limit`, flagging overflow.
+fn into_page<T>(mut rows: Vec<T>, limit: usize) -> OvertureResultPage<T> {
+    let truncated = rows.len() > limit;
+    rows.truncate(limit);
+    OvertureResultPage {
+        results: rows,
+        truncated,
+    }
+}
+
+impl From<&OvertureSearchResult> for OvertureGeocodeResult {
+    fn from(value: &OvertureSearchResult) -> Self {
+        Self {
+            id: value.id.clone(),
+            label: value.label.clone(),
+            lat: value.lat,
+            lon: value.lon,
+        }
+    }
+}
+
+/// Write search or geocode hits to `table_name` as `(id, label, lat, lon)`,
+/// replacing the table if it exists, and return the number of rows written.
+/// Missing coordinates are stored as NULL. `source_table` is the table the
+/// hits came from; neither it nor its `_lookup` table may be the target.
+pub fn overture_materialize_results(
+    db_path: &str,
+    source_table: &str,
+    table_name: &str,
+    results: &[OvertureGeocodeResult],
+) -> OvertureResult<usize> {
+    validate_qualified_table_name(table_name)?;
+    let target = split_qualified_name(table_name);
+    let same_table = |other: &str| {
+        let (schema, name) = split_qualified_name(other);
+        schema.eq_ignore_ascii_case(target.0) && name.eq_ignore_ascii_case(target.1)
+    };
+    if same_table(source_table) || same_table(&lookup_table_name(source_table)) {
+        return Err(format!(
+            "--into table {table_name} must differ from {source_table} and its lookup table"
+        )
+        .into());
+    }
+    let conn = open_connection(db_path)?;
+    let (schema, _) = split_qualified_name(table_name);
+    if schema != "main" {
+        conn.execute_batch(&format!("CREATE SCHEMA IF NOT EXISTS {schema}"))?;
+    }
+
+    conn.execute_batch(&format!(
+        "BEGIN TRANSACTION; \
+         CREATE OR REPLACE TABLE {table_name} (id VARCHAR, label VARCHAR, lat DOUBLE, lon DOUBLE);"
+    ))?;
+    let mut stmt = conn.prepare(&format!("INSERT INTO {table_name} VALUES (?, ?, ?, ?)"))?;
+    for result in results {
+        if let Err(err) =
+            stmt.execute(duckdb::params![result.id, result.label, result.lat, result.lon])
+        {
+            conn.execute_batch("ROLLBACK")?;
+            return Err(format!("writing results to {table_name} failed: {err}").into());
+        }
+    }
+    conn.execute_batch("COMMIT")?;
+    tracing::info!(
+        table = table_name,
+        rows = results.len(),
+        "overture_materialize_results: wrote results"
+    );
+    Ok(results.len())
+}
+
+/// Upper bound for search/geocode limits: `SPATIA_OVERTURE_MAX_RESULTS` if it
+/// is a positive integer, else [`DEFAULT_MAX_RESULTS`].
+fn max_results() -> usize {
+    match std::env::var("SPATIA_OVERTURE_MAX_RESULTS") {
+        Ok(raw) => match raw.trim().parse::<usize>() {
+            Ok(n) if n > 0 => n,
+            _ => {
+                tracing::warn!(
+                    value = raw.as_str(),
+                    "SPATIA_OVERTURE_MAX_RESULTS is not a positive integer, using default"
+                );
+                DEFAULT_MAX_RESULTS
+            }
+        },
+        Err(_) => DEFAULT_MAX_RESULTS,
+    }
 }
 
 fn create_lookup_table(conn: &Connection, table_name: &str, theme: &str) -> OvertureResult<()> {
         let lookup_table = lookup_table_name(table_name);
-        validate_table_name(&lookup_table)?;
-
-        let sql = if theme == "addresses" {
-                format!(
-                        "CREATE OR REPLACE TABLE {lookup} AS \
-                         SELECT \
-                             CAST(id AS VARCHAR) AS source_id, \
-                             trim(regexp_replace( \
-                                 concat_ws(' ', \
-                                     coalesce(number, ''), \
-                                     coalesce(street, ''), \
-                                     coalesce(postal_city, ''), \
-                                     coalesce(postcode, ''), \
-                                     coalesce(country, '') \
-                                 ), \
-                                 '\\s+', \
-                                 ' ' \
-                             )) AS label, \
-                             lower(trim(regexp_replace( \
-                                 concat_ws(' ', \
-                                     coalesce(number, ''), \
-                                     coalesce(street, ''), \
-                                     coalesce(postal_city, ''), \
-                                     coalesce(postcode, ''), \
-                                     coalesce(country, '') \
-                                 ), \
-                                 '\\s+', \
-                                 ' ' \
-                             ))) AS label_norm \
-                         FROM {source} \
-                         WHERE trim(regexp_replace( \
-                                 concat_ws(' ', \
-                                     coalesce(number, ''), \
-                                     coalesce(street, ''), \
-                                     coalesce(postal_city, ''), \
-                                     coalesce(postcode, ''), \
-                                     coalesce(country, '') \
-                                 ), \
-                                 '\\s+', \
-                                 ' ' \
-                             )) != ''",
-                        lookup = lookup_table,
-                        source = table_name
-                )
-        } else if has_column(conn, table_name, "names")? {
-                format!(
-                        "CREATE OR REPLACE TABLE {lookup} AS \
-                         SELECT \
-                             CAST(id AS VARCHAR) AS source_id, \
-                             trim(CAST(names AS VARCHAR)) AS label, \
-                             lower(trim(CAST(names AS VARCHAR))) AS label_norm \
-                         FROM {source} \
-                         WHERE names IS NOT NULL \
-                             AND trim(CAST(names AS VARCHAR)) != ''",
-                        lookup = lookup_table,
-                        source = table_name
-                )
-        } else {
-                format!(
-                        "CREATE OR REPLACE TABLE {lookup} AS \
-                         SELECT \
-                             CAST(id AS VARCHAR) AS source_id, \
-                             CAST(id AS VARCHAR) AS label, \
-                             lower(CAST(id AS VARCHAR)) AS label_norm \
-                         FROM {source}",
-                        lookup = lookup_table,
-                        source = table_name
-                )
-        };
+        validate_qualified_table_name(&lookup_table)?;
+
+        let has_names = has_column(conn, table_name, "names")?;
+        let sql = format!(
+                "CREATE OR REPLACE TABLE {lookup} AS \
+                 SELECT source_id, label, lower(label) AS label_norm \
+                 FROM ( \
+                     SELECT CAST(id AS VARCHAR) AS source_id, {label} AS label \
+                     FROM {source} \
+                 ) \
+                 WHERE label IS NOT NULL AND label != ''",
+                lookup = lookup_table,
+                label = lookup_label_sql(theme, has_names),
+                source = table_name
+        );
 
         conn.execute(&sql, [])?;
         Ok(())
 }
 
+/// Label expression for a row of `theme`: the joined address parts for
+/// addresses, the primary name for divisions, the whole `names` struct as
+/// text for other named themes, else the id. Shared by the lookup table and
+/// [`overture_query`] so both match the same text.
+fn lookup_label_sql(theme: &str, has_names: bool) -> &'static str {
+        if theme == "addresses" {
+                "trim(regexp_replace( \
+                     concat_ws(' ', \
+                         coalesce(number, ''), \
+                         coalesce(street, ''), \
+                         coalesce(postal_city, ''), \
+                         coalesce(postcode, ''), \
+                         coalesce(country, '') \
+                     ), \
+                     '\\s+', \
+                     ' ' \
+                 ))"
+        } else if theme == "divisions" {
+                "trim(names.primary)"
+        } else if has_names {
+                "trim(CAST(names AS VARCHAR))"
+        } else {
+                "CAST(id AS VARCHAR)"
+        }
+}
+
 fn has_column(conn: &Connection, table_name: &str, column: &str) -> OvertureResult<bool> {
+        let (schema, table) = split_qualified_name(table_name);
         let mut stmt = conn.prepare(
             "SELECT column_name FROM information_schema.columns \
-             WHERE table_schema = 'main' AND table_name = ? \
+             WHERE table_schema = ? AND table_name = ? \
              ORDER BY ordinal_position"
         )?;
-        let mut rows = stmt.query(duckdb::params![table_name])?;
+        let mut rows = stmt.query(duckdb::params![schema, table])?;
 
         while let Some(row) = rows.next()? {
                 let name: String = row.get(0)?;
@@ -323,11 +1635,30 @@ fn has_column(conn: &Connection, table_name: &str, column: &str) -> OvertureResu
         Ok(false)
 }
 
+/// Base-table side of the `id = lookup.source_id` join. Overture ids are
+/// VARCHAR and are compared as they are, so the ART index that
+/// [`overture_index_extract`] builds on `id` can serve the join; other id
+/// types are cast to match the VARCHAR `source_id`.
+fn id_join_key(conn: &Connection, table_name: &str) -> OvertureResult<&'static str> {
+    let (schema, table) = split_qualified_name(table_name);
+    let mut stmt = conn.prepare(
+        "SELECT data_type FROM information_schema.columns \
+         WHERE table_schema = ? AND table_name = ? AND lower(column_name) = 'id'",
+    )?;
+    let mut rows = stmt.query(duckdb::params![schema, table])?;
+    let is_varchar = match rows.next()? {
+        Some(row) => row.get::<_, String>(0)? == "VARCHAR",
+        None => false,
+    };
+    Ok(if is_varchar { "t.id" } else { "CAST(t.id AS VARCHAR)" })
+}
+
+/// Load `spatial` and `httpfs` with the shared [`load_extension`] and create
+/// the S3 secret when credentials are configured.
 fn ensure_extensions(conn: &Connection) -> OvertureResult<()> {
-    conn.execute("INSTALL spatial", [])?;
-    conn.execute("LOAD spatial", [])?;
-    conn.execute("INSTALL httpfs", [])?;
-    conn.execute("LOAD httpfs", [])?;
+    load_extension(conn, "spatial")?;
+    load_extension(conn, "httpfs")?;
+    spatia_geocode::configure_s3_secret(conn)?;
     Ok(())
 }
 
@@ -352,7 +1683,9 @@ fn overture_source_path(release: &str, theme: &str, item_type: &str) -> String {
     )
 }
 
-fn overture_release() -> String {
+/// Overture release used for extracts: `SPATIA_OVERTURE_RELEASE` if set, else
+/// the pinned [`OVERTURE_RELEASE`].
+pub fn overture_release() -> String {
     std::env::var("SPATIA_OVERTURE_RELEASE").unwrap_or_else(|_| OVERTURE_RELEASE.to_string())
 }
 
@@ -375,7 +1708,8 @@ pub fn fetch_buildings_in_bbox(
     xmax: f64,
     ymax: f64,
 ) -> OvertureResult<String> {
-    let conn = Connection::open(db_path)?;
+    BBox::validate(xmin, ymin, xmax, ymax)?;
+    let conn = open_connection(db_path)?;
     ensure_extensions(&conn)?;
 
     // Create cache table if it doesn't exist
@@ -479,8 +1813,125 @@ pub fn fetch_buildings_in_bbox(
 #[cfg(test)]
 mod tests {
     use super::{
-        default_table_name, lookup_table_name, overture_source_path, BBox, OVERTURE_RELEASE,
+        bbox_filter_sql, create_lookup_table, current_row_count,
+        default_table_name, geocode_source, highlight_label, id_join_key, load_extension,
+        lookup_table_name,
+        out_of_memory_hint, overture_extract_explain, overture_index_extract,
+        overture_geocode_page, overture_rebuild_lookup, overture_search, overture_search_page,
+        overture_materialize_results, overture_search_with_highlight, overture_source_path,
+        query_source, record_provenance, run_extract, table_extent, BBox, DivisionLevel, ExtractFilters,
+        ExtractRequirement, GeocodeSource, OvertureGeocodeResult, ProvenanceKey, SearchWeights,
+        OVERTURE_RELEASE, TEMP_DIR_ENV,
     };
+    use duckdb::Connection;
+    use std::fs;
+    use std::time::{SystemTime, UNIX_EPOCH};
+
+    fn unique_suffix() -> u128 {
+        SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .expect("time")
+            .as_nanos()
+    }
+
+    /// A fresh database path; remove it with [`cleanup_temp_db`].
+    fn temp_db_path() -> String {
+        format!("/tmp/spatia_overture_test_{}.duckdb", unique_suffix())
+    }
+
+    fn temp_db_with_lookup(labels: &[&str]) -> String {
+        let db_path = temp_db_path();
+        let conn = Connection::open(&db_path).expect("open");
+        conn.execute_batch(
+            "CREATE TABLE places_lookup (source_id VARCHAR, label VARCHAR, label_norm VARCHAR)",
+        )
+        .expect("create lookup");
+        for (i, label) in labels.iter().enumerate() {
+            conn.execute(
+                "INSERT INTO places_lookup VALUES (?, ?, lower(?))",
+                duckdb::params![i.to_string(), label, label],
+            )
+            .expect("insert label");
+        }
+        db_path
+    }
+
+    fn cleanup_temp_db(db_path: &str) {
+        let _ = fs::remove_file(db_path);
+        let _ = fs::remove_file(format!("{db_path}.wal"));
+        let _ = fs::remove_file(format!("{db_path}.wal.lck"));
+    }
+
+    fn search_labels(db_path: &str, query: &str) -> Vec<String> {
+        overture_search(db_path, "places", query, 10)
+            .expect("search")
+            .into_iter()
+            .map(|r| r.label)
+            .collect()
+    }
+
+    #[test]
+    fn highlight_marks_contiguous_query() {
+        assert_eq!(highlight_label("Lincoln Park", "coln pa"), "Lin<mark>coln Pa</mark>rk");
+    }
+
+    #[test]
+    fn highlight_marks_each_token_when_reordered() {
+        assert_eq!(
+            highlight_label("Lincoln Park", "park lincoln"),
+            "<mark>Lincoln</mark> <mark>Park</mark>"
+        );
+    }
+
+    #[test]
+    fn highlight_escapes_html_and_handles_non_ascii() {
+        assert_eq!(highlight_label("Café <Bar>", "café"), "<mark>Café</mark> &lt;Bar&gt;");
+    }
+
+    #[test]
+    fn search_highlight_is_opt_in() {
+        let db_path = temp_db_with_lookup(&["Lincoln Park"]);
+        let plain = overture_search(&db_path, "places", "park", 10).expect("search");
+        assert_eq!(plain[0].highlight, None);
+        let marked =
+            overture_search_with_highlight(&db_path, "places", "park", 10, true).expect("search");
+        assert_eq!(marked[0].highlight.as_deref(), Some("Lincoln <mark>Park</mark>"));
+        cleanup_temp_db(&db_path);
+    }
+
+    #[test]
+    fn search_matches_reordered_tokens() {
+        let db_path = temp_db_with_lookup(&["Lincoln Park", "Lincoln Elementary", "Pike Place"]);
+        assert_eq!(search_labels(&db_path, "park lincoln"), vec!["Lincoln Park"]);
+        cleanup_temp_db(&db_path);
+    }
+
+    #[test]
+    fn search_requires_every_token() {
+        let db_path = temp_db_with_lookup(&["Lincoln Park", "Green Lake Park"]);
+        assert!(search_labels(&db_path, "lincoln lake").is_empty());
+        cleanup_temp_db(&db_path);
+    }
+
+    #[test]
+    fn search_ranks_contiguous_match_above_reordered() {
+        let db_path = temp_db_with_lookup(&["Park at Lincoln Square", "Lincoln Park"]);
+        assert_eq!(
+            search_labels(&db_path, "lincoln park"),
+            vec!["Lincoln Park", "Park at Lincoln Square"]
+        );
+        cleanup_temp_db(&db_path);
+    }
+
+    #[test]
+    fn search_ranks_word_start_tokens_above_substrings() {
+        let db_path = temp_db_with_lookup(&["Oakparks Lincolnshire Hall", "Park North Lincoln"]);
+        assert_eq!(
+            search_labels(&db_path, "lincoln park"),
+            vec!["Park North Lincoln", "Oakparks Lincolnshire Hall"]
+        );
+        cleanup_temp_db(&db_path);
+    }
 
     #[test]
     fn bbox_parse_success() {
@@ -491,10 +1942,73 @@ mod tests {
         assert_eq!(bbox.ymax, 47.7);
     }
 
+    #[test]
+    fn bbox_parse_reports_non_numeric_coordinate() {
+        let err = BBox::parse("-122.4,47.5,east,47.7").expect_err("should fail");
+        assert_eq!(err.to_string(), "bbox xmax 'east' is not a valid number");
+        let err = BBox::parse("-122.4,47.5,-122.2,47.x").expect_err("should fail");
+        assert_eq!(err.to_string(), "bbox ymax '47.x' is not a valid number");
+    }
+
+    #[test]
+    fn bbox_parse_reports_each_malformed_field() {
+        let cases = [
+            ("abc,47.5,-122.2,47.7", "bbox xmin 'abc' is not a valid number"),
+            ("-122.4,4 7,-122.2,47.7", "bbox ymin '4 7' is not a valid number"),
+            ("-122.4,47.5,,47.7", "bbox xmax is missing"),
+            ("-122.4,47.5,-122.2,", "bbox ymax is missing"),
+            ("-122.4,NaN,-122.2,47.7", "bbox ymin must be a finite number, got NaN"),
+            ("-122.4,47.5,inf,47.7", "bbox xmax must be a finite number, got inf"),
+            ("-122.4,47.5,-122.2", "bbox must be: xmin,ymin,xmax,ymax (got 3 values)"),
+            ("", "bbox must be: xmin,ymin,xmax,ymax (got 1 value)"),
+        ];
+        for (input, expected) in cases {
+            let err = BBox::parse(input).expect_err(input);
+            assert_eq!(err.to_string(), expected, "input {input:?}");
+        }
+    }
+
     #[test]
     fn bbox_parse_rejects_invalid_order() {
         let err = BBox::parse("1,1,0,2").expect_err("should fail");
-        assert!(err.to_string().contains("xmin < xmax"));
+        assert_eq!(err.to_string(), "bbox must satisfy xmin < xmax, got xmin 1 and xmax 0");
+        let err = BBox::validate(0.0, 2.0, 1.0, 2.0).expect_err("should fail");
+        assert!(err.to_string().contains("ymin < ymax"));
+    }
+
+    #[test]
+    fn bbox_parse_rejects_out_of_range_coordinates() {
+        let err = BBox::parse("-200,-100,200,100").expect_err("out of range");
+        assert_eq!(
+            err.to_string(),
+            "bbox xmin -200 is outside the longitude range [-180, 180]"
+        );
+        let err = BBox::parse("-122.4,47.5,-122.2,95").expect_err("out of range");
+        assert_eq!(
+            err.to_string(),
+            "bbox ymax 95 is outside the latitude range [-90, 90]"
+        );
+        assert!(BBox::parse("-180,-90,180,90").is_ok());
+
+        let bbox = BBox::parse_projected("500000,5200000,510000,5210000").expect("projected");
+        assert_eq!(bbox.xmax, 510000.0);
+    }
+
+    #[test]
+    fn bbox_parse_flags_swapped_lat_lon() {
+        let err = BBox::parse("47.5,-122.4,47.7,-122.2").expect_err("swapped");
+        assert_eq!(
+            err.to_string(),
+            "bbox ymin -122.4 is outside the latitude range [-90, 90]; the values look like \
+             lat,lon, but the order is xmin,ymin,xmax,ymax (lon,lat)"
+        );
+    }
+
+    #[test]
+    fn bbox_area_and_wkt() {
+        let bbox = BBox::validate(-1.0, 2.0, 1.0, 2.5).expect("bbox");
+        assert_eq!(bbox.area_deg2(), 1.0);
+        assert_eq!(bbox.to_wkt(), "POLYGON((-1 2, 1 2, 1 2.5, -1 2.5, -1 2))");
     }
 
     #[test]
@@ -528,4 +2042,750 @@ mod tests {
         assert!(path.contains("theme=addresses"));
         assert!(path.contains("type=address"));
     }
+
+    #[test]
+    fn explain_resolves_table_source_and_filter() {
+        let bbox = BBox::parse("-122.4,47.5,-122.2,47.7").expect("bbox");
+        let filters = ExtractFilters::default();
+        let plan =
+            overture_extract_explain("places", "place", bbox, None, &filters).expect("explain");
+        assert_eq!(plan.table, "overture_places_place");
+        assert!(plan.source_path.contains("theme=places"));
+        assert!(plan.source_path.contains(&plan.release));
+        assert_eq!(
+            plan.bbox_filter,
+            "bbox.xmin <= -122.2 AND bbox.xmax >= -122.4 AND bbox.ymin <= 47.7 AND bbox.ymax >= 47.5"
+        );
+        assert!(plan.sql.starts_with("CREATE OR REPLACE TABLE overture_places_place AS"));
+        assert!(plan.sql.contains(&plan.source_path));
+        assert!(plan.sql.ends_with(&plan.bbox_filter));
+    }
+
+    #[test]
+    fn explain_rejects_invalid_table_name() {
+        let bbox = BBox::parse("0,0,1,1").expect("bbox");
+        let filters = ExtractFilters::default();
+        assert!(
+            overture_extract_explain("places", "place", bbox, Some("bad-name"), &filters).is_err()
+        );
+    }
+
+    #[test]
+    fn explain_appends_required_column_filters() {
+        let bbox = BBox::parse("0,0,1,1").expect("bbox");
+        let filters = ExtractFilters {
+            require: vec![ExtractRequirement::Names, ExtractRequirement::Geometry],
+            ..Default::default()
+        };
+        let plan =
+            overture_extract_explain("places", "place", bbox, None, &filters).expect("explain");
+        assert!(plan
+            .sql
+            .ends_with("AND names.primary IS NOT NULL AND geometry IS NOT NULL"));
+    }
+
+    #[test]
+    fn extract_requirement_parses_case_insensitively() {
+        assert_eq!("Names".parse::<ExtractRequirement>(), Ok(ExtractRequirement::Names));
+        assert_eq!("geometry".parse::<ExtractRequirement>(), Ok(ExtractRequirement::Geometry));
+        assert!("bbox".parse::<ExtractRequirement>().is_err());
+    }
+
+    #[test]
+    fn explain_filters_divisions_by_level() {
+        let bbox = BBox::parse("0,0,1,1").expect("bbox");
+        let filters = ExtractFilters {
+            level: Some(DivisionLevel::Region),
+            ..Default::default()
+        };
+        let plan = overture_extract_explain("divisions", "division_area", bbox, None, &filters)
+            .expect("explain");
+        assert!(plan.sql.ends_with("AND subtype = 'region'"));
+        assert!(plan.source_path.contains("type=division_area"));
+    }
+
+    #[test]
+    fn level_is_rejected_outside_divisions() {
+        let bbox = BBox::parse("0,0,1,1").expect("bbox");
+        let filters = ExtractFilters {
+            level: Some(DivisionLevel::Country),
+            ..Default::default()
+        };
+        let err = overture_extract_explain("places", "place", bbox, None, &filters)
+            .expect_err("should fail");
+        assert!(err.to_string().contains("only applies to the divisions theme"));
+    }
+
+    #[test]
+    fn unknown_theme_type_needs_force() {
+        let bbox = BBox::parse("0,0,1,1").expect("bbox");
+        let filters = ExtractFilters::default();
+        let err = overture_extract_explain("places", "places", bbox, None, &filters)
+            .expect_err("should fail");
+        assert!(err.to_string().contains("places/place"));
+
+        let filters = ExtractFilters {
+            force: true,
+            ..Default::default()
+        };
+        let plan = overture_extract_explain("places", "places", bbox, None, &filters)
+            .expect("forced explain");
+        assert!(plan.source_path.contains("type=places"));
+    }
+
+    #[test]
+    fn schema_option_qualifies_the_target_table() {
+        let bbox = BBox::parse("0,0,1,1").expect("bbox");
+        let filters = ExtractFilters {
+            schema: Some("overture".to_string()),
+            ..Default::default()
+        };
+        let plan =
+            overture_extract_explain("places", "place", bbox, None, &filters).expect("explain");
+        assert_eq!(plan.table, "overture.overture_places_place");
+        assert!(plan
+            .sql
+            .starts_with("CREATE OR REPLACE TABLE overture.overture_places_place AS"));
+
+        let err = overture_extract_explain("places", "place", bbox, Some("ref.places"), &filters)
+            .expect_err("double qualification");
+        assert!(err.to_string().contains("already schema-qualified"));
+        let filters = ExtractFilters {
+            schema: Some("bad-schema".to_string()),
+            ..Default::default()
+        };
+        assert!(overture_extract_explain("places", "place", bbox, None, &filters).is_err());
+    }
+
+    #[test]
+    fn explain_adds_since_filter_and_rejects_bad_dates() {
+        let bbox = BBox::parse("0,0,1,1").expect("bbox");
+        let filters = ExtractFilters {
+            since: Some("2026-01-15".to_string()),
+            ..Default::default()
+        };
+        let plan =
+            overture_extract_explain("places", "place", bbox, None, &filters).expect("explain");
+        assert!(plan.sql.ends_with("AND update_time >= '2026-01-15'"));
+
+        for bad in ["2026-1-15", "2026-13-01", "2026-01-15'; DROP", "yesterday"] {
+            let filters = ExtractFilters {
+                since: Some(bad.to_string()),
+                ..Default::default()
+            };
+            let err = overture_extract_explain("places", "place", bbox, None, &filters)
+                .expect_err("bad date");
+            assert!(err.to_string().contains("YYYY-MM-DD"), "{bad}: {err}");
+        }
+    }
+
+    #[test]
+    fn since_extract_upserts_changed_rows_by_id() {
+        let suffix = unique_suffix();
+        let source = format!("/tmp/spatia_overture_test_{suffix}.parquet");
+        let plain_source = format!("/tmp/spatia_overture_test_{suffix}_plain.parquet");
+        let conn = Connection::open_in_memory().expect("open");
+        conn.execute_batch(&format!(
+            "COPY (SELECT * FROM (VALUES \
+                 ('a', 'Alpha v2', '2026-02-01T00:00:00Z'), \
+                 ('b', 'Beta', '2025-06-01T00:00:00Z'), \
+                 ('c', 'Gamma', '2026-03-01T00:00:00Z')) AS t(id, name, update_time), \
+                 (SELECT {{'xmin': 0.5, 'ymin': 0.5, 'xmax': 0.5, 'ymax': 0.5}} AS bbox)) \
+             TO '{source}' (FORMAT PARQUET); \
+             COPY (SELECT 'd' AS id, 'Delta' AS name, \
+                 {{'xmin': 0.5, 'ymin': 0.5, 'xmax': 0.5, 'ymax': 0.5}} AS bbox) \
+             TO '{plain_source}' (FORMAT PARQUET); \
+             CREATE TABLE places_wa AS SELECT * FROM read_parquet('{source}') WHERE id = 'b'; \
+             INSERT INTO places_wa VALUES \
+                 ('a', 'Alpha', '2025-01-01T00:00:00Z', \
+                  {{'xmin': 0.5, 'ymin': 0.5, 'xmax': 0.5, 'ymax': 0.5}});"
+        ))
+        .expect("setup");
+
+        let bbox = BBox::parse("0,0,1,1").expect("bbox");
+        let filters = ExtractFilters {
+            since: Some("2026-01-01".to_string()),
+            ..Default::default()
+        };
+        run_extract(&conn, "places_wa", &source, bbox, &filters).expect("merge");
+        let mut stmt = conn
+            .prepare("SELECT string_agg(id || '=' || name, ',' ORDER BY id) FROM places_wa")
+            .expect("prepare");
+        let rows: String = stmt.query_row([], |row| row.get(0)).expect("rows");
+        assert_eq!(rows, "a=Alpha v2,b=Beta,c=Gamma");
+
+        // Without update_time the date is ignored and the table is replaced.
+        run_extract(&conn, "places_wa", &plain_source, bbox, &filters).expect("full extract");
+        let mut stmt = conn
+            .prepare("SELECT string_agg(id, ',' ORDER BY id) FROM places_wa")
+            .expect("prepare");
+        let ids: String = stmt.query_row([], |row| row.get(0)).expect("ids");
+        assert_eq!(ids, "d");
+
+        let _ = fs::remove_file(&source);
+        let _ = fs::remove_file(&plain_source);
+    }
+
+    #[test]
+    fn query_source_filters_bbox_and_ranks_labels() {
+        let suffix = unique_suffix();
+        let source = format!("/tmp/spatia_overture_test_{suffix}.parquet");
+        let conn = Connection::open_in_memory().expect("open");
+        conn.execute_batch(&format!(
+            "COPY (SELECT id, {{'primary': name}} AS names, \
+                 {{'xmin': x, 'ymin': 0.5, 'xmax': x, 'ymax': 0.5}} AS bbox \
+                 FROM (VALUES \
+                     ('a', 'Pike Place Market', 0.5), \
+                     ('b', 'Pike', 0.5), \
+                     ('c', 'Spike Lane', 0.5), \
+                     ('d', 'Pike Outside', 5.0), \
+                     ('e', 'Cafe', 0.5)) AS t(id, name, x)) \
+             TO '{source}' (FORMAT PARQUET)"
+        ))
+        .expect("setup");
+
+        let bbox = BBox::parse("0,0,1,1").expect("bbox");
+        let hits = query_source(&conn, &source, "divisions", bbox, "pike", 10).expect("query");
+        let labels: Vec<&str> = hits.iter().map(|hit| hit.label.as_str()).collect();
+        assert_eq!(labels, vec!["Pike", "Pike Place Market", "Spike Lane"]);
+        assert!(hits.iter().all(|hit| hit.lat.is_none()), "source has no geometry");
+
+        // One extra row is returned so the caller can mark the page truncated.
+        let hits = query_source(&conn, &source, "divisions", bbox, "pike", 1).expect("query");
+        assert_eq!(hits.len(), 2);
+
+        let _ = fs::remove_file(&source);
+    }
+
+    #[test]
+    fn division_level_parses_known_values_only() {
+        assert_eq!("Country".parse::<DivisionLevel>(), Ok(DivisionLevel::Country));
+        assert_eq!("locality".parse::<DivisionLevel>(), Ok(DivisionLevel::Locality));
+        assert!("neighborhood".parse::<DivisionLevel>().is_err());
+    }
+
+    #[test]
+    fn divisions_lookup_uses_primary_name() {
+        let db_path = temp_db_path();
+        let conn = Connection::open(&db_path).expect("open");
+        conn.execute_batch(
+            "CREATE TABLE regions AS SELECT * FROM (VALUES \
+                 ('r1', {'primary': 'Washington'}), \
+                 ('r2', {'primary': NULL})) AS t(id, names)",
+        )
+        .expect("setup");
+        create_lookup_table(&conn, "regions", "divisions").expect("lookup");
+        drop(conn);
+
+        let labels: Vec<String> = overture_search(&db_path, "regions", "washington", 10)
+            .expect("search")
+            .into_iter()
+            .map(|r| r.label)
+            .collect();
+        assert_eq!(labels, vec!["Washington"]);
+        cleanup_temp_db(&db_path);
+    }
+
+    #[test]
+    fn rebuild_lookup_regenerates_from_base_table() {
+        let suffix = unique_suffix();
+        // The search index lands next to the database, so keep both in one dir.
+        let dir = format!("/tmp/spatia_overture_rebuild_test_{suffix}");
+        fs::create_dir_all(&dir).expect("create dir");
+        let db_path = format!("{dir}/spatia.duckdb");
+        let conn = Connection::open(&db_path).expect("open");
+        conn.execute_batch(
+            "CREATE TABLE regions AS SELECT * FROM (VALUES \
+                 ('r1', {'primary': 'Washington'}), \
+                 ('r2', {'primary': 'Oregon'}), \
+                 ('r3', {'primary': NULL})) AS t(id, names); \
+             CREATE TABLE regions_lookup AS SELECT 'stale' AS source_id, \
+                 'Stale' AS label, 'stale' AS label_norm",
+        )
+        .expect("setup");
+        drop(conn);
+
+        let result = overture_rebuild_lookup(&db_path, "regions", "divisions").expect("rebuild");
+        assert_eq!(result.lookup_table, "regions_lookup");
+        assert_eq!(result.row_count, 2);
+        let labels: Vec<String> = overture_search(&db_path, "regions", "oregon", 10)
+            .expect("search")
+            .into_iter()
+            .map(|r| r.label)
+            .collect();
+        assert_eq!(labels, vec!["Oregon"]);
+
+        let err = overture_rebuild_lookup(&db_path, "regions", "addresses").expect_err("columns");
+        assert!(err.to_string().contains("number, street"), "got: {err}");
+        let err = overture_rebuild_lookup(&db_path, "missing", "places").expect_err("missing");
+        assert!(err.to_string().contains("does not exist"));
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn table_extent_stays_within_requested_bbox() {
+        let db_path = temp_db_path();
+        let conn = Connection::open(&db_path).expect("open");
+        conn.execute_batch(
+            "CREATE TABLE source AS SELECT id, 'POINT' AS geometry, \
+                 {'xmin': x, 'ymin': y, 'xmax': x, 'ymax': y} AS bbox \
+             FROM (VALUES ('a', -122.35, 47.55), ('b', -122.25, 47.65), \
+                 ('c', -121.0, 47.6)) AS t(id, x, y)",
+        )
+        .expect("setup");
+        let requested = BBox::parse("-122.4,47.5,-122.2,47.7").expect("bbox");
+        conn.execute_batch(&format!(
+            "CREATE TABLE places_wa AS SELECT * FROM source WHERE {}",
+            bbox_filter_sql(requested)
+        ))
+        .expect("extract");
+
+        let extent = table_extent(&conn, "places_wa")
+            .expect("extent")
+            .expect("has geometry");
+        assert_eq!(extent.xmin, -122.35);
+        assert_eq!(extent.ymax, 47.65);
+        assert!(extent.xmin >= requested.xmin && extent.xmax <= requested.xmax);
+        assert!(extent.ymin >= requested.ymin && extent.ymax <= requested.ymax);
+
+        conn.execute_batch("CREATE TABLE plain AS SELECT 1 AS id")
+            .expect("plain");
+        assert_eq!(table_extent(&conn, "plain").expect("extent"), None);
+        drop(conn);
+        cleanup_temp_db(&db_path);
+    }
+
+    #[test]
+    fn provenance_matches_only_identical_extracts() {
+        let db_path = temp_db_path();
+        let conn = Connection::open(&db_path).expect("open");
+        conn.execute_batch("CREATE TABLE places_wa AS SELECT * FROM range(3) t(id)")
+            .expect("setup");
+
+        let filters = ExtractFilters {
+            require: vec![ExtractRequirement::Names],
+            ..Default::default()
+        };
+        let key = ProvenanceKey {
+            table: "places_wa",
+            theme: "places",
+            item_type: "place",
+            bbox: BBox::parse("0,0,1,1").expect("bbox"),
+            release: "2026-02-18.0",
+            filters: &filters,
+        };
+        assert_eq!(current_row_count(&conn, &key).expect("check"), None);
+
+        record_provenance(&conn, &key).expect("record");
+        assert_eq!(current_row_count(&conn, &key).expect("check"), Some(3));
+
+        let newer_release = ProvenanceKey {
+            release: "2026-03-18.0",
+            ..key
+        };
+        assert_eq!(current_row_count(&conn, &newer_release).expect("check"), None);
+        let other_bbox = ProvenanceKey {
+            bbox: BBox::parse("0,0,2,2").expect("bbox"),
+            ..key
+        };
+        assert_eq!(current_row_count(&conn, &other_bbox).expect("check"), None);
+        let unfiltered = ExtractFilters::default();
+        let other_filters = ProvenanceKey {
+            filters: &unfiltered,
+            ..key
+        };
+        assert_eq!(current_row_count(&conn, &other_filters).expect("check"), None);
+        let since = ExtractFilters {
+            since: Some("2026-01-01".to_string()),
+            ..filters.clone()
+        };
+        let other_since = ProvenanceKey {
+            filters: &since,
+            ..key
+        };
+        assert_eq!(current_row_count(&conn, &other_since).expect("check"), None);
+        record_provenance(&conn, &other_since).expect("record since");
+        assert_eq!(current_row_count(&conn, &other_since).expect("check"), Some(3));
+        let later_since = ExtractFilters {
+            since: Some("2026-02-01".to_string()),
+            ..filters.clone()
+        };
+        let later = ProvenanceKey {
+            filters: &later_since,
+            ..key
+        };
+        assert_eq!(current_row_count(&conn, &later).expect("check"), None);
+
+        conn.execute_batch("DROP TABLE places_wa").expect("drop");
+        assert_eq!(current_row_count(&conn, &key).expect("check"), None);
+
+        drop(conn);
+        cleanup_temp_db(&db_path);
+    }
+
+    #[test]
+    fn index_extract_creates_id_index() {
+        let db_path = temp_db_path();
+        {
+            let conn = Connection::open(&db_path).expect("open");
+            conn.execute_batch(
+                "CREATE TABLE places_wa AS SELECT CAST(range AS VARCHAR) AS id FROM range(3)",
+            )
+            .expect("setup");
+        }
+
+        let indexes = overture_index_extract(&db_path, "places_wa").expect("index");
+        assert_eq!(indexes, vec!["places_wa_id_idx".to_string()]);
+        // Re-running keeps the existing index instead of failing.
+        let again = overture_index_extract(&db_path, "places_wa").expect("re-index");
+        assert_eq!(again, indexes);
+
+        let err = overture_index_extract(&db_path, "missing_table").expect_err("missing");
+        assert!(err.to_string().contains("does not exist"));
+
+        cleanup_temp_db(&db_path);
+    }
+
+    #[test]
+    fn schema_qualified_extract_is_indexed_and_searchable() {
+        let suffix = unique_suffix();
+        let dir = format!("/tmp/spatia_overture_schema_test_{suffix}");
+        fs::create_dir_all(&dir).expect("create dir");
+        let db_path = format!("{dir}/spatia.duckdb");
+        {
+            let conn = Connection::open(&db_path).expect("open");
+            conn.execute_batch(
+                "CREATE SCHEMA overture; \
+                 CREATE TABLE overture.regions AS SELECT * FROM (VALUES \
+                     ('r1', {'primary': 'Washington'}), \
+                     ('r2', {'primary': 'Oregon'})) AS t(id, names); \
+                 CREATE TABLE regions (id VARCHAR)",
+            )
+            .expect("setup");
+        }
+
+        let result =
+            overture_rebuild_lookup(&db_path, "overture.regions", "divisions").expect("rebuild");
+        assert_eq!(result.lookup_table, "overture.regions_lookup");
+        assert_eq!(result.row_count, 2);
+        let labels: Vec<String> = overture_search(&db_path, "overture.regions", "wash", 10)
+            .expect("search")
+            .into_iter()
+            .map(|r| r.label)
+            .collect();
+        assert_eq!(labels, vec!["Washington"]);
+
+        let indexes = overture_index_extract(&db_path, "overture.regions").expect("index");
+        assert_eq!(indexes, vec!["regions_id_idx".to_string()]);
+        let err = overture_index_extract(&db_path, "overture.missing").expect_err("missing");
+        assert!(err.to_string().contains("does not exist"));
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn overture_geocode_rejects_table_without_geometry() {
+        let db_path = temp_db_path();
+        {
+            let conn = Connection::open(&db_path).expect("open");
+            conn.execute_batch(
+                "CREATE TABLE places_flat AS SELECT 'p1' AS id, 'Pike Place' AS names; \
+                 CREATE TABLE places_flat_lookup AS SELECT 'p1' AS source_id, \
+                     'Pike Place' AS label, 'pike place' AS label_norm;",
+            )
+            .expect("setup");
+        }
+
+        let err = overture_geocode_page(&db_path, "places_flat", "pike", 5).expect_err("no geom");
+        assert_eq!(
+            err.to_string(),
+            "table 'places_flat' has no geometry column; re-extract it with geometry \
+             (overture_extract keeps it unless the source theme has none)"
+        );
+        cleanup_temp_db(&db_path);
+    }
+
+    #[test]
+    fn geocode_source_detects_theme_from_columns() {
+        let db_path = temp_db_path();
+        let conn = Connection::open(&db_path).expect("open");
+        conn.execute_batch(
+            "CREATE TABLE addresses_ca (id VARCHAR, geometry BLOB, number VARCHAR, street VARCHAR); \
+             CREATE TABLE addresses_ca_lookup (source_id VARCHAR, label VARCHAR, label_norm VARCHAR); \
+             CREATE TABLE places_wa (id VARCHAR, geometry BLOB, names VARCHAR); \
+             CREATE TABLE segments (id VARCHAR, geometry BLOB, subtype VARCHAR); \
+             CREATE TABLE no_geometry (id VARCHAR, names VARCHAR);",
+        )
+        .expect("setup");
+
+        assert_eq!(
+            geocode_source(&conn, "addresses_ca", "addresses_ca_lookup").expect("addresses"),
+            GeocodeSource::Addresses
+        );
+
+        let err = geocode_source(&conn, "places_wa", "places_wa_lookup").expect_err("no lookup");
+        assert!(err.to_string().contains("re-run overture_extract"));
+        conn.execute_batch(
+            "CREATE TABLE places_wa_lookup (source_id VARCHAR, label VARCHAR, label_norm VARCHAR)",
+        )
+        .expect("lookup");
+        assert_eq!(
+            geocode_source(&conn, "places_wa", "places_wa_lookup").expect("places"),
+            GeocodeSource::Names
+        );
+
+        let err = geocode_source(&conn, "segments", "segments_lookup").expect_err("no labels");
+        assert!(err.to_string().contains("extract the addresses theme"));
+
+        let err = geocode_source(&conn, "no_geometry", "no_geometry_lookup").expect_err("no geom");
+        assert!(err.to_string().contains("has no geometry column; re-extract"));
+
+        let err = geocode_source(&conn, "missing", "missing_lookup").expect_err("missing");
+        assert!(err.to_string().contains("does not exist"));
+
+        drop(conn);
+        cleanup_temp_db(&db_path);
+    }
+
+    #[test]
+    fn search_page_flags_truncation() {
+        let db_path = temp_db_with_lookup(&["Lincoln Park", "Lincoln Square", "Lincoln Hall"]);
+
+        let page = overture_search_page(
+            &db_path,
+            "places",
+            "lincoln",
+            2,
+            false,
+            false,
+            SearchWeights::default(),
+        )
+        .expect("search");
+        assert_eq!(page.results.len(), 2);
+        assert!(page.truncated);
+
+        let page = overture_search_page(
+            &db_path,
+            "places",
+            "lincoln",
+            3,
+            false,
+            false,
+            SearchWeights::default(),
+        )
+        .expect("search");
+        assert_eq!(page.results.len(), 3);
+        assert!(!page.truncated);
+
+        cleanup_temp_db(&db_path);
+    }
+
+    #[test]
+    fn search_ranking_presets_order_fixture() {
+        let db_path = temp_db_with_lookup(&[
+            "Lincoln Memorial Park Trail",
+            "Xlincoln",
+            "Old Lincolnton Rd",
+            "Lincoln",
+        ]);
+        let ranked = |ranking: &str| -> Vec<String> {
+            let weights: SearchWeights = ranking.parse().expect("ranking");
+            overture_search_page(&db_path, "places", "lincoln", 10, false, false, weights)
+                .expect("search")
+                .results
+                .into_iter()
+                .map(|r| r.label)
+                .collect()
+        };
+
+        assert_eq!(
+            ranked("balanced"),
+            vec!["Lincoln", "Lincoln Memorial Park Trail", "Old Lincolnton Rd", "Xlincoln"]
+        );
+        assert_eq!(ranked("balanced"), search_labels(&db_path, "lincoln"));
+        assert_eq!(
+            ranked("prefix_heavy"),
+            vec!["Lincoln", "Old Lincolnton Rd", "Lincoln Memorial Park Trail", "Xlincoln"]
+        );
+        assert_eq!(
+            ranked("Exact_Only"),
+            vec!["Lincoln", "Xlincoln", "Old Lincolnton Rd", "Lincoln Memorial Park Trail"]
+        );
+        assert!("fuzzy".parse::<SearchWeights>().is_err());
+
+        cleanup_temp_db(&db_path);
+    }
+
+    #[test]
+    fn lookup_join_compares_varchar_ids_uncast() {
+        let db_path = temp_db_with_lookup(&[]);
+        let conn = Connection::open(&db_path).expect("open");
+        conn.execute_batch(
+            "CREATE TABLE places (id VARCHAR); CREATE TABLE numbered (ID INTEGER);",
+        )
+        .expect("create base tables");
+
+        assert_eq!(id_join_key(&conn, "places").expect("varchar id"), "t.id");
+        assert_eq!(
+            id_join_key(&conn, "numbered").expect("integer id"),
+            "CAST(t.id AS VARCHAR)"
+        );
+
+        drop(conn);
+        cleanup_temp_db(&db_path);
+    }
+
+    #[test]
+    fn search_coords_come_from_the_base_geometry() {
+        let db_path = temp_db_with_lookup(&["Lincoln Park", "Lincoln Square"]);
+        let conn = Connection::open(&db_path).expect("open");
+        load_extension(&conn, "spatial").expect("spatial");
+        conn.execute_batch(
+            "CREATE TABLE places AS SELECT * FROM (VALUES \
+                 ('0', {'primary': 'Lincoln Park'}, ST_Point(-122.3, 47.6)), \
+                 ('1', {'primary': 'Lincoln Square'}, ST_Point(-87.7, 41.9))) \
+                 AS t(id, names, geometry)",
+        )
+        .expect("create base");
+        drop(conn);
+
+        let page = overture_search_page(
+            &db_path,
+            "places",
+            "lincoln",
+            10,
+            false,
+            true,
+            SearchWeights::default(),
+        )
+        .expect("search");
+        let hits: Vec<(&str, Option<f64>, Option<f64>)> = page
+            .results
+            .iter()
+            .map(|r| (r.label.as_str(), r.lat, r.lon))
+            .collect();
+        assert_eq!(
+            hits,
+            vec![
+                ("Lincoln Park", Some(47.6), Some(-122.3)),
+                ("Lincoln Square", Some(41.9), Some(-87.7)),
+            ]
+        );
+
+        cleanup_temp_db(&db_path);
+    }
+
+    #[test]
+    fn search_coords_are_skipped_without_geometry() {
+        let db_path = temp_db_with_lookup(&["Lincoln Park"]);
+        let conn = Connection::open(&db_path).expect("open");
+        conn.execute_batch("CREATE TABLE places (id VARCHAR, names VARCHAR)")
+            .expect("create base");
+        drop(conn);
+
+        let page = overture_search_page(
+            &db_path,
+            "places",
+            "lincoln",
+            10,
+            false,
+            true,
+            SearchWeights::default(),
+        )
+        .expect("search");
+        assert_eq!(page.results.len(), 1);
+        assert_eq!(page.results[0].label, "Lincoln Park");
+        assert_eq!((page.results[0].lat, page.results[0].lon), (None, None));
+
+        cleanup_temp_db(&db_path);
+    }
+
+    #[test]
+    fn materialized_results_replace_the_target_table() {
+        let db_path = temp_db_with_lookup(&[]);
+        let results = vec![
+            OvertureGeocodeResult {
+                id: Some("p1".to_string()),
+                label: "Lincoln Park".to_string(),
+                lat: Some(47.6),
+                lon: Some(-122.3),
+            },
+            OvertureGeocodeResult {
+                id: None,
+                label: "Lincoln Square".to_string(),
+                lat: None,
+                lon: None,
+            },
+        ];
+        let written = overture_materialize_results(&db_path, "places", "saved_places", &results)
+            .expect("write");
+        assert_eq!(written, 2);
+        let written =
+            overture_materialize_results(&db_path, "places", "saved_places", &results[..1])
+                .expect("replace");
+        assert_eq!(written, 1);
+
+        let conn = Connection::open(&db_path).expect("open");
+        let (count, label, lat): (i64, String, Option<f64>) = conn
+            .query_row("SELECT COUNT(*), max(label), max(lat) FROM saved_places", [], |row| {
+                Ok((row.get(0)?, row.get(1)?, row.get(2)?))
+            })
+            .expect("read back");
+        assert_eq!((count, label.as_str(), lat), (1, "Lincoln Park", Some(47.6)));
+        drop(conn);
+
+        assert!(
+            overture_materialize_results(&db_path, "places", "saved-places", &results).is_err()
+        );
+        cleanup_temp_db(&db_path);
+    }
+
+    #[test]
+    fn materialized_results_never_replace_the_searched_tables() {
+        let db_path = temp_db_with_lookup(&["Lincoln Park"]);
+        let results = vec![OvertureGeocodeResult {
+            id: Some("p1".to_string()),
+            label: "Lincoln Park".to_string(),
+            lat: None,
+            lon: None,
+        }];
+        for target in ["places", "places_lookup", "main.PLACES_LOOKUP"] {
+            let err = overture_materialize_results(&db_path, "places", target, &results)
+                .expect_err("target is a source table");
+            assert!(err.to_string().contains("must differ from places"), "{err}");
+        }
+        let err = overture_materialize_results(
+            &db_path,
+            "overture.regions",
+            "overture.regions_lookup",
+            &results,
+        )
+        .expect_err("qualified source");
+        assert!(err.to_string().contains("must differ"), "{err}");
+
+        // The lookup table the search reads is untouched.
+        let conn = Connection::open(&db_path).expect("open");
+        let count: i64 = conn
+            .query_row("SELECT COUNT(*) FROM places_lookup", [], |row| row.get(0))
+            .expect("count");
+        assert_eq!(count, 1);
+        drop(conn);
+        cleanup_temp_db(&db_path);
+    }
+
+    #[test]
+    fn out_of_memory_errors_explain_what_to_change() {
+        let dir = "/tmp/spatia_spill";
+        let oom = out_of_memory_hint(
+            "Out of Memory Error: failed to allocate data of size 16.0 MiB".into(),
+            None,
+        );
+        assert!(oom.to_string().contains("smaller bbox"));
+        assert!(oom.to_string().contains(TEMP_DIR_ENV));
+        let spilled = out_of_memory_hint("Out of Memory Error".into(), Some(dir));
+        assert!(spilled.to_string().contains("even with spilling to /tmp/spatia_spill"));
+        let other = out_of_memory_hint("HTTP 403".into(), Some(dir));
+        assert_eq!(other.to_string(), "HTTP 403");
+    }
 }
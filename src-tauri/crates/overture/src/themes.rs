@@ -0,0 +1,148 @@
+use serde::Serialize;
+
+use spatia_geocode::edit_distance;
+
+use crate::overture::{overture_release, OVERTURE_RELEASE};
+use crate::OvertureResult;
+
+/// Theme → types published in the pinned [`OVERTURE_RELEASE`].
+const KNOWN_THEMES: &[(&str, &[&str])] = &[
+    ("addresses", &["address"]),
+    (
+        "base",
+        &[
+            "bathymetry",
+            "infrastructure",
+            "land",
+            "land_cover",
+            "land_use",
+            "water",
+        ],
+    ),
+    ("buildings", &["building", "building_part"]),
+    (
+        "divisions",
+        &["division", "division_area", "division_boundary"],
+    ),
+    ("places", &["place"]),
+    ("transportation", &["connector", "segment"]),
+];
+
+#[derive(Debug, Clone, Serialize)]
+pub struct OvertureTheme {
+    pub theme: &'static str,
+    pub types: &'static [&'static str],
+}
+
+/// The theme/type combinations `overture_extract` accepts without `--force`.
+#[derive(Debug, Clone, Serialize)]
+pub struct OvertureThemeCatalog {
+    pub release: &'static str,
+    pub themes: Vec<OvertureTheme>,
+}
+
+pub fn overture_themes() -> OvertureThemeCatalog {
+    OvertureThemeCatalog {
+        release: OVERTURE_RELEASE,
+        themes: KNOWN_THEMES
+            .iter()
+            .map(|&(theme, types)| OvertureTheme { theme, types })
+            .collect(),
+    }
+}
+
+/// Reject a theme/type pair the pinned release does not publish, naming the
+/// closest known pair. Without this a typo reads a nonexistent S3 prefix and
+/// fails with an opaque parquet error.
+pub(crate) fn validate_theme_type(theme: &str, item_type: &str) -> OvertureResult<()> {
+    let known = KNOWN_THEMES
+        .iter()
+        .any(|(known_theme, types)| *known_theme == theme && types.contains(&item_type));
+    if known {
+        return Ok(());
+    }
+
+    let mut message = format!(
+        "unknown Overture theme/type '{theme}/{item_type}' for release {}",
+        overture_release()
+    );
+    if let Some(suggestion) = closest_pair(theme, item_type) {
+        message.push_str(&format!(" (did you mean {suggestion}?)"));
+    }
+    message.push_str("; run overture_themes for the list, or pass --force for a newer release");
+    Err(message.into())
+}
+
+/// Reject a theme the pinned release does not publish, listing the known
+/// ones, for commands such as `overture_rebuild_lookup` that take a theme
+/// without a type.
+pub(crate) fn validate_theme(theme: &str) -> OvertureResult<()> {
+    if KNOWN_THEMES.iter().any(|(known, _)| *known == theme) {
+        return Ok(());
+    }
+    let known: Vec<&str> = KNOWN_THEMES.iter().map(|(known, _)| *known).collect();
+    Err(format!(
+        "unknown Overture theme '{theme}'; known themes: {}",
+        known.join(", ")
+    )
+    .into())
+}
+
+/// Closest known `theme/type` by edit distance, if near enough to be a typo
+/// (at most a third of the input's length, minimum 2 edits).
+fn closest_pair(theme: &str, item_type: &str) -> Option<String> {
+    let input = format!("{theme}/{item_type}");
+    let max_distance = (input.chars().count() / 3).max(2);
+    KNOWN_THEMES
+        .iter()
+        .flat_map(|(theme, types)| {
+            types
+                .iter()
+                .map(move |item_type| format!("{theme}/{item_type}"))
+        })
+        .map(|candidate| (edit_distance(&input, &candidate), candidate))
+        .filter(|(distance, _)| *distance <= max_distance)
+        .min_by_key(|(distance, _)| *distance)
+        .map(|(_, candidate)| candidate)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{overture_themes, validate_theme, validate_theme_type};
+
+    #[test]
+    fn known_pairs_are_accepted() {
+        assert!(validate_theme_type("places", "place").is_ok());
+        assert!(validate_theme_type("divisions", "division_area").is_ok());
+        assert!(validate_theme_type("transportation", "segment").is_ok());
+        let catalog = overture_themes();
+        assert!(catalog
+            .themes
+            .iter()
+            .any(|theme| theme.theme == "addresses" && theme.types == ["address"]));
+    }
+
+    #[test]
+    fn unknown_theme_lists_the_known_themes() {
+        assert!(validate_theme("divisions").is_ok());
+        let err = validate_theme("place").expect_err("unknown theme");
+        assert_eq!(
+            err.to_string(),
+            "unknown Overture theme 'place'; known themes: addresses, base, buildings, \
+             divisions, places, transportation"
+        );
+    }
+
+    #[test]
+    fn typo_suggests_closest_pair() {
+        let err = validate_theme_type("place", "place").expect_err("unknown");
+        assert!(
+            err.to_string().contains("did you mean places/place?"),
+            "got: {err}"
+        );
+        let err = validate_theme_type("places", "segment").expect_err("wrong theme");
+        assert!(err.to_string().contains("--force"));
+        let err = validate_theme_type("weather", "forecast").expect_err("unknown");
+        assert!(!err.to_string().contains("did you mean"), "got: {err}");
+    }
+}
@@ -5,7 +5,7 @@ mod db_health;
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 use tauri::{Emitter, Manager};
-use tracing::{debug, error, info};
+use tracing::{debug, error, info, warn};
 
 /// Resolved at startup by `run()` → `setup` hook via Tauri's app-data dir.
 /// Falls back to the legacy relative path only if setup never ran (e.g. unit tests).
@@ -30,6 +30,123 @@ fn active_domain_pack() -> &'static spatia_engine::DomainPack {
     })
 }
 
+/// Gemini client shared by the AI commands through Tauri managed state, so
+/// repeated calls reuse one `reqwest` connection pool instead of building a
+/// fresh client per request. `None` when no API key is configured; rebuilt
+/// whenever the key is saved or deleted.
+struct AiState {
+    gemini: std::sync::RwLock<Option<spatia_ai::GeminiClient>>,
+    /// Cancel token of each Gemini call in flight, keyed by request id, so
+    /// `cancel_analysis` can stop one call without touching the others.
+    generations: std::sync::Mutex<std::collections::HashMap<String, spatia_ai::CancelToken>>,
+    /// Source of ids for calls made without a `request_id`.
+    next_generation: std::sync::atomic::AtomicU64,
+}
+
+/// A Gemini call registered in [`AiState`]; unregistered when dropped.
+struct Generation<'a> {
+    state: &'a AiState,
+    request_id: String,
+    token: spatia_ai::CancelToken,
+}
+
+impl Drop for Generation<'_> {
+    fn drop(&mut self) {
+        self.state.generations().remove(&self.request_id);
+    }
+}
+
+impl AiState {
+    fn from_env() -> Self {
+        let gemini = match spatia_ai::GeminiClient::from_env() {
+            Ok(client) => {
+                info!(model = client.model(), "spatia: Gemini client initialised");
+                Some(client)
+            }
+            Err(err) => {
+                info!(reason = %err, "spatia: Gemini not configured; AI features use fallbacks");
+                None
+            }
+        };
+        Self {
+            gemini: std::sync::RwLock::new(gemini),
+            generations: std::sync::Mutex::new(std::collections::HashMap::new()),
+            next_generation: std::sync::atomic::AtomicU64::new(0),
+        }
+    }
+
+    /// Cheap clone of the shared client; the HTTP pool behind it is shared.
+    fn client(&self) -> Option<spatia_ai::GeminiClient> {
+        self.gemini.read().ok().and_then(|guard| guard.clone())
+    }
+
+    /// [`Self::client`] switched to a per-request `model` when it is on the
+    /// `spatia_ai::KNOWN_MODELS` allowlist; other names keep the default.
+    fn client_for(&self, model: Option<&str>) -> Option<spatia_ai::GeminiClient> {
+        self.client()
+            .map(|client| client.with_requested_model(model))
+    }
+
+    /// The in-flight token map. Each update is a single insert or remove, so
+    /// a panic elsewhere while the lock was held cannot leave it half-written;
+    /// a poisoned lock is recovered with its entries intact.
+    fn generations(
+        &self,
+    ) -> std::sync::MutexGuard<'_, std::collections::HashMap<String, spatia_ai::CancelToken>> {
+        self.generations.lock().unwrap_or_else(|poisoned| {
+            warn!("AiState: generations lock was poisoned, recovering it");
+            poisoned.into_inner()
+        })
+    }
+
+    /// Register a Gemini call under `request_id` (or a generated id when the
+    /// caller sent none) with its own cancel token. An id already in flight
+    /// is refused, since cancelling it would stop both calls.
+    fn start_generation(&self, request_id: Option<String>) -> Result<Generation<'_>, String> {
+        let request_id = match request_id.filter(|id| !id.trim().is_empty()) {
+            Some(id) => id,
+            None => {
+                let n = self
+                    .next_generation
+                    .fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+                format!("auto-{n}")
+            }
+        };
+        let token = spatia_ai::CancelToken::new();
+        let mut generations = self.generations();
+        if generations.contains_key(&request_id) {
+            return Err(format!("request {request_id} is already in flight"));
+        }
+        generations.insert(request_id.clone(), token.clone());
+        Ok(Generation {
+            state: self,
+            request_id,
+            token,
+        })
+    }
+
+    /// Cancel the call registered under `request_id`, or every call in flight
+    /// when it is `None`. Returns how many calls were cancelled.
+    fn cancel_generations(&self, request_id: Option<&str>) -> usize {
+        let generations = self.generations();
+        let mut cancelled = 0;
+        for (id, token) in generations.iter() {
+            if request_id.is_none_or(|wanted| wanted == id) {
+                token.cancel();
+                cancelled += 1;
+            }
+        }
+        cancelled
+    }
+
+    fn reload(&self) {
+        let fresh = Self::from_env();
+        if let (Ok(mut current), Ok(fresh)) = (self.gemini.write(), fresh.gemini.into_inner()) {
+            *current = fresh;
+        }
+    }
+}
+
 fn db_path() -> &'static str {
     DB_PATH
         .get()
@@ -41,16 +158,26 @@ fn db_path() -> &'static str {
 struct AnalysisChatResponse {
     assistant: String,
     system_prompt: String,
+    /// Gemini model that answered; `None` when Gemini is not configured.
+    model: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize)]
 struct AnalysisSqlResponse {
     sql: String,
+    /// True when the first SQL failed to bind and the model was asked once
+    /// to correct it.
+    retry_attempted: bool,
+    /// Gemini model that wrote the SQL; `None` for the rule-based fallback.
+    model: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 struct VisualizationCommandResponse {
     visualization: String,
+    /// Gemini model that chose the visualization; never read from the reply.
+    #[serde(default, skip_deserializing)]
+    model: Option<String>,
 }
 
 #[tauri::command]
@@ -107,20 +234,17 @@ fn ingest_csv_with_progress(
     emit_ingest_progress(&app, effective_table, "started", "Starting CSV ingestion", 5)?;
     emit_ingest_progress(&app, effective_table, "reading", format!("Reading file: {csv_path}"), 30)?;
 
-    let ingest_result = if let Some(table) = resolved_table {
-        spatia_engine::ingest_csv_to_table(db_path(), &csv_path, table)
-            .map(|_| table.to_string())
-            .map_err(|err| err.to_string())
-    } else {
-        spatia_engine::ingest_csv(db_path(), &csv_path)
-            .map(|_| "raw_staging".to_string())
-            .map_err(|err| err.to_string())
-    };
+    let ingest_result =
+        spatia_engine::ingest_csv_with_row_progress(db_path(), &csv_path, resolved_table, &[], |rows| {
+            let _ = emit_ingest_progress(&app, effective_table, "writing", format!("Loaded {rows} rows"), 60);
+        })
+        .map(|rows| (effective_table.to_string(), rows))
+        .map_err(|err| err.to_string());
 
     match ingest_result {
-        Ok(table) => {
-            info!(table = %table, "ingest_csv_with_progress: completed successfully");
-            emit_ingest_progress(&app, &table, "writing", format!("Loaded table: {table}"), 85)?;
+        Ok((table, rows)) => {
+            info!(table = %table, rows, "ingest_csv_with_progress: completed successfully");
+            emit_ingest_progress(&app, &table, "writing", format!("Loaded table: {table} ({rows} rows)"), 85)?;
             emit_ingest_progress(&app, &table, "completed", "Ingestion complete", 100)?;
             Ok(format!("{{\"status\":\"ok\",\"table\":\"{}\"}}", table))
         }
@@ -167,9 +291,9 @@ async fn clean_table_with_progress(
     table_name: String,
 ) -> Result<String, String> {
     info!(table = %table_name, "clean_table_with_progress: starting");
-    let client = match spatia_ai::GeminiClient::from_env() {
-        Ok(c) => c,
-        Err(_) => {
+    let client = match app.state::<AiState>().client() {
+        Some(c) => c,
+        None => {
             info!(table = %table_name, "clean_table_with_progress: skipped (no API key)");
             return Ok(r#"{"status":"skipped","reason":"no_api_key"}"#.to_string());
         }
@@ -354,8 +478,7 @@ async fn geocode_table_column(
     // Extract per-row address components when component columns are available,
     // or fall back to distinct address strings for the simple case.
     let components: Vec<spatia_engine::AddressComponents> = {
-        let conn =
-            duckdb::Connection::open(db_path()).map_err(|e| e.to_string())?;
+        let conn = spatia_engine::open_connection(db_path()).map_err(|e| e.to_string())?;
 
         if have_components {
             // Build a SELECT that includes the optional component columns.
@@ -423,9 +546,10 @@ async fn geocode_table_column(
     )?;
 
     // Use progress-aware geocoding so we can relay Nominatim progress to the UI
+    let mode = spatia_engine::ProviderMode::from_env().map_err(|e| e.to_string())?;
     let app_clone = app.clone();
     let (results, geocode_stats) =
-        spatia_engine::geocode_batch_with_progress(db_path(), &components, move |update| {
+        spatia_engine::geocode_batch_with_progress(db_path(), &components, mode, move |update| {
             let pct = match update.stage.as_str() {
                 "cache" => 25,
                 "overture" => 30,
@@ -488,8 +612,7 @@ async fn geocode_table_column(
     )?;
 
     if !results.is_empty() {
-        let conn =
-            duckdb::Connection::open(db_path()).map_err(|e| e.to_string())?;
+        let conn = spatia_engine::open_connection(db_path()).map_err(|e| e.to_string())?;
 
         // Add geocode columns if not already present (including _gers_id)
         for alter_sql in [
@@ -606,7 +729,7 @@ async fn geocode_table_column(
 fn table_to_geojson(table_name: String) -> Result<String, String> {
     spatia_engine::validate_table_name(&table_name).map_err(|e| e.to_string())?;
 
-    let conn = duckdb::Connection::open(db_path()).map_err(|e| e.to_string())?;
+    let conn = spatia_engine::open_connection(db_path()).map_err(|e| e.to_string())?;
     conn.execute("LOAD spatial", []).map_err(|e| e.to_string())?;
 
     let schema =
@@ -777,7 +900,7 @@ fn table_geom_to_geojson(
 fn drop_table(table_name: String) -> Result<String, String> {
     spatia_engine::validate_table_name(&table_name).map_err(|e| e.to_string())?;
 
-    let conn = duckdb::Connection::open(db_path()).map_err(|e| e.to_string())?;
+    let conn = spatia_engine::open_connection(db_path()).map_err(|e| e.to_string())?;
     conn.execute_batch(&format!(
         r#"DROP TABLE IF EXISTS "{}""#,
         table_name
@@ -790,9 +913,18 @@ fn drop_table(table_name: String) -> Result<String, String> {
 
 // ---- Building footprints ----
 
+/// `projected` skips the lon/lat range check for a bbox in a projected CRS.
 #[tauri::command]
-async fn fetch_buildings_in_view(bbox_str: String) -> Result<String, String> {
-    let bbox = spatia_engine::BBox::parse(&bbox_str).map_err(|e| e.to_string())?;
+async fn fetch_buildings_in_view(
+    bbox_str: String,
+    projected: Option<bool>,
+) -> Result<String, String> {
+    let bbox = if projected.unwrap_or(false) {
+        spatia_engine::BBox::parse_projected(&bbox_str)
+    } else {
+        spatia_engine::BBox::parse(&bbox_str)
+    }
+    .map_err(|e| e.to_string())?;
     spatia_engine::fetch_buildings_in_bbox(
         db_path(),
         bbox.xmin,
@@ -805,38 +937,103 @@ async fn fetch_buildings_in_view(bbox_str: String) -> Result<String, String> {
 
 // ---- Analysis commands ----
 
+/// One streamed fragment of an `analysis_chat` reply, emitted as
+/// `analysis-chat-chunk` while Gemini is still generating.
+#[derive(Debug, Clone, Serialize)]
+struct AnalysisChatChunkEvent {
+    table_name: String,
+    text: String,
+}
+
 #[tauri::command]
-async fn analysis_chat(table_name: String, user_message: String) -> Result<String, String> {
+async fn analysis_chat(
+    app: tauri::AppHandle,
+    ai: tauri::State<'_, AiState>,
+    table_name: String,
+    user_message: String,
+    columns: Option<Vec<String>>,
+    include_samples: Option<bool>,
+    model: Option<String>,
+    request_id: Option<String>,
+) -> Result<String, String> {
     info!(table = %table_name, "analysis_chat: starting");
     if user_message.trim().is_empty() {
         return Err("user_message cannot be empty".to_string());
     }
 
-    let schema =
-        spatia_engine::table_schema(db_path(), &table_name).map_err(|err| err.to_string())?;
+    // An explicit column list trims the schema injected into the prompt,
+    // which keeps very wide tables within the model's token budget.
+    let schema = match columns.filter(|c| !c.is_empty()) {
+        Some(columns) => {
+            let projection =
+                spatia_engine::table_schema_projection(db_path(), &table_name, &columns)
+                    .map_err(|err| err.to_string())?;
+            if !projection.missing.is_empty() {
+                warn!(
+                    table = %table_name,
+                    missing = ?projection.missing,
+                    "analysis_chat: requested columns not found"
+                );
+            }
+            projection.columns
+        }
+        None => {
+            spatia_engine::table_schema(db_path(), &table_name).map_err(|err| err.to_string())?
+        }
+    };
     let pack = active_domain_pack();
     let domain_ctx = if pack.system_prompt_extension.is_empty() {
         None
     } else {
         Some(pack.system_prompt_extension.as_str())
     };
-    let system_prompt =
-        spatia_ai::build_analysis_chat_system_prompt_with_domain(&table_name, &schema, domain_ctx);
+    // Distinct values of low-cardinality text columns let the model answer
+    // questions about specific values; off by default so large tables keep
+    // the schema-only prompt.
+    let samples = if include_samples.unwrap_or(false) {
+        match spatia_engine::fetch_column_samples(db_path(), &table_name) {
+            Ok(samples) => Some(samples),
+            Err(err) => {
+                warn!(table = %table_name, error = %err, "analysis_chat: failed to fetch samples, using schema only");
+                None
+            }
+        }
+    } else {
+        None
+    };
+    let system_prompt = spatia_ai::build_analysis_chat_system_prompt_with_samples(
+        &table_name,
+        &schema,
+        samples.as_ref(),
+        domain_ctx,
+    );
     let full_prompt = format!(
         "{system}\n\n## User message\n{message}\n",
         system = system_prompt,
         message = user_message.trim()
     );
 
-    let assistant = match spatia_ai::GeminiClient::from_env() {
-        Ok(client) => client
-            .generate(&full_prompt)
-            .await
-            .map_err(|err| {
-                error!(table = %table_name, error = %err, "analysis_chat: Gemini call failed");
-                err.to_string()
-            })?,
-        Err(_) => "Gemini is not configured. Set SPATIA_GEMINI_API_KEY to enable AI analysis chat."
+    let client = ai.client_for(model.as_deref());
+    let generation = ai.start_generation(request_id)?;
+    let assistant = match &client {
+        Some(client) => spatia_ai::cancellable(
+            &generation.token,
+            client.generate_stream(&full_prompt, |text| {
+                let chunk = AnalysisChatChunkEvent {
+                    table_name: table_name.clone(),
+                    text: text.to_string(),
+                };
+                if let Err(err) = app.emit("analysis-chat-chunk", chunk) {
+                    warn!(error = %err, "analysis_chat: failed to emit chunk");
+                }
+            }),
+        )
+        .await
+        .map_err(|err| {
+            error!(table = %table_name, error = %err, "analysis_chat: Gemini call failed");
+            err.to_string()
+        })?,
+        None => "Gemini is not configured. Set SPATIA_GEMINI_API_KEY to enable AI analysis chat."
             .to_string(),
     };
 
@@ -844,50 +1041,204 @@ async fn analysis_chat(table_name: String, user_message: String) -> Result<Strin
     let payload = AnalysisChatResponse {
         assistant,
         system_prompt,
+        model: client.map(|client| client.model().to_string()),
     };
     serde_json::to_string(&payload).map_err(|err| err.to_string())
 }
 
 #[tauri::command]
-async fn generate_analysis_sql(table_name: String, user_goal: String) -> Result<String, String> {
+async fn generate_analysis_sql(
+    ai: tauri::State<'_, AiState>,
+    table_name: String,
+    user_goal: String,
+    model: Option<String>,
+    relevant_columns: Option<Vec<String>>,
+    request_id: Option<String>,
+) -> Result<String, String> {
     if user_goal.trim().is_empty() {
         return Err("user_goal cannot be empty".to_string());
     }
 
     let schema =
         spatia_engine::table_schema(db_path(), &table_name).map_err(|err| err.to_string())?;
+    let relevant_columns = relevant_columns.unwrap_or_default();
     let pack = active_domain_pack();
     let domain_ctx = if pack.system_prompt_extension.is_empty() {
         None
     } else {
         Some(pack.system_prompt_extension.as_str())
     };
-    let prompt =
-        spatia_ai::build_analysis_sql_prompt_with_domain(&table_name, &schema, &user_goal, domain_ctx);
-
-    let sql = match spatia_ai::GeminiClient::from_env() {
-        Ok(client) => client
-            .generate(&prompt)
-            .await
-            .map_err(|err| err.to_string())?,
-        Err(_) => {
-            format!(
-                "CREATE OR REPLACE VIEW analysis_result AS SELECT * FROM {} LIMIT 100;",
-                table_name
+    // Wide tables (Overture extracts have 100+ columns) get a prompt listing
+    // only the hinted or goal-related columns; small tables keep the full one.
+    let prompt = spatia_ai::build_analysis_sql_prompt_focused(
+        &table_name,
+        &schema,
+        &user_goal,
+        &relevant_columns,
+        domain_ctx,
+    );
+
+    let client = ai.client_for(model.as_deref());
+    let (sql, retry_attempted) = match &client {
+        Some(client) => {
+            let focused = spatia_ai::select_analysis_columns(&schema, &user_goal, &relevant_columns);
+            let table_schemas = vec![(table_name.clone(), focused)];
+            let generation = ai.start_generation(request_id)?;
+            spatia_ai::cancellable(
+                &generation.token,
+                generate_checked_analysis_sql(client, &prompt, &user_goal, &table_schemas, domain_ctx),
             )
+            .await?
+        }
+        None => {
+            debug!("generate_analysis_sql: Gemini not configured, using rule-based fallback");
+            let sql = spatia_ai::build_fallback_analysis_sql(&table_name, &schema, &user_goal);
+            (sql.trim().to_string(), false)
         }
+    };
+
+    let payload = AnalysisSqlResponse {
+        sql,
+        retry_attempted,
+        model: client.map(|client| client.model().to_string()),
+    };
+    serde_json::to_string(&payload).map_err(|err| err.to_string())
+}
+
+/// Generate analysis SQL with Gemini and bind it against the workspace with
+/// `check_analysis_sql`. On failure the model is re-prompted once with the
+/// error and the rejected SQL; the second answer is returned whether or not
+/// it binds, so latency and cost stay bounded at two calls.
+async fn generate_checked_analysis_sql(
+    client: &spatia_ai::GeminiClient,
+    prompt: &str,
+    user_goal: &str,
+    table_schemas: &[(String, Vec<spatia_engine::TableColumn>)],
+    domain_ctx: Option<&str>,
+) -> Result<(String, bool), String> {
+    let raw = client.generate(prompt).await.map_err(|err| err.to_string())?;
+    let sql = spatia_ai::strip_markdown_fences(&raw);
+    let check_err = match spatia_engine::check_analysis_sql(db_path(), &sql) {
+        Ok(()) => return Ok((sql, false)),
+        Err(err) => err.to_string(),
+    };
+
+    warn!(error = %check_err, "generate_analysis_sql: generated SQL failed, retrying once");
+    let retry_prompt = spatia_ai::build_analysis_retry_prompt_with_domain(
+        user_goal,
+        table_schemas,
+        &sql,
+        &check_err,
+        None,
+        domain_ctx,
+    );
+    let raw = client
+        .generate(&retry_prompt)
+        .await
+        .map_err(|err| err.to_string())?;
+    let retry_sql = spatia_ai::strip_markdown_fences(&raw);
+    if let Err(err) = spatia_engine::check_analysis_sql(db_path(), &retry_sql) {
+        warn!(error = %err, "generate_analysis_sql: corrected SQL still fails");
+    }
+    Ok((retry_sql, true))
+}
+
+#[tauri::command]
+async fn generate_analysis_sql_multi(
+    ai: tauri::State<'_, AiState>,
+    table_names: Vec<String>,
+    user_goal: String,
+    model: Option<String>,
+    request_id: Option<String>,
+) -> Result<String, String> {
+    if user_goal.trim().is_empty() {
+        return Err("user_goal cannot be empty".to_string());
+    }
+    if table_names.is_empty() {
+        return Err("table_names cannot be empty".to_string());
     }
-    .trim()
-    .to_string();
 
-    let payload = AnalysisSqlResponse { sql };
+    let mut table_schemas = Vec::with_capacity(table_names.len());
+    for name in &table_names {
+        let schema = spatia_engine::table_schema(db_path(), name).map_err(|err| err.to_string())?;
+        table_schemas.push((name.clone(), schema));
+    }
+    let pack = active_domain_pack();
+    let domain_ctx = if pack.system_prompt_extension.is_empty() {
+        None
+    } else {
+        Some(pack.system_prompt_extension.as_str())
+    };
+    let prompt = spatia_ai::build_analysis_sql_prompt_multi(&table_schemas, &user_goal, domain_ctx);
+
+    let client = ai.client_for(model.as_deref());
+    let (sql, retry_attempted) = match &client {
+        Some(client) => {
+            let generation = ai.start_generation(request_id)?;
+            spatia_ai::cancellable(
+                &generation.token,
+                generate_checked_analysis_sql(client, &prompt, &user_goal, &table_schemas, domain_ctx),
+            )
+            .await?
+        }
+        // The rule-based generator cannot write JOINs, so it only stands in
+        // for Gemini when a single table was requested.
+        None if table_schemas.len() == 1 => {
+            debug!("generate_analysis_sql_multi: Gemini not configured, using rule-based fallback");
+            let (table_name, schema) = &table_schemas[0];
+            let sql = spatia_ai::build_fallback_analysis_sql(table_name, schema, &user_goal);
+            (sql.trim().to_string(), false)
+        }
+        None => {
+            return Err(
+                "Gemini is not configured. Set SPATIA_GEMINI_API_KEY to enable multi-table analysis."
+                    .to_string(),
+            )
+        }
+    };
+
+    info!(tables = ?table_names, retry_attempted, "generate_analysis_sql_multi: completed");
+    let payload = AnalysisSqlResponse {
+        sql,
+        retry_attempted,
+        model: client.map(|client| client.model().to_string()),
+    };
     serde_json::to_string(&payload).map_err(|err| err.to_string())
 }
 
+/// Cancel the Gemini call started with `request_id`, or every call in
+/// flight when no id is given.
+#[tauri::command]
+fn cancel_analysis(ai: tauri::State<'_, AiState>, request_id: Option<String>) {
+    let cancelled = ai.cancel_generations(request_id.as_deref());
+    info!(request_id = ?request_id, cancelled, "cancel_analysis: cancelled Gemini requests");
+}
+
 #[tauri::command]
-fn execute_analysis_sql(sql: String) -> Result<String, String> {
+fn execute_analysis_sql(
+    sql: String,
+    null_geometry: Option<String>,
+    feature_limit: Option<usize>,
+    view_name: Option<String>,
+    geometry_join: Option<spatia_engine::GeometryJoin>,
+    attach: Option<Vec<(String, String)>>,
+    format: Option<String>,
+) -> Result<String, String> {
     debug!(sql = %sql, "execute_analysis_sql: executing");
-    let result = spatia_engine::execute_analysis_sql_to_geojson(db_path(), &sql)
+    let mut options = spatia_engine::AnalysisOptions::default();
+    if let Some(format) = format {
+        options.format = format.parse()?;
+    }
+    if let Some(mode) = null_geometry {
+        options.null_geometry = mode.parse()?;
+    }
+    if let Some(limit) = feature_limit {
+        options.feature_limit = limit;
+    }
+    options.geometry_join = geometry_join;
+    options.attach = attach.unwrap_or_default();
+    let result =
+        spatia_engine::execute_analysis_sql_with_options(db_path(), &sql, view_name.as_deref(), options)
         .map_err(|err| {
             error!(sql = %sql, error = %err, "execute_analysis_sql: failed");
             err.to_string()
@@ -895,10 +1246,26 @@ fn execute_analysis_sql(sql: String) -> Result<String, String> {
     serde_json::to_string(&result).map_err(|err| err.to_string())
 }
 
+/// DuckDB's plan for the view built by analysis SQL, for debugging slow
+/// queries. `analyze` runs `EXPLAIN ANALYZE`, which executes the query.
+#[tauri::command]
+fn explain_analysis_sql(sql: String, analyze: Option<bool>) -> Result<String, String> {
+    let analyze = analyze.unwrap_or(false);
+    debug!(sql = %sql, analyze, "explain_analysis_sql: explaining");
+    let plan = spatia_engine::explain_analysis_sql(db_path(), &sql, analyze).map_err(|err| {
+        error!(sql = %sql, error = %err, "explain_analysis_sql: failed");
+        err.to_string()
+    })?;
+    serde_json::to_string(&plan).map_err(|err| err.to_string())
+}
+
 #[tauri::command]
 async fn generate_visualization_command(
+    ai: tauri::State<'_, AiState>,
     table_name: String,
     user_goal: String,
+    model: Option<String>,
+    request_id: Option<String>,
 ) -> Result<String, String> {
     if user_goal.trim().is_empty() {
         return Err("user_goal cannot be empty".to_string());
@@ -906,22 +1273,27 @@ async fn generate_visualization_command(
 
     let prompt = spatia_ai::build_visualization_command_prompt(&table_name, &user_goal);
 
-    let visualization = match spatia_ai::GeminiClient::from_env() {
-        Ok(client) => {
-            let text = client
-                .generate(&prompt)
+    let client = ai.client_for(model.as_deref());
+    let visualization = match &client {
+        Some(client) => {
+            let generation = ai.start_generation(request_id)?;
+            let text = spatia_ai::cancellable(&generation.token, client.generate(&prompt))
                 .await
                 .map_err(|err| err.to_string())?;
+            let text = spatia_ai::strip_markdown_fences(&text);
             match serde_json::from_str::<VisualizationCommandResponse>(&text) {
                 Ok(parsed) => parsed.visualization,
                 Err(_) => "scatter".to_string(),
             }
         }
-        Err(_) => "scatter".to_string(),
+        None => "scatter".to_string(),
     };
 
-    serde_json::to_string(&VisualizationCommandResponse { visualization })
-        .map_err(|err| err.to_string())
+    let payload = VisualizationCommandResponse {
+        visualization,
+        model: client.map(|client| client.model().to_string()),
+    };
+    serde_json::to_string(&payload).map_err(|err| err.to_string())
 }
 
 // ---- Preview table ----
@@ -941,7 +1313,7 @@ fn preview_table(table_name: String, limit: Option<u32>) -> Result<String, Strin
     // DOUBLE, DATE, etc.) serialize correctly. The duckdb-rs driver returns Err
     // for `row.get::<_, String>(i)` on non-VARCHAR columns, which `.ok()` turns
     // into None → JSON null, making numeric columns appear empty in previews.
-    let conn = duckdb::Connection::open(db_path()).map_err(|e| e.to_string())?;
+    let conn = spatia_engine::open_connection(db_path()).map_err(|e| e.to_string())?;
     let cast_select = col_names
         .iter()
         .map(|c| format!(r#"CAST("{c}" AS VARCHAR) AS "{c}""#))
@@ -981,25 +1353,22 @@ fn preview_table(table_name: String, limit: Option<u32>) -> Result<String, Strin
 
 #[tauri::command]
 fn list_tables() -> Result<String, String> {
-    let conn = duckdb::Connection::open(db_path()).map_err(|e| e.to_string())?;
+    let cache_table = spatia_engine::local_cache_table_name().map_err(|e| e.to_string())?;
+    let entries = spatia_engine::list_tables(db_path(), "main").map_err(|e| e.to_string())?;
 
-    let mut stmt = conn
-        .prepare(
-            "SELECT table_name FROM information_schema.tables \
-             WHERE table_schema = 'main' AND table_type = 'BASE TABLE' \
-             AND table_name NOT IN ('geocode_cache', 'analysis_result') \
-             ORDER BY table_name",
-        )
-        .map_err(|e| e.to_string())?;
-
-    let mut rows = stmt
-        .query([])
-        .map_err(|e| e.to_string())?;
-    let mut tables = Vec::new();
-    while let Some(row) = rows.next().map_err(|e| e.to_string())? {
-        let name: String = row.get::<_, String>(0).map_err(|e| e.to_string())?;
-        tables.push(serde_json::json!({ "name": name }));
-    }
+    // Views (analysis_result among them) and the geocode cache are not data
+    // tables the user loaded.
+    let tables: Vec<serde_json::Value> = entries
+        .into_iter()
+        .filter(|entry| entry.kind == spatia_engine::TableKind::Table)
+        .filter(|entry| entry.name != "analysis_result")
+        .filter(|entry| {
+            cache_table
+                .as_deref()
+                .is_none_or(|cache| !entry.name.eq_ignore_ascii_case(cache))
+        })
+        .map(|entry| serde_json::json!({ "name": entry.name }))
+        .collect();
 
     serde_json::to_string(&serde_json::json!({ "tables": tables })).map_err(|e| e.to_string())
 }
@@ -1043,8 +1412,8 @@ async fn ingest_file_pipeline(
 
             // clean_table internally runs up to 3 rounds with early exit.
             // Call it once — no outer loop needed.
-            match spatia_ai::GeminiClient::from_env() {
-                Ok(client) => {
+            match app.state::<AiState>().client() {
+                Some(client) => {
                     match handle.block_on(spatia_ai::clean_table(db_path(), &table_name, &client)) {
                         Ok(result) => {
                             let total_statements = result.statements_applied.len();
@@ -1056,7 +1425,7 @@ async fn ingest_file_pipeline(
                         }
                     }
                 }
-                Err(_) => "skipped (no API key)".to_string(),
+                None => "skipped (no API key)".to_string(),
             }
         };
 
@@ -1096,7 +1465,7 @@ async fn ingest_file_pipeline(
         };
 
         // Get row count
-        let conn = duckdb::Connection::open(db_path()).map_err(|e| e.to_string())?;
+        let conn = spatia_engine::open_connection(db_path()).map_err(|e| e.to_string())?;
         let row_count: i64 = conn
             .query_row(
                 &format!(r#"SELECT COUNT(*) FROM "{}""#, table_name),
@@ -1220,13 +1589,18 @@ struct ChatTurnResult {
     /// True when the first SQL attempt failed and a second AI call produced the
     /// SQL that ultimately succeeded (or also failed).
     retry_attempted: bool,
+    /// Gemini model that answered; `None` when Gemini is not configured.
+    model: Option<String>,
 }
 
 #[tauri::command]
 async fn chat_turn(
+    ai: tauri::State<'_, AiState>,
     table_names: Vec<String>,
     user_message: String,
     conversation_history: Vec<serde_json::Value>,
+    model: Option<String>,
+    request_id: Option<String>,
 ) -> Result<String, String> {
     info!(tables = ?table_names, history_len = conversation_history.len(), "chat_turn: starting");
     if user_message.trim().is_empty() {
@@ -1274,9 +1648,9 @@ async fn chat_turn(
     );
 
     // Call Gemini with JSON mode
-    let client = match spatia_ai::GeminiClient::from_env() {
-        Ok(c) => c,
-        Err(_) => {
+    let client = match ai.client_for(model.as_deref()) {
+        Some(c) => c,
+        None => {
             let result = ChatTurnResult {
                 message: "Gemini is not configured. Set SPATIA_GEMINI_API_KEY to enable AI analysis.".to_string(),
                 sql: None,
@@ -1287,13 +1661,15 @@ async fn chat_turn(
                 result_rows: None,
                 visualization_type: "scatter".to_string(),
                 retry_attempted: false,
+                model: None,
             };
             return serde_json::to_string(&result).map_err(|e| e.to_string());
         }
     };
 
-    let response_text = client
-        .generate_json(&prompt)
+    let generation = ai.start_generation(request_id)?;
+    let cancel = &generation.token;
+    let response_text = spatia_ai::cancellable(cancel, client.generate_json(&prompt))
         .await
         .map_err(|e| {
             error!(error = %e, "chat_turn: Gemini JSON call failed");
@@ -1315,7 +1691,7 @@ async fn chat_turn(
     let sql = parsed
         .get("sql")
         .and_then(|v| v.as_str())
-        .map(|s| s.to_string())
+        .map(spatia_ai::strip_markdown_fences)
         .filter(|s| !s.is_empty());
 
     let map_actions: Vec<Value> = parsed
@@ -1336,7 +1712,7 @@ async fn chat_turn(
     let (geojson, row_count, total_count, result_rows, retry_attempted, final_sql) =
         if let Some(ref sql_str) = sql {
             debug!(sql = %sql_str, "chat_turn: executing analysis SQL");
-            match spatia_engine::execute_analysis_sql_to_geojson(db_path(), sql_str) {
+            match spatia_engine::execute_analysis_sql_to_geojson(db_path(), sql_str, None) {
                 Ok(engine_result) => {
                     info!(
                         row_count = engine_result.row_count,
@@ -1388,6 +1764,7 @@ async fn chat_turn(
                             result_rows: None,
                             visualization_type,
                             retry_attempted: false,
+                            model: Some(client.model().to_string()),
                         };
                         return serde_json::to_string(&result).map_err(|e| e.to_string());
                     }
@@ -1402,7 +1779,12 @@ async fn chat_turn(
                         domain_context.as_deref(),
                     );
 
-                    let retry_sql_raw = match client.generate(&retry_prompt).await {
+                    let retry_sql_raw = match spatia_ai::cancellable(
+                        cancel,
+                        client.generate(&retry_prompt),
+                    )
+                    .await
+                    {
                         Ok(text) => text,
                         Err(retry_gemini_err) => {
                             error!(
@@ -1421,23 +1803,18 @@ async fn chat_turn(
                                 result_rows: None,
                                 visualization_type,
                                 retry_attempted: true,
+                                model: Some(client.model().to_string()),
                             };
                             return serde_json::to_string(&result).map_err(|e| e.to_string());
                         }
                     };
 
                     // Strip markdown fences if the model wrapped its response.
-                    let retry_sql = retry_sql_raw
-                        .trim()
-                        .trim_start_matches("```sql")
-                        .trim_start_matches("```")
-                        .trim_end_matches("```")
-                        .trim()
-                        .to_string();
+                    let retry_sql = spatia_ai::strip_markdown_fences(&retry_sql_raw);
 
                     info!(retry_sql = %retry_sql, "chat_turn: retrying with corrected SQL");
 
-                    match spatia_engine::execute_analysis_sql_to_geojson(db_path(), &retry_sql) {
+                    match spatia_engine::execute_analysis_sql_to_geojson(db_path(), &retry_sql, None) {
                         Ok(engine_result) => {
                             info!(
                                 row_count = engine_result.row_count,
@@ -1475,6 +1852,7 @@ async fn chat_turn(
                                 result_rows: None,
                                 visualization_type,
                                 retry_attempted: true,
+                                model: Some(client.model().to_string()),
                             };
                             return serde_json::to_string(&result).map_err(|e| e.to_string());
                         }
@@ -1507,6 +1885,7 @@ async fn chat_turn(
         result_rows,
         visualization_type: validated_visualization_type,
         retry_attempted,
+        model: Some(client.model().to_string()),
     };
     serde_json::to_string(&result).map_err(|e| e.to_string())
 }
@@ -1525,13 +1904,20 @@ fn get_log_path() -> Result<String, String> {
 
 #[tauri::command]
 fn export_table_csv(table_name: String, file_path: String) -> Result<(), String> {
-    let conn = duckdb::Connection::open(db_path()).map_err(|e| e.to_string())?;
+    let conn = spatia_engine::open_connection(db_path()).map_err(|e| e.to_string())?;
     spatia_engine::export_table_csv(&conn, &table_name, &file_path).map_err(|e| e.to_string())
 }
 
+#[tauri::command]
+fn export_table_geoparquet(table_name: String, file_path: String) -> Result<String, String> {
+    let conn = spatia_engine::open_connection(db_path()).map_err(|e| e.to_string())?;
+    spatia_engine::export_table_geoparquet(&conn, &table_name, &file_path)
+        .map_err(|e| e.to_string())
+}
+
 #[tauri::command]
 fn export_analysis_geojson(file_path: String) -> Result<(), String> {
-    let conn = duckdb::Connection::open(db_path()).map_err(|e| e.to_string())?;
+    let conn = spatia_engine::open_connection(db_path()).map_err(|e| e.to_string())?;
     spatia_engine::export_analysis_geojson(&conn, &file_path).map_err(|e| e.to_string())
 }
 
@@ -1564,6 +1950,9 @@ fn save_api_key(app: tauri::AppHandle, key_name: String, key_value: String) -> R
     if let Some(name) = env_name {
         std::env::set_var(name, &key_value);
     }
+    if key_name == "gemini_api_key" {
+        app.state::<AiState>().reload();
+    }
     Ok(())
 }
 
@@ -1588,6 +1977,9 @@ fn delete_api_key(app: tauri::AppHandle, key_name: String) -> Result<(), String>
     if let Some(name) = env_name {
         std::env::remove_var(name);
     }
+    if key_name == "gemini_api_key" {
+        app.state::<AiState>().reload();
+    }
     Ok(())
 }
 
@@ -1702,9 +2094,12 @@ pub fn run() {
     let file_appender = tracing_appender::rolling::daily(&logs_dir, "spatia.log");
     let (non_blocking_file, _file_guard) = tracing_appender::non_blocking(file_appender);
 
-    // EnvFilter: default INFO for file; honour RUST_LOG env var for overrides
-    let env_filter = tracing_subscriber::EnvFilter::try_from_default_env()
-        .unwrap_or_else(|_| tracing_subscriber::EnvFilter::new("info"));
+    // EnvFilter: default INFO for file; SPATIA_LOG wins over RUST_LOG for overrides
+    let env_filter = std::env::var("SPATIA_LOG")
+        .ok()
+        .and_then(|value| tracing_subscriber::EnvFilter::try_new(value).ok())
+        .or_else(|| tracing_subscriber::EnvFilter::try_from_default_env().ok())
+        .unwrap_or_else(|| tracing_subscriber::EnvFilter::new("info"));
 
     use tracing_subscriber::prelude::*;
     let file_layer = tracing_subscriber::fmt::layer()
@@ -1793,6 +2188,9 @@ pub fn run() {
                 }
             }
 
+            // Build the shared Gemini client once, after stored keys are in the env.
+            app.manage(AiState::from_env());
+
             // Resolve the active domain pack from SPATIA_DOMAIN_PACK env var.
             let pack = spatia_engine::DomainPack::from_env();
             info!(domain_pack = %pack.id, "spatia: active domain pack");
@@ -1821,7 +2219,10 @@ pub fn run() {
                     fetch_buildings_in_view,
                     analysis_chat,
                     generate_analysis_sql,
+                    generate_analysis_sql_multi,
+                    cancel_analysis,
                     execute_analysis_sql,
+                    explain_analysis_sql,
                     generate_visualization_command,
                     list_tables,
                     preview_table,
@@ -1831,6 +2232,7 @@ pub fn run() {
                     get_log_path,
                     get_domain_pack_config,
                     export_table_csv,
+                    export_table_geoparquet,
                     export_analysis_geojson,
                     save_file,
                     save_api_key,
@@ -1855,7 +2257,10 @@ pub fn run() {
                     fetch_buildings_in_view,
                     analysis_chat,
                     generate_analysis_sql,
+                    generate_analysis_sql_multi,
+                    cancel_analysis,
                     execute_analysis_sql,
+                    explain_analysis_sql,
                     generate_visualization_command,
                     list_tables,
                     preview_table,
@@ -1865,6 +2270,7 @@ pub fn run() {
                     get_log_path,
                     get_domain_pack_config,
                     export_table_csv,
+                    export_table_geoparquet,
                     export_analysis_geojson,
                     save_file,
                     save_api_key,
@@ -1,4 +1,4 @@
-import { useRef, useState } from "react";
+import { useEffect, useRef, useState } from "react";
 import { invoke } from "@tauri-apps/api/core";
 import { save } from "@tauri-apps/plugin-dialog";
 import { Button } from "@/components/ui/button";
@@ -91,6 +91,19 @@ export function ChatCard({ mapViewRef, panelWidth = 300 }: Props) {
   const [loading, setLoading] = useState(false);
   const [error, setError] = useState<string | null>(null);
   const messagesEndRef = useRef<HTMLDivElement>(null);
+  const loadingRef = useRef(false);
+  loadingRef.current = loading;
+  const requestIdRef = useRef<string | null>(null);
+
+  // Leaving the chat while Gemini is still answering aborts this card's request.
+  useEffect(
+    () => () => {
+      if (loadingRef.current && requestIdRef.current && isTauri()) {
+        void invoke("cancel_analysis", { requestId: requestIdRef.current });
+      }
+    },
+    [],
+  );
 
   const messages = useAppStore((s) => s.chatMessages);
   const addMessage = useAppStore((s) => s.addMessage);
@@ -134,10 +147,13 @@ export function ChatCard({ mapViewRef, panelWidth = 300 }: Props) {
     }
 
     try {
+      const requestId = crypto.randomUUID();
+      requestIdRef.current = requestId;
       const raw = await invoke<string>("chat_turn", {
         tableNames,
         userMessage: text,
         conversationHistory,
+        requestId,
       });
 
       const result = JSON.parse(raw) as {